@@ -4,7 +4,11 @@ use crate::ExchangeExt;
 use crate::Trade;
 
 /// Core exchange algorithm.
-pub trait Algo<O> {
+///
+/// Algorithms are generic over the exchange `E` they match on, so that they
+/// may ask it for whatever configuration they need through traits of their
+/// own, beyond what every exchange offers.
+pub trait Algo<O, E: ?Sized> {
     type Error;
     type Output;
 
@@ -13,7 +17,7 @@ pub trait Algo<O> {
     /// This method takes an order as input and attempts to match it against the
     /// existing limit orders in the orderbook. Matching is done in a specific
     /// order based on the orderbook's rules, such as price-time priority.
-    fn matching<E>(
+    fn matching(
         exchange: &mut E,
         incoming_order: O,
     ) -> Result<Self::Output, Self::Error>
@@ -27,6 +31,7 @@ pub trait Algo<O> {
             OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
             OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
             OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+            Trade = <<E as Exchange>::Order as Asset>::Trade,
         >,
-        O: TryInto<<E as Exchange>::Order>;
+        O: Clone + TryInto<<E as Exchange>::Order>;
 }
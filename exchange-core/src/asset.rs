@@ -2,6 +2,7 @@ use std::error::Error;
 use std::ops::Add;
 use std::ops::Div;
 use std::ops::Mul;
+use std::ops::Rem;
 use std::ops::Sub;
 
 use either::Either;
@@ -10,7 +11,8 @@ use num::Zero;
 pub trait Asset: PartialOrd {
     /// Order unique identifier.
     type OrderId: Copy + Eq + Ord;
-    type OrderNotional: Div<Self::OrderQuantity, Output = Self::OrderPrice>
+    type OrderNotional: Add<Output = Self::OrderNotional>
+        + Div<Self::OrderQuantity, Output = Self::OrderPrice>
         + Div<Self::OrderPrice, Output = Self::OrderQuantity>
         + Sub<Output = Self::OrderNotional>
         + Copy
@@ -18,12 +20,16 @@ pub trait Asset: PartialOrd {
         + Zero;
     /// Order price.
     type OrderPrice: Mul<Self::OrderQuantity, Output = Self::OrderNotional>
+        + Sub<Output = Self::OrderPrice>
         + Copy
         + Ord;
     /// Order quantity.
     type OrderQuantity: Add<Output = Self::OrderQuantity>
         + Sub<Output = Self::OrderQuantity>
         + Mul<Self::OrderPrice, Output = Self::OrderNotional>
+        + Rem<Output = Self::OrderQuantity>
+        + Prorate
+        + From<u8>
         + Copy
         + Ord
         + Zero;
@@ -43,6 +49,12 @@ pub trait Asset: PartialOrd {
     fn limit_price(&self) -> Option<Self::OrderPrice>;
     /// Return order remaining amount.
     fn remaining(&self) -> Either<Self::OrderNotional, Self::OrderQuantity>;
+    /// Return order remaining amount, hidden reserve included, unlike
+    /// [`Asset::remaining`], which only tells what is shown of a resting
+    /// iceberg order.
+    fn total_remaining(
+        &self,
+    ) -> Either<Self::OrderNotional, Self::OrderQuantity>;
     /// Return current order status.
     fn status(&self) -> Self::OrderStatus;
     /// Returns `true` if order is fill or kill.
@@ -55,8 +67,39 @@ pub trait Asset: PartialOrd {
     fn is_immediate_or_cancel(&self) -> bool;
     /// Returns `true` if order is post-only.
     fn is_post_only(&self) -> bool;
+    /// Returns `true` if order is all-or-none, i.e. it only ever trades as a
+    /// whole, resting until it can, unlike a fill-or-kill one.
+    fn is_all_or_none(&self) -> bool;
+    /// Returns `true` if order only ever trades in the closing auction.
+    fn is_at_the_close(&self) -> bool;
+    /// Returns `true` if order is reduce-only.
+    fn is_reduce_only(&self) -> bool;
+    /// Returns `true` if order is hidden, i.e. never shown by the book.
+    fn is_hidden(&self) -> bool;
+    /// Return the quantity shown at a time, if this is an iceberg order.
+    fn display_quantity(&self) -> Option<Self::OrderQuantity>;
+    /// Show the given quantity at a time instead, if this is an iceberg
+    /// order.
+    fn set_display_quantity(&mut self, display_quantity: Self::OrderQuantity);
+    /// Caps what is left of the order at `quantity`, if it is priced by
+    /// quantity and has more left than that.
+    fn cap(&mut self, quantity: Self::OrderQuantity);
+    /// Return the least quantity an incoming order must have left to trade
+    /// with this one, once it rests, if any.
+    fn min_counterparty_qty(&self) -> Option<Self::OrderQuantity>;
+    /// Return the most the order may take of `available`, the liquidity it
+    /// could trade with right away, as it comes in, if capped.
+    fn max_participation(
+        &self,
+        available: Self::OrderQuantity,
+    ) -> Option<Self::OrderQuantity>;
+    /// Return the farthest from the best opposite price the order may trade
+    /// at, if capped.
+    fn max_slippage(&self) -> Option<Self::OrderPrice>;
     /// Cancel the order.
     fn cancel(&mut self);
+    /// Expire the order, i.e. close it because its time in force is over.
+    fn expire(&mut self);
 }
 
 pub trait Trade<Rhs>: Asset
@@ -68,10 +111,30 @@ where
         &mut self,
         other: &mut Rhs,
     ) -> Result<Self::Trade, Self::TradeError>;
+    /// Returns `true` if both orders belong to the same account, in which
+    /// case they must not trade with each other.
+    fn is_self_trade(&self, other: &Rhs) -> bool;
+    /// Execute a trade of no more than `quantity`, even if both orders
+    /// could exchange more than that.
+    fn trade_up_to(
+        &mut self,
+        other: &mut Rhs,
+        quantity: Self::OrderQuantity,
+    ) -> Result<Self::Trade, Self::TradeError>;
     /// Returns `Ok` if orders match.
     fn matches(&self, other: &Rhs) -> Result<(), Self::TradeError>;
 }
 
+/// A quantity that can be split in proportion to some weights.
+pub trait Prorate {
+    /// Returns the share of `self` that `part` is entitled to out of `whole`,
+    /// i.e. `self * part / whole`, rounded down to a multiple of `increment`.
+    fn prorate(self, part: Self, whole: Self, increment: Self) -> Self;
+    /// Returns the last decimal place `self` needs, however many trailing
+    /// zeros it is written with, e.g. `0.1` for `1.50`, or one for integers.
+    fn precision(self) -> Self;
+}
+
 /// The logical opposite of a value.
 pub trait Opposite<Opposite = Self> {
     /// Returns the opposite value.
@@ -1,6 +1,8 @@
 use std::ops::Deref;
 use std::ops::DerefMut;
 
+use num::Zero;
+
 use crate::Algo;
 use crate::Asset;
 use crate::Trade;
@@ -11,12 +13,18 @@ pub type Volume<Order> = (
     <Order as Asset>::OrderQuantity,
     <Order as Asset>::OrderQuantity,
 );
+pub type Depth<Order> = Vec<(
+    <Order as Asset>::OrderPrice,
+    <Order as Asset>::OrderQuantity,
+)>;
 
 /// An interface for dealing with exchange.
 ///
 /// This is the core trait for exchange implementation.
 pub trait Exchange {
-    type Algo<O>: Algo<O>;
+    type Algo<O>: Algo<O, Self>
+    where
+        O: Asset;
     /// The type of order that will be stored in the exchange.
     type Order: Asset;
     type OrderRef<'e>: Deref<Target = Self::Order>
@@ -32,6 +40,19 @@ pub trait Exchange {
         side: &<Self::Order as Asset>::OrderSide,
     ) -> impl Iterator<Item = Self::OrderRef<'_>> + '_;
 
+    /// Returns an iterator over every resting order of the given side, hidden
+    /// ones included, in the priority they are matched in.
+    ///
+    /// Unlike [`Exchange::iter`], which walks what the exchange shows, this
+    /// walks all the liquidity an incoming order may take. Exchanges that
+    /// hide no orders walk the very same ones.
+    fn iter_with_hidden(
+        &self,
+        side: &<Self::Order as Asset>::OrderSide,
+    ) -> impl Iterator<Item = Self::OrderRef<'_>> + '_ {
+        self.iter(side)
+    }
+
     /// Inserts an order into the exchange.
     ///
     /// # Safety
@@ -50,18 +71,46 @@ pub trait Exchange {
         order: &<Self::Order as Asset>::OrderId,
     ) -> Option<Self::Order>;
 
+    /// Returns a reference of the resting order with the given id, if any.
+    fn get(
+        &self,
+        order_id: &<Self::Order as Asset>::OrderId,
+    ) -> Option<Self::OrderRef<'_>>;
+
     /// Returns a reference of the most relevant order in the exchange.
     fn peek(
         &self,
         side: &<Self::Order as Asset>::OrderSide,
     ) -> Option<Self::OrderRef<'_>>;
 
+    /// Returns a reference of the `n`-th most relevant order of the given
+    /// side, counting from zero, in price-time priority.
+    ///
+    /// `peek_nth(side, 0)` is the same as `peek(side)`.
+    fn peek_nth(
+        &self,
+        side: &<Self::Order as Asset>::OrderSide,
+        n: usize,
+    ) -> Option<Self::OrderRef<'_>> {
+        self.iter(side).nth(n)
+    }
+
     /// Returns a mutable reference of the most relevant order in the exchange.
     fn peek_mut(
         &mut self,
         side: &<Self::Order as Asset>::OrderSide,
     ) -> Option<Self::OrderRefMut<'_>>;
 
+    /// Returns a mutable reference of the resting order with the given id, if
+    /// any, wherever it stands in its level.
+    ///
+    /// This lets matching fill an order in place, keeping its time priority,
+    /// rather than taking it off the exchange and inserting it back.
+    fn get_mut(
+        &mut self,
+        order_id: &<Self::Order as Asset>::OrderId,
+    ) -> Option<Self::OrderRefMut<'_>>;
+
     /// Removes the most relevant order in the exchange.
     fn pop(
         &mut self,
@@ -80,8 +129,8 @@ pub trait Exchange {
         &mut self,
         incoming_order: O,
     ) -> Result<
-        <Self::Algo<O> as Algo<O>>::Output,
-        <Self::Algo<O> as Algo<O>>::Error,
+        <Self::Algo<O> as Algo<O, Self>>::Output,
+        <Self::Algo<O> as Algo<O, Self>>::Error,
     >
     where
         Self: ExchangeExt + Sized,
@@ -93,9 +142,11 @@ pub trait Exchange {
             OrderQuantity = <<Self as Exchange>::Order as Asset>::OrderQuantity,
             OrderSide = <<Self as Exchange>::Order as Asset>::OrderSide,
             OrderStatus = <<Self as Exchange>::Order as Asset>::OrderStatus,
+            Trade = <<Self as Exchange>::Order as Asset>::Trade,
         >,
+        O: Clone,
     {
-        <Self::Algo<O> as Algo<O>>::matching(self, incoming_order)
+        <Self::Algo<O> as Algo<O, Self>>::matching(self, incoming_order)
     }
 }
 
@@ -104,6 +155,15 @@ pub trait ExchangeExt: Exchange {
     /// prices.
     fn spread(&self) -> Option<Spread<Self::Order>>;
 
+    /// Returns the highest bid price, if there is any bid.
+    fn best_bid(&self) -> Option<<Self::Order as Asset>::OrderPrice>;
+
+    /// Returns the lowest ask price, if there is any ask.
+    fn best_ask(&self) -> Option<<Self::Order as Asset>::OrderPrice>;
+
+    /// Returns the price of the last trade, if there was any.
+    fn last_price(&self) -> Option<<Self::Order as Asset>::OrderPrice>;
+
     /// Returns the number of shares being bid on or offered.
     fn len(&self) -> (usize, usize);
 
@@ -113,4 +173,91 @@ pub trait ExchangeExt: Exchange {
     }
 
     fn volume(&self) -> Volume<Self::Order>;
+
+    /// Returns up to `levels` aggregated price levels of the given side, from
+    /// the most to the least relevant one, along with the total remaining
+    /// quantity resting at each of them.
+    ///
+    /// Fewer levels are returned if the side does not have enough of them.
+    fn depth(
+        &self,
+        side: &<Self::Order as Asset>::OrderSide,
+        levels: usize,
+    ) -> Depth<Self::Order>;
+
+    /// Returns a CRC32 checksum of the top `depth` levels of both sides, so
+    /// that a client keeping its own copy of the book is able to detect when
+    /// it went out of sync.
+    ///
+    /// Ask levels come first, then bid levels, both from the most to the
+    /// least relevant one. Each level is formatted as `price:quantity`,
+    /// levels are separated by `,` and both sides are separated by `|`, e.g.
+    /// `100:20,101:40|99:50,98:20`.
+    ///
+    /// Amounts are normalized before being formatted, without any trailing
+    /// zeros, so that equal books have equal checksums no matter how their
+    /// amounts were reached, e.g. `100:10` rather than `100.00:10.0`.
+    fn checksum(&self, depth: usize) -> u32;
+
+    /// Returns every price level of the given side, from the most to the
+    /// least relevant one, along with how many orders rest at it and their
+    /// total remaining quantity.
+    fn level_summary(
+        &self,
+        side: &<Self::Order as Asset>::OrderSide,
+    ) -> impl Iterator<
+        Item = (
+            <Self::Order as Asset>::OrderPrice,
+            usize,
+            <Self::Order as Asset>::OrderQuantity,
+        ),
+    > + '_;
+
+    /// Returns an iterator over the orders of both sides, walking the book
+    /// outward from the mid price.
+    ///
+    /// Sides take turns, each one in price-time priority, starting with the
+    /// best ask, then the best bid, then the second ask and so on. Once a
+    /// side runs out, the rest of the other one follows.
+    fn iter_from_mid(
+        &self,
+    ) -> impl Iterator<
+        Item = (<Self::Order as Asset>::OrderSide, Self::OrderRef<'_>),
+    > + '_;
+
+    /// Returns the volume-weighted average price at which `quantity` would
+    /// be filled by sweeping the resting orders of the given side, without
+    /// actually touching them.
+    ///
+    /// Returns `None` if `quantity` is zero or if the side does not rest
+    /// enough quantity to fill it.
+    fn vwap(
+        &self,
+        side: &<Self::Order as Asset>::OrderSide,
+        quantity: <Self::Order as Asset>::OrderQuantity,
+    ) -> Option<<Self::Order as Asset>::OrderPrice> {
+        if quantity.is_zero() {
+            return None;
+        }
+
+        let mut left = quantity;
+        let mut notional = <Self::Order as Asset>::OrderNotional::zero();
+
+        for order in self.iter(side) {
+            let limit_price = order.limit_price()?;
+            let filled = order
+                .remaining()
+                .either(|funds| funds / limit_price, |quantity| quantity)
+                .min(left);
+
+            notional = notional + limit_price * filled;
+            left = left - filled;
+
+            if left.is_zero() {
+                return Some(notional / quantity);
+            }
+        }
+
+        None
+    }
 }
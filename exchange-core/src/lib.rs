@@ -4,8 +4,10 @@ pub use crate::algo::Algo;
 mod asset;
 pub use crate::asset::Asset;
 pub use crate::asset::Opposite;
+pub use crate::asset::Prorate;
 pub use crate::asset::Trade;
 
 mod exchange;
+pub use crate::exchange::Depth;
 pub use crate::exchange::Exchange;
 pub use crate::exchange::ExchangeExt;
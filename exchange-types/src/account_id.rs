@@ -0,0 +1,32 @@
+use uuid::Uuid;
+
+/// Identifies the account that owns an order.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountId(Uuid);
+
+impl AccountId {
+    #[inline]
+    pub fn new(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    #[inline]
+    pub const fn from_u128(value: u128) -> Self {
+        Self(Uuid::from_u128(value))
+    }
+
+    #[inline]
+    #[cfg(any(test, feature = "test"))]
+    pub fn random() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl From<Uuid> for AccountId {
+    #[inline]
+    fn from(uuid: Uuid) -> Self {
+        Self::new(uuid)
+    }
+}
@@ -3,9 +3,94 @@ use std::ops::Add;
 use std::ops::AddAssign;
 use std::ops::Div;
 use std::ops::Mul;
+use std::ops::Rem;
 use std::ops::Sub;
 use std::ops::SubAssign;
 
+use exchange_core::Prorate;
+use rust_decimal::Decimal;
+#[cfg(not(feature = "integer"))]
+use rust_decimal::RoundingStrategy;
+
+/// The number every amount is made of.
+///
+/// Amounts are decimals by default. With the `integer` feature, they are
+/// plain integers instead, e.g. prices in ticks and quantities in lots, which
+/// gives up fractional amounts for much cheaper arithmetic.
+#[cfg(not(feature = "integer"))]
+pub(crate) type Repr = Decimal;
+#[cfg(feature = "integer")]
+pub(crate) type Repr = i64;
+
+/// The most decimal places an amount keeps.
+#[cfg(not(feature = "integer"))]
+pub(crate) const SCALE: u32 = 28;
+#[cfg(feature = "integer")]
+pub(crate) const SCALE: u32 = 0;
+
+/// Integer amounts are written as strings, just like decimal ones, so that
+/// either mode reads what the other writes, as long as it has no fraction.
+#[cfg(all(feature = "serde", feature = "integer"))]
+mod integer_serde {
+    use std::fmt;
+
+    use rust_decimal::prelude::ToPrimitive;
+    use rust_decimal::Decimal;
+    use serde::de;
+    use serde::Deserializer;
+    use serde::Serializer;
+
+    use super::Repr;
+
+    pub(super) fn serialize<S>(
+        amount: &Repr,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(amount)
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<Repr, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(AmountVisitor)
+    }
+
+    struct AmountVisitor;
+
+    impl<'de> de::Visitor<'de> for AmountVisitor {
+        type Value = Repr;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a whole amount, as a string or an integer")
+        }
+
+        fn visit_i64<E: de::Error>(self, amount: i64) -> Result<Repr, E> {
+            Ok(amount)
+        }
+
+        fn visit_u64<E: de::Error>(self, amount: u64) -> Result<Repr, E> {
+            Repr::try_from(amount).map_err(|_| {
+                E::invalid_value(de::Unexpected::Unsigned(amount), &self)
+            })
+        }
+
+        fn visit_str<E: de::Error>(self, amount: &str) -> Result<Repr, E> {
+            amount
+                .parse::<Decimal>()
+                .ok()
+                .filter(|decimal| decimal.fract().is_zero())
+                .and_then(|decimal| decimal.to_i64())
+                .ok_or_else(|| {
+                    E::invalid_value(de::Unexpected::Str(amount), &self)
+                })
+        }
+    }
+}
+
 macro_rules! forward_binop {
     (impl $imp:ident for $res:ty, $method:ident) => {
         #[automatically_derived]
@@ -76,25 +161,68 @@ macro_rules! amount {
         #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
         #[repr(transparent)]
         #[cfg_attr(feature = "serde", serde(transparent))]
-        pub struct $t(::rust_decimal::Decimal);
+        pub struct $t(
+            #[cfg_attr(
+                all(feature = "serde", feature = "integer"),
+                serde(with = "integer_serde")
+            )]
+            pub(crate) Repr,
+        );
 
-        #[automatically_derived]
         impl $t {
             #[inline]
             pub fn is_zero(&self) -> bool {
                 <$t as ::num::Zero>::is_zero(self)
             }
+
+            /// Returns the same amount without trailing zeros, e.g. `10`
+            /// rather than `10.00`, so that equal amounts are formatted
+            /// alike.
+            #[inline]
+            pub fn normalize(self) -> Self {
+                Self::from_decimal(self.to_decimal().normalize())
+            }
+
+            /// Returns the amount as a decimal, whatever it is made of.
+            #[inline]
+            #[allow(dead_code)]
+            pub(crate) fn to_decimal(self) -> Decimal {
+                #[allow(clippy::useless_conversion)]
+                Decimal::from(self.0)
+            }
+
+            /// Makes an amount out of a decimal already rounded to
+            /// [`SCALE`] decimal places.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the decimal does not fit in an amount.
+            #[inline]
+            #[allow(dead_code)]
+            pub(crate) fn from_decimal(decimal: Decimal) -> Self {
+                debug_assert!(
+                    decimal.scale() <= SCALE || decimal.fract().is_zero()
+                );
+
+                #[cfg(not(feature = "integer"))]
+                return Self(decimal);
+
+                #[cfg(feature = "integer")]
+                return Self(
+                    ::rust_decimal::prelude::ToPrimitive::to_i64(&decimal)
+                        .expect("amount out of range"),
+                );
+            }
         }
 
         #[automatically_derived]
         impl<T> From<T> for $t
         where
-            ::rust_decimal::Decimal: From<T>,
+            Repr: From<T>,
         {
             #[inline]
-            fn from(decimal: T) -> $t {
-                let decimal = ::rust_decimal::Decimal::from(decimal);
-                Self(decimal)
+            fn from(amount: T) -> $t {
+                Self(Repr::from(amount))
             }
         }
 
@@ -118,17 +246,18 @@ macro_rules! amount {
         impl ::num::Zero for $t {
             #[inline]
             fn zero() -> Self {
-                Self(<::rust_decimal::Decimal as ::num::Zero>::zero())
+                Self(<Repr as ::num::Zero>::zero())
             }
 
             #[inline]
             fn is_zero(&self) -> bool {
-                <::rust_decimal::Decimal as ::num::Zero>::is_zero(&self.0)
+                <Repr as ::num::Zero>::is_zero(&self.0)
             }
         }
 
         forward_binop!(impl Add for $t, add);
         forward_binop!(impl Sub for $t, sub);
+        forward_binop!(impl Rem for $t, rem);
         forward_binop_assign!(impl AddAssign for $t, add_assign);
         forward_binop_assign!(impl SubAssign for $t, sub_assign);
     )*)
@@ -156,6 +285,72 @@ impl Mul<Price> for Quantity {
     }
 }
 
+#[cfg(not(feature = "integer"))]
+impl Prorate for Quantity {
+    #[inline]
+    fn prorate(self, part: Self, whole: Self, increment: Self) -> Self {
+        let share = (self.0 * part.0 / whole.0).round_dp_with_strategy(
+            increment.0.scale(),
+            RoundingStrategy::ToZero,
+        );
+
+        Quantity(share - share % increment.0)
+    }
+
+    #[inline]
+    fn precision(self) -> Self {
+        Quantity(Decimal::new(1, self.0.normalize().scale()))
+    }
+}
+
+#[cfg(feature = "integer")]
+impl Prorate for Quantity {
+    #[inline]
+    fn prorate(self, part: Self, whole: Self, increment: Self) -> Self {
+        // Integer division already rounds toward zero, and widening spares
+        // the product from overflowing.
+        let share =
+            i128::from(self.0) * i128::from(part.0) / i128::from(whole.0);
+        let share = share - share % i128::from(increment.0);
+
+        Quantity(share as i64)
+    }
+
+    #[inline]
+    fn precision(self) -> Self {
+        Quantity(1)
+    }
+}
+
+impl Notional {
+    /// Returns the largest quantity that can be bought at `price` without
+    /// exceeding this notional.
+    ///
+    /// Unlike a plain division, this never rounds up, which would make the
+    /// resulting quantity cost slightly more than what is available.
+    #[inline]
+    #[cfg(not(feature = "integer"))]
+    pub fn affordable(self, price: Price) -> Quantity {
+        let quantity = self.0 / price.0;
+
+        if quantity * price.0 > self.0 {
+            Quantity(quantity - Decimal::new(1, quantity.scale()))
+        } else {
+            Quantity(quantity)
+        }
+    }
+
+    /// Returns the largest quantity that can be bought at `price` without
+    /// exceeding this notional.
+    ///
+    /// Integer division already rounds toward zero, so it never rounds up.
+    #[inline]
+    #[cfg(feature = "integer")]
+    pub fn affordable(self, price: Price) -> Quantity {
+        Quantity(self.0 / price.0)
+    }
+}
+
 impl Div<Price> for Notional {
     type Output = Quantity;
 
@@ -175,3 +370,27 @@ impl Div<Quantity> for Notional {
         Price(notional.0 / quantity.0)
     }
 }
+
+#[cfg(all(test, feature = "serde", feature = "integer"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_as_string() {
+        let quantity = Quantity::from(10);
+
+        let json = serde_json::to_value(quantity).unwrap();
+        assert_eq!(json, serde_json::json!("10"));
+
+        for json in [json, serde_json::json!(10), serde_json::json!("10.00")] {
+            assert_eq!(
+                serde_json::from_value::<Quantity>(json).unwrap(),
+                quantity
+            );
+        }
+        assert!(
+            serde_json::from_value::<Quantity>(serde_json::json!("10.5"))
+                .is_err()
+        );
+    }
+}
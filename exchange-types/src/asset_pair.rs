@@ -0,0 +1,122 @@
+use std::fmt;
+use std::str::FromStr;
+
+use compact_str::CompactString;
+
+use crate::error::AssetPairError;
+
+/// A tradable pair of assets, such as `BTC/USDC`.
+///
+/// The `base` is the asset being bought or sold, and the `quote` is the asset
+/// it is priced and settled in.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "CompactString", into = "CompactString")
+)]
+pub struct AssetPair {
+    base: CompactString,
+    quote: CompactString,
+}
+
+impl AssetPair {
+    #[inline]
+    pub fn new(base: &str, quote: &str) -> Self {
+        Self {
+            base: CompactString::new(base),
+            quote: CompactString::new(quote),
+        }
+    }
+
+    /// Returns the asset being bought or sold.
+    #[inline]
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Returns the asset the base is priced in.
+    #[inline]
+    pub fn quote(&self) -> &str {
+        &self.quote
+    }
+}
+
+impl FromStr for AssetPair {
+    type Err = AssetPairError;
+
+    fn from_str(symbol: &str) -> Result<Self, Self::Err> {
+        let is_asset = |asset: &str| {
+            !asset.is_empty() && asset.chars().all(char::is_alphanumeric)
+        };
+
+        match symbol.split_once('/') {
+            Some((base, quote)) if is_asset(base) && is_asset(quote) => {
+                Ok(Self::new(base, quote))
+            }
+            _ => Err(AssetPairError::Malformed(CompactString::new(symbol))),
+        }
+    }
+}
+
+impl TryFrom<CompactString> for AssetPair {
+    type Error = AssetPairError;
+
+    #[inline]
+    fn try_from(symbol: CompactString) -> Result<Self, Self::Error> {
+        symbol.parse()
+    }
+}
+
+impl From<AssetPair> for CompactString {
+    #[inline]
+    fn from(asset_pair: AssetPair) -> Self {
+        compact_str::format_compact!("{asset_pair}")
+    }
+}
+
+impl fmt::Display for AssetPair {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.base, self.quote)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let asset_pair: AssetPair = "BTC/USDC".parse().unwrap();
+
+        assert_eq!(asset_pair.base(), "BTC");
+        assert_eq!(asset_pair.quote(), "USDC");
+        assert_eq!(asset_pair.to_string(), "BTC/USDC");
+    }
+
+    #[test]
+    fn reject_malformed() {
+        for symbol in ["BTCUSDC", "/USDC", "BTC/", "BTC/USDC/ETH", "BTC /USDC"]
+        {
+            assert!(
+                symbol.parse::<AssetPair>().is_err(),
+                "{symbol} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let asset_pair = AssetPair::new("ETH", "USDC");
+
+        let json = serde_json::to_string(&asset_pair).unwrap();
+        assert_eq!(json, r#""ETH/USDC""#);
+
+        let parsed: AssetPair = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, asset_pair);
+
+        assert!(serde_json::from_str::<AssetPair>(r#""ETHUSDC""#).is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use crate::OrderId;
+use crate::OrderSide;
+use crate::Price;
+use crate::Quantity;
+
+/// A change to a single order shown by the book, as needed to keep a full,
+/// order-level (L3) copy of it without ever taking a snapshot again.
+///
+/// Only what the book shows is reported: hidden orders never come up, and an
+/// iceberg order shows its current slice alone. Once a slice is fully filled,
+/// the order is removed and added again with the next one, since it loses its
+/// time priority by then.
+///
+/// It is generic so that any matching algorithm may report it, whatever the
+/// orders it matches, though it defaults to the types of this crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum BookEvent<I = OrderId, S = OrderSide, P = Price, Q = Quantity> {
+    /// An order was added at the back of its price level.
+    OrderAdded {
+        order_id: I,
+        side: S,
+        limit_price: P,
+        quantity: Q,
+    },
+    /// An order was taken off the book, either filled or cancelled.
+    OrderRemoved { order_id: I },
+    /// An order was partially filled, showing `delta` less than it did,
+    /// without losing its time priority.
+    OrderReduced { order_id: I, delta: Q },
+}
+
+impl<I: Copy, S, P, Q> BookEvent<I, S, P, Q> {
+    /// Returns the id of the order that changed.
+    #[inline]
+    pub fn order_id(&self) -> I {
+        match self {
+            Self::OrderAdded { order_id, .. }
+            | Self::OrderRemoved { order_id }
+            | Self::OrderReduced { order_id, .. } => *order_id,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+/// Why an order was cancelled, as echoed back to whoever placed it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
+pub enum CancelReason {
+    /// Cancelled at the request of its own account.
+    #[default]
+    UserRequested,
+    /// Cancelled by an operator of the exchange.
+    Admin,
+    /// Cancelled by risk controls, e.g. once an account runs out of margin.
+    Risk,
+}
@@ -0,0 +1,83 @@
+use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+
+use rust_decimal::Decimal;
+use rust_decimal::RoundingStrategy;
+
+use crate::amount;
+use crate::Price;
+
+/// How far, in percent of a reference price, the market may move within a
+/// rolling window of trades before matching is halted.
+///
+/// The reference is the price of the oldest trade in the window, so a move
+/// is only ever measured against what traded at most `window` trades ago.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CircuitBreaker {
+    percent: Decimal,
+    window: NonZeroUsize,
+}
+
+impl CircuitBreaker {
+    const HUNDRED: Decimal = Decimal::ONE_HUNDRED;
+
+    /// # Panics
+    ///
+    /// Panics if `percent` is negative.
+    #[inline]
+    pub fn new(percent: impl Into<Decimal>, window: NonZeroUsize) -> Self {
+        let percent = percent.into();
+        assert!(
+            !percent.is_sign_negative(),
+            "circuit breaker must not be negative"
+        );
+
+        Self { percent, window }
+    }
+
+    /// Returns how many trades the reference price is looked back for.
+    #[inline]
+    pub fn window(&self) -> NonZeroUsize {
+        self.window
+    }
+
+    /// Returns the range of prices trades may take place at around the given
+    /// reference price, bounds included, without tripping the breaker.
+    #[inline]
+    pub fn band(&self, reference: Price) -> RangeInclusive<Price> {
+        let reference = reference.to_decimal();
+        let width = reference * self.percent / Self::HUNDRED;
+
+        // Bounds that fall between two prices are rounded outward, so that
+        // only a move past the threshold ever halts matching.
+        let low = (reference - width).round_dp_with_strategy(
+            amount::SCALE,
+            RoundingStrategy::ToNegativeInfinity,
+        );
+        let high = (reference + width).round_dp_with_strategy(
+            amount::SCALE,
+            RoundingStrategy::ToPositiveInfinity,
+        );
+
+        Price::from_decimal(low)..=Price::from_decimal(high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn band_around_reference() {
+        let circuit_breaker =
+            CircuitBreaker::new(dec!(10), NonZeroUsize::new(5).unwrap());
+
+        assert_eq!(
+            circuit_breaker.band(100.into()),
+            Price::from(90)..=Price::from(110)
+        );
+    }
+}
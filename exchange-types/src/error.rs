@@ -1,9 +1,20 @@
+use compact_str::CompactString;
 use thiserror::Error;
 
+#[derive(Debug, Error)]
+pub enum AssetPairError {
+    #[error("malformed asset pair (expected=BASE/QUOTE, found={})", .0)]
+    Malformed(CompactString),
+}
+
 #[derive(Debug, Error)]
 pub enum ConversionError {
     #[error("unable to convert a non-limit order into limit")]
     Incompatible,
+    #[error("display quantity must be positive")]
+    DisplayQuantity,
+    #[error("hidden orders cannot be iceberg orders")]
+    HiddenIceberg,
 }
 
 #[derive(Debug, Error)]
@@ -14,6 +25,14 @@ pub enum OrderError {
     NoFill,
     #[error("filling quantity exceeds available quantity")]
     Overfill,
+    #[error("quantity must exceed filled quantity")]
+    BelowFilled,
+    #[error("quantity must be positive")]
+    NonPositiveQuantity,
+    #[error("limit price must be positive")]
+    NonPositivePrice,
+    #[error("maximum participation must be between 1 and 100 percent")]
+    MaxParticipation,
 }
 
 #[derive(Debug, Error)]
@@ -22,6 +41,8 @@ pub enum TradeError {
     Price(#[from] PriceError),
     #[error("incompatible side")]
     SameSide,
+    #[error("nothing left to exchange")]
+    Exhausted,
     #[error(transparent)]
     Status(#[from] StatusError),
 }
@@ -0,0 +1,112 @@
+use rust_decimal::Decimal;
+use rust_decimal::RoundingStrategy;
+
+use crate::amount;
+use crate::Notional;
+use crate::Trade;
+
+/// Maker and taker fee rates, in basis points of the traded notional.
+///
+/// Negative rates are rebates. Fees are rounded half-up to a fixed number of
+/// decimal places, so they add up exactly when reconciled from the trade
+/// tape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeSchedule {
+    maker_bps: Decimal,
+    taker_bps: Decimal,
+    decimal_places: u32,
+}
+
+impl FeeSchedule {
+    /// Basis points in a whole, as a basis point is a hundredth of a percent.
+    const BPS: Decimal = Decimal::from_parts(10_000, 0, 0, false, 0);
+
+    #[inline]
+    pub fn new(
+        maker_bps: impl Into<Decimal>,
+        taker_bps: impl Into<Decimal>,
+        decimal_places: u32,
+    ) -> Self {
+        Self {
+            maker_bps: maker_bps.into(),
+            taker_bps: taker_bps.into(),
+            decimal_places,
+        }
+    }
+
+    /// Returns the fee charged to the maker of a trade of `notional`.
+    #[inline]
+    pub fn maker_fee(&self, notional: Notional) -> Notional {
+        self.fee(self.maker_bps, notional)
+    }
+
+    /// Returns the fee charged to the taker of a trade of `notional`.
+    #[inline]
+    pub fn taker_fee(&self, notional: Notional) -> Notional {
+        self.fee(self.taker_bps, notional)
+    }
+
+    /// Charges both sides of `trade` according to this schedule.
+    #[inline]
+    pub fn apply(&self, trade: &mut Trade) {
+        trade.maker_fee = self.maker_fee(trade.notional);
+        trade.taker_fee = self.taker_fee(trade.notional);
+    }
+
+    #[inline]
+    #[allow(clippy::unnecessary_min_or_max)]
+    fn fee(&self, bps: Decimal, notional: Notional) -> Notional {
+        // Integer amounts keep no decimal places at all, whatever the schedule.
+        Notional::from_decimal(
+            (notional.to_decimal() * bps / Self::BPS).round_dp_with_strategy(
+                self.decimal_places.min(amount::SCALE),
+                RoundingStrategy::MidpointAwayFromZero,
+            ),
+        )
+    }
+}
+
+impl Default for FeeSchedule {
+    /// No fees at all.
+    #[inline]
+    fn default() -> Self {
+        Self::new(0, 0, 8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(feature = "integer"))]
+    fn round_half_up() {
+        use rust_decimal_macros::dec;
+
+        let fee_schedule = FeeSchedule::new(dec!(-0.5), 25, 2);
+
+        assert_eq!(
+            fee_schedule.taker_fee(dec!(1002).into()),
+            dec!(2.51).into()
+        );
+        assert_eq!(fee_schedule.taker_fee(dec!(1000).into()), dec!(2.5).into());
+        assert_eq!(fee_schedule.taker_fee(dec!(3).into()), dec!(0.01).into());
+        assert_eq!(
+            fee_schedule.maker_fee(dec!(1000).into()),
+            dec!(-0.05).into()
+        );
+        assert_eq!(
+            fee_schedule.maker_fee(dec!(100).into()),
+            dec!(-0.01).into()
+        );
+    }
+
+    #[test]
+    fn no_fees() {
+        let fee_schedule = FeeSchedule::default();
+
+        assert!(fee_schedule.maker_fee(123.into()).is_zero());
+        assert!(fee_schedule.taker_fee(123.into()).is_zero());
+    }
+}
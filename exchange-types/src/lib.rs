@@ -1,8 +1,26 @@
+mod account_id;
+pub use account_id::AccountId;
+
 mod amount;
 pub use amount::*;
 
+mod asset_pair;
+pub use asset_pair::AssetPair;
+
+mod book_event;
+pub use book_event::BookEvent;
+
+mod cancel_reason;
+pub use cancel_reason::CancelReason;
+
+mod circuit_breaker;
+pub use circuit_breaker::CircuitBreaker;
+
 pub mod error;
 
+mod fee_schedule;
+pub use fee_schedule::FeeSchedule;
+
 mod order;
 pub use order::LimitOrder;
 pub use order::Order;
@@ -11,7 +29,9 @@ mod order_id;
 pub use order_id::OrderId;
 
 mod order_request;
+pub use order_request::OcoLeg;
 pub use order_request::OrderRequest;
+pub use order_request::OrderRequestError;
 
 mod order_side;
 pub use order_side::OrderSide;
@@ -23,5 +43,8 @@ mod order_type;
 pub use order_type::OrderType;
 pub use order_type::TimeInForce;
 
+mod price_collar;
+pub use price_collar::PriceCollar;
+
 mod trade;
 pub use trade::Trade;
@@ -5,14 +5,17 @@ use std::ops::AddAssign;
 
 use either::Either;
 use exchange_core::Asset;
-use rust_decimal::Decimal;
+use exchange_core::Prorate;
+use num::Zero;
 
+use crate::error::ConversionError;
 use crate::error::OrderError;
 use crate::error::TradeError;
 use crate::order_type::ByBase;
 use crate::order_type::ByFunds;
 use crate::order_type::PricedBy;
 use crate::order_type::TimeInForce;
+use crate::AccountId;
 use crate::Notional;
 use crate::OrderId;
 use crate::OrderSide;
@@ -33,6 +36,37 @@ pub struct Order {
     #[cfg_attr(feature = "serde", serde(flatten))]
     type_: OrderType,
     status: OrderStatus,
+    /// The account that owns the order, if any. Orders of the same account
+    /// are never traded against each other.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    account_id: Option<AccountId>,
+    /// The reduce-only flag indicates that the order should only reduce an
+    /// existing position, so it may only take liquidity and never rests on
+    /// the book.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    reduce_only: bool,
+    /// The least quantity an incoming order must have left to trade with
+    /// this one once it rests, if any, so that it is not picked off by tiny
+    /// orders. Smaller ones pass it over, trading with the next one instead.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    min_counterparty_qty: Option<Quantity>,
+    /// The most of the liquidity it could trade with right away the order
+    /// may take as it comes in, in percent, if any, so that a large order
+    /// trades gradually. Whatever it may not take rests, if it can.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    max_participation: Option<u8>,
 }
 
 impl Order {
@@ -43,9 +77,130 @@ impl Order {
             side,
             type_,
             status: OrderStatus::Open,
+            account_id: None,
+            reduce_only: false,
+            min_counterparty_qty: None,
+            max_participation: None,
         }
     }
 
+    /// Sets the account that owns the order.
+    #[inline]
+    pub fn with_account_id(mut self, account_id: AccountId) -> Self {
+        self.account_id = Some(account_id);
+        self
+    }
+
+    /// Sets whether the order is reduce-only.
+    #[inline]
+    pub fn with_reduce_only(mut self, reduce_only: bool) -> Self {
+        self.reduce_only = reduce_only;
+        self
+    }
+
+    /// Sets the least quantity an incoming order must have left to trade
+    /// with this one once it rests.
+    #[inline]
+    pub fn with_min_counterparty_qty(
+        mut self,
+        min_counterparty_qty: Quantity,
+    ) -> Self {
+        self.min_counterparty_qty = Some(min_counterparty_qty);
+        self
+    }
+
+    /// Sets the most of the liquidity it could trade with right away the
+    /// order may take as it comes in, in percent.
+    #[inline]
+    pub fn with_max_participation(mut self, max_participation: u8) -> Self {
+        self.max_participation = Some(max_participation);
+        self
+    }
+
+    /// Returns the account that owns the order, if any.
+    #[inline]
+    pub fn account_id(&self) -> Option<AccountId> {
+        self.account_id
+    }
+
+    /// Returns the total value of the order, i.e. its limit price times its
+    /// quantity, or its funds.
+    ///
+    /// Market orders priced by quantity have no price to be valued at until
+    /// they are matched, so they have no notional.
+    #[inline]
+    pub fn notional(&self) -> Option<Notional> {
+        match self.type_ {
+            OrderType::Limit {
+                limit_price,
+                priced_by: ByBase { quantity, .. },
+                ..
+            } => Some(limit_price * quantity),
+            OrderType::Market {
+                priced_by: PricedBy::Funds(ByFunds { funds, .. }),
+                ..
+            } => Some(funds),
+            OrderType::Market {
+                priced_by: PricedBy::Base(_),
+                ..
+            } => None,
+        }
+    }
+
+    /// Returns an error if the order has no positive quantity, or funds, to
+    /// begin with, since such an order could never be filled, or if it could
+    /// never rest on the book, being an iceberg one that shows nothing or
+    /// that is hidden as well.
+    #[inline]
+    pub fn validate(&self) -> Result<(), OrderError> {
+        let positive = match self.type_ {
+            OrderType::Limit {
+                priced_by: ByBase { quantity, .. },
+                ..
+            }
+            | OrderType::Market {
+                priced_by: PricedBy::Base(ByBase { quantity, .. }),
+                ..
+            } => quantity > Quantity::zero(),
+            OrderType::Market {
+                priced_by: PricedBy::Funds(ByFunds { funds, .. }),
+                ..
+            } => funds > Notional::zero(),
+        };
+
+        if !positive {
+            return Err(OrderError::NonPositiveQuantity);
+        }
+
+        if let OrderType::Limit { limit_price, .. } = self.type_ {
+            if limit_price <= Price::zero() {
+                return Err(OrderError::NonPositivePrice);
+            }
+        }
+
+        if let OrderType::Limit {
+            display_quantity: Some(display_quantity),
+            hidden,
+            ..
+        } = self.type_
+        {
+            if display_quantity <= Quantity::zero() {
+                Err(ConversionError::DisplayQuantity)?;
+            }
+            if hidden {
+                Err(ConversionError::HiddenIceberg)?;
+            }
+        }
+
+        if let Some(max_participation) = self.max_participation {
+            if !(1..=100).contains(&max_participation) {
+                return Err(OrderError::MaxParticipation);
+            }
+        }
+
+        Ok(())
+    }
+
     #[inline]
     #[cfg(any(test, feature = "test"))]
     pub fn builder() -> builder::Builder<(), ()> {
@@ -68,10 +223,16 @@ impl Order {
                 time_in_force: Default::default(),
                 priced_by: ByBase {
                     quantity: quantity.into(),
-                    filled: Decimal::ZERO.into(),
+                    filled: Quantity::default(),
                 },
+                display_quantity: None,
+                hidden: false,
             },
             status: OrderStatus::Open,
+            account_id: None,
+            reduce_only: false,
+            min_counterparty_qty: None,
+            max_participation: None,
         }
     }
 
@@ -114,6 +275,8 @@ impl Order {
             } => filled.add_assign(quantity * price),
         };
 
+        // Leftover funds that cannot buy anything more at the next price
+        // still keep the order partial, see `ByFunds`.
         self.status = if match self.remaining() {
             Either::Left(notional) => notional.is_zero(),
             Either::Right(quantity) => quantity.is_zero(),
@@ -224,6 +387,13 @@ impl Asset for Order {
         }
     }
 
+    #[inline]
+    fn total_remaining(
+        &self,
+    ) -> Either<Self::OrderNotional, Self::OrderQuantity> {
+        self.remaining()
+    }
+
     #[inline]
     fn status(&self) -> OrderStatus {
         self.status
@@ -253,6 +423,7 @@ impl Asset for Order {
             OrderStatus::Cancelled
                 | OrderStatus::Closed
                 | OrderStatus::Completed
+                | OrderStatus::Expired
         )
     }
 
@@ -269,7 +440,104 @@ impl Asset for Order {
 
     #[inline]
     fn is_post_only(&self) -> bool {
-        matches!(self.type_, OrderType::Limit { time_in_force: TimeInForce::GoodTillCancel { post_only }, .. } if post_only)
+        matches!(self.type_, OrderType::Limit { time_in_force: TimeInForce::GoodTillCancel { post_only, .. }, .. } if post_only)
+    }
+
+    #[inline]
+    fn is_all_or_none(&self) -> bool {
+        matches!(self.type_, OrderType::Limit { time_in_force: TimeInForce::GoodTillCancel { all_or_none, .. }, .. } if all_or_none)
+    }
+
+    #[inline]
+    fn is_at_the_close(&self) -> bool {
+        matches!(
+            self.type_,
+            OrderType::Limit {
+                time_in_force: TimeInForce::AtTheClose,
+                ..
+            }
+        )
+    }
+
+    #[inline]
+    fn is_reduce_only(&self) -> bool {
+        self.reduce_only
+    }
+
+    #[inline]
+    fn is_hidden(&self) -> bool {
+        matches!(self.type_, OrderType::Limit { hidden, .. } if hidden)
+    }
+
+    #[inline]
+    fn display_quantity(&self) -> Option<Self::OrderQuantity> {
+        match self.type_ {
+            OrderType::Limit {
+                display_quantity, ..
+            } => display_quantity,
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn set_display_quantity(&mut self, display_quantity: Self::OrderQuantity) {
+        if let OrderType::Limit {
+            display_quantity: Some(ref mut shown),
+            ..
+        } = self.type_
+        {
+            *shown = display_quantity;
+        }
+    }
+
+    #[inline]
+    fn cap(&mut self, quantity: Self::OrderQuantity) {
+        if let OrderType::Limit {
+            priced_by:
+                ByBase {
+                    quantity: ref mut total,
+                    filled,
+                },
+            ..
+        }
+        | OrderType::Market {
+            priced_by:
+                PricedBy::Base(ByBase {
+                    quantity: ref mut total,
+                    filled,
+                }),
+            ..
+        } = self.type_
+        {
+            *total = (*total).min(filled + quantity);
+        }
+    }
+
+    #[inline]
+    fn min_counterparty_qty(&self) -> Option<Self::OrderQuantity> {
+        self.min_counterparty_qty
+    }
+
+    #[inline]
+    fn max_participation(
+        &self,
+        available: Self::OrderQuantity,
+    ) -> Option<Self::OrderQuantity> {
+        self.max_participation.map(|max_participation| {
+            available.prorate(
+                Quantity::from(u32::from(max_participation)),
+                Quantity::from(100),
+                available.precision(),
+            )
+        })
+    }
+
+    #[inline]
+    fn max_slippage(&self) -> Option<Self::OrderPrice> {
+        match self.type_ {
+            OrderType::Market { max_slippage, .. } => max_slippage,
+            _ => None,
+        }
     }
 
     #[inline]
@@ -280,6 +548,13 @@ impl Asset for Order {
             _ => (),
         }
     }
+
+    #[inline]
+    fn expire(&mut self) {
+        if self.is_open() {
+            self.status = OrderStatus::Expired;
+        }
+    }
 }
 
 #[cfg(any(test, feature = "test"))]
@@ -297,6 +572,9 @@ mod builder {
         side: S,
         type_: MaybeUninit<OrderType>,
         type_variant: PhantomData<T>,
+        account_id: Option<AccountId>,
+        id: Option<OrderId>,
+        reduce_only: bool,
     }
 
     pub struct Limit<T>(Uninhabited, PhantomData<T>);
@@ -308,10 +586,16 @@ mod builder {
 
     pub enum GoodTillCancel {}
     pub enum ImmediateOrCancel {}
+    pub enum GoodTillDate {}
+    pub enum GoodForDuration {}
+    pub enum AtTheClose {}
 
     pub trait LimitTypeVariant {}
     impl LimitTypeVariant for GoodTillCancel {}
     impl LimitTypeVariant for ImmediateOrCancel {}
+    impl LimitTypeVariant for GoodTillDate {}
+    impl LimitTypeVariant for GoodForDuration {}
+    impl LimitTypeVariant for AtTheClose {}
 
     impl Builder<(), ()> {
         #[inline]
@@ -320,6 +604,9 @@ mod builder {
                 side: (),
                 type_: MaybeUninit::uninit(),
                 type_variant: PhantomData,
+                account_id: None,
+                id: None,
+                reduce_only: false,
             }
         }
     }
@@ -331,11 +618,53 @@ mod builder {
                 side,
                 type_: self.type_,
                 type_variant: self.type_variant,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
             }
         }
     }
 
     impl<T> Builder<OrderSide, T> {
+        #[inline]
+        pub const fn account_id(&self, account_id: AccountId) -> Self {
+            Builder {
+                side: self.side,
+                type_: self.type_,
+                type_variant: PhantomData,
+                account_id: Some(account_id),
+                id: self.id,
+                reduce_only: self.reduce_only,
+            }
+        }
+
+        /// Makes the order reduce-only, so that whatever is left of it after
+        /// matching is cancelled instead of resting on the book.
+        #[inline]
+        pub const fn reduce_only(&self) -> Self {
+            Builder {
+                side: self.side,
+                type_: self.type_,
+                type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: true,
+            }
+        }
+
+        /// Pins the id of the order, which is random otherwise.
+        #[inline]
+        pub const fn id(&self, id: OrderId) -> Self {
+            Builder {
+                side: self.side,
+                type_: self.type_,
+                type_variant: PhantomData,
+                account_id: self.account_id,
+                id: Some(id),
+                reduce_only: self.reduce_only,
+            }
+        }
+
         #[inline]
         pub fn limit(
             &self,
@@ -344,17 +673,25 @@ mod builder {
         ) -> Builder<OrderSide, Limit<GoodTillCancel>> {
             let type_ = OrderType::Limit {
                 limit_price: limit_price.into(),
-                time_in_force: TimeInForce::GoodTillCancel { post_only: false },
+                time_in_force: TimeInForce::GoodTillCancel {
+                    post_only: false,
+                    all_or_none: false,
+                },
                 priced_by: ByBase {
                     quantity: quantity.into(),
-                    filled: Decimal::ZERO.into(),
+                    filled: Quantity::default(),
                 },
+                display_quantity: None,
+                hidden: false,
             };
 
             Builder {
                 side: self.side,
                 type_: MaybeUninit::new(type_),
                 type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
             }
         }
 
@@ -367,14 +704,42 @@ mod builder {
                 all_or_none: false,
                 priced_by: PricedBy::Base(ByBase {
                     quantity: quantity.into(),
-                    filled: Decimal::ZERO.into(),
+                    filled: Quantity::default(),
+                }),
+                max_slippage: None,
+            };
+
+            Builder {
+                side: self.side,
+                type_: MaybeUninit::new(type_),
+                type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
+            }
+        }
+
+        #[inline]
+        pub fn market_funds(
+            &self,
+            funds: impl Into<Notional>,
+        ) -> Builder<OrderSide, Market> {
+            let type_ = OrderType::Market {
+                all_or_none: false,
+                priced_by: PricedBy::Funds(ByFunds {
+                    funds: funds.into(),
+                    filled: Notional::default(),
                 }),
+                max_slippage: None,
             };
 
             Builder {
                 side: self.side,
                 type_: MaybeUninit::new(type_),
                 type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
             }
         }
     }
@@ -386,6 +751,8 @@ mod builder {
                 limit_price,
                 time_in_force: _,
                 priced_by,
+                display_quantity,
+                hidden,
             } = self.type_()
             else {
                 // SAFETY: since this is a `Builder<_, Limit<_>>`, this will
@@ -395,14 +762,22 @@ mod builder {
 
             let type_ = OrderType::Limit {
                 limit_price,
-                time_in_force: TimeInForce::GoodTillCancel { post_only: false },
+                time_in_force: TimeInForce::GoodTillCancel {
+                    post_only: false,
+                    all_or_none: false,
+                },
                 priced_by,
+                display_quantity,
+                hidden,
             };
 
             Builder {
                 side: self.side,
                 type_: MaybeUninit::new(type_),
                 type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
             }
         }
 
@@ -414,6 +789,8 @@ mod builder {
                 limit_price,
                 time_in_force: _,
                 priced_by,
+                display_quantity,
+                hidden,
             } = self.type_()
             else {
                 // SAFETY: since this is a `Builder<_, Limit<_>>`, this will
@@ -427,12 +804,191 @@ mod builder {
                     all_or_none: false,
                 },
                 priced_by,
+                display_quantity,
+                hidden,
+            };
+
+            Builder {
+                side: self.side,
+                type_: MaybeUninit::new(type_),
+                type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
+            }
+        }
+
+        #[inline]
+        pub const fn gtd(
+            &self,
+            expire_at: u64,
+        ) -> Builder<OrderSide, Limit<GoodTillDate>> {
+            let OrderType::Limit {
+                limit_price,
+                time_in_force: _,
+                priced_by,
+                display_quantity,
+                hidden,
+            } = self.type_()
+            else {
+                // SAFETY: since this is a `Builder<_, Limit<_>>`, this will
+                // always be `Limit`.
+                unsafe { unreachable_unchecked() }
+            };
+
+            let type_ = OrderType::Limit {
+                limit_price,
+                time_in_force: TimeInForce::GoodTillDate { expire_at },
+                priced_by,
+                display_quantity,
+                hidden,
+            };
+
+            Builder {
+                side: self.side,
+                type_: MaybeUninit::new(type_),
+                type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
+            }
+        }
+
+        #[inline]
+        pub const fn gfd(
+            &self,
+            millis: u64,
+        ) -> Builder<OrderSide, Limit<GoodForDuration>> {
+            let OrderType::Limit {
+                limit_price,
+                time_in_force: _,
+                priced_by,
+                display_quantity,
+                hidden,
+            } = self.type_()
+            else {
+                // SAFETY: since this is a `Builder<_, Limit<_>>`, this will
+                // always be `Limit`.
+                unsafe { unreachable_unchecked() }
+            };
+
+            let type_ = OrderType::Limit {
+                limit_price,
+                time_in_force: TimeInForce::GoodForDuration { millis },
+                priced_by,
+                display_quantity,
+                hidden,
+            };
+
+            Builder {
+                side: self.side,
+                type_: MaybeUninit::new(type_),
+                type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
+            }
+        }
+
+        #[inline]
+        pub const fn atc(&self) -> Builder<OrderSide, Limit<AtTheClose>> {
+            let OrderType::Limit {
+                limit_price,
+                time_in_force: _,
+                priced_by,
+                display_quantity,
+                hidden,
+            } = self.type_()
+            else {
+                // SAFETY: since this is a `Builder<_, Limit<_>>`, this will
+                // always be `Limit`.
+                unsafe { unreachable_unchecked() }
+            };
+
+            let type_ = OrderType::Limit {
+                limit_price,
+                time_in_force: TimeInForce::AtTheClose,
+                priced_by,
+                display_quantity,
+                hidden,
+            };
+
+            Builder {
+                side: self.side,
+                type_: MaybeUninit::new(type_),
+                type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
+            }
+        }
+
+        #[inline]
+        pub fn iceberg(
+            &self,
+            display_quantity: impl Into<Quantity>,
+        ) -> Builder<OrderSide, Limit<T>> {
+            let OrderType::Limit {
+                limit_price,
+                time_in_force,
+                priced_by,
+                display_quantity: _,
+                hidden,
+            } = self.type_()
+            else {
+                // SAFETY: since this is a `Builder<_, Limit<_>>`, this will
+                // always be `Limit`.
+                unsafe { unreachable_unchecked() }
+            };
+
+            let type_ = OrderType::Limit {
+                limit_price,
+                time_in_force,
+                priced_by,
+                display_quantity: Some(display_quantity.into()),
+                hidden,
             };
 
             Builder {
                 side: self.side,
                 type_: MaybeUninit::new(type_),
                 type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
+            }
+        }
+
+        #[inline]
+        pub const fn hidden(&self) -> Builder<OrderSide, Limit<T>> {
+            let OrderType::Limit {
+                limit_price,
+                time_in_force,
+                priced_by,
+                display_quantity,
+                hidden: _,
+            } = self.type_()
+            else {
+                // SAFETY: since this is a `Builder<_, Limit<_>>`, this will
+                // always be `Limit`.
+                unsafe { unreachable_unchecked() }
+            };
+
+            let type_ = OrderType::Limit {
+                limit_price,
+                time_in_force,
+                priced_by,
+                display_quantity,
+                hidden: true,
+            };
+
+            Builder {
+                side: self.side,
+                type_: MaybeUninit::new(type_),
+                type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
             }
         }
     }
@@ -444,8 +1000,54 @@ mod builder {
         ) -> Builder<OrderSide, Limit<GoodTillCancel>> {
             let OrderType::Limit {
                 limit_price,
-                time_in_force: _,
+                time_in_force,
+                priced_by,
+                display_quantity,
+                hidden,
+            } = self.type_()
+            else {
+                // SAFETY: since this is a `Builder<_, Limit<_>>`, this will
+                // always be `Limit`.
+                unsafe { unreachable_unchecked() }
+            };
+
+            let type_ = OrderType::Limit {
+                limit_price,
+                time_in_force: TimeInForce::GoodTillCancel {
+                    post_only: true,
+                    all_or_none: matches!(
+                        time_in_force,
+                        TimeInForce::GoodTillCancel {
+                            all_or_none: true,
+                            ..
+                        }
+                    ),
+                },
+                priced_by,
+                display_quantity,
+                hidden,
+            };
+
+            Builder {
+                side: self.side,
+                type_: MaybeUninit::new(type_),
+                type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
+            }
+        }
+
+        #[inline]
+        pub const fn all_or_none(
+            &self,
+        ) -> Builder<OrderSide, Limit<GoodTillCancel>> {
+            let OrderType::Limit {
+                limit_price,
+                time_in_force,
                 priced_by,
+                display_quantity,
+                hidden,
             } = self.type_()
             else {
                 // SAFETY: since this is a `Builder<_, Limit<_>>`, this will
@@ -455,14 +1057,28 @@ mod builder {
 
             let type_ = OrderType::Limit {
                 limit_price,
-                time_in_force: TimeInForce::GoodTillCancel { post_only: true },
+                time_in_force: TimeInForce::GoodTillCancel {
+                    post_only: matches!(
+                        time_in_force,
+                        TimeInForce::GoodTillCancel {
+                            post_only: true,
+                            ..
+                        }
+                    ),
+                    all_or_none: true,
+                },
                 priced_by,
+                display_quantity,
+                hidden,
             };
 
             Builder {
                 side: self.side,
                 type_: MaybeUninit::new(type_),
                 type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
             }
         }
     }
@@ -476,6 +1092,8 @@ mod builder {
                 limit_price,
                 time_in_force: _,
                 priced_by,
+                display_quantity,
+                hidden,
             } = self.type_()
             else {
                 // SAFETY: since this is a `Builder<_, Limit<_>>`, this will
@@ -489,12 +1107,17 @@ mod builder {
                     all_or_none: true,
                 },
                 priced_by,
+                display_quantity,
+                hidden,
             };
 
             Builder {
                 side: self.side,
                 type_: MaybeUninit::new(type_),
                 type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
             }
         }
     }
@@ -505,6 +1128,7 @@ mod builder {
             let OrderType::Market {
                 all_or_none: _,
                 priced_by,
+                max_slippage,
             } = self.type_()
             else {
                 // SAFETY: since this is a `Builder<_, Market<_>>`, this will
@@ -515,12 +1139,50 @@ mod builder {
             let type_ = OrderType::Market {
                 all_or_none: true,
                 priced_by,
+                max_slippage,
             };
 
             Builder {
                 side: self.side,
                 type_: MaybeUninit::new(type_),
                 type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
+            }
+        }
+
+        /// Caps how far from the best opposite price the order may trade,
+        /// cancelling whatever is left of it past that.
+        #[inline]
+        pub fn max_slippage(
+            &self,
+            max_slippage: impl Into<Price>,
+        ) -> Builder<OrderSide, Market> {
+            let OrderType::Market {
+                all_or_none,
+                priced_by,
+                max_slippage: _,
+            } = self.type_()
+            else {
+                // SAFETY: since this is a `Builder<_, Market<_>>`, this will
+                // always be `Market`.
+                unsafe { unreachable_unchecked() }
+            };
+
+            let type_ = OrderType::Market {
+                all_or_none,
+                priced_by,
+                max_slippage: Some(max_slippage.into()),
+            };
+
+            Builder {
+                side: self.side,
+                type_: MaybeUninit::new(type_),
+                type_variant: PhantomData,
+                account_id: self.account_id,
+                id: self.id,
+                reduce_only: self.reduce_only,
             }
         }
     }
@@ -533,14 +1195,34 @@ mod builder {
             unsafe { self.type_.assume_init() }
         }
 
+        /// Builds the order.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the order is not valid, see [`Order::validate`].
         #[inline]
+        #[track_caller]
         pub fn build(self) -> Order {
-            Order {
-                id: OrderId::random(),
+            self.try_build().expect("orders must be valid")
+        }
+
+        /// Builds the order, returning an error if it is not valid, see
+        /// [`Order::validate`].
+        #[inline]
+        pub fn try_build(self) -> Result<Order, OrderError> {
+            let order = Order {
+                id: self.id.unwrap_or_else(OrderId::random),
                 side: self.side,
                 type_: self.type_(),
                 status: OrderStatus::Open,
-            }
+                account_id: self.account_id,
+                reduce_only: self.reduce_only,
+                min_counterparty_qty: None,
+                max_participation: None,
+            };
+            order.validate()?;
+
+            Ok(order)
         }
     }
 }
@@ -548,9 +1230,9 @@ mod builder {
 #[cfg(test)]
 mod tests {
     use exchange_core::Trade as _;
-    use rust_decimal_macros::dec;
 
     use super::*;
+    use crate::error::ConversionError;
 
     mod valid_trades {
         use super::*;
@@ -559,14 +1241,12 @@ mod tests {
         fn same_prices() {
             let mut ask: LimitOrder = Order::builder()
                 .side(OrderSide::Ask)
-                .limit(dec!(10), dec!(10))
+                .limit(10, 10)
                 .build()
                 .try_into()
                 .unwrap();
-            let mut bid = Order::builder()
-                .side(OrderSide::Bid)
-                .limit(dec!(10), dec!(10))
-                .build();
+            let mut bid =
+                Order::builder().side(OrderSide::Bid).limit(10, 10).build();
 
             assert!(ask.trade(&mut bid).is_ok());
         }
@@ -575,14 +1255,12 @@ mod tests {
         fn different_prices() {
             let mut ask: LimitOrder = Order::builder()
                 .side(OrderSide::Ask)
-                .limit(dec!(10), dec!(10))
+                .limit(10, 10)
                 .build()
                 .try_into()
                 .unwrap();
-            let mut bid = Order::builder()
-                .side(OrderSide::Bid)
-                .limit(dec!(20), dec!(10))
-                .build();
+            let mut bid =
+                Order::builder().side(OrderSide::Bid).limit(20, 10).build();
 
             assert!(ask.trade(&mut bid).is_ok());
         }
@@ -591,14 +1269,12 @@ mod tests {
         fn partial_maker() {
             let mut ask: LimitOrder = Order::builder()
                 .side(OrderSide::Ask)
-                .limit(dec!(10), dec!(5))
+                .limit(10, 5)
                 .build()
                 .try_into()
                 .unwrap();
-            let mut bid = Order::builder()
-                .side(OrderSide::Bid)
-                .limit(dec!(20), dec!(10))
-                .build();
+            let mut bid =
+                Order::builder().side(OrderSide::Bid).limit(20, 10).build();
 
             assert!(ask.trade(&mut bid).is_ok());
             assert!(ask.is_closed());
@@ -609,14 +1285,12 @@ mod tests {
         fn partial_taker() {
             let mut ask: LimitOrder = Order::builder()
                 .side(OrderSide::Ask)
-                .limit(dec!(10), dec!(10))
+                .limit(10, 10)
                 .build()
                 .try_into()
                 .unwrap();
-            let mut bid = Order::builder()
-                .side(OrderSide::Bid)
-                .limit(dec!(20), dec!(5))
-                .build();
+            let mut bid =
+                Order::builder().side(OrderSide::Bid).limit(20, 5).build();
 
             assert!(ask.trade(&mut bid).is_ok());
             assert!(!ask.is_closed());
@@ -631,14 +1305,12 @@ mod tests {
         fn same_side() {
             let mut ask_1: LimitOrder = Order::builder()
                 .side(OrderSide::Ask)
-                .limit(dec!(10), dec!(10))
+                .limit(10, 10)
                 .build()
                 .try_into()
                 .unwrap();
-            let mut ask_2 = Order::builder()
-                .side(OrderSide::Ask)
-                .limit(dec!(10), dec!(10))
-                .build();
+            let mut ask_2 =
+                Order::builder().side(OrderSide::Ask).limit(10, 10).build();
 
             assert!(ask_1.trade(&mut ask_2).is_err());
         }
@@ -647,41 +1319,148 @@ mod tests {
         fn incompatible_prices() {
             let mut ask: LimitOrder = Order::builder()
                 .side(OrderSide::Ask)
-                .limit(dec!(20), dec!(10))
+                .limit(20, 10)
                 .build()
                 .try_into()
                 .unwrap();
-            let mut bid = Order::builder()
-                .side(OrderSide::Bid)
-                .limit(dec!(10), dec!(10))
-                .build();
+            let mut bid =
+                Order::builder().side(OrderSide::Bid).limit(10, 10).build();
 
             assert!(ask.trade(&mut bid).is_err());
         }
     }
 
     #[test]
-    fn cancel_order() {
-        let mut ask = Order::builder()
+    fn self_trade() {
+        let account_id = AccountId::random();
+
+        let ask: LimitOrder = Order::builder()
+            .side(OrderSide::Ask)
+            .account_id(account_id)
+            .limit(10, 10)
+            .build()
+            .try_into()
+            .unwrap();
+        let bid = Order::builder().side(OrderSide::Bid).limit(10, 10).build();
+
+        assert!(!ask.is_self_trade(&bid));
+        assert!(!ask.is_self_trade(&bid.with_account_id(AccountId::random())));
+        assert!(ask.is_self_trade(&bid.with_account_id(account_id)));
+    }
+
+    #[test]
+    fn empty_display_quantity() {
+        assert!(matches!(
+            Order::builder()
+                .side(OrderSide::Ask)
+                .limit(10, 10)
+                .iceberg(0)
+                .try_build(),
+            Err(OrderError::Conversion(ConversionError::DisplayQuantity))
+        ));
+    }
+
+    #[test]
+    fn non_positive_quantity() {
+        for quantity in [0, -5] {
+            assert!(matches!(
+                Order::builder()
+                    .side(OrderSide::Ask)
+                    .limit(10, quantity)
+                    .try_build(),
+                Err(OrderError::NonPositiveQuantity)
+            ));
+            assert!(matches!(
+                Order::builder()
+                    .side(OrderSide::Bid)
+                    .market(quantity)
+                    .try_build(),
+                Err(OrderError::NonPositiveQuantity)
+            ));
+            assert!(matches!(
+                Order::builder()
+                    .side(OrderSide::Bid)
+                    .market_funds(quantity)
+                    .try_build(),
+                Err(OrderError::NonPositiveQuantity)
+            ));
+        }
+
+        assert!(Order::builder()
             .side(OrderSide::Ask)
-            .limit(dec!(10), dec!(10))
-            .build();
+            .limit(10, 1)
+            .try_build()
+            .is_ok());
+    }
+
+    #[test]
+    fn max_participation_out_of_range() {
+        let order = Order::builder().side(OrderSide::Bid).limit(10, 10).build();
+
+        for max_participation in [0, 101] {
+            let order = order.with_max_participation(max_participation);
+
+            assert!(matches!(
+                order.validate(),
+                Err(OrderError::MaxParticipation)
+            ));
+        }
+
+        assert!(order.with_max_participation(100).validate().is_ok());
+    }
+
+    #[test]
+    fn non_positive_price() {
+        for price in [0, -5] {
+            assert!(matches!(
+                Order::builder()
+                    .side(OrderSide::Ask)
+                    .limit(price, 10)
+                    .try_build(),
+                Err(OrderError::NonPositivePrice)
+            ));
+        }
+    }
+
+    #[test]
+    fn cancel_order() {
+        let mut ask =
+            Order::builder().side(OrderSide::Ask).limit(10, 10).build();
         ask.cancel();
         assert_eq!(ask.status(), OrderStatus::Cancelled);
     }
 
+    #[test]
+    fn expire_order() {
+        let mut ask: LimitOrder = Order::builder()
+            .side(OrderSide::Ask)
+            .limit(10, 10)
+            .build()
+            .try_into()
+            .unwrap();
+        let mut bid =
+            Order::builder().side(OrderSide::Bid).limit(10, 5).build();
+
+        assert!(ask.trade(&mut bid).is_ok());
+
+        ask.expire();
+        bid.expire();
+
+        // Expiring does not overwrite how an order was already closed.
+        assert_eq!(ask.status(), OrderStatus::Expired);
+        assert_eq!(bid.status(), OrderStatus::Completed);
+    }
+
     #[test]
     fn close_order() {
         let mut ask: LimitOrder = Order::builder()
             .side(OrderSide::Ask)
-            .limit(dec!(10), dec!(10))
+            .limit(10, 10)
             .build()
             .try_into()
             .unwrap();
-        let mut bid = Order::builder()
-            .side(OrderSide::Bid)
-            .limit(dec!(10), dec!(5))
-            .build();
+        let mut bid =
+            Order::builder().side(OrderSide::Bid).limit(10, 5).build();
 
         assert!(ask.trade(&mut bid).is_ok());
 
@@ -6,6 +6,7 @@ use std::ops::AddAssign as _;
 use either::Either;
 use exchange_core::Asset;
 use exchange_core::Trade;
+use num::Zero as _;
 
 use crate::error::ConversionError;
 use crate::error::OrderError;
@@ -13,6 +14,7 @@ use crate::error::PriceError;
 use crate::error::StatusError;
 use crate::error::TradeError;
 use crate::order_type::ByBase;
+use crate::AccountId;
 use crate::Notional;
 use crate::Order;
 use crate::OrderId;
@@ -33,13 +35,111 @@ pub struct LimitOrder {
     /// liquidity. If any part of the order results in taking liquidity,
     /// the order will be rejected and no part of it will execute.
     post_only: bool,
+    /// An all-or-none order only ever trades as a whole, so it is passed over
+    /// by incoming orders too small to fill it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    all_or_none: bool,
+    /// The least quantity an incoming order must have left to trade with
+    /// this one, if any.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    min_counterparty_qty: Option<Quantity>,
+    /// Only a post-only order may rest while reduce-only, and only as much of
+    /// it as reduces the position of its account, see
+    /// [`Asset::is_reduce_only`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    reduce_only: bool,
+    /// Unix timestamp, in seconds, from which a good-till-date order is
+    /// expired.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    expire_at: Option<u64>,
+    /// How long, in milliseconds, a good-for-duration order lasts for, until
+    /// it is accepted and given an expiry instead.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    duration: Option<u64>,
+    /// An at-the-close order only ever trades in the closing auction.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    at_the_close: bool,
     quantity: Quantity,
     #[cfg_attr(feature = "serde", serde(default))]
     filled: Quantity,
+    /// The quantity shown on the book at a time, if this is an iceberg order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    display_quantity: Option<Quantity>,
+    /// Whether the order is kept off the book as far as market data goes.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    hidden: bool,
+    /// The quantity filled out of the currently displayed slice of an
+    /// iceberg order.
+    #[cfg_attr(feature = "serde", serde(default))]
+    slice_filled: Quantity,
     status: OrderStatus,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    account_id: Option<AccountId>,
+    /// Where the order stands in the time priority of the book it rests on.
+    #[cfg_attr(feature = "serde", serde(default))]
+    sequence: u64,
+    /// Unix timestamp, in seconds, the order was first queued at, or zero if
+    /// it was never stamped.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "num::Zero::is_zero")
+    )]
+    created_at: u64,
 }
 
 impl LimitOrder {
+    /// Constructs a new, unfilled good-till-cancel `LimitOrder`.
+    #[inline]
+    pub fn new(
+        id: OrderId,
+        side: OrderSide,
+        limit_price: impl Into<Price>,
+        quantity: impl Into<Quantity>,
+    ) -> Self {
+        Self {
+            id,
+            side,
+            unit_price: limit_price.into(),
+            post_only: false,
+            all_or_none: false,
+            reduce_only: false,
+            min_counterparty_qty: None,
+            expire_at: None,
+            duration: None,
+            at_the_close: false,
+            quantity: quantity.into(),
+            filled: Quantity::default(),
+            display_quantity: None,
+            hidden: false,
+            slice_filled: Quantity::default(),
+            status: OrderStatus::Open,
+            account_id: None,
+            sequence: 0,
+            created_at: 0,
+        }
+    }
+
     /// Fill an order within the specified amount.
     ///
     /// # Panics
@@ -62,6 +162,10 @@ impl LimitOrder {
     pub(crate) unsafe fn fill_unchecked(&mut self, quantity: Quantity) {
         self.filled.add_assign(quantity);
 
+        if self.display_quantity.is_some() {
+            self.slice_filled.add_assign(quantity);
+        }
+
         self.status = if self.remaining().is_zero() {
             OrderStatus::Completed
         } else {
@@ -80,7 +184,7 @@ impl LimitOrder {
             return Err(OrderError::NoFill);
         }
 
-        if quantity > self.remaining() {
+        if quantity > self.displayed() {
             return Err(OrderError::Overfill);
         }
 
@@ -94,6 +198,163 @@ impl LimitOrder {
     pub fn remaining(&self) -> Quantity {
         self.quantity - self.filled
     }
+
+    /// Returns the remaining quantity shown on the book.
+    ///
+    /// This is the whole remaining quantity, except for iceberg orders, which
+    /// only show what is left of their current slice. Hidden orders are not
+    /// shown at all, but still trade up to this quantity.
+    #[inline]
+    pub fn displayed(&self) -> Quantity {
+        match self.display_quantity {
+            Some(display_quantity) => {
+                (display_quantity - self.slice_filled).min(self.remaining())
+            }
+            None => self.remaining(),
+        }
+    }
+
+    /// Returns the quantity shown on the book at a time, if this is an
+    /// iceberg order.
+    #[inline]
+    pub fn display_quantity(&self) -> Option<Quantity> {
+        self.display_quantity
+    }
+
+    /// Returns `true` if the order is kept off the book as far as market
+    /// data goes.
+    #[inline]
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Shows a new slice of an iceberg order out of its hidden reserve,
+    /// once the current one is fully filled.
+    ///
+    /// Returns `true` if a new slice was shown, in which case the order must
+    /// lose its time priority.
+    #[inline]
+    pub fn replenish(&mut self) -> bool {
+        if self.display_quantity.is_none()
+            || !self.displayed().is_zero()
+            || self.remaining().is_zero()
+        {
+            return false;
+        }
+
+        self.slice_filled = Quantity::default();
+
+        true
+    }
+
+    /// Returns the total quantity of the order, filled or not.
+    #[inline]
+    pub fn quantity(&self) -> Quantity {
+        self.quantity
+    }
+
+    /// Returns when a good-till-date order expires, if it does.
+    #[inline]
+    pub fn expire_at(&self) -> Option<u64> {
+        self.expire_at
+    }
+
+    /// Starts the duration of a good-for-duration order at `now`, a unix
+    /// timestamp in seconds, making it expire once it has passed, rounded up
+    /// to whole seconds. Any other order is left as it is.
+    #[inline]
+    pub fn start_duration(&mut self, now: u64) {
+        if let Some(millis) = self.duration.take() {
+            self.expire_at = Some(now.saturating_add(millis.div_ceil(1000)));
+        }
+    }
+
+    /// Returns `true` if the order is expired by `now`.
+    #[inline]
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expire_at.is_some_and(|expire_at| expire_at <= now)
+    }
+
+    /// Returns the account that owns the order, if any.
+    #[inline]
+    pub fn account_id(&self) -> Option<AccountId> {
+        self.account_id
+    }
+
+    /// Returns the quantity filled so far.
+    #[inline]
+    pub fn filled(&self) -> Quantity {
+        self.filled
+    }
+
+    /// Returns the sequence the order was queued at, which orders resting at
+    /// the same price are matched by.
+    ///
+    /// Sequences are assigned by the book as orders are queued, each one
+    /// greater than the previous, so they only compare among orders of the
+    /// same book.
+    #[inline]
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Sets the sequence the order is queued at.
+    #[inline]
+    pub fn set_sequence(&mut self, sequence: u64) {
+        self.sequence = sequence;
+    }
+
+    /// Returns the unix timestamp, in seconds, the order was first queued at,
+    /// or zero if the book it rests on has no clock to stamp it with.
+    #[inline]
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Sets the unix timestamp, in seconds, the order was first queued at.
+    #[inline]
+    pub fn set_created_at(&mut self, created_at: u64) {
+        self.created_at = created_at;
+    }
+
+    /// Returns how long, in seconds, the order has been resting by `now`, or
+    /// `None` if it was never stamped.
+    #[inline]
+    pub fn age(&self, now: u64) -> Option<u64> {
+        (self.created_at != 0).then(|| now.saturating_sub(self.created_at))
+    }
+
+    /// Amends the order limit price and total quantity, keeping whatever
+    /// was already filled.
+    ///
+    /// An iceberg order moved to another price shows a whole slice there,
+    /// however much of its current one was filled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the order untouched, if `quantity` does not
+    /// exceed the already filled quantity.
+    #[inline]
+    pub fn amend(
+        &mut self,
+        limit_price: Price,
+        quantity: Quantity,
+    ) -> Result<(), OrderError> {
+        if limit_price <= Price::zero() {
+            return Err(OrderError::NonPositivePrice);
+        }
+        if quantity <= self.filled {
+            return Err(OrderError::BelowFilled);
+        }
+
+        if limit_price != self.unit_price {
+            self.slice_filled = Quantity::default();
+        }
+        self.unit_price = limit_price;
+        self.quantity = quantity;
+
+        Ok(())
+    }
 }
 
 impl Borrow<LimitOrder> for Reverse<LimitOrder> {
@@ -145,8 +406,15 @@ impl Asset for LimitOrder {
 
     #[inline]
     fn remaining(&self) -> Either<Self::OrderNotional, Self::OrderQuantity> {
-        let remaining = self.remaining();
-        Either::Right(remaining)
+        let displayed = self.displayed();
+        Either::Right(displayed)
+    }
+
+    #[inline]
+    fn total_remaining(
+        &self,
+    ) -> Either<Self::OrderNotional, Self::OrderQuantity> {
+        Either::Right(self.remaining())
     }
 
     #[inline]
@@ -171,6 +439,7 @@ impl Asset for LimitOrder {
             OrderStatus::Cancelled
                 | OrderStatus::Closed
                 | OrderStatus::Completed
+                | OrderStatus::Expired
         )
     }
 
@@ -184,6 +453,65 @@ impl Asset for LimitOrder {
         self.post_only
     }
 
+    #[inline]
+    fn is_all_or_none(&self) -> bool {
+        self.all_or_none
+    }
+
+    #[inline]
+    fn is_at_the_close(&self) -> bool {
+        self.at_the_close
+    }
+
+    #[inline]
+    fn is_reduce_only(&self) -> bool {
+        // Reduce-only orders never rest on the book, unless post-only too.
+        self.reduce_only
+    }
+
+    #[inline]
+    fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    #[inline]
+    fn display_quantity(&self) -> Option<Self::OrderQuantity> {
+        self.display_quantity
+    }
+
+    #[inline]
+    fn set_display_quantity(&mut self, display_quantity: Self::OrderQuantity) {
+        if let Some(shown) = self.display_quantity.as_mut() {
+            *shown = display_quantity;
+        }
+    }
+
+    #[inline]
+    fn cap(&mut self, quantity: Self::OrderQuantity) {
+        self.quantity = self.quantity.min(self.filled + quantity);
+    }
+
+    #[inline]
+    fn min_counterparty_qty(&self) -> Option<Self::OrderQuantity> {
+        self.min_counterparty_qty
+    }
+
+    #[inline]
+    fn max_participation(
+        &self,
+        _available: Self::OrderQuantity,
+    ) -> Option<Self::OrderQuantity> {
+        // Only ever checked as the order comes in, so it is not kept once it
+        // rests.
+        None
+    }
+
+    #[inline]
+    fn max_slippage(&self) -> Option<Self::OrderPrice> {
+        // Only market orders may cap their slippage.
+        None
+    }
+
     #[inline]
     fn cancel(&mut self) {
         match self.status() {
@@ -192,6 +520,13 @@ impl Asset for LimitOrder {
             _ => (),
         }
     }
+
+    #[inline]
+    fn expire(&mut self) {
+        if self.is_open() {
+            self.status = OrderStatus::Expired;
+        }
+    }
 }
 
 impl Trade<Order> for LimitOrder {
@@ -205,13 +540,29 @@ impl Trade<Order> for LimitOrder {
         Self::Trade::try_new(maker, taker)
     }
 
+    #[inline]
+    fn trade_up_to(
+        &mut self,
+        other: &mut Order,
+        quantity: Quantity,
+    ) -> Result<Self::Trade, Self::TradeError> {
+        let (maker, taker) = (self, other);
+
+        Self::Trade::try_new_up_to(maker, taker, quantity)
+    }
+
+    #[inline]
+    fn is_self_trade(&self, other: &Order) -> bool {
+        self.account_id.is_some() && self.account_id == other.account_id()
+    }
+
     #[inline]
     fn matches(&self, other: &Order) -> Result<(), Self::TradeError> {
         let (maker, taker) = (self, other);
 
         // Matching cannot occur between closed orders.
         if taker.is_closed() || maker.is_closed() {
-            return Err(StatusError::Closed)?;
+            Err(StatusError::Closed)?;
         }
 
         let maker_limit_price = maker
@@ -247,15 +598,31 @@ impl From<LimitOrder> for Order {
             side: order.side,
             type_: OrderType::Limit {
                 limit_price: order.unit_price,
-                time_in_force: TimeInForce::GoodTillCancel {
-                    post_only: order.post_only,
+                time_in_force: match (order.expire_at, order.duration) {
+                    _ if order.at_the_close => TimeInForce::AtTheClose,
+                    (Some(expire_at), _) => {
+                        TimeInForce::GoodTillDate { expire_at }
+                    }
+                    (None, Some(millis)) => {
+                        TimeInForce::GoodForDuration { millis }
+                    }
+                    (None, None) => TimeInForce::GoodTillCancel {
+                        post_only: order.post_only,
+                        all_or_none: order.all_or_none,
+                    },
                 },
                 priced_by: ByBase {
                     quantity: order.quantity,
                     filled: order.filled,
                 },
+                display_quantity: order.display_quantity,
+                hidden: order.hidden,
             },
             status: order.status,
+            account_id: order.account_id,
+            reduce_only: order.reduce_only,
+            min_counterparty_qty: order.min_counterparty_qty,
+            max_participation: None,
         }
     }
 }
@@ -266,21 +633,64 @@ impl TryFrom<Order> for LimitOrder {
     fn try_from(order: Order) -> Result<Self, Self::Error> {
         let OrderType::Limit {
             limit_price,
-            time_in_force: TimeInForce::GoodTillCancel { post_only },
+            time_in_force,
             priced_by,
+            display_quantity,
+            hidden,
         } = order.type_
         else {
             return Err(ConversionError::Incompatible)?;
         };
 
+        if display_quantity.is_some_and(|display_quantity| {
+            display_quantity <= Quantity::default()
+        }) {
+            return Err(ConversionError::DisplayQuantity);
+        }
+
+        // An order that is never shown has no slice to show either.
+        if hidden && display_quantity.is_some() {
+            return Err(ConversionError::HiddenIceberg);
+        }
+
+        let (post_only, all_or_none, expire_at, duration) = match time_in_force
+        {
+            TimeInForce::GoodTillCancel {
+                post_only,
+                all_or_none,
+            } => (post_only, all_or_none, None, None),
+            TimeInForce::GoodTillDate { expire_at } => {
+                (false, false, Some(expire_at), None)
+            }
+            TimeInForce::GoodForDuration { millis } => {
+                (false, false, None, Some(millis))
+            }
+            TimeInForce::AtTheClose => (false, false, None, None),
+            TimeInForce::ImmediateOrCancel { .. } => {
+                return Err(ConversionError::Incompatible);
+            }
+        };
+
         Ok(LimitOrder {
             id: order.id,
             side: order.side,
             unit_price: limit_price,
             post_only,
+            all_or_none,
+            reduce_only: order.reduce_only,
+            min_counterparty_qty: order.min_counterparty_qty,
+            expire_at,
+            duration,
+            at_the_close: time_in_force == TimeInForce::AtTheClose,
             quantity: priced_by.quantity,
             filled: priced_by.filled,
+            display_quantity,
+            hidden,
+            slice_filled: Quantity::default(),
             status: order.status,
+            account_id: order.account_id,
+            sequence: 0,
+            created_at: 0,
         })
     }
 }
@@ -1,3 +1,7 @@
+use std::fmt;
+use std::sync::atomic;
+use std::sync::atomic::AtomicU64;
+
 use uuid::Uuid;
 
 #[repr(transparent)]
@@ -11,6 +15,20 @@ impl OrderId {
         Self(uuid)
     }
 
+    /// Constructs an `OrderId` from a 128-bit value, e.g. for synthetic
+    /// orders that are not submitted by any client.
+    #[inline]
+    pub const fn from_u128(value: u128) -> Self {
+        Self(Uuid::from_u128(value))
+    }
+
+    /// Constructs the next `OrderId` out of a shared counter, so that ids are
+    /// unique and reproducible across runs, e.g. for tests.
+    #[inline]
+    pub fn sequential(counter: &AtomicU64) -> Self {
+        Self::from_u128(counter.fetch_add(1, atomic::Ordering::Relaxed).into())
+    }
+
     #[inline]
     #[cfg(any(test, feature = "test"))]
     pub fn random() -> Self {
@@ -18,6 +36,13 @@ impl OrderId {
     }
 }
 
+impl fmt::Display for OrderId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 impl From<Uuid> for OrderId {
     #[inline]
     fn from(uuid: Uuid) -> Self {
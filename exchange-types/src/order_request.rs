@@ -1,9 +1,11 @@
-use compact_str::CompactString;
-use rust_decimal::Decimal;
 use thiserror::Error;
 use uuid::Uuid;
 
+use crate::error::OrderError;
 use crate::order_type::ByBase;
+use crate::AccountId;
+use crate::AssetPair;
+use crate::CancelReason;
 use crate::Order;
 use crate::OrderId;
 use crate::OrderSide;
@@ -12,12 +14,18 @@ use crate::Price;
 use crate::Quantity;
 use crate::TimeInForce;
 
+#[cfg(feature = "postcard")]
+mod wire;
+
 #[derive(Debug, Error)]
 pub enum OrderRequestError {
     #[error("order type mismatch")]
     MismatchType,
+    #[error(transparent)]
+    Order(#[from] OrderError),
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(tag = "type_op", rename_all = "UPPERCASE"))]
 pub enum OrderRequest {
@@ -25,15 +33,107 @@ pub enum OrderRequest {
         account_id: Uuid,
         amount: Quantity,
         order_id: Uuid,
-        symbol: CompactString,
+        symbol: AssetPair,
         limit_price: Price,
         side: OrderSide,
     },
-    Delete {
+    /// Removes a resting order, without telling anything about it.
+    Delete { order_id: Uuid },
+    /// Removes a resting order, just like [`OrderRequest::Delete`], though
+    /// for the given reason, confirming what was left of it.
+    Cancel {
+        order_id: Uuid,
+        #[cfg_attr(feature = "serde", serde(default))]
+        reason: CancelReason,
+    },
+    Amend {
+        order_id: Uuid,
+        new_price: Price,
+        new_quantity: Quantity,
+    },
+    /// Creates two linked limit orders, one-cancels-other: once either of
+    /// them has filled `cancel_after`, or anything at all if not set, the
+    /// other one is cancelled.
+    CreateOco {
+        account_id: Uuid,
+        symbol: AssetPair,
+        legs: [OcoLeg; 2],
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        cancel_after: Option<Quantity>,
+    },
+    /// Creates an at-the-close limit order, kept apart from the book until
+    /// the closing auction, see [`TimeInForce::AtTheClose`].
+    CreateAtClose {
+        account_id: Uuid,
+        amount: Quantity,
         order_id: Uuid,
+        symbol: AssetPair,
+        limit_price: Price,
+        side: OrderSide,
     },
 }
 
+/// One of the two limit orders of [`OrderRequest::CreateOco`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OcoLeg {
+    pub order_id: Uuid,
+    pub side: OrderSide,
+    pub limit_price: Price,
+    pub amount: Quantity,
+}
+
+impl OcoLeg {
+    /// Turns the leg into the limit order it stands for, placed by the
+    /// given account.
+    #[inline]
+    pub fn into_order(
+        self,
+        account_id: Uuid,
+    ) -> Result<Order, OrderRequestError> {
+        Ok(limit_order(
+            self.order_id,
+            account_id,
+            self.side,
+            self.limit_price,
+            self.amount,
+            TimeInForce::default(),
+        )?)
+    }
+}
+
+#[inline]
+fn limit_order(
+    order_id: Uuid,
+    account_id: Uuid,
+    side: OrderSide,
+    limit_price: Price,
+    amount: Quantity,
+    time_in_force: TimeInForce,
+) -> Result<Order, OrderError> {
+    let order = Order::new(
+        OrderId::new(order_id),
+        side,
+        OrderType::Limit {
+            limit_price,
+            time_in_force,
+            priced_by: ByBase {
+                quantity: amount,
+                filled: Quantity::default(),
+            },
+            display_quantity: None,
+            hidden: false,
+        },
+    )
+    .with_account_id(AccountId::new(account_id));
+    order.validate()?;
+
+    Ok(order)
+}
+
 impl TryFrom<OrderRequest> for Order {
     type Error = OrderRequestError;
 
@@ -41,24 +141,127 @@ impl TryFrom<OrderRequest> for Order {
     fn try_from(order_request: OrderRequest) -> Result<Self, Self::Error> {
         match order_request {
             OrderRequest::Create {
+                account_id,
                 order_id,
                 amount,
                 limit_price,
                 side,
                 ..
-            } => Ok(Order::new(
-                OrderId::new(order_id),
+            } => Ok(limit_order(
+                order_id,
+                account_id,
                 side,
-                OrderType::Limit {
-                    limit_price,
-                    time_in_force: TimeInForce::default(),
-                    priced_by: ByBase {
-                        quantity: amount,
-                        filled: Decimal::ZERO.into(),
+                limit_price,
+                amount,
+                TimeInForce::default(),
+            )?),
+            OrderRequest::CreateAtClose {
+                account_id,
+                order_id,
+                amount,
+                limit_price,
+                side,
+                ..
+            } => Ok(limit_order(
+                order_id,
+                account_id,
+                side,
+                limit_price,
+                amount,
+                TimeInForce::AtTheClose,
+            )?),
+            OrderRequest::Delete { .. }
+            | OrderRequest::Cancel { .. }
+            | OrderRequest::Amend { .. }
+            | OrderRequest::CreateOco { .. } => {
+                Err(OrderRequestError::MismatchType)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reject_non_positive_amount() {
+        for amount in [0, -5] {
+            let order_request = OrderRequest::Create {
+                account_id: Uuid::from_u128(1),
+                amount: amount.into(),
+                order_id: Uuid::from_u128(1),
+                symbol: AssetPair::new("BTC", "USDC"),
+                limit_price: 10.into(),
+                side: OrderSide::Bid,
+            };
+
+            assert!(matches!(
+                Order::try_from(order_request),
+                Err(OrderRequestError::Order(OrderError::NonPositiveQuantity))
+            ));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "postcard")]
+    fn postcard_round_trip() {
+        let order_requests = [
+            OrderRequest::Create {
+                account_id: Uuid::from_u128(1),
+                amount: 3.into(),
+                order_id: Uuid::from_u128(2),
+                symbol: AssetPair::new("BTC", "USDC"),
+                limit_price: 10.into(),
+                side: OrderSide::Ask,
+            },
+            OrderRequest::Delete {
+                order_id: Uuid::from_u128(2),
+            },
+            OrderRequest::Cancel {
+                order_id: Uuid::from_u128(2),
+                reason: CancelReason::Risk,
+            },
+            OrderRequest::Amend {
+                order_id: Uuid::from_u128(2),
+                new_price: 11.into(),
+                new_quantity: 1.into(),
+            },
+            OrderRequest::CreateOco {
+                account_id: Uuid::from_u128(1),
+                symbol: AssetPair::new("BTC", "USDC"),
+                legs: [
+                    OcoLeg {
+                        order_id: Uuid::from_u128(3),
+                        side: OrderSide::Ask,
+                        limit_price: 12.into(),
+                        amount: 2.into(),
+                    },
+                    OcoLeg {
+                        order_id: Uuid::from_u128(4),
+                        side: OrderSide::Ask,
+                        limit_price: 9.into(),
+                        amount: 2.into(),
                     },
-                },
-            )),
-            OrderRequest::Delete { .. } => Err(OrderRequestError::MismatchType),
+                ],
+                cancel_after: Some(1.into()),
+            },
+            OrderRequest::CreateAtClose {
+                account_id: Uuid::from_u128(1),
+                amount: 3.into(),
+                order_id: Uuid::from_u128(5),
+                symbol: AssetPair::new("BTC", "USDC"),
+                limit_price: 10.into(),
+                side: OrderSide::Bid,
+            },
+        ];
+
+        for order_request in order_requests {
+            let mut buf = Vec::new();
+            order_request.write_postcard(&mut buf).unwrap();
+
+            let decoded = OrderRequest::from_postcard(&buf).unwrap();
+            assert_eq!(decoded, order_request);
         }
     }
 }
@@ -0,0 +1,252 @@
+#[cfg(not(feature = "integer"))]
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::amount::Repr;
+use crate::AssetPair;
+use crate::CancelReason;
+use crate::OcoLeg;
+use crate::OrderRequest;
+use crate::OrderSide;
+
+/// An amount on the wire: a decimal as its fixed 16 bytes, or an integer as
+/// it is, which `postcard` already encodes as a varint.
+#[cfg(not(feature = "integer"))]
+type Amount = [u8; 16];
+#[cfg(feature = "integer")]
+type Amount = i64;
+
+#[cfg(not(feature = "integer"))]
+#[inline]
+fn encode(amount: Repr) -> Amount {
+    amount.serialize()
+}
+
+#[cfg(feature = "integer")]
+#[inline]
+fn encode(amount: Repr) -> Amount {
+    amount
+}
+
+#[cfg(not(feature = "integer"))]
+#[inline]
+fn decode(amount: Amount) -> Repr {
+    Decimal::deserialize(amount)
+}
+
+#[cfg(feature = "integer")]
+#[inline]
+fn decode(amount: Amount) -> Repr {
+    amount
+}
+
+/// What [`OrderRequest`] looks like on the wire, in binary formats.
+///
+/// Formats such as `postcard` are not self-describing, so they can neither
+/// deserialize internally tagged enums nor decimals, which both need to look
+/// ahead at what comes next. This keeps the very same fields, externally
+/// tagged, with decimals as their fixed 16 bytes.
+#[derive(Serialize, Deserialize)]
+enum Wire<S> {
+    Create {
+        account_id: Uuid,
+        amount: Amount,
+        order_id: Uuid,
+        symbol: S,
+        limit_price: Amount,
+        side: OrderSide,
+    },
+    Delete {
+        order_id: Uuid,
+    },
+    Cancel {
+        order_id: Uuid,
+        reason: CancelReason,
+    },
+    Amend {
+        order_id: Uuid,
+        new_price: Amount,
+        new_quantity: Amount,
+    },
+    CreateOco {
+        account_id: Uuid,
+        symbol: S,
+        legs: [WireLeg; 2],
+        cancel_after: Option<Amount>,
+    },
+    CreateAtClose {
+        account_id: Uuid,
+        amount: Amount,
+        order_id: Uuid,
+        symbol: S,
+        limit_price: Amount,
+        side: OrderSide,
+    },
+}
+
+/// What [`OcoLeg`] looks like on the wire.
+#[derive(Serialize, Deserialize)]
+struct WireLeg {
+    order_id: Uuid,
+    side: OrderSide,
+    limit_price: Amount,
+    amount: Amount,
+}
+
+impl From<&OcoLeg> for WireLeg {
+    #[inline]
+    fn from(leg: &OcoLeg) -> Self {
+        Self {
+            order_id: leg.order_id,
+            side: leg.side,
+            limit_price: encode(leg.limit_price.0),
+            amount: encode(leg.amount.0),
+        }
+    }
+}
+
+impl From<WireLeg> for OcoLeg {
+    #[inline]
+    fn from(leg: WireLeg) -> Self {
+        Self {
+            order_id: leg.order_id,
+            side: leg.side,
+            limit_price: decode(leg.limit_price).into(),
+            amount: decode(leg.amount).into(),
+        }
+    }
+}
+
+impl OrderRequest {
+    /// Appends the request to `buf`, encoded with `postcard`.
+    #[inline]
+    pub fn write_postcard(&self, buf: &mut Vec<u8>) -> postcard::Result<()> {
+        let wire = match self {
+            OrderRequest::Create {
+                account_id,
+                amount,
+                order_id,
+                symbol,
+                limit_price,
+                side,
+            } => Wire::Create {
+                account_id: *account_id,
+                amount: encode(amount.0),
+                order_id: *order_id,
+                symbol,
+                limit_price: encode(limit_price.0),
+                side: *side,
+            },
+            OrderRequest::Delete { order_id } => Wire::Delete {
+                order_id: *order_id,
+            },
+            OrderRequest::Cancel { order_id, reason } => Wire::Cancel {
+                order_id: *order_id,
+                reason: *reason,
+            },
+            OrderRequest::Amend {
+                order_id,
+                new_price,
+                new_quantity,
+            } => Wire::Amend {
+                order_id: *order_id,
+                new_price: encode(new_price.0),
+                new_quantity: encode(new_quantity.0),
+            },
+            OrderRequest::CreateOco {
+                account_id,
+                symbol,
+                legs,
+                cancel_after,
+            } => Wire::CreateOco {
+                account_id: *account_id,
+                symbol,
+                legs: legs.each_ref().map(WireLeg::from),
+                cancel_after: cancel_after.map(|quantity| encode(quantity.0)),
+            },
+            OrderRequest::CreateAtClose {
+                account_id,
+                amount,
+                order_id,
+                symbol,
+                limit_price,
+                side,
+            } => Wire::CreateAtClose {
+                account_id: *account_id,
+                amount: encode(amount.0),
+                order_id: *order_id,
+                symbol,
+                limit_price: encode(limit_price.0),
+                side: *side,
+            },
+        };
+
+        postcard::to_io(&wire, buf).map(drop)
+    }
+
+    /// Decodes a request encoded with [`OrderRequest::write_postcard`].
+    #[inline]
+    pub fn from_postcard(bytes: &[u8]) -> postcard::Result<Self> {
+        let wire = postcard::from_bytes::<Wire<AssetPair>>(bytes)?;
+
+        Ok(match wire {
+            Wire::Create {
+                account_id,
+                amount,
+                order_id,
+                symbol,
+                limit_price,
+                side,
+            } => OrderRequest::Create {
+                account_id,
+                amount: decode(amount).into(),
+                order_id,
+                symbol,
+                limit_price: decode(limit_price).into(),
+                side,
+            },
+            Wire::Delete { order_id } => OrderRequest::Delete { order_id },
+            Wire::Cancel { order_id, reason } => {
+                OrderRequest::Cancel { order_id, reason }
+            }
+            Wire::Amend {
+                order_id,
+                new_price,
+                new_quantity,
+            } => OrderRequest::Amend {
+                order_id,
+                new_price: decode(new_price).into(),
+                new_quantity: decode(new_quantity).into(),
+            },
+            Wire::CreateOco {
+                account_id,
+                symbol,
+                legs,
+                cancel_after,
+            } => OrderRequest::CreateOco {
+                account_id,
+                symbol,
+                legs: legs.map(OcoLeg::from),
+                cancel_after: cancel_after
+                    .map(|quantity| decode(quantity).into()),
+            },
+            Wire::CreateAtClose {
+                account_id,
+                amount,
+                order_id,
+                symbol,
+                limit_price,
+                side,
+            } => OrderRequest::CreateAtClose {
+                account_id,
+                amount: decode(amount).into(),
+                order_id,
+                symbol,
+                limit_price: decode(limit_price).into(),
+                side,
+            },
+        })
+    }
+}
@@ -8,4 +8,6 @@ pub enum OrderStatus {
     Cancelled,
     Closed,
     Completed,
+    /// Closed because its time in force was over, rather than by request.
+    Expired,
 }
@@ -21,6 +21,22 @@ pub enum OrderType {
         time_in_force: TimeInForce,
         #[cfg_attr(feature = "serde", serde(flatten))]
         priced_by: ByBase,
+        /// The quantity shown on the book at a time, making it an iceberg
+        /// order. The rest of the order is kept hidden in reserve and only
+        /// shown once the displayed quantity is fully filled.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        display_quantity: Option<Quantity>,
+        /// The hidden flag keeps the order off the book as far as market data
+        /// goes. It still rests and trades, but only after every displayed
+        /// order at the same price, whatever its time priority.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "core::ops::Not::not")
+        )]
+        hidden: bool,
     },
     /// Market orders differ from limit orders in that they provide no pricing
     /// guarantees. They however do provide a way to buy or sell specific
@@ -35,15 +51,26 @@ pub enum OrderType {
         all_or_none: bool,
         #[cfg_attr(feature = "serde", serde(flatten))]
         priced_by: PricedBy,
+        /// The farthest from the best opposite price the order may trade at,
+        /// that price being taken as it arrives. Once the book gets any
+        /// farther than that, whatever is left of the order is cancelled.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        max_slippage: Option<Price>,
     },
 }
 
 /// Time in force policies provide guarantees about the lifetime of an
 /// [order](Order).
 ///
-/// There are two policies: good till canceled
-/// [`GTC`](TimeInForce::GoodTillCancel) and immediate or cancel
-/// [`IOC`](TimeInForce::ImmediateOrCancel).
+/// There are five policies: good till canceled
+/// [`GTC`](TimeInForce::GoodTillCancel), immediate or cancel
+/// [`IOC`](TimeInForce::ImmediateOrCancel), good till date
+/// [`GTD`](TimeInForce::GoodTillDate), good for duration
+/// [`GFD`](TimeInForce::GoodForDuration) and at the close
+/// [`ATC`](TimeInForce::AtTheClose).
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(tag = "type"))]
@@ -59,6 +86,17 @@ pub enum TimeInForce {
             serde(default, skip_serializing_if = "core::ops::Not::not")
         )]
         post_only: bool,
+        /// The `all-or-none` flag indicates that the order only ever trades
+        /// as a whole. Unlike a fill-or-kill order, it is not rejected when
+        /// there is nothing to trade with right away, but rests whole until
+        /// it can be filled, and is passed over by incoming orders too small
+        /// to fill it meanwhile. It is rejected if it crosses orders that
+        /// cannot fill it whole, though, as it could not rest either.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "core::ops::Not::not")
+        )]
+        all_or_none: bool,
     },
     /// An order will try to fill the order as much as it can before the order
     /// expires.
@@ -73,11 +111,38 @@ pub enum TimeInForce {
         )]
         all_or_none: bool,
     },
+    /// An order will be on the book until it is canceled or it expires,
+    /// whichever comes first.
+    #[cfg_attr(feature = "serde", serde(rename = "GTD"))]
+    GoodTillDate {
+        /// Unix timestamp, in seconds, from which the order is expired.
+        expire_at: u64,
+    },
+    /// An order will be on the book until it is canceled or its duration has
+    /// passed since it was accepted, whichever comes first.
+    ///
+    /// The book turns it into a good-till-date order as it accepts it, by its
+    /// clock, so that it expires just like one. Without a clock, it never
+    /// does.
+    #[cfg_attr(feature = "serde", serde(rename = "GFD"))]
+    GoodForDuration {
+        /// How long, in milliseconds, the order lasts for. Expiries are kept
+        /// in whole seconds, so it is rounded up to them.
+        millis: u64,
+    },
+    /// An order that only ever trades in the closing auction. Until then, it
+    /// is kept apart from the book, out of continuous matching, and whatever
+    /// is left of it once the auction uncrossed the book is expired.
+    #[cfg_attr(feature = "serde", serde(rename = "ATC"))]
+    AtTheClose,
 }
 
 impl Default for TimeInForce {
     fn default() -> Self {
-        Self::GoodTillCancel { post_only: false }
+        Self::GoodTillCancel {
+            post_only: false,
+            all_or_none: false,
+        }
     }
 }
 
@@ -98,6 +163,12 @@ pub struct ByBase {
     pub(crate) filled: Quantity,
 }
 
+/// Spends up to `funds`, buying at each price the largest quantity the funds
+/// left afford, which may be a fraction of a unit, but is never rounded up.
+///
+/// Funds too little to buy anything more, or one more lot on a book with a
+/// lot size, are left unspent. Market orders are immediate-or-cancel, so they
+/// are returned as the order expires, as its remaining funds.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ByFunds {
@@ -105,3 +176,46 @@ pub struct ByFunds {
     #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) filled: Notional,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn good_till_date_serde_roundtrip() {
+        let time_in_force = TimeInForce::GoodTillDate {
+            expire_at: 1_700_000_000,
+        };
+
+        let json = serde_json::to_string(&time_in_force).unwrap();
+        assert_eq!(json, r#"{"type":"GTD","expire_at":1700000000}"#);
+
+        let parsed: TimeInForce = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, time_in_force);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn good_for_duration_serde_roundtrip() {
+        let time_in_force = TimeInForce::GoodForDuration { millis: 5_000 };
+
+        let json = serde_json::to_string(&time_in_force).unwrap();
+        assert_eq!(json, r#"{"type":"GFD","millis":5000}"#);
+
+        let parsed: TimeInForce = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, time_in_force);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn at_the_close_serde_roundtrip() {
+        let time_in_force = TimeInForce::AtTheClose;
+
+        let json = serde_json::to_string(&time_in_force).unwrap();
+        assert_eq!(json, r#"{"type":"ATC"}"#);
+
+        let parsed: TimeInForce = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, time_in_force);
+    }
+}
@@ -0,0 +1,125 @@
+use std::ops::RangeInclusive;
+
+use rust_decimal::Decimal;
+use rust_decimal::RoundingStrategy;
+
+use crate::amount;
+use crate::Price;
+
+/// How far, in percent of the mid price, incoming limit orders may be priced
+/// away from it, as a circuit breaker against orders priced way off the
+/// market.
+///
+/// A reference price converted from a float, e.g. one fed by another system,
+/// may be off by some tiny conversion noise, enough to reject an order priced
+/// right at a bound. The collar may be given an explicit tolerance for it, by
+/// which both bounds are widened; there is none by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PriceCollar {
+    percent: Decimal,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Decimal::is_zero")
+    )]
+    tolerance: Decimal,
+}
+
+impl PriceCollar {
+    const HUNDRED: Decimal = Decimal::ONE_HUNDRED;
+
+    /// # Panics
+    ///
+    /// Panics if `percent` is negative.
+    #[inline]
+    pub fn new(percent: impl Into<Decimal>) -> Self {
+        let percent = percent.into();
+        assert!(
+            !percent.is_sign_negative(),
+            "price collar must not be negative"
+        );
+
+        Self {
+            percent,
+            tolerance: Decimal::ZERO,
+        }
+    }
+
+    /// Widens both bounds by `tolerance`, an absolute price, so that orders
+    /// priced right at them are not rejected because of noise in a reference
+    /// price converted from a float.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tolerance` is negative.
+    #[inline]
+    pub fn with_tolerance(mut self, tolerance: impl Into<Decimal>) -> Self {
+        let tolerance = tolerance.into();
+        assert!(
+            !tolerance.is_sign_negative(),
+            "price collar tolerance must not be negative"
+        );
+
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Returns the range of limit prices accepted around the mid price of
+    /// the given spread, bounds included.
+    #[inline]
+    pub fn band(&self, (ask, bid): (Price, Price)) -> RangeInclusive<Price> {
+        self.around((ask.to_decimal() + bid.to_decimal()) / Decimal::TWO)
+    }
+
+    /// Returns the range of limit prices accepted around the given reference
+    /// price, bounds included.
+    #[inline]
+    pub fn band_around(&self, reference: Price) -> RangeInclusive<Price> {
+        self.around(reference.to_decimal())
+    }
+
+    #[inline]
+    fn around(&self, mid: Decimal) -> RangeInclusive<Price> {
+        let width = mid * self.percent / Self::HUNDRED + self.tolerance;
+
+        // Bounds that fall between two prices are rounded inward, so that
+        // the band never gets any wider than it should.
+        let low = (mid - width).round_dp_with_strategy(
+            amount::SCALE,
+            RoundingStrategy::ToPositiveInfinity,
+        );
+        let high = (mid + width).round_dp_with_strategy(
+            amount::SCALE,
+            RoundingStrategy::ToNegativeInfinity,
+        );
+
+        Price::from_decimal(low)..=Price::from_decimal(high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn band_around_mid() {
+        let collar = PriceCollar::new(dec!(5));
+
+        assert_eq!(
+            collar.band((101.into(), 99.into())),
+            Price::from(95)..=Price::from(105)
+        );
+    }
+
+    #[test]
+    fn tolerance_widens_band() {
+        let collar = PriceCollar::new(dec!(5)).with_tolerance(dec!(1));
+
+        assert_eq!(
+            collar.band_around(100.into()),
+            Price::from(94)..=Price::from(106)
+        );
+    }
+}
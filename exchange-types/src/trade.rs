@@ -2,11 +2,15 @@ use either::Either;
 use exchange_core::Asset as _;
 use exchange_core::Trade as _;
 
+use crate::error::PriceError;
+use crate::error::StatusError;
 use crate::error::TradeError;
+use crate::AccountId;
 use crate::LimitOrder;
 use crate::Notional;
 use crate::Order;
 use crate::OrderId;
+use crate::OrderSide;
 use crate::Price;
 use crate::Quantity;
 
@@ -14,6 +18,9 @@ use crate::Quantity;
 #[cfg_attr(test, derive(Copy, Clone))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Trade {
+    /// Id of the trade itself, unique within the book it took place in.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) trade_id: u64,
     pub(crate) taker: OrderId,
     pub(crate) maker: OrderId,
     /// Amount exchanged.
@@ -22,6 +29,45 @@ pub struct Trade {
     pub(crate) price: Price,
     /// Total value of the underlying trade.
     pub(crate) notional: Notional,
+    /// Quantity the incoming order had left right after this trade, unless
+    /// it is priced by funds.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) taker_remaining: Option<Quantity>,
+    /// Quantity the resting order showed right after this trade.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) maker_remaining: Quantity,
+    /// Quantity the resting order had left right after this trade, hidden
+    /// reserve included. Only its owner may know of it, so it is never
+    /// serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) maker_total_remaining: Quantity,
+    /// Quantity the resting order had filled in total right after this
+    /// trade, trades of earlier passes included. It gives away how much of a
+    /// hidden or iceberg order has traded, so it is never serialized either.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) maker_filled: Quantity,
+    /// Fee charged to the maker, negative for a rebate.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Notional::is_zero")
+    )]
+    pub(crate) maker_fee: Notional,
+    /// Fee charged to the taker, negative for a rebate.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Notional::is_zero")
+    )]
+    pub(crate) taker_fee: Notional,
+    /// Account that bought, if the order had one. It tells who traded with
+    /// whom, so it is never serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) buyer_account: Option<AccountId>,
+    /// Account that sold, if the order had one, never serialized either.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) seller_account: Option<AccountId>,
 }
 
 impl Trade {
@@ -30,6 +76,93 @@ impl Trade {
     pub fn try_new(
         maker: &mut LimitOrder,
         taker: &mut Order,
+    ) -> Result<Trade, TradeError> {
+        Self::exchange(maker, taker, None)
+    }
+
+    /// Constructs a new `Trade` of no more than `quantity`, returning an
+    /// error if something fails.
+    #[track_caller]
+    pub fn try_new_up_to(
+        maker: &mut LimitOrder,
+        taker: &mut Order,
+        quantity: Quantity,
+    ) -> Result<Trade, TradeError> {
+        Self::exchange(maker, taker, Some(quantity))
+    }
+
+    /// Constructs a new `Trade` of an auction, crossing a bid and an ask at
+    /// the single clearing `price`, for no more than `quantity` and no more
+    /// than either of them shows, returning an error if something fails.
+    ///
+    /// There is no incoming order in an auction, so the order queued last,
+    /// which is the one that crossed the book, is reported as the taker.
+    #[track_caller]
+    pub fn try_cross(
+        bid: &mut LimitOrder,
+        ask: &mut LimitOrder,
+        price: Price,
+        quantity: Quantity,
+    ) -> Result<Trade, TradeError> {
+        if bid.is_closed() || ask.is_closed() {
+            Err(StatusError::Closed)?;
+        }
+
+        if bid.side() != OrderSide::Bid || ask.side() != OrderSide::Ask {
+            return Err(TradeError::SameSide);
+        }
+
+        let crosses = |order: &LimitOrder| {
+            order
+                .limit_price()
+                .is_some_and(|limit_price| match order.side() {
+                    OrderSide::Ask => limit_price <= price,
+                    OrderSide::Bid => limit_price >= price,
+                })
+        };
+        if !crosses(bid) || !crosses(ask) {
+            Err(PriceError::Incompatible)?;
+        }
+
+        let exchanged = quantity.min(bid.displayed()).min(ask.displayed());
+        if exchanged.is_zero() {
+            return Err(TradeError::Exhausted);
+        }
+
+        bid.fill(exchanged);
+        ask.fill(exchanged);
+
+        let (buyer_account, seller_account) =
+            (bid.account_id(), ask.account_id());
+        let (maker, taker) = if bid.sequence() < ask.sequence() {
+            (bid, ask)
+        } else {
+            (ask, bid)
+        };
+
+        Ok(Trade {
+            trade_id: 0,
+            taker: taker.id(),
+            maker: maker.id(),
+            quantity: exchanged,
+            price,
+            notional: exchanged * price,
+            taker_remaining: Some(taker.remaining()),
+            maker_remaining: shown(maker),
+            maker_total_remaining: maker.remaining(),
+            maker_filled: maker.filled(),
+            maker_fee: Notional::default(),
+            taker_fee: Notional::default(),
+            buyer_account,
+            seller_account,
+        })
+    }
+
+    #[track_caller]
+    fn exchange(
+        maker: &mut LimitOrder,
+        taker: &mut Order,
+        limit: Option<Quantity>,
     ) -> Result<Trade, TradeError> {
         maker.matches(&*taker)?;
 
@@ -37,23 +170,72 @@ impl Trade {
             maker.limit_price().expect("maker must always have a price");
 
         let exchanged = match taker.remaining() {
-            Either::Left(funds) => funds / price,
+            Either::Left(funds) => funds.affordable(price),
             Either::Right(quantity) => quantity,
         }
-        .min(maker.remaining());
+        .min(maker.displayed());
+        let exchanged = limit.map_or(exchanged, |limit| exchanged.min(limit));
+
+        // Leftover funds may be too little to buy anything at this price.
+        if exchanged.is_zero() {
+            return Err(TradeError::Exhausted);
+        }
 
         maker.fill(exchanged);
         taker.fill(exchanged, price);
 
+        let (buyer_account, seller_account) = match taker.side() {
+            OrderSide::Bid => (taker.account_id(), maker.account_id()),
+            OrderSide::Ask => (maker.account_id(), taker.account_id()),
+        };
+
         Ok(Trade {
+            trade_id: 0,
             taker: taker.id(),
             maker: maker.id(),
             quantity: exchanged,
             price,
             notional: exchanged * price,
+            taker_remaining: taker.remaining().right(),
+            maker_remaining: shown(maker),
+            maker_total_remaining: maker.remaining(),
+            maker_filled: maker.filled(),
+            maker_fee: Notional::default(),
+            taker_fee: Notional::default(),
+            buyer_account,
+            seller_account,
         })
     }
 
+    /// Returns the id of the trade, as assigned by the book it took place in.
+    ///
+    /// Ids are handed out from the very sequence resting orders are queued
+    /// by, so every trade of a book has an id greater than those before it,
+    /// and replaying the same requests against an empty book assigns the
+    /// very same ids again. They are zero until the trade is assigned one.
+    #[inline]
+    pub const fn trade_id(&self) -> u64 {
+        self.trade_id
+    }
+
+    /// Sets the id of the trade.
+    #[inline]
+    pub fn set_trade_id(&mut self, trade_id: u64) {
+        self.trade_id = trade_id;
+    }
+
+    /// Returns the id of the incoming order.
+    #[inline]
+    pub const fn taker(&self) -> OrderId {
+        self.taker
+    }
+
+    /// Returns the id of the resting order.
+    #[inline]
+    pub const fn maker(&self) -> OrderId {
+        self.maker
+    }
+
     /// Returns the amount exchanged.
     #[inline]
     pub const fn quantity(&self) -> Quantity {
@@ -71,4 +253,140 @@ impl Trade {
     pub const fn notional(&self) -> Notional {
         self.notional
     }
+
+    /// Returns the quantity the incoming order had left right after this
+    /// trade, or `None` if it is priced by funds.
+    ///
+    /// When an incoming order sweeps several resting orders, this tells how
+    /// much of it was still left after each of them.
+    #[inline]
+    pub const fn taker_remaining(&self) -> Option<Quantity> {
+        self.taker_remaining
+    }
+
+    /// Returns the quantity the resting order showed on the book right after
+    /// this trade, i.e. what is left of its current slice if it is an iceberg
+    /// order, or nothing at all if it is hidden.
+    ///
+    /// This is all anyone else may know of the resting order, so it is what
+    /// public trades report.
+    #[inline]
+    pub const fn maker_remaining(&self) -> Quantity {
+        self.maker_remaining
+    }
+
+    /// Returns the quantity the resting order had left right after this
+    /// trade, hidden reserve included, or zero if the trade was deserialized.
+    ///
+    /// This is meant for reports private to the owner of the resting order.
+    #[inline]
+    pub const fn maker_total_remaining(&self) -> Quantity {
+        self.maker_total_remaining
+    }
+
+    /// Returns the quantity the resting order had filled in total right after
+    /// this trade, including whatever it filled before this very pass, or zero
+    /// if the trade was deserialized.
+    ///
+    /// This is meant for reports private to the owner of the resting order.
+    #[inline]
+    pub const fn maker_filled(&self) -> Quantity {
+        self.maker_filled
+    }
+
+    /// Returns the account that bought, if its order had one, or `None` if
+    /// the trade was deserialized.
+    ///
+    /// This is meant for the book itself and reports private to the owner.
+    #[inline]
+    pub const fn buyer_account(&self) -> Option<AccountId> {
+        self.buyer_account
+    }
+
+    /// Returns the account that sold, if its order had one, or `None` if the
+    /// trade was deserialized.
+    ///
+    /// This is meant for the book itself and reports private to the owner.
+    #[inline]
+    pub const fn seller_account(&self) -> Option<AccountId> {
+        self.seller_account
+    }
+
+    /// Returns the fee charged to the maker.
+    #[inline]
+    pub const fn maker_fee(&self) -> Notional {
+        self.maker_fee
+    }
+
+    /// Returns the fee charged to the taker.
+    #[inline]
+    pub const fn taker_fee(&self) -> Notional {
+        self.taker_fee
+    }
+}
+
+/// Returns the quantity the resting order shows on the book, which is nothing
+/// at all if it is hidden.
+#[inline]
+fn shown(order: &LimitOrder) -> Quantity {
+    if order.is_hidden() {
+        Quantity::default()
+    } else {
+        order.displayed()
+    }
+}
+
+// The serialized amounts are decimal strings.
+#[cfg(all(test, not(feature = "integer")))]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::OrderSide;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let mut maker = LimitOrder::new(
+            OrderId::from_u128(1),
+            OrderSide::Ask,
+            dec!(50000),
+            dec!(2),
+        );
+        let mut taker = Order::builder()
+            .side(OrderSide::Bid)
+            .id(OrderId::from_u128(2))
+            .market(dec!(0.5))
+            .build();
+
+        let trade = Trade::try_new(&mut maker, &mut taker).unwrap();
+
+        let json = serde_json::to_value(trade).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "trade_id": 0,
+                "taker": "00000000-0000-0000-0000-000000000002",
+                "maker": "00000000-0000-0000-0000-000000000001",
+                "quantity": "0.5",
+                "price": "50000",
+                "notional": "25000.0",
+                "taker_remaining": "0.0",
+                "maker_remaining": "1.5",
+            })
+        );
+
+        let parsed: Trade = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.trade_id(), trade.trade_id());
+        assert_eq!(parsed.taker(), trade.taker());
+        assert_eq!(parsed.maker(), trade.maker());
+        assert_eq!(parsed.quantity(), trade.quantity());
+        assert_eq!(parsed.price(), trade.price());
+        assert_eq!(parsed.notional(), trade.notional());
+        assert_eq!(parsed.taker_remaining(), trade.taker_remaining());
+        assert_eq!(parsed.maker_remaining(), trade.maker_remaining());
+        assert_eq!(parsed.maker_filled(), Quantity::default());
+        assert_eq!(parsed.maker_fee(), trade.maker_fee());
+        assert_eq!(parsed.taker_fee(), trade.taker_fee());
+    }
 }
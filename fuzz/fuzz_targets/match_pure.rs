@@ -0,0 +1,152 @@
+//! Drives [`match_pure`] with random sequences of orders, each matched
+//! against the book the previous one left, checking after every step that
+//! the book is not crossed and that quantity is conserved.
+//!
+//! ```sh
+//! cargo +nightly fuzz run match_pure
+//! ```
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use exchange_core::Asset;
+use exchange_core::ExchangeExt;
+use exchange_core::Opposite;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::Quantity;
+use libfuzzer_sys::fuzz_target;
+use matching_engine_algo::match_pure;
+use matching_engine_algo::BookState;
+use matching_engine_algo::Orderbook;
+
+#[derive(Arbitrary, Clone, Copy, Debug)]
+enum Kind {
+    Limit,
+    PostOnly,
+    ImmediateOrCancel,
+    Iceberg(u8),
+    Market,
+    AllOrNone,
+    Participation(u8),
+    MinCounterparty(u8),
+}
+
+#[derive(Arbitrary, Debug)]
+struct Op {
+    bid: bool,
+    kind: Kind,
+    /// How far off 90 the limit price is, up to 20.
+    offset: u8,
+    quantity: u8,
+}
+
+impl Op {
+    fn side(&self) -> OrderSide {
+        match self.bid {
+            true => OrderSide::Bid,
+            false => OrderSide::Ask,
+        }
+    }
+
+    fn limit_price(&self) -> u32 {
+        90 + u32::from(self.offset % 21)
+    }
+
+    fn quantity(&self) -> u32 {
+        u32::from(self.quantity).max(1)
+    }
+
+    fn order(&self, id: u128) -> Order {
+        let builder = Order::builder()
+            .side(self.side())
+            .id(OrderId::from_u128(id));
+        let limit = builder.limit(self.limit_price(), self.quantity());
+
+        match self.kind {
+            Kind::Limit => limit.build(),
+            Kind::PostOnly => limit.post_only().build(),
+            Kind::ImmediateOrCancel => limit.ioc().build(),
+            Kind::Iceberg(display_quantity) => limit
+                .iceberg(u32::from(display_quantity).clamp(1, self.quantity()))
+                .build(),
+            Kind::Market => builder.market(self.quantity()).build(),
+            Kind::AllOrNone => limit.all_or_none().build(),
+            Kind::Participation(max_participation) => limit
+                .build()
+                .with_max_participation(max_participation.clamp(1, 100)),
+            Kind::MinCounterparty(quantity) => limit
+                .build()
+                .with_min_counterparty_qty(Quantity::from(quantity.max(1))),
+        }
+    }
+}
+
+/// Returns the quantity left of every order resting on the given side,
+/// hidden reserves included.
+fn resting(book: &BookState, side: OrderSide) -> Quantity {
+    book.orders()
+        .iter()
+        .filter(|order| order.side() == side)
+        .fold(Quantity::default(), |acc, order| acc + order.remaining())
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut book = BookState::default();
+
+    for (id, op) in ops.iter().enumerate() {
+        let side = op.side();
+        let quantity = Quantity::from(op.quantity());
+        let same_before = resting(&book, side);
+        let opposite_before = resting(&book, side.opposite());
+
+        let (after, trades) = match_pure(book, op.order(id as u128))
+            .expect("every state it returns must be that of a valid book");
+
+        let traded = trades.iter().fold(Quantity::default(), |acc, trade| {
+            assert_eq!(trade.taker(), OrderId::from_u128(id as u128));
+            if !matches!(op.kind, Kind::Market) {
+                match side {
+                    OrderSide::Ask => {
+                        assert!(trade.price() >= op.limit_price().into())
+                    }
+                    OrderSide::Bid => {
+                        assert!(trade.price() <= op.limit_price().into())
+                    }
+                }
+            }
+
+            acc + trade.quantity()
+        });
+        assert!(traded <= quantity);
+
+        // Whatever the opposite side lost was traded, and the incoming order
+        // rests either all it did not trade or, if cancelled, nothing at all.
+        let same_after = resting(&after, side);
+        assert_eq!(opposite_before - resting(&after, side.opposite()), traded);
+        assert!(same_after >= same_before);
+        let rested = same_after - same_before;
+        assert!(rested == Quantity::default() || rested + traded == quantity);
+        match op.kind {
+            Kind::ImmediateOrCancel | Kind::Market => {
+                assert_eq!(rested, Quantity::default())
+            }
+            Kind::AllOrNone => {
+                assert!(traded == Quantity::default() || traded == quantity)
+            }
+            _ => {}
+        }
+
+        // The book it leaves is never crossed.
+        let orderbook = Orderbook::from_snapshot(after.clone())
+            .expect("every state it returns must be that of a valid book");
+        if let (Some(bid), Some(ask)) =
+            (orderbook.best_bid(), orderbook.best_ask())
+        {
+            assert!(bid < ask);
+        }
+
+        book = after;
+    }
+});
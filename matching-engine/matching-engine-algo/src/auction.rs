@@ -0,0 +1,111 @@
+use exchange_core::Algo;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_core::Trade;
+
+use crate::BookEventOf;
+use crate::DefaultExchangeError;
+use crate::MatchingConfig;
+use crate::MatchingReport;
+use crate::MatchingStrategy;
+use crate::RejectReason;
+use crate::Trades;
+
+/// Queues incoming orders without matching them, so that a call auction,
+/// such as an opening or a closing one, crosses them all at once at a single
+/// price with [`Orderbook::cross`](crate::Orderbook::cross).
+///
+/// The book may well be crossed meanwhile. Orders that could never rest on
+/// it, i.e. market and immediate-or-cancel ones, are expired right away.
+///
+/// ```
+/// use matching_engine_algo::AuctionAlgo;
+/// use matching_engine_algo::MatchingAlgo;
+/// use matching_engine_algo::Orderbook;
+///
+/// let mut orderbook = Orderbook::new().with_algo::<AuctionAlgo>();
+/// // Orders are queued until the auction is over...
+/// let cross = orderbook.cross();
+/// // ...and matched as they come afterwards.
+/// let orderbook = orderbook.with_algo::<MatchingAlgo>();
+/// ```
+pub struct AuctionAlgo;
+impl MatchingStrategy for AuctionAlgo {
+    type Algo<O: Asset> = Self;
+}
+
+impl<O, E> Algo<O, E> for AuctionAlgo
+where
+    O: Asset,
+    E: MatchingConfig,
+{
+    type Error = DefaultExchangeError;
+    type Output = MatchingReport<
+        <O as Asset>::Trade,
+        <O as Asset>::OrderStatus,
+        BookEventOf<O>,
+        <O as Asset>::OrderId,
+    >;
+
+    fn matching(
+        exchange: &mut E,
+        mut incoming_order: O,
+    ) -> Result<Self::Output, DefaultExchangeError>
+    where
+        E: Exchange + ExchangeExt,
+        <E as Exchange>::Order: Trade<O>,
+        O: Asset<
+            OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+            OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+            OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+            OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+            OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+            OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+            Trade = <<E as Exchange>::Order as Asset>::Trade,
+        >,
+        O: Clone + TryInto<<E as Exchange>::Order>,
+    {
+        // At-the-close orders wait for the closing auction instead, even
+        // while another auction is on.
+        if incoming_order.is_at_the_close() {
+            return Ok(crate::queue_for_close(exchange, incoming_order));
+        }
+
+        if incoming_order.is_immediate_or_cancel() {
+            incoming_order.expire();
+        }
+
+        let mut events = Vec::new();
+        let mut rejected_reason = None;
+
+        if incoming_order.is_open() {
+            let incoming_order_id = incoming_order.id();
+
+            match incoming_order.clone().try_into() {
+                Ok(order) => {
+                    // SAFETY: This call is safe because the book is meant to
+                    // be crossed while the auction is on, until every
+                    // crossing order is matched by `Orderbook::cross`.
+                    unsafe {
+                        exchange.insert(order);
+                    }
+
+                    events.extend(crate::added(exchange, incoming_order_id));
+                }
+                Err(_) => {
+                    incoming_order.cancel();
+                    rejected_reason = Some(RejectReason::Malformed);
+                }
+            }
+        }
+
+        Ok(MatchingReport {
+            trades: Trades::new(),
+            status: incoming_order.status(),
+            events,
+            cancelled: Vec::new(),
+            rejected_reason,
+        })
+    }
+}
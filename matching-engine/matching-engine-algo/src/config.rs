@@ -0,0 +1,95 @@
+use std::ops::RangeInclusive;
+
+use exchange_core::Asset;
+use exchange_core::ExchangeExt;
+
+/// What to do when an incoming order would trade against a resting order of
+/// the very same account.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SelfTradePrevention {
+    /// Cancels the incoming order, leaving the resting one untouched.
+    #[default]
+    CancelNewest,
+    /// Cancels the resting order and keeps matching the incoming one.
+    CancelOldest,
+    /// Cancels both the incoming and the resting orders.
+    CancelBoth,
+}
+
+/// The least quantity iceberg orders may show at a time, and what to do with
+/// incoming ones asking to show less.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayFloor<Q> {
+    /// Shows the floor instead of what was asked.
+    Bump(Q),
+    /// Rejects the order before it trades.
+    Reject(Q),
+}
+
+impl<Q: Copy> DisplayFloor<Q> {
+    /// Returns the least quantity iceberg orders may show at a time.
+    #[inline]
+    pub fn min(&self) -> Q {
+        match *self {
+            Self::Bump(min) | Self::Reject(min) => min,
+        }
+    }
+}
+
+/// The rules an exchange sets for the algorithms matching on it, beyond what
+/// [`ExchangeExt`] tells of its book, and the ids it gives their trades.
+pub trait MatchingConfig: ExchangeExt {
+    /// Returns how self-trades are prevented.
+    fn self_trade_prevention(&self) -> SelfTradePrevention;
+
+    /// Returns the lot size every traded quantity must be a multiple of, if
+    /// any.
+    fn lot_size(&self) -> Option<<Self::Order as Asset>::OrderQuantity>;
+
+    /// Returns the least quantity iceberg orders may show at a time, if any.
+    fn display_floor(
+        &self,
+    ) -> Option<DisplayFloor<<Self::Order as Asset>::OrderQuantity>>;
+
+    /// Returns the range of limit prices incoming orders must lie within,
+    /// bounds included, if they are collared and there is a reference price
+    /// to collar them around.
+    fn price_collar(
+        &self,
+    ) -> Option<RangeInclusive<<Self::Order as Asset>::OrderPrice>>;
+
+    /// Returns the percentage of what an incoming order takes of a level
+    /// that the pro-rata algorithm splits in proportion to the quantity each
+    /// resting order shows, the rest being allocated by time priority.
+    fn pro_rata_ratio(&self) -> u8;
+
+    /// Returns how much of a post-only, reduce-only order may rest without
+    /// increasing the net position of its account, i.e. what the account
+    /// holds on the opposite side, less what its other such orders on the
+    /// same side already rest.
+    fn reducible(
+        &self,
+        order: &Self::Order,
+    ) -> <Self::Order as Asset>::OrderQuantity;
+
+    /// Cancels the order linked to the given resting one, taking it off the
+    /// exchange, if the latter has traded enough to trigger it, as with
+    /// one-cancels-other pairs.
+    ///
+    /// Matching calls this after every trade with a resting order, before
+    /// that order may be removed, so that its sibling never trades in the
+    /// very same pass.
+    fn cancel_linked(
+        &mut self,
+        order_id: &<Self::Order as Asset>::OrderId,
+    ) -> Option<Self::Order>;
+
+    /// Keeps an at-the-close order apart from the book until the closing
+    /// auction, so that it neither trades nor shows on the book meanwhile.
+    fn queue_for_close(&mut self, order: Self::Order);
+
+    /// Assigns the trade the next id of the exchange, as soon as it is
+    /// executed, which is when the exchange accounts for it too, e.g. in the
+    /// net positions it keeps.
+    fn assign_trade_id(&mut self, trade: &mut <Self::Order as Asset>::Trade);
+}
@@ -2,25 +2,113 @@ use exchange_core::Algo;
 use exchange_core::Asset;
 use exchange_core::Exchange;
 use exchange_core::ExchangeExt;
-use exchange_core::Opposite;
 use exchange_core::Trade;
+use exchange_types::BookEvent;
+use num::Zero;
+use smallvec::SmallVec;
 
 mod orderbook;
-pub use orderbook::Orderbook;
 #[cfg(any(test, feature = "test"))]
 pub use orderbook::__fmt::OrderbookView;
+pub use orderbook::system_clock;
+pub use orderbook::AmendError;
+#[cfg(feature = "serde")]
+pub use orderbook::BookDump;
+pub use orderbook::BookView;
+#[cfg(feature = "sled")]
+pub use orderbook::Checkpoint;
+pub use orderbook::Clock;
+pub use orderbook::Cross;
+pub use orderbook::IncrementError;
+pub use orderbook::InsertError;
+pub use orderbook::LinkError;
+pub use orderbook::OrderView;
+pub use orderbook::Orderbook;
+pub use orderbook::OrderbookSnapshot;
+pub use orderbook::PeekMut;
+#[cfg(feature = "sled")]
+pub use orderbook::PersistError;
+#[cfg(feature = "sled")]
+pub use orderbook::Persistence;
+#[cfg(feature = "sled")]
+pub use orderbook::Recovery;
+pub use orderbook::SeedError;
+pub use orderbook::SnapshotError;
+pub use orderbook::Tiebreak;
 
+mod auction;
+pub use auction::AuctionAlgo;
+mod config;
+pub use config::DisplayFloor;
+pub use config::MatchingConfig;
+pub use config::SelfTradePrevention;
 mod policy;
+mod pro_rata;
+pub use pro_rata::ProRataAlgo;
+mod pure;
+pub use pure::match_pure;
+pub use pure::BookState;
+mod report;
+pub use report::MatchingReport;
+pub use report::RejectReason;
+
+/// Trades executed while matching a single incoming order, in the order they
+/// happened.
+///
+/// Most incoming orders trade against a handful of resting orders at most, so
+/// they are kept inline to spare the allocation.
+pub type Trades<T> = SmallVec<[T; 4]>;
 
+/// The [`BookEvent`] reported while matching orders of type `O`.
+pub type BookEventOf<O> = BookEvent<
+    <O as Asset>::OrderId,
+    <O as Asset>::OrderSide,
+    <O as Asset>::OrderPrice,
+    <O as Asset>::OrderQuantity,
+>;
+
+/// Selects the [`Algo`] an [`Orderbook`] matches incoming orders with.
+///
+/// An orderbook must be able to match any kind of incoming order, which a
+/// single `A: Algo<O, Orderbook<A>>` bound cannot express, so each algorithm
+/// names itself for every order type here instead.
+pub trait MatchingStrategy: Sized {
+    type Algo<O: Asset>: Algo<
+        O,
+        Orderbook<Self>,
+        Output = MatchingReport<
+            <O as Asset>::Trade,
+            <O as Asset>::OrderStatus,
+            BookEventOf<O>,
+            <O as Asset>::OrderId,
+        >,
+        Error = DefaultExchangeError,
+    >;
+}
+
+/// Matches incoming orders against resting ones in price-time priority.
 pub struct MatchingAlgo;
-impl<O> Algo<O> for MatchingAlgo {
+impl MatchingStrategy for MatchingAlgo {
+    type Algo<O: Asset> = Self;
+}
+
+impl<O, E> Algo<O, E> for MatchingAlgo
+where
+    O: Asset,
+    E: MatchingConfig,
+{
     type Error = DefaultExchangeError;
-    type Output = ();
+    type Output = MatchingReport<
+        <O as Asset>::Trade,
+        <O as Asset>::OrderStatus,
+        BookEventOf<O>,
+        <O as Asset>::OrderId,
+    >;
 
-    fn matching<E>(
+    fn matching(
         exchange: &mut E,
         mut incoming_order: O,
-    ) -> Result<(), DefaultExchangeError>
+    ) -> Result<Self::Output, DefaultExchangeError>
     where
         E: Exchange + ExchangeExt,
         <E as Exchange>::Order: Trade<O>,
@@ -31,65 +119,368 @@ impl<O> Algo<O> for MatchingAlgo {
             OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
             OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
             OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+            Trade = <<E as Exchange>::Order as Asset>::Trade,
         >,
-        O: TryInto<<E as Exchange>::Order>,
+        O: Clone + TryInto<<E as Exchange>::Order>,
     {
-        policy::before_policies()
-            .iter()
-            .for_each(|policy| policy.enforce(&mut incoming_order, exchange));
+        // At-the-close orders only ever trade in the closing auction.
+        if incoming_order.is_at_the_close() {
+            return Ok(queue_for_close(exchange, incoming_order));
+        }
+
+        // The first policy to reject the incoming order tells why it was.
+        let mut rejected_reason =
+            policy::before_policies()
+                .iter()
+                .fold(None, |reason, policy| {
+                    reason.or(policy.enforce(&mut incoming_order, exchange))
+                });
+
+        // Only an order that is bound to rest by now is capped to what it may
+        // reduce, if it is reduce-only.
+        rejected_reason = rejected_reason
+            .or_else(|| policy::cap_reduce_only(&mut incoming_order, exchange));
+
+        let self_trade_prevention = exchange.self_trade_prevention();
+        let lot_size = exchange.lot_size();
+        let mut best_price = None;
+        let mut trades = Trades::new();
+        let mut events = Vec::new();
+        let mut cancelled = Vec::new();
+
+        // An all-or-none order that cannot be filled whole right away does
+        // not trade at all, and is cancelled rather than left to rest
+        // crossing the book.
+        rejected_reason = rejected_reason.or_else(|| {
+            policy::cancel_unfillable(&mut incoming_order, exchange)
+        });
+
+        // An order with a maximum participation rate may only take so much
+        // of what it could trade with right away, leaving the rest.
+        let mut participation =
+            policy::participation_cap(&incoming_order, exchange);
 
         while !incoming_order.is_closed() {
-            let Some(mut top_order) =
-                exchange.peek_mut(&incoming_order.side().opposite())
+            if participation.is_some_and(|left| left.is_zero()) {
+                break;
+            }
+
+            // Resting all-or-none orders too large for what is left of the
+            // incoming order are passed over, keeping their priority.
+            let Some(top_order_id) =
+                policy::next_maker(&incoming_order, exchange)
             else {
                 // Since there is no opposite order anymore, we can move on.
                 break;
             };
+            let mut top_order = exchange
+                .get_mut(&top_order_id)
+                .expect("order was just found on the book");
 
-            let Ok(_trade) = top_order.trade(&mut incoming_order) else {
-                // Since incoming order is not matching to top order
-                // anymore, we can also move on.
+            let limit_price = top_order
+                .limit_price()
+                .expect("maker orders always have a limit price");
+            let best_price = *best_price.get_or_insert(limit_price);
+
+            // Market orders may cap how far from the best opposite price they
+            // sweep the book, so they stop short of trading any farther.
+            if policy::prevent_slippage(
+                &mut incoming_order,
+                best_price,
+                limit_price,
+            ) {
+                rejected_reason.get_or_insert(RejectReason::MaxSlippage);
                 break;
-            };
+            }
+
+            let before = shown(&*top_order, limit_price);
+
+            // Orders of the same account must never trade with each other,
+            // so one of them (or both) is cancelled instead.
+            let is_self_trade = policy::prevent_self_trade(
+                self_trade_prevention,
+                &mut incoming_order,
+                &mut *top_order,
+            );
+
+            if is_self_trade {
+                if incoming_order.is_closed() {
+                    rejected_reason.get_or_insert(RejectReason::SelfTrade);
+                }
+            } else {
+                let lots =
+                    incoming_order.remaining().left().and_then(|funds| {
+                        whole_lots::<O>(funds, limit_price, lot_size)
+                    });
+                let up_to = match (lots, participation) {
+                    (Some(lots), Some(left)) => Some(lots.min(left)),
+                    (lots, left) => lots.or(left),
+                };
+                let wanted = shown(&incoming_order, limit_price);
+
+                let trade = match up_to {
+                    Some(quantity) => {
+                        top_order.trade_up_to(&mut incoming_order, quantity)
+                    }
+                    None => top_order.trade(&mut incoming_order),
+                };
+
+                let Ok(trade) = trade else {
+                    // Since incoming order is not matching to top order
+                    // anymore, we can also move on.
+                    break;
+                };
 
-            if top_order.is_closed() {
-                let top_order_id = top_order.id();
+                if let Some(left) = participation.as_mut() {
+                    *left =
+                        *left - (wanted - shown(&incoming_order, limit_price));
+                }
+
+                trades.push(trade);
+            }
+
+            let is_closed = top_order.is_closed();
+            let is_hidden = top_order.is_hidden();
+            let left = shown(&*top_order, limit_price);
+
+            // We must explicity drop to reuse the `exchange`.
+            drop(top_order);
 
-                // We must explicity drop to reuse the `exchange`.
-                drop(top_order);
+            // Its sibling, if linked one-cancels-other, must be gone before
+            // anything may trade with it, and so before the order itself is.
+            let sibling = exchange.cancel_linked(&top_order_id);
 
+            if is_closed {
                 // As long as top order is completed, it can be safely removed
                 // from orderbook.
                 exchange
                     .remove(&top_order_id)
                     .expect("order should be `Some`");
+
+                // Self-trade prevention cancels rather than fills it.
+                if is_self_trade {
+                    cancelled.push(top_order_id);
+                }
+            }
+
+            if !is_hidden {
+                maker_events(
+                    &mut events,
+                    exchange,
+                    top_order_id,
+                    is_closed,
+                    before,
+                    left,
+                );
             }
+
+            linked_cancelled(&mut events, &mut cancelled, sibling);
+        }
+
+        // Trades are given their ids in the order they were executed, now
+        // that the book is no longer borrowed by any resting order.
+        for trade in &mut trades {
+            exchange.assign_trade_id(trade);
         }
 
-        policy::late_policies()
-            .iter()
-            .for_each(|policy| policy.enforce(&mut incoming_order, exchange));
+        rejected_reason = policy::late_policies().iter().fold(
+            rejected_reason,
+            |reason, policy| {
+                reason.or(policy.enforce(&mut incoming_order, exchange))
+            },
+        );
+
+        // Whatever is left of an order capped to its maximum participation
+        // must not rest crossing the orders it was not allowed to take.
+        rejected_reason = rejected_reason.or_else(|| {
+            policy::cancel_capped(&mut incoming_order, participation, exchange)
+        });
+
+        // So must whatever is left of any order crossing only resting orders
+        // it was not eligible to trade with.
+        rejected_reason = rejected_reason.or_else(|| {
+            policy::cancel_ineligible(&mut incoming_order, exchange)
+        });
 
         // If incoming order is not full-filled and open, it must be inserted
         // into the orderbook.
         if incoming_order.is_open() {
-            if let Ok(order) = incoming_order.try_into() {
-                // SAFETY: This call is safe because we ensure that the
-                // 'incoming_order' will enter the order book if, and only if,
-                // all orders on the opposite side that match with it have
-                // already been executed. This is explicit at
-                // `Order::trade(&mut incoming_trade, &mut top_order)` returning
-                // `Err`.
-                unsafe {
-                    exchange.insert(order);
+            let incoming_order_id = incoming_order.id();
+
+            match incoming_order.clone().try_into() {
+                Ok(order) => {
+                    // SAFETY: This call is safe because we ensure that the
+                    // 'incoming_order' will enter the order book if, and only
+                    // if, all orders on the opposite side that match with it
+                    // have already been executed. This is explicit at
+                    // `Order::trade(&mut incoming_trade, &mut top_order)`
+                    // returning `Err`, and an order that would still cross
+                    // those it was not allowed or eligible to trade with is
+                    // cancelled above instead.
+                    unsafe {
+                        exchange.insert(order);
+                    }
+
+                    events.extend(added(exchange, incoming_order_id));
+                }
+                // An order that cannot rest as it is, e.g. a hidden iceberg
+                // one, is cancelled rather than left out of the book silently.
+                Err(_) => {
+                    incoming_order.cancel();
+                    rejected_reason.get_or_insert(RejectReason::Malformed);
                 }
             }
         }
 
-        Ok(())
+        // Market orders are immediate-or-cancel, so whatever is left of them
+        // is closed by now and reported as such, since they could never rest
+        // on the book.
+        let status = incoming_order.status();
+
+        Ok(MatchingReport {
+            trades,
+            status,
+            events,
+            cancelled,
+            rejected_reason,
+        })
+    }
+}
+
+/// Queues an at-the-close order apart from the book until the closing
+/// auction, see [`Orderbook::run_closing_auction`], reporting it as open
+/// without any trade.
+pub(crate) fn queue_for_close<O, E>(
+    exchange: &mut E,
+    mut incoming_order: O,
+) -> MatchingReport<
+    <O as Asset>::Trade,
+    <O as Asset>::OrderStatus,
+    BookEventOf<O>,
+    <O as Asset>::OrderId,
+>
+where
+    E: MatchingConfig,
+    O: Asset + Clone + TryInto<<E as Exchange>::Order>,
+{
+    let mut rejected_reason = None;
+
+    match incoming_order.clone().try_into() {
+        Ok(order) => exchange.queue_for_close(order),
+        Err(_) => {
+            incoming_order.cancel();
+            rejected_reason = Some(RejectReason::Malformed);
+        }
+    }
+
+    MatchingReport {
+        trades: Trades::new(),
+        status: incoming_order.status(),
+        events: Vec::new(),
+        cancelled: Vec::new(),
+        rejected_reason,
     }
 }
 
+/// Returns the quantity an order shows on the book, as priced at
+/// `limit_price`.
+pub(crate) fn shown<O: Asset>(
+    order: &O,
+    limit_price: O::OrderPrice,
+) -> O::OrderQuantity {
+    order
+        .remaining()
+        .either(|funds| funds / limit_price, |quantity| quantity)
+}
+
+/// Returns the most `funds` may buy at `limit_price` in whole lots, or `None`
+/// if there is no lot size.
+///
+/// Whatever funds are too little to buy one more lot are left unspent, so
+/// they are returned once the order expires, as its remaining funds.
+pub(crate) fn whole_lots<O: Asset>(
+    funds: O::OrderNotional,
+    limit_price: O::OrderPrice,
+    lot_size: Option<O::OrderQuantity>,
+) -> Option<O::OrderQuantity> {
+    let lot_size = lot_size?;
+    let quantity = funds / limit_price;
+
+    Some(quantity - quantity % lot_size)
+}
+
+/// Reports the order that was just added to the book, unless it is hidden.
+pub(crate) fn added<E>(
+    exchange: &E,
+    order_id: <<E as Exchange>::Order as Asset>::OrderId,
+) -> Option<BookEventOf<<E as Exchange>::Order>>
+where
+    E: Exchange,
+{
+    let order = exchange.get(&order_id)?;
+
+    if order.is_hidden() {
+        return None;
+    }
+
+    let limit_price = order
+        .limit_price()
+        .expect("resting orders always have a limit price");
+
+    Some(BookEvent::OrderAdded {
+        order_id,
+        side: order.side(),
+        limit_price,
+        quantity: shown(&*order, limit_price),
+    })
+}
+
+/// Reports how a resting order changed on the book, given what it showed
+/// before being traded with, or cancelled, and what it was left with.
+pub(crate) fn maker_events<E>(
+    events: &mut Vec<BookEventOf<<E as Exchange>::Order>>,
+    exchange: &E,
+    order_id: <<E as Exchange>::Order as Asset>::OrderId,
+    is_closed: bool,
+    shown: <<E as Exchange>::Order as Asset>::OrderQuantity,
+    left: <<E as Exchange>::Order as Asset>::OrderQuantity,
+) where
+    E: Exchange,
+{
+    if is_closed {
+        events.push(BookEvent::OrderRemoved { order_id });
+    } else if left.is_zero() {
+        // Only iceberg orders are left open with nothing shown, and they have
+        // just shown their next slice, at the back of their level.
+        events.push(BookEvent::OrderRemoved { order_id });
+        events.extend(added(exchange, order_id));
+    } else if left < shown {
+        events.push(BookEvent::OrderReduced {
+            order_id,
+            delta: shown - left,
+        });
+    }
+}
+
+/// Reports the sibling of a one-cancels-other pair that was just cancelled,
+/// if any.
+pub(crate) fn linked_cancelled<O: Asset>(
+    events: &mut Vec<BookEventOf<O>>,
+    cancelled: &mut Vec<O::OrderId>,
+    sibling: Option<O>,
+) {
+    let Some(sibling) = sibling else {
+        return;
+    };
+
+    let order_id = sibling.id();
+    if !sibling.is_hidden() {
+        events.push(BookEvent::OrderRemoved { order_id });
+    }
+
+    cancelled.push(order_id);
+}
+
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum DefaultExchangeError {}
@@ -1,23 +1,135 @@
+mod auction;
+pub use auction::Cross;
+mod batch;
+#[cfg(feature = "serde")]
+mod dump;
+#[cfg(feature = "serde")]
+pub use dump::BookDump;
 mod index;
-
+mod ladder;
+mod peek;
+pub use peek::PeekMut;
+#[cfg(feature = "sled")]
+mod persist;
+#[cfg(feature = "sled")]
+pub use persist::Checkpoint;
+#[cfg(feature = "sled")]
+pub use persist::PersistError;
+#[cfg(feature = "sled")]
+pub use persist::Persistence;
+#[cfg(feature = "sled")]
+pub use persist::Recovery;
+mod seed;
+pub use seed::SeedError;
+mod snapshot;
+pub use snapshot::OrderbookSnapshot;
+pub use snapshot::SnapshotError;
+mod view;
+use std::cmp::Ordering;
 use std::collections::btree_map::Entry;
-use std::collections::VecDeque;
+use std::collections::HashMap;
+use std::iter;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::RangeInclusive;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
+use either::Either;
+use exchange_core::Algo;
 use exchange_core::Asset;
+use exchange_core::Depth;
 use exchange_core::Exchange;
 use exchange_core::ExchangeExt;
+use exchange_core::Opposite;
+use exchange_types::error::OrderError;
+use exchange_types::AccountId;
 use exchange_types::LimitOrder;
 use exchange_types::Order;
+use exchange_types::OrderId;
 use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::Price;
+use exchange_types::PriceCollar;
+use exchange_types::Quantity;
+use exchange_types::Trade;
 use num::Zero;
+use thiserror::Error;
+pub use view::BookView;
+pub use view::OrderView;
 
+use crate::orderbook::index::Level;
+use crate::orderbook::index::LinkedOrders;
+use crate::orderbook::index::OrdersByAccount;
 use crate::orderbook::index::OrdersById;
 use crate::orderbook::index::OrdersBySide;
+use crate::policy;
+use crate::BookEventOf;
+use crate::DisplayFloor;
 use crate::MatchingAlgo;
+use crate::MatchingConfig;
+use crate::MatchingReport;
+use crate::MatchingStrategy;
+use crate::RejectReason;
+use crate::SelfTradePrevention;
+use crate::Trades;
 
-pub struct Orderbook {
+/// An orderbook that matches incoming orders with the algorithm selected by
+/// `A`, [`MatchingAlgo`] by default.
+///
+/// Hidden orders rest and trade just like any other order, after every
+/// displayed order at the same price, but are left out of everything that
+/// shows the book, such as [`Exchange::iter`], [`ExchangeExt::depth`] and
+/// [`ExchangeExt::len`]. Only [`Exchange::peek`], [`Exchange::peek_mut`],
+/// [`Exchange::get_mut`], [`Exchange::pop`] and
+/// [`Exchange::iter_with_hidden`], which matching is built upon, ever reach
+/// them.
+pub struct Orderbook<A = MatchingAlgo> {
     orders_by_id: OrdersById<LimitOrder>,
     orders_by_side: OrdersBySide<LimitOrder>,
+    orders_by_account: OrdersByAccount,
+    linked_orders: LinkedOrders,
+    self_trade_prevention: SelfTradePrevention,
+    /// How orders queued at the same price and sequence are ranked.
+    tiebreak: Tiebreak,
+    /// How far off the mid price incoming limit orders may be, if set.
+    price_collar: Option<PriceCollar>,
+    /// What the collar is centred on instead of the mid price, if set.
+    reference_price: Option<Price>,
+    /// Every limit price must be a multiple of it, if set.
+    tick_size: Option<Price>,
+    /// Every quantity must be a multiple of it, if set.
+    lot_size: Option<Quantity>,
+    /// The least iceberg orders may show at a time, if set.
+    display_floor: Option<DisplayFloor<Quantity>>,
+    /// The percentage of a level the pro-rata algorithm splits pro rata.
+    pro_rata_ratio: u8,
+    /// Number of displayed asks, kept up to date on every change to the book.
+    ask_count: usize,
+    /// Number of displayed bids, kept up to date on every change to the book.
+    bid_count: usize,
+    /// Quantity shown by resting asks, kept up to date on every change to the
+    /// book.
+    ask_volume: Quantity,
+    /// Quantity shown by resting bids, kept up to date on every change to the
+    /// book.
+    bid_volume: Quantity,
+    /// The sequence the next queued order gets.
+    next_sequence: u64,
+    /// The price of the last trade, kept once the trade itself is gone.
+    last_trade_price: Option<Price>,
+    /// What orders are stamped with as they are queued, if set.
+    clock: Option<Clock>,
+    /// The side each account holds a net position on, and how much of it,
+    /// which post-only reduce-only orders are capped to. Kept up to date on
+    /// every trade.
+    positions: HashMap<AccountId, (OrderSide, Quantity)>,
+    /// At-the-close orders, in the order they came in, kept apart from the
+    /// book until the closing auction.
+    closing: Vec<LimitOrder>,
+    #[cfg(feature = "sled")]
+    storage: Option<persist::Storage>,
+    algo: PhantomData<A>,
 }
 
 impl Orderbook {
@@ -27,21 +139,826 @@ impl Orderbook {
     }
 }
 
-impl Default for Orderbook {
+impl<A> Orderbook<A> {
+    /// Switches the algorithm used to match incoming orders, keeping every
+    /// resting order as it is.
+    ///
+    /// ```
+    /// use matching_engine_algo::Orderbook;
+    /// use matching_engine_algo::ProRataAlgo;
+    ///
+    /// let orderbook = Orderbook::new().with_algo::<ProRataAlgo>();
+    /// ```
+    #[inline]
+    pub fn with_algo<B>(self) -> Orderbook<B> {
+        Orderbook {
+            orders_by_id: self.orders_by_id,
+            orders_by_side: self.orders_by_side,
+            orders_by_account: self.orders_by_account,
+            linked_orders: self.linked_orders,
+            self_trade_prevention: self.self_trade_prevention,
+            tiebreak: self.tiebreak,
+            price_collar: self.price_collar,
+            reference_price: self.reference_price,
+            tick_size: self.tick_size,
+            lot_size: self.lot_size,
+            display_floor: self.display_floor,
+            pro_rata_ratio: self.pro_rata_ratio,
+            ask_count: self.ask_count,
+            bid_count: self.bid_count,
+            ask_volume: self.ask_volume,
+            bid_volume: self.bid_volume,
+            next_sequence: self.next_sequence,
+            last_trade_price: self.last_trade_price,
+            clock: self.clock,
+            positions: self.positions,
+            closing: self.closing,
+            #[cfg(feature = "sled")]
+            storage: self.storage,
+            algo: PhantomData,
+        }
+    }
+
+    /// Sets how orders of the same account are kept from trading with each
+    /// other.
+    #[inline]
+    pub fn with_self_trade_prevention(
+        mut self,
+        self_trade_prevention: SelfTradePrevention,
+    ) -> Self {
+        self.self_trade_prevention = self_trade_prevention;
+        self
+    }
+
+    /// Sets how orders queued at the same price with the same sequence, such
+    /// as those restored from a snapshot taken before sequences were kept,
+    /// are ranked against each other.
+    ///
+    /// Orders already resting are ranked again right away, so this may just
+    /// as well be set once the book is restored.
+    pub fn with_tiebreak(mut self, tiebreak: Tiebreak) -> Self {
+        self.tiebreak = tiebreak;
+
+        let orders_by_id = &self.orders_by_id;
+        let order = |order_id: &OrderId| {
+            orders_by_id
+                .get(order_id)
+                .expect("every order in tree must also be in index")
+        };
+
+        for side in [OrderSide::Ask, OrderSide::Bid] {
+            for level in self.orders_by_side[side].values_mut() {
+                level.sort_by(|a, b| {
+                    let (a, b) = (order(a), order(b));
+
+                    a.sequence()
+                        .cmp(&b.sequence())
+                        .then_with(|| tiebreak.cmp(a, b))
+                });
+            }
+        }
+
+        self
+    }
+
+    #[inline]
+    pub fn tiebreak(&self) -> Tiebreak {
+        self.tiebreak
+    }
+
+    /// Sets the clock orders are stamped with as they are first queued, see
+    /// [`LimitOrder::created_at`].
+    ///
+    /// By default, there is none, so orders are left unstamped and the book
+    /// never depends on the time of day.
+    #[inline]
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Stamps the order with the current time, unless it already was, e.g.
+    /// when restored from a snapshot, or there is no clock. That is when it
+    /// is accepted, so a good-for-duration order starts its duration too.
+    #[inline]
+    fn stamp(&self, order: &mut LimitOrder) {
+        if let Some(clock) = self.clock.filter(|_| order.created_at() == 0) {
+            let now = clock();
+            order.set_created_at(now);
+            order.start_duration(now);
+        }
+    }
+
+    /// Sets the net position of the account, i.e. how much it holds long, if
+    /// `side` is [`OrderSide::Bid`], or short, if [`OrderSide::Ask`].
+    ///
+    /// Orders that are both post-only and reduce-only rest rather than being
+    /// cancelled, though only as much of them as reduces the position. One
+    /// that would cross is rejected as post-only first, whatever its size,
+    /// and only then is one bound to rest capped, or rejected as reduce-only
+    /// if there is nothing left for it to reduce. The book keeps positions
+    /// up to date with every trade of an order that has an account, so they
+    /// only need to be set for what was held before, or traded elsewhere.
+    #[inline]
+    pub fn set_position(
+        &mut self,
+        account_id: AccountId,
+        side: OrderSide,
+        quantity: Quantity,
+    ) {
+        self.positions.insert(account_id, (side, quantity));
+    }
+
+    /// Returns the side the account holds a net position on, and how much of
+    /// it, if it holds any.
+    #[inline]
+    pub fn position(
+        &self,
+        account_id: &AccountId,
+    ) -> Option<(OrderSide, Quantity)> {
+        self.positions.get(account_id).copied()
+    }
+
+    /// Moves the net positions of both accounts of the trade, if they have
+    /// one, by the quantity traded: the buyer's towards long, the seller's
+    /// towards short. A position traded down to nothing is dropped.
+    fn settle(&mut self, trade: &Trade) {
+        for (account_id, side) in [
+            (trade.buyer_account(), OrderSide::Bid),
+            (trade.seller_account(), OrderSide::Ask),
+        ] {
+            let Some(account_id) = account_id else {
+                continue;
+            };
+
+            let quantity = trade.quantity();
+            let position = match self.positions.get(&account_id).copied() {
+                None => (side, quantity),
+                Some((held_side, held)) if held_side == side => {
+                    (side, held + quantity)
+                }
+                Some((held_side, held)) => match held.cmp(&quantity) {
+                    Ordering::Greater => (held_side, held - quantity),
+                    Ordering::Equal => {
+                        self.positions.remove(&account_id);
+                        continue;
+                    }
+                    Ordering::Less => (side, quantity - held),
+                },
+            };
+            self.positions.insert(account_id, position);
+        }
+    }
+
+    /// Sets how far off the mid price incoming limit orders may be priced,
+    /// cancelling those beyond it before they match.
+    ///
+    /// There is no mid price to collar around unless both sides rest some
+    /// displayed order, so until then, any price is accepted, unless a
+    /// reference price is set, see [`Orderbook::set_reference_price`].
+    #[inline]
+    pub fn with_price_collar(mut self, price_collar: PriceCollar) -> Self {
+        self.price_collar = Some(price_collar);
+        self
+    }
+
+    /// Sets the reference price the collar is centred on instead of the mid
+    /// price, e.g. an index fed by another system, which may then be set
+    /// again as it changes.
+    ///
+    /// Such prices often come in as floats, so a collar fed by them should
+    /// be given a tolerance, see [`PriceCollar::with_tolerance`].
+    #[inline]
+    pub fn set_reference_price(&mut self, reference_price: Price) {
+        self.reference_price = Some(reference_price);
+    }
+
+    /// Sets the tick size every limit price must be a multiple of.
+    ///
+    /// By default, any price is accepted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tick_size` is not positive.
+    #[inline]
+    pub fn with_tick_size(mut self, tick_size: Price) -> Self {
+        assert!(tick_size > Price::zero(), "tick size must be positive");
+        self.tick_size = Some(tick_size);
+        self
+    }
+
+    /// Sets the lot size every quantity must be a multiple of.
+    ///
+    /// By default, any quantity is accepted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lot_size` is not positive.
+    #[inline]
+    pub fn with_lot_size(mut self, lot_size: Quantity) -> Self {
+        assert!(lot_size > Quantity::zero(), "lot size must be positive");
+        self.lot_size = Some(lot_size);
+        self
+    }
+
+    /// Sets the least quantity incoming iceberg orders may show at a time,
+    /// along with whether those asking to show less are bumped up to it or
+    /// rejected.
+    ///
+    /// By default, any display quantity is accepted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the floor is not positive.
+    #[inline]
+    pub fn with_display_floor(
+        mut self,
+        display_floor: DisplayFloor<Quantity>,
+    ) -> Self {
+        assert!(
+            display_floor.min() > Quantity::zero(),
+            "display floor must be positive"
+        );
+        self.display_floor = Some(display_floor);
+        self
+    }
+
+    /// Sets the percentage of what an incoming order takes of a level that
+    /// [`ProRataAlgo`](crate::ProRataAlgo) splits in proportion to the
+    /// quantity each resting order shows, allocating the rest by time
+    /// priority first, e.g. 60 to split 60% pro rata and fill 40% FIFO.
+    ///
+    /// By default, the whole of it is split pro rata. Other algorithms do not
+    /// care.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pro_rata_ratio` is over 100.
+    #[inline]
+    pub fn with_pro_rata_ratio(mut self, pro_rata_ratio: u8) -> Self {
+        assert!(pro_rata_ratio <= 100, "pro-rata ratio must be a percentage");
+        self.pro_rata_ratio = pro_rata_ratio;
+        self
+    }
+
+    #[inline]
+    pub fn tick_size(&self) -> Option<Price> {
+        self.tick_size
+    }
+
+    #[inline]
+    pub fn lot_size(&self) -> Option<Quantity> {
+        self.lot_size
+    }
+
+    /// Checks that the limit price and quantity of an incoming order are
+    /// multiples of the tick and lot sizes, respectively.
+    ///
+    /// Orders priced by funds have no quantity to check, just like market
+    /// orders have no limit price.
+    pub fn validate(&self, order: &Order) -> Result<(), IncrementError> {
+        if let Some(limit_price) = order.limit_price() {
+            self.validate_price(limit_price)?;
+        }
+
+        if let Some(quantity) = order.remaining().right() {
+            self.validate_quantity(quantity)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_price(&self, price: Price) -> Result<(), IncrementError> {
+        match self.tick_size {
+            Some(tick_size) if !(price % tick_size).is_zero() => {
+                Err(IncrementError::Tick { price, tick_size })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_quantity(
+        &self,
+        quantity: Quantity,
+    ) -> Result<(), IncrementError> {
+        match self.lot_size {
+            Some(lot_size) if !(quantity % lot_size).is_zero() => {
+                Err(IncrementError::Lot { quantity, lot_size })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the order with the given id, if there is any, be it resting
+    /// on the book or queued for the closing auction, see
+    /// [`Orderbook::closing`].
+    #[inline]
+    pub fn get(&self, order_id: &OrderId) -> Option<&LimitOrder> {
+        self.orders_by_id.get(order_id).or_else(|| {
+            self.closing.iter().find(|order| &order.id() == order_id)
+        })
+    }
+
+    /// Returns the at-the-close orders queued for the closing auction, in
+    /// the order they came in, none of which is on the book yet.
+    #[inline]
+    pub fn closing(&self) -> &[LimitOrder] {
+        &self.closing
+    }
+
+    /// Links two resting orders as a one-cancels-other pair: once either of
+    /// them has filled `cancel_after`, or anything at all if not set, the
+    /// other one is cancelled, before anything else may trade with it.
+    ///
+    /// Removing either order, e.g. deleting it, unlinks the other one, which
+    /// is then left resting on its own. Linked orders may have their quantity
+    /// amended, though not their price, see [`Orderbook::amend`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving both orders untouched, unless both rest on
+    /// the book, unlinked, and are different orders.
+    pub fn link(
+        &mut self,
+        first: &OrderId,
+        second: &OrderId,
+        cancel_after: Option<Quantity>,
+    ) -> Result<(), LinkError> {
+        if first == second {
+            return Err(LinkError::SameOrder { order_id: *first });
+        }
+
+        for order_id in [first, second] {
+            if !self.orders_by_id.contains_key(order_id) {
+                return Err(LinkError::NotFound {
+                    order_id: *order_id,
+                });
+            }
+
+            if self.linked_orders.contains_key(order_id) {
+                return Err(LinkError::AlreadyLinked {
+                    order_id: *order_id,
+                });
+            }
+        }
+
+        self.linked_orders.insert(*first, *second, cancel_after);
+
+        #[cfg(feature = "sled")]
+        if let Some(storage) = self.storage.as_mut() {
+            storage.link(first, second, cancel_after);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the order linked to the given one, one-cancels-other, if any.
+    #[inline]
+    pub fn linked(&self, order_id: &OrderId) -> Option<OrderId> {
+        Some(self.linked_orders.get(order_id)?.sibling)
+    }
+
+    /// Unlinks the given order from its sibling, if it has any.
+    fn unlink(&mut self, order_id: &OrderId) {
+        #[cfg_attr(not(feature = "sled"), allow(unused_variables))]
+        let Some(link) = self.linked_orders.remove(order_id) else {
+            return;
+        };
+
+        #[cfg(feature = "sled")]
+        if let Some(storage) = self.storage.as_mut() {
+            storage.unlink(order_id, &link.sibling);
+        }
+    }
+
+    /// Returns the running counters of the given side.
+    #[inline]
+    fn counters(&mut self, side: &OrderSide) -> (&mut usize, &mut Quantity) {
+        match side {
+            OrderSide::Ask => (&mut self.ask_count, &mut self.ask_volume),
+            OrderSide::Bid => (&mut self.bid_count, &mut self.bid_volume),
+        }
+    }
+
+    /// Accounts for an order that just entered the book.
+    ///
+    /// Hidden orders are indexed by account, but left out of the counters.
+    #[inline]
+    fn track(&mut self, order: &LimitOrder) {
+        self.orders_by_account.insert(order);
+
+        if order.is_hidden() {
+            return;
+        }
+
+        let (count, volume) = self.counters(&order.side());
+        *count += 1;
+        *volume += order.displayed();
+    }
+
+    /// Accounts for an order that just left the book.
+    #[inline]
+    fn untrack(&mut self, order: &LimitOrder) {
+        self.orders_by_account.remove(order);
+
+        if order.is_hidden() {
+            return;
+        }
+
+        let (count, volume) = self.counters(&order.side());
+        *count -= 1;
+        *volume -= order.displayed();
+    }
+}
+
+impl<A: MatchingStrategy> Orderbook<A> {
+    /// Amends the limit price and total quantity of a resting order.
+    ///
+    /// Reducing the quantity alone keeps the order's time priority, whereas
+    /// increasing what it shows sends it to the back of its level, and
+    /// changing its price sends it to the back of the new level, as if it was
+    /// a new order, though it keeps the time it was first queued at. A
+    /// repriced order that crosses the opposite side is matched against it
+    /// before resting, just like an incoming order, and what that resulted in
+    /// is reported just the same.
+    ///
+    /// A repriced iceberg order takes its hidden reserve along, and shows a
+    /// whole slice again at the back of its new level. Should it cross, it
+    /// takes liquidity with all it has left, not just the slice it showed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the orderbook untouched, if there is no
+    /// resting order with the given id, if the new price or quantity is off
+    /// the tick or lot size, if the new quantity does not exceed what was
+    /// already filled, if a reduce-only order would grow past what it
+    /// reduces, if the order is linked to another one and would be repriced,
+    /// or if the repriced order would be rejected, e.g. for being post-only
+    /// and crossing the opposite side.
+    pub fn amend(
+        &mut self,
+        order_id: &OrderId,
+        limit_price: Price,
+        quantity: Quantity,
+    ) -> Result<
+        MatchingReport<Trade, OrderStatus, BookEventOf<Order>, OrderId>,
+        AmendError,
+    > {
+        self.validate_price(limit_price)?;
+        self.validate_quantity(quantity)?;
+
+        // Amending a copy validates the new parameters before the orderbook
+        // is touched at all.
+        let mut amended = *self
+            .orders_by_id
+            .get(order_id)
+            .ok_or(AmendError::NotFound)?;
+        amended.amend(limit_price, quantity)?;
+
+        // A reduce-only order may never grow past what it reduces.
+        if amended.is_reduce_only()
+            && amended.remaining() > self.reducible(&amended)
+        {
+            return Err(AmendError::Rejected(RejectReason::ReduceOnly));
+        }
+
+        let order = self
+            .orders_by_id
+            .get_mut(order_id)
+            .expect("order was just found in the index");
+
+        if order.limit_price() == amended.limit_price() {
+            let increased = amended.displayed() > order.displayed();
+            let shown = amended.displayed() - order.displayed();
+
+            #[cfg(feature = "sled")]
+            let previous = order.sequence();
+
+            // Showing more must not jump the queue, so the order goes to the
+            // back of its level, whereas showing less keeps its priority.
+            if increased {
+                amended.set_sequence(self.next_sequence);
+                self.next_sequence += 1;
+
+                let level = self.orders_by_side[amended.side()]
+                    .get_mut(&limit_price)
+                    .expect("every order in index must also be in tree");
+                level.remove(order_id);
+                level.push_back(*order_id, amended.is_hidden());
+            }
+
+            *order = amended;
+            if !amended.is_hidden() {
+                *self.counters(&amended.side()).1 += shown;
+            }
+
+            #[cfg(feature = "sled")]
+            if let Some(storage) = self.storage.as_mut() {
+                match increased {
+                    true => storage.push_back(&amended, Some(previous)),
+                    false => storage.update(&amended),
+                }
+            }
+
+            return Ok(MatchingReport {
+                trades: Trades::new(),
+                status: amended.status(),
+                events: Vec::new(),
+                cancelled: Vec::new(),
+                rejected_reason: None,
+            });
+        }
+
+        // Repricing takes the order off the book, which would unlink it from
+        // its one-cancels-other sibling, leaving both free to fill.
+        if self.linked_orders.contains_key(order_id) {
+            return Err(AmendError::Linked);
+        }
+
+        // A repriced order is only taken out of the book once it is known that
+        // it will not be rejected on its way back in.
+        let mut incoming_order = Order::from(amended);
+        if let Some(reason) = policy::before_policies()
+            .iter()
+            .find_map(|policy| policy.enforce(&mut incoming_order, self))
+        {
+            return Err(AmendError::Rejected(reason));
+        }
+
+        self.remove(order_id)
+            .expect("order was just found in the index");
+
+        let report = self
+            .matching(Order::from(amended))
+            .unwrap_or_else(|error| match error {});
+
+        // Whatever is left rests at the back of its new level, though it was
+        // still first queued back when it was placed.
+        if let Some(order) = self.orders_by_id.get_mut(order_id) {
+            order.set_created_at(amended.created_at());
+
+            #[cfg(feature = "sled")]
+            if let Some(storage) = self.storage.as_mut() {
+                storage.update(order);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Removes every resting order that is expired by `now`, a unix timestamp
+    /// in seconds, returning them with the `Expired` status.
+    ///
+    /// Only good-till-date orders ever expire, and they are returned in the
+    /// order they were queued. This is meant to be called periodically by
+    /// whoever drives the orderbook.
+    pub fn expire(&mut self, now: u64) -> Vec<Order> {
+        let mut expired = self
+            .orders_by_id
+            .values()
+            .filter(|order| order.is_expired(now))
+            .map(|order| (order.sequence(), order.id()))
+            .collect::<Vec<_>>();
+
+        // The index is a hash map, so it alone would make the output differ
+        // from one run to the next.
+        expired.sort_unstable();
+
+        expired
+            .iter()
+            .map(|(_, order_id)| {
+                let mut order = self
+                    .remove(order_id)
+                    .expect("order was just found in the index");
+                order.expire();
+
+                Order::from(order)
+            })
+            .collect()
+    }
+
+    /// Cancels every resting order of the given side, returning them in
+    /// price-time priority.
+    pub fn cancel_all_side(&mut self, side: &OrderSide) -> Vec<Order> {
+        // Taking the whole tree at once leaves no level behind at all.
+        let levels = mem::take(&mut *self.orders_by_side[side]);
+        let (count, volume) = self.counters(side);
+        *count = 0;
+        *volume = Quantity::zero();
+
+        let levels = match side {
+            OrderSide::Ask => Either::Left(levels.into_values()),
+            OrderSide::Bid => Either::Right(levels.into_values().rev()),
+        };
+
+        levels
+            .flatten()
+            .map(|order_id| {
+                let mut order = self.orders_by_id.remove(&order_id).expect(
+                    "every order that lives in tree must also be in the index",
+                );
+                order.cancel();
+
+                self.orders_by_account.remove(&order);
+                self.unlink(&order_id);
+
+                #[cfg(feature = "sled")]
+                if let Some(storage) = self.storage.as_mut() {
+                    storage.remove(&order);
+                }
+
+                Order::from(order)
+            })
+            .collect()
+    }
+
+    /// Cancels every resting order, returning them in price-time priority,
+    /// asks first.
+    pub fn cancel_all(&mut self) -> Vec<Order> {
+        let mut cancelled = self.cancel_all_side(&OrderSide::Ask);
+        cancelled.append(&mut self.cancel_all_side(&OrderSide::Bid));
+
+        debug_assert!(self.orders_by_id.is_empty());
+
+        cancelled
+    }
+
+    /// Moves every resting order out of the book, in price-time priority,
+    /// asks first, e.g. to hand the book over to another engine.
+    ///
+    /// Unlike [`Orderbook::cancel_all`], orders are left just as they rested,
+    /// status included. The book is emptied right away, whether the iterator
+    /// is consumed or not, and every index it kept is given up rather than
+    /// cleared, so that no capacity is held on to. Anything else, such as
+    /// the last trade price or the sequence the next queued order gets, is
+    /// kept as it is.
+    pub fn drain(&mut self) -> impl Iterator<Item = Order> {
+        let asks = mem::take(&mut *self.orders_by_side[OrderSide::Ask]);
+        let bids = mem::take(&mut *self.orders_by_side[OrderSide::Bid]);
+        let mut orders_by_id = mem::take(&mut self.orders_by_id);
+        self.orders_by_account = OrdersByAccount::default();
+        self.linked_orders = LinkedOrders::default();
+
+        for side in [OrderSide::Ask, OrderSide::Bid] {
+            let (count, volume) = self.counters(&side);
+            *count = 0;
+            *volume = Quantity::zero();
+        }
+
+        #[cfg(feature = "sled")]
+        if let Some(storage) = self.storage.as_mut() {
+            orders_by_id
+                .values()
+                .for_each(|order| storage.remove(order));
+            storage.unlink_all();
+        }
+
+        asks.into_values()
+            .chain(bids.into_values().rev())
+            .flatten()
+            .map(move |order_id| {
+                orders_by_id
+                    .remove(&order_id)
+                    .map(Order::from)
+                    .expect("every order in tree must also be in the index")
+            })
+    }
+
+    /// Cancels every resting order of the given account, e.g. once it
+    /// disconnects, returning them in the order they were queued.
+    pub fn cancel_by_account(&mut self, account_id: &AccountId) -> Vec<Order> {
+        let order_ids = self
+            .orders_by_account
+            .get(account_id)
+            .map(|order_ids| order_ids.iter().copied().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let mut cancelled = order_ids
+            .iter()
+            .map(|order_id| {
+                let mut order = self
+                    .remove(order_id)
+                    .expect("every indexed order must rest on the book");
+                order.cancel();
+
+                order
+            })
+            .collect::<Vec<_>>();
+
+        cancelled.sort_by_key(LimitOrder::sequence);
+
+        cancelled.into_iter().map(Order::from).collect()
+    }
+
+    /// Inserts an order into the book, unlike [`Exchange::insert`], checking
+    /// first that it neither reuses the id of a resting or queued order nor
+    /// overlaps the opposite side, since either would leave the book
+    /// corrupted.
+    pub fn insert_checked(
+        &mut self,
+        order: LimitOrder,
+    ) -> Result<(), InsertError> {
+        let order_id = order.id();
+        if self.get(&order_id).is_some() {
+            return Err(InsertError::Duplicate { order_id });
+        }
+
+        if self.crosses(&order) {
+            return Err(InsertError::Crossed);
+        }
+
+        // SAFETY: we just checked that the order does not overlap the
+        // opposite side.
+        unsafe { self.insert(order) };
+
+        Ok(())
+    }
+
+    /// Returns an iterator over the price levels of the given side, from the
+    /// most to the least relevant one, along with the total remaining
+    /// quantity shown at each level. Hidden iceberg reserves are left out.
+    #[inline]
+    pub(crate) fn levels<'a>(
+        &'a self,
+        side: &'a OrderSide,
+    ) -> impl Iterator<
+        Item = (
+            <LimitOrder as Asset>::OrderPrice,
+            <LimitOrder as Asset>::OrderQuantity,
+        ),
+    > + 'a {
+        self.level_summary(side)
+            .map(|(limit_price, _, remaining)| (limit_price, remaining))
+    }
+
+    /// Returns `true` if the order would overlap the opposite side, were it
+    /// inserted as is.
+    #[inline]
+    fn crosses(&self, order: &LimitOrder) -> bool {
+        let Some(limit_price) = order.limit_price() else {
+            return true;
+        };
+
+        let Some(best_price) = self
+            .peek(&order.side().opposite())
+            .and_then(LimitOrder::limit_price)
+        else {
+            return false;
+        };
+
+        match order.side() {
+            OrderSide::Ask => limit_price <= best_price,
+            OrderSide::Bid => limit_price >= best_price,
+        }
+    }
+}
+
+impl<A> Default for Orderbook<A> {
     #[inline]
     fn default() -> Self {
         Self {
             orders_by_id: Default::default(),
             orders_by_side: Default::default(),
+            orders_by_account: Default::default(),
+            linked_orders: Default::default(),
+            self_trade_prevention: Default::default(),
+            tiebreak: Default::default(),
+            price_collar: None,
+            reference_price: None,
+            tick_size: None,
+            lot_size: None,
+            display_floor: None,
+            pro_rata_ratio: 100,
+            ask_count: 0,
+            bid_count: 0,
+            ask_volume: Quantity::zero(),
+            bid_volume: Quantity::zero(),
+            next_sequence: 0,
+            last_trade_price: None,
+            clock: None,
+            positions: HashMap::new(),
+            closing: Vec::new(),
+            #[cfg(feature = "sled")]
+            storage: None,
+            algo: PhantomData,
         }
     }
 }
 
-impl Exchange for Orderbook {
-    type Algo<O> = MatchingAlgo;
+impl<A: MatchingStrategy> Exchange for Orderbook<A> {
+    type Algo<O>
+        = A::Algo<O>
+    where
+        O: Asset;
     type Order = LimitOrder;
-    type OrderRef<'e> = &'e LimitOrder where Self: 'e;
-    type OrderRefMut<'e> = &'e mut LimitOrder where Self: 'e;
+    type OrderRef<'e>
+        = &'e LimitOrder
+    where
+        Self: 'e;
+    type OrderRefMut<'e>
+        = PeekMut<'e>
+    where
+        Self: 'e;
 
     #[inline]
     fn iter(
@@ -58,16 +975,43 @@ impl Exchange for Orderbook {
         self.orders_by_side.iter(side).map(order_id_to_order)
     }
 
-    unsafe fn insert(&mut self, order: Self::Order) {
+    #[inline]
+    fn iter_with_hidden(
+        &self,
+        side: &<Self::Order as Asset>::OrderSide,
+    ) -> impl Iterator<Item = Self::OrderRef<'_>> + '_ {
+        self.orders_by_side.iter_with_hidden(side).map(|order_id| {
+            self.orders_by_id
+                .get(order_id)
+                .expect("every order in tree must also be in index")
+        })
+    }
+
+    unsafe fn insert(&mut self, mut order: Self::Order) {
+        debug_assert!(
+            !self.orders_by_id.contains_key(&order.id()),
+            "order ids must be unique among resting orders"
+        );
+
+        order.set_sequence(self.next_sequence);
+        self.next_sequence += 1;
+        self.stamp(&mut order);
+
         self.orders_by_side[order.side()]
             .entry(
                 order
                     .limit_price()
                     .expect("bookable orders must have a limit price"),
             )
-            .or_insert_with(|| VecDeque::with_capacity(8))
-            .push_back(order.id());
+            .or_insert_with(|| Level::with_capacity(8))
+            .push_back(order.id(), order.is_hidden());
+
+        #[cfg(feature = "sled")]
+        if let Some(storage) = self.storage.as_mut() {
+            storage.push_back(&order, None);
+        }
 
+        self.track(&order);
         self.orders_by_id.insert(order.id(), order);
     }
 
@@ -75,13 +1019,25 @@ impl Exchange for Orderbook {
         &mut self,
         order_id: &<Self::Order as Asset>::OrderId,
     ) -> Option<Self::Order> {
-        let order = self.orders_by_id.remove(order_id)?;
+        let Some(order) = self.orders_by_id.remove(order_id) else {
+            // At-the-close orders are not on the book until the closing
+            // auction, yet they may be cancelled all the same.
+            let index = self
+                .closing
+                .iter()
+                .position(|order| &order.id() == order_id)?;
+
+            return Some(self.closing.remove(index));
+        };
 
         assert!(
             &order.id() == order_id,
             "order id must be the same; something is wrong otherwise"
         );
 
+        // Unlike `pop`, this takes the order off the book for good.
+        self.unlink(order_id);
+
         let limit_price = order
             .limit_price()
             .expect("bookable orders must have a limit price");
@@ -96,11 +1052,7 @@ impl Exchange for Orderbook {
         let order_id = if level.get().len() == 1 {
             level.remove().pop_front()
         } else {
-            level
-                .get()
-                .iter()
-                .position(|&order_id| order.id() == order_id)
-                .and_then(|index| level.get_mut().remove(index))
+            level.get_mut().remove(&order.id())
         }
         .expect("indexed orders must be in the book tree");
 
@@ -109,9 +1061,21 @@ impl Exchange for Orderbook {
             "order id must be the same; something is wrong otherwise"
         );
 
+        #[cfg(feature = "sled")]
+        if let Some(storage) = self.storage.as_mut() {
+            storage.remove(&order);
+        }
+
+        self.untrack(&order);
+
         order.into()
     }
 
+    #[inline]
+    fn get(&self, order_id: &OrderId) -> Option<Self::OrderRef<'_>> {
+        Orderbook::get(self, order_id)
+    }
+
     fn peek(&self, side: &OrderSide) -> Option<Self::OrderRef<'_>> {
         let order_id = self.orders_by_side.peek(side)?;
 
@@ -121,15 +1085,54 @@ impl Exchange for Orderbook {
             .into()
     }
 
-    fn peek_mut(&mut self, side: &OrderSide) -> Option<Self::OrderRefMut<'_>> {
-        let order_id = self.orders_by_side.peek(side)?;
+    fn peek_nth(
+        &self,
+        side: &OrderSide,
+        n: usize,
+    ) -> Option<Self::OrderRef<'_>> {
+        let order_id = self.orders_by_side.nth(side, n)?;
 
         self.orders_by_id
-            .get_mut(order_id)
+            .get(order_id)
             .expect("every order that lives in tree must also be in the index")
             .into()
     }
 
+    fn peek_mut(&mut self, side: &OrderSide) -> Option<Self::OrderRefMut<'_>> {
+        let order_id = *self.orders_by_side.peek(side)?;
+
+        self.get_mut(&order_id)
+    }
+
+    fn get_mut(&mut self, order_id: &OrderId) -> Option<Self::OrderRefMut<'_>> {
+        let order = self.orders_by_id.get_mut(order_id)?;
+        let side = order.side();
+        let limit_price = order
+            .limit_price()
+            .expect("bookable orders must have a limit price");
+
+        let level = self.orders_by_side[side]
+            .get_mut(&limit_price)
+            .expect("every order in the index must also be in tree");
+
+        let volume = match side {
+            OrderSide::Ask => &mut self.ask_volume,
+            OrderSide::Bid => &mut self.bid_volume,
+        };
+
+        Some(PeekMut {
+            displayed: order.displayed(),
+            filled: order.filled(),
+            order,
+            level,
+            volume,
+            next_sequence: &mut self.next_sequence,
+            last_trade_price: &mut self.last_trade_price,
+            #[cfg(feature = "sled")]
+            storage: self.storage.as_mut(),
+        })
+    }
+
     fn pop(&mut self, side: &OrderSide) -> Option<Self::Order> {
         let mut level = match side {
             side @ OrderSide::Ask => self.orders_by_side[side].first_entry(),
@@ -154,32 +1157,97 @@ impl Exchange for Orderbook {
             "order id must be the same; something is wrong otherwise"
         );
 
+        #[cfg(feature = "sled")]
+        if let Some(storage) = self.storage.as_mut() {
+            storage.remove(&order);
+        }
+
+        self.untrack(&order);
+
         order.into()
     }
+
+    #[allow(clippy::type_complexity)]
+    fn matching<O>(
+        &mut self,
+        incoming_order: O,
+    ) -> Result<
+        <Self::Algo<O> as Algo<O, Self>>::Output,
+        <Self::Algo<O> as Algo<O, Self>>::Error,
+    >
+    where
+        Self: ExchangeExt + Sized,
+        Self::Order: exchange_core::Trade<O> + TryFrom<O>,
+        O: Asset<
+            OrderId = <<Self as Exchange>::Order as Asset>::OrderId,
+            OrderNotional = <<Self as Exchange>::Order as Asset>::OrderNotional,
+            OrderPrice = <<Self as Exchange>::Order as Asset>::OrderPrice,
+            OrderQuantity = <<Self as Exchange>::Order as Asset>::OrderQuantity,
+            OrderSide = <<Self as Exchange>::Order as Asset>::OrderSide,
+            OrderStatus = <<Self as Exchange>::Order as Asset>::OrderStatus,
+            Trade = <<Self as Exchange>::Order as Asset>::Trade,
+        >,
+        O: Clone,
+    {
+        #[cfg(feature = "sled")]
+        if let Some(storage) = self.storage.as_mut() {
+            storage.begin(self.next_sequence);
+        }
+
+        let result =
+            <Self::Algo<O> as Algo<O, Self>>::matching(self, incoming_order);
+
+        // Whatever is left resting by now is what gets written, see
+        // `Persistence::Resting`.
+        #[cfg(feature = "sled")]
+        if let Some(storage) = self.storage.as_mut() {
+            storage.commit();
+        }
+
+        result
+    }
 }
 
-impl ExchangeExt for Orderbook {
+impl<A: MatchingStrategy> ExchangeExt for Orderbook<A> {
     #[inline]
     fn spread(
         &self,
     ) -> Option<(<Order as Asset>::OrderPrice, <Order as Asset>::OrderPrice)>
     {
-        Some((
-            self.peek(&OrderSide::Ask)?.limit_price()?,
-            self.peek(&OrderSide::Bid)?.limit_price()?,
-        ))
+        Some((self.best_ask()?, self.best_bid()?))
+    }
+
+    #[inline]
+    fn best_bid(&self) -> Option<<Order as Asset>::OrderPrice> {
+        // Hidden orders are left out, unlike for `peek`.
+        self.iter(&OrderSide::Bid).next()?.limit_price()
+    }
+
+    #[inline]
+    fn best_ask(&self) -> Option<<Order as Asset>::OrderPrice> {
+        self.iter(&OrderSide::Ask).next()?.limit_price()
     }
 
     #[inline]
     fn len(&self) -> (usize, usize) {
-        (
-            self.orders_by_side[OrderSide::Ask]
-                .iter()
-                .fold(0, |acc, (_, level)| acc + level.len()),
-            self.orders_by_side[OrderSide::Bid]
-                .iter()
-                .fold(0, |acc, (_, level)| acc + level.len()),
-        )
+        debug_assert!(
+            (self.ask_count, self.bid_count)
+                == (
+                    self.orders_by_side[OrderSide::Ask]
+                        .iter()
+                        .fold(0, |acc, (_, level)| {
+                            acc + level.displayed().len()
+                        }),
+                    self.orders_by_side[OrderSide::Bid]
+                        .iter()
+                        .fold(0, |acc, (_, level)| {
+                            acc + level.displayed().len()
+                        }),
+                ),
+            "running counters must match the orders in the tree"
+        );
+
+        (self.ask_count, self.bid_count)
     }
 
     #[inline]
@@ -189,22 +1257,293 @@ impl ExchangeExt for Orderbook {
         <Order as Asset>::OrderQuantity,
         <Order as Asset>::OrderQuantity,
     ) {
-        let ask = self
-            .iter(&OrderSide::Ask)
-            .map(LimitOrder::remaining)
-            .reduce(|acc, curr| acc + curr)
-            .unwrap_or_else(Zero::zero);
+        let volume = |side| {
+            self.iter(&side)
+                .map(LimitOrder::displayed)
+                .reduce(|acc, curr| acc + curr)
+                .unwrap_or_else(Zero::zero)
+        };
 
-        let bid = self
+        debug_assert!(
+            (self.ask_volume, self.bid_volume)
+                == (volume(OrderSide::Ask), volume(OrderSide::Bid)),
+            "running volumes must match the orders in the tree"
+        );
+
+        (self.ask_volume, self.bid_volume)
+    }
+
+    #[inline]
+    fn last_price(&self) -> Option<Price> {
+        self.last_trade_price
+    }
+
+    #[inline]
+    fn depth(&self, side: &OrderSide, levels: usize) -> Depth<LimitOrder> {
+        self.levels(side).take(levels).collect()
+    }
+
+    fn level_summary(
+        &self,
+        side: &OrderSide,
+    ) -> impl Iterator<Item = (Price, usize, Quantity)> + '_ {
+        let remaining = |order_id: &OrderId| {
+            self.orders_by_id
+                .get(order_id)
+                .expect("every order in tree must also be in index")
+                .displayed()
+        };
+
+        // Hidden iceberg reserves are left out, just like for `depth`, and so
+        // are hidden orders, along with levels made only of them.
+        self.orders_by_side
+            .levels(side)
+            .map(|(limit_price, level)| (limit_price, level.displayed()))
+            .filter(|(_, level)| !level.is_empty())
+            .map(move |(&limit_price, level)| {
+                let remaining = level
+                    .iter()
+                    .map(remaining)
+                    .fold(Zero::zero(), |acc, curr| acc + curr);
+
+                (limit_price, level.len(), remaining)
+            })
+    }
+
+    fn iter_from_mid(
+        &self,
+    ) -> impl Iterator<Item = (OrderSide, Self::OrderRef<'_>)> + '_ {
+        let mut asks = self
+            .iter(&OrderSide::Ask)
+            .map(|order| (OrderSide::Ask, order))
+            .fuse();
+        let mut bids = self
             .iter(&OrderSide::Bid)
-            .map(LimitOrder::remaining)
-            .reduce(|acc, curr| acc + curr)
-            .unwrap_or_else(Zero::zero);
+            .map(|order| (OrderSide::Bid, order))
+            .fuse();
 
-        (ask, bid)
+        // Both sides are already walked from the mid price outward, so they
+        // only need to take turns, falling back to the other one once a side
+        // runs out.
+        let mut side = OrderSide::Ask;
+        iter::from_fn(move || {
+            let next = match side {
+                OrderSide::Ask => asks.next().or_else(|| bids.next()),
+                OrderSide::Bid => bids.next().or_else(|| asks.next()),
+            };
+            side = side.opposite();
+
+            next
+        })
+    }
+
+    fn checksum(&self, depth: usize) -> u32 {
+        let side = |side| {
+            self.depth(&side, depth)
+                .into_iter()
+                .map(|(price, quantity)| {
+                    format!("{}:{}", price.normalize(), quantity.normalize())
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let payload =
+            format!("{}|{}", side(OrderSide::Ask), side(OrderSide::Bid));
+
+        crc32fast::hash(payload.as_bytes())
     }
 }
 
+impl<A: MatchingStrategy> MatchingConfig for Orderbook<A> {
+    #[inline]
+    fn self_trade_prevention(&self) -> SelfTradePrevention {
+        self.self_trade_prevention
+    }
+
+    #[inline]
+    fn lot_size(&self) -> Option<Quantity> {
+        self.lot_size
+    }
+
+    #[inline]
+    fn display_floor(&self) -> Option<DisplayFloor<Quantity>> {
+        self.display_floor
+    }
+
+    #[inline]
+    fn price_collar(&self) -> Option<RangeInclusive<Price>> {
+        let price_collar = self.price_collar?;
+
+        match self.reference_price {
+            Some(reference_price) => {
+                Some(price_collar.band_around(reference_price))
+            }
+            None => Some(price_collar.band(self.spread()?)),
+        }
+    }
+
+    #[inline]
+    fn pro_rata_ratio(&self) -> u8 {
+        self.pro_rata_ratio
+    }
+
+    #[inline]
+    fn reducible(&self, order: &LimitOrder) -> Quantity {
+        let Some(account_id) = order.account_id() else {
+            return Quantity::zero();
+        };
+
+        // Only an order on the opposite side of the position reduces it.
+        let held = match self.positions.get(&account_id) {
+            Some(&(side, held)) if side == order.side().opposite() => held,
+            _ => return Quantity::zero(),
+        };
+
+        let resting = self
+            .orders_by_account
+            .get(&account_id)
+            .into_iter()
+            .flatten()
+            .filter(|&order_id| order_id != &order.id())
+            .filter_map(|order_id| self.orders_by_id.get(order_id))
+            .filter(|resting| {
+                resting.is_reduce_only() && resting.side() == order.side()
+            })
+            .fold(Quantity::zero(), |acc, resting| acc + resting.remaining());
+
+        held - held.min(resting)
+    }
+
+    fn cancel_linked(&mut self, order_id: &OrderId) -> Option<LimitOrder> {
+        let link = *self.linked_orders.get(order_id)?;
+        let filled = self.orders_by_id.get(order_id)?.filled();
+
+        let triggered = match link.cancel_after {
+            Some(cancel_after) => filled >= cancel_after,
+            None => !filled.is_zero(),
+        };
+        if !triggered {
+            return None;
+        }
+
+        let mut sibling = self.remove(&link.sibling)?;
+        sibling.cancel();
+
+        Some(sibling)
+    }
+
+    #[inline]
+    fn queue_for_close(&mut self, mut order: LimitOrder) {
+        self.stamp(&mut order);
+        self.closing.push(order);
+    }
+
+    #[inline]
+    fn assign_trade_id(&mut self, trade: &mut Trade) {
+        trade.set_trade_id(self.next_sequence);
+        self.next_sequence += 1;
+        self.settle(trade);
+
+        #[cfg(feature = "sled")]
+        if let Some(storage) = self.storage.as_mut() {
+            storage.advance(self.next_sequence);
+        }
+    }
+}
+
+/// A source of the current time, as a unix timestamp in seconds.
+///
+/// A plain function, so that tests may stamp orders with a fixed time.
+pub type Clock = fn() -> u64;
+
+/// The [`Clock`] of the system.
+#[inline]
+pub fn system_clock() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// How orders queued at the same price with the same sequence are ranked
+/// against each other.
+///
+/// Sequences are handed out by the book itself, so they only ever tie for
+/// orders restored with the very same one. Those are queued in the order they
+/// are restored in, until [`Orderbook::with_tiebreak`] ranks them again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tiebreak {
+    /// Orders keep the order they were queued in.
+    #[default]
+    Insertion,
+    /// The order with the lowest id comes first.
+    OrderId,
+    /// The order with the largest remaining quantity comes first.
+    Size,
+}
+
+impl Tiebreak {
+    #[inline]
+    fn cmp(self, a: &LimitOrder, b: &LimitOrder) -> Ordering {
+        match self {
+            Self::Insertion => Ordering::Equal,
+            Self::OrderId => a.id().cmp(&b.id()),
+            Self::Size => b.remaining().cmp(&a.remaining()),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AmendError {
+    #[error("order not found")]
+    NotFound,
+    #[error(transparent)]
+    Increment(#[from] IncrementError),
+    #[error(transparent)]
+    Order(#[from] OrderError),
+    #[error("linked orders cannot be repriced")]
+    Linked,
+    #[error("order rejected (reason={:?})", .0)]
+    Rejected(RejectReason),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum InsertError {
+    #[error("order would cross the book")]
+    Crossed,
+    #[error("duplicate order (order_id={})", .order_id)]
+    Duplicate { order_id: OrderId },
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LinkError {
+    #[error("order not found (order_id={})", .order_id)]
+    NotFound { order_id: OrderId },
+    #[error("order is already linked (order_id={})", .order_id)]
+    AlreadyLinked { order_id: OrderId },
+    #[error("order cannot be linked to itself (order_id={})", .order_id)]
+    SameOrder { order_id: OrderId },
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum IncrementError {
+    #[error(
+        "price is not a multiple of the tick size (price={}, tick_size={})",
+        .price,
+        .tick_size
+    )]
+    Tick { price: Price, tick_size: Price },
+    #[error(
+        "quantity is not a multiple of the lot size (quantity={}, lot_size={})",
+        .quantity,
+        .lot_size
+    )]
+    Lot {
+        quantity: Quantity,
+        lot_size: Quantity,
+    },
+}
+
 #[cfg(any(test, feature = "test"))]
 #[doc(hidden)]
 pub(crate) mod __fmt {
@@ -213,7 +1552,7 @@ pub(crate) mod __fmt {
     use super::*;
 
     #[cfg(any(test, feature = "test"))]
-    impl fmt::Debug for Orderbook {
+    impl<A: MatchingStrategy> fmt::Debug for Orderbook<A> {
         #[inline]
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             __fmt::OrderbookView::from(self).fmt(f)
@@ -221,23 +1560,23 @@ pub(crate) mod __fmt {
     }
 
     #[repr(transparent)]
-    pub struct OrderbookView<'a>(&'a Orderbook);
+    pub struct OrderbookView<'a, A = MatchingAlgo>(&'a Orderbook<A>);
 
-    impl<'a> OrderbookView<'a> {
+    impl<'a, A> OrderbookView<'a, A> {
         #[inline]
-        pub const fn new(orderbook: &'a Orderbook) -> Self {
+        pub const fn new(orderbook: &'a Orderbook<A>) -> Self {
             Self(orderbook)
         }
     }
 
-    impl<'a> From<&'a Orderbook> for OrderbookView<'a> {
+    impl<'a, A> From<&'a Orderbook<A>> for OrderbookView<'a, A> {
         #[inline]
-        fn from(orderbook: &'a Orderbook) -> Self {
+        fn from(orderbook: &'a Orderbook<A>) -> Self {
             Self::new(orderbook)
         }
     }
 
-    impl<'a> fmt::Debug for OrderbookView<'a> {
+    impl<'a, A: MatchingStrategy> fmt::Debug for OrderbookView<'a, A> {
         #[inline]
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             let filter = |ref side| {
@@ -259,17 +1598,23 @@ pub(crate) mod __fmt {
     impl<'o> fmt::Debug for OrderbookOrderView<'o> {
         #[inline]
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            f.debug_struct("Order")
-                .field(
-                    "limit_price",
-                    &self
-                        .0
-                        .limit_price()
-                        .expect("orderbook orders always have limit price"),
-                )
-                .field("remaining", &self.0.remaining())
-                .field("status", &self.0.status())
-                .finish()
+            let mut f = f.debug_struct("Order");
+            f.field(
+                "limit_price",
+                &self
+                    .0
+                    .limit_price()
+                    .expect("orderbook orders always have limit price"),
+            )
+            .field("remaining", &self.0.displayed())
+            .field("status", &self.0.status());
+
+            // Orders of a book without a clock are never stamped.
+            if self.0.created_at() != 0 {
+                f.field("created_at", &self.0.created_at());
+            }
+
+            f.finish()
         }
     }
 }
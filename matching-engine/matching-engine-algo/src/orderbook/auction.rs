@@ -0,0 +1,248 @@
+use std::cmp::Reverse;
+use std::mem;
+
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::Price;
+use exchange_types::Quantity;
+use exchange_types::Trade;
+use num::Zero;
+
+use super::Orderbook;
+use crate::MatchingConfig;
+use crate::MatchingStrategy;
+
+/// What crossing a call auction at its clearing price matched.
+#[derive(Debug)]
+pub struct Cross {
+    price: Price,
+    quantity: Quantity,
+    trades: Vec<Trade>,
+    cancelled: Vec<OrderId>,
+}
+
+impl Cross {
+    /// Returns the single price every trade took place at.
+    #[inline]
+    pub fn price(&self) -> Price {
+        self.price
+    }
+
+    /// Returns the quantity matched in total, on either side.
+    #[inline]
+    pub fn quantity(&self) -> Quantity {
+        self.quantity
+    }
+
+    /// Returns the trades executed, in the order they happened.
+    #[inline]
+    pub fn trades(&self) -> &[Trade] {
+        &self.trades
+    }
+
+    /// Returns the resting orders cancelled because a linked sibling of
+    /// theirs traded.
+    #[inline]
+    pub fn cancelled(&self) -> &[OrderId] {
+        &self.cancelled
+    }
+
+    #[inline]
+    pub fn into_trades(self) -> Vec<Trade> {
+        self.trades
+    }
+}
+
+impl<A: MatchingStrategy> Orderbook<A> {
+    /// Crosses the book of a call auction at a single clearing price, the
+    /// one matching the most quantity, returning `None` if no bid crosses
+    /// any ask at all.
+    ///
+    /// Among the prices matching just as much, the one leaving the least
+    /// quantity unmatched wins, then the one closest to the last trade price,
+    /// if any, and then the lowest of them.
+    ///
+    /// Bids and asks that cross are matched in price-time priority, all of
+    /// them at the clearing price, hidden orders and iceberg reserves
+    /// included. Orders of the same account are not kept from trading with
+    /// each other. The book no longer crosses afterwards, so it may be
+    /// switched back to continuous matching, see
+    /// [`AuctionAlgo`](crate::AuctionAlgo).
+    pub fn cross(&mut self) -> Option<Cross> {
+        let (price, quantity) = self.clearing_price()?;
+
+        let mut trades = Vec::new();
+        let mut cancelled = Vec::new();
+        let mut left = quantity;
+
+        while !left.is_zero() {
+            let (Some(bid), Some(ask)) =
+                (self.peek(&OrderSide::Bid), self.peek(&OrderSide::Ask))
+            else {
+                unreachable!("the clearing quantity must be there to match");
+            };
+
+            // Both orders are filled apart from the book, and written back
+            // to it one at a time, which replenishes icebergs as usual.
+            let (mut bid, mut ask) = (*bid, *ask);
+            let mut trade = Trade::try_cross(&mut bid, &mut ask, price, left)
+                .expect("best orders must cross at the clearing price");
+            self.assign_trade_id(&mut trade);
+            left -= trade.quantity();
+
+            for order in [bid, ask] {
+                *self
+                    .peek_mut(&order.side())
+                    .expect("crossed order must still be in front") = order;
+            }
+
+            // Just like for continuous matching, a linked sibling is gone
+            // before the order itself may be.
+            for order in [bid, ask] {
+                if let Some(sibling) = self.cancel_linked(&order.id()) {
+                    cancelled.push(sibling.id());
+                }
+
+                if order.is_closed() {
+                    self.remove(&order.id());
+                }
+            }
+
+            trades.push(trade);
+        }
+
+        self.last_trade_price = Some(price);
+
+        Some(Cross {
+            price,
+            quantity,
+            trades,
+            cancelled,
+        })
+    }
+
+    /// Runs the closing auction: every at-the-close order queued joins the
+    /// book, in the order it came in, and the book is crossed at a single
+    /// clearing price, see [`Orderbook::cross`].
+    ///
+    /// Resting orders take part as well, and keep resting afterwards unless
+    /// filled, while whatever is left of the at-the-close orders is expired,
+    /// returned in the order they came in. One whose id is taken already by
+    /// a resting order never joins the book, and is expired as is.
+    pub fn run_closing_auction(&mut self) -> (Option<Cross>, Vec<Order>) {
+        let closing = mem::take(&mut self.closing);
+        let mut joined = Vec::with_capacity(closing.len());
+
+        for order in closing {
+            if self.orders_by_id.contains_key(&order.id()) {
+                joined.push(Err(order));
+                continue;
+            }
+
+            joined.push(Ok(order.id()));
+            // SAFETY: This call is safe because the book may only cross until
+            // `Orderbook::cross` matched every crossing order, right below,
+            // and its id is not taken by any other order.
+            unsafe {
+                self.insert(order);
+            }
+        }
+
+        let cross = self.cross();
+
+        let expired = joined
+            .into_iter()
+            .filter_map(|joined| {
+                let mut order = match joined {
+                    Ok(order_id) => self.remove(&order_id)?,
+                    Err(order) => order,
+                };
+                order.expire();
+
+                Some(Order::from(order))
+            })
+            .collect();
+
+        (cross, expired)
+    }
+
+    /// Returns the clearing price, along with the quantity it matches, out
+    /// of the aggregated demand and supply curves of the book.
+    fn clearing_price(&self) -> Option<(Price, Quantity)> {
+        let remaining = |order_id| {
+            self.orders_by_id
+                .get(order_id)
+                .expect("every order in tree must also be in index")
+                .remaining()
+        };
+
+        // Every level of a side, from the lowest to the highest price, along
+        // with the total remaining quantity resting at it.
+        let curve = |side| {
+            self.orders_by_side[side]
+                .iter()
+                .map(|(&limit_price, level)| {
+                    let quantity = level
+                        .iter()
+                        .map(remaining)
+                        .fold(Quantity::zero(), |acc, quantity| acc + quantity);
+
+                    (limit_price, quantity)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let bids = curve(OrderSide::Bid);
+        let asks = curve(OrderSide::Ask);
+
+        let mut prices = bids
+            .iter()
+            .chain(&asks)
+            .map(|&(limit_price, _)| limit_price)
+            .collect::<Vec<_>>();
+        prices.sort_unstable();
+        prices.dedup();
+
+        // The demand at a price is what every bid at it or above buys, and
+        // the supply is what every ask at it or below sells.
+        let mut demand = bids
+            .iter()
+            .fold(Quantity::zero(), |acc, &(_, quantity)| acc + quantity);
+        let mut supply = Quantity::zero();
+        let mut bids = bids.into_iter().peekable();
+        let mut asks = asks.into_iter().peekable();
+
+        let distance = |price: Price| match self.last_trade_price {
+            Some(reference) if price > reference => price - reference,
+            Some(reference) => reference - price,
+            None => Price::zero(),
+        };
+
+        prices
+            .into_iter()
+            .filter_map(|price| {
+                while let Some((_, quantity)) =
+                    bids.next_if(|&(limit_price, _)| limit_price < price)
+                {
+                    demand -= quantity;
+                }
+                while let Some((_, quantity)) =
+                    asks.next_if(|&(limit_price, _)| limit_price <= price)
+                {
+                    supply += quantity;
+                }
+
+                let matched = demand.min(supply);
+                let unmatched = demand.max(supply) - matched;
+
+                (!matched.is_zero()).then_some((price, matched, unmatched))
+            })
+            .min_by_key(|&(price, matched, unmatched)| {
+                (Reverse(matched), unmatched, distance(price))
+            })
+            .map(|(price, matched, _)| (price, matched))
+    }
+}
@@ -0,0 +1,74 @@
+use exchange_core::Asset;
+use exchange_types::LimitOrder;
+use exchange_types::OrderSide;
+
+use super::Orderbook;
+use crate::MatchingStrategy;
+
+impl<A: MatchingStrategy> Orderbook<A> {
+    /// Inserts many resting orders at once, e.g. when loading a snapshot of
+    /// a book, without matching them.
+    ///
+    /// Orders are grouped by level first, so each level is looked up and
+    /// grown only once however many orders it gets. Orders of the same level
+    /// are queued in the order they are given, after those already resting
+    /// there.
+    ///
+    /// # Safety
+    ///
+    /// Just like [`Exchange::insert`](exchange_core::Exchange::insert), the
+    /// caller must guarantee that no order overlaps the opposite side of the
+    /// book, neither the orders already resting nor the ones given. Orders
+    /// must also be open and have unique ids.
+    pub unsafe fn insert_batch(
+        &mut self,
+        orders: impl IntoIterator<Item = LimitOrder>,
+    ) {
+        let mut orders = orders.into_iter().collect::<Vec<_>>();
+
+        for order in &mut orders {
+            order.set_sequence(self.next_sequence);
+            self.next_sequence += 1;
+            self.stamp(order);
+        }
+
+        for side in [OrderSide::Ask, OrderSide::Bid] {
+            let mut levels = orders
+                .iter()
+                .filter(|order| order.side() == side)
+                .map(|order| {
+                    let limit_price = order
+                        .limit_price()
+                        .expect("bookable orders must have a limit price");
+
+                    (limit_price, order.id(), order.is_hidden())
+                })
+                .collect::<Vec<_>>();
+
+            // A stable sort keeps the arrival order within each level.
+            levels.sort_by_key(|&(limit_price, ..)| limit_price);
+
+            for orders in levels.chunk_by(|a, b| a.0 == b.0) {
+                let (limit_price, ..) = orders[0];
+
+                let level =
+                    self.orders_by_side[side].entry(limit_price).or_default();
+                for &(_, order_id, hidden) in orders {
+                    level.push_back(order_id, hidden);
+                }
+            }
+        }
+
+        #[cfg(feature = "sled")]
+        if let Some(storage) = self.storage.as_mut() {
+            orders
+                .iter()
+                .for_each(|order| storage.push_back(order, None));
+        }
+
+        orders.iter().for_each(|order| self.track(order));
+
+        self.orders_by_id
+            .extend(orders.into_iter().map(|order| (order.id(), order)));
+    }
+}
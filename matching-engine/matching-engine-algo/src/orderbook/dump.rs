@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+
+use exchange_types::LimitOrder;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::Price;
+use exchange_types::Quantity;
+
+use super::Orderbook;
+
+/// A raw copy of everything an [`Orderbook`] keeps, both indices and the
+/// counters cached along with them, meant for post-mortem analysis once one
+/// of its invariants broke.
+///
+/// Unlike an [`OrderbookSnapshot`](super::OrderbookSnapshot), it assumes
+/// nothing about the book: each index is copied as it is, even if they
+/// disagree with each other, and where they do is told rather than panicked
+/// upon. It cannot be restored from.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct BookDump {
+    /// Every order in the index by id, in id order.
+    orders: Vec<LimitOrder>,
+    /// Every ask level, best first, along with the ids queued at it.
+    asks: Vec<LevelDump>,
+    /// Every bid level, best first, along with the ids queued at it.
+    bids: Vec<LevelDump>,
+    /// Ids queued at a level, though missing from the index by id.
+    unindexed: Vec<OrderId>,
+    /// Ids in the index by id, though queued at no level.
+    unqueued: Vec<OrderId>,
+    /// Every one-cancels-other pair, the lesser order id first.
+    links: Vec<(OrderId, OrderId)>,
+    /// Every at-the-close order, in the order they came in.
+    closing: Vec<LimitOrder>,
+    ask_count: usize,
+    bid_count: usize,
+    ask_volume: Quantity,
+    bid_volume: Quantity,
+    next_sequence: u64,
+    last_trade_price: Option<Price>,
+    reference_price: Option<Price>,
+}
+
+/// A price level, as copied into a [`BookDump`].
+#[derive(Clone, Debug, serde::Serialize)]
+struct LevelDump {
+    price: Price,
+    /// Displayed ids first, then hidden ones, each in queue order.
+    order_ids: Vec<OrderId>,
+}
+
+impl<A> Orderbook<A> {
+    /// Takes a [`BookDump`] of the book, whatever state it is in, without
+    /// ever panicking on it.
+    pub fn dump(&self) -> BookDump {
+        let levels = |side| {
+            self.orders_by_side
+                .levels(&side)
+                .map(|(&price, level)| LevelDump {
+                    price,
+                    order_ids: level.iter().copied().collect(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let (asks, bids) = (levels(OrderSide::Ask), levels(OrderSide::Bid));
+
+        let queued = asks
+            .iter()
+            .chain(&bids)
+            .flat_map(|level| &level.order_ids)
+            .collect::<HashSet<_>>();
+        let unindexed = asks
+            .iter()
+            .chain(&bids)
+            .flat_map(|level| &level.order_ids)
+            .filter(|order_id| !self.orders_by_id.contains_key(order_id))
+            .copied()
+            .collect();
+        let unqueued = self
+            .orders_by_id
+            .keys()
+            .filter(|order_id| !queued.contains(order_id))
+            .copied()
+            .collect();
+
+        BookDump {
+            orders: self.orders_by_id.values().copied().collect(),
+            asks,
+            bids,
+            unindexed,
+            unqueued,
+            links: self
+                .linked_orders
+                .pairs()
+                .map(|(first, link)| (first, link.sibling))
+                .collect(),
+            closing: self.closing.clone(),
+            ask_count: self.ask_count,
+            bid_count: self.bid_count,
+            ask_volume: self.ask_volume,
+            bid_volume: self.bid_volume,
+            next_sequence: self.next_sequence,
+            last_trade_price: self.last_trade_price,
+            reference_price: self.reference_price,
+        }
+    }
+
+    /// Removes the order from the index by id only, leaving it queued at its
+    /// level, as no book ever should, so that what follows once an invariant
+    /// broke can be tested.
+    #[cfg(any(test, feature = "test"))]
+    #[doc(hidden)]
+    pub fn unindex(&mut self, order_id: &OrderId) -> Option<LimitOrder> {
+        self.orders_by_id.remove(order_id)
+    }
+}
+
+impl BookDump {
+    /// Returns the ids queued at a level, though missing from the index by
+    /// id.
+    #[inline]
+    pub fn unindexed(&self) -> &[OrderId] {
+        &self.unindexed
+    }
+
+    /// Returns the ids in the index by id, though queued at no level.
+    #[inline]
+    pub fn unqueued(&self) -> &[OrderId] {
+        &self.unqueued
+    }
+}
@@ -0,0 +1,109 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::iter::Chain;
+
+/// The orders resting at a single price, in time priority.
+///
+/// Hidden orders are queued apart, after every displayed order, so they are
+/// only matched once there is no displayed order left at the price.
+pub struct Level<OrderId> {
+    displayed: VecDeque<OrderId>,
+    hidden: VecDeque<OrderId>,
+}
+
+impl<OrderId: PartialEq> Level<OrderId> {
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            displayed: VecDeque::with_capacity(capacity),
+            hidden: VecDeque::new(),
+        }
+    }
+
+    /// Returns the displayed orders, hidden ones left out.
+    #[inline]
+    pub fn displayed(&self) -> &VecDeque<OrderId> {
+        &self.displayed
+    }
+
+    /// Returns an iterator over every order, displayed ones first.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &OrderId> {
+        self.displayed.iter().chain(&self.hidden)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.displayed.len() + self.hidden.len()
+    }
+
+    #[inline]
+    pub fn front(&self) -> Option<&OrderId> {
+        self.displayed.front().or_else(|| self.hidden.front())
+    }
+
+    #[inline]
+    pub fn push_back(&mut self, order_id: OrderId, hidden: bool) {
+        if hidden {
+            self.hidden.push_back(order_id);
+        } else {
+            self.displayed.push_back(order_id);
+        }
+    }
+
+    /// Sorts both queues with a stable sort, so orders that compare equal
+    /// keep their place.
+    #[inline]
+    pub fn sort_by(
+        &mut self,
+        mut compare: impl FnMut(&OrderId, &OrderId) -> Ordering,
+    ) {
+        for queue in [&mut self.displayed, &mut self.hidden] {
+            queue.make_contiguous().sort_by(&mut compare);
+        }
+    }
+
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<OrderId> {
+        self.displayed
+            .pop_front()
+            .or_else(|| self.hidden.pop_front())
+    }
+
+    /// Removes the given order, wherever it is queued.
+    #[inline]
+    pub fn remove(&mut self, order_id: &OrderId) -> Option<OrderId> {
+        for queue in [&mut self.displayed, &mut self.hidden] {
+            if let Some(index) =
+                queue.iter().position(|queued| queued == order_id)
+            {
+                return queue.remove(index);
+            }
+        }
+
+        None
+    }
+}
+
+impl<OrderId> Default for Level<OrderId> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            displayed: Default::default(),
+            hidden: Default::default(),
+        }
+    }
+}
+
+impl<OrderId> IntoIterator for Level<OrderId> {
+    type IntoIter = Chain<
+        <VecDeque<OrderId> as IntoIterator>::IntoIter,
+        <VecDeque<OrderId> as IntoIterator>::IntoIter,
+    >;
+    type Item = OrderId;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.displayed.into_iter().chain(self.hidden)
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use exchange_types::OrderId;
+use exchange_types::Quantity;
+
+/// How a resting order is linked to its sibling, one-cancels-other.
+#[derive(Clone, Copy, Debug)]
+pub struct Link {
+    pub sibling: OrderId,
+    /// How much the order must fill before its sibling is cancelled, or
+    /// anything at all if not set.
+    pub cancel_after: Option<Quantity>,
+}
+
+/// The one-cancels-other links between resting orders, kept both ways.
+#[derive(Default)]
+pub struct LinkedOrders(HashMap<OrderId, Link>);
+
+impl LinkedOrders {
+    #[inline]
+    pub fn insert(
+        &mut self,
+        first: OrderId,
+        second: OrderId,
+        cancel_after: Option<Quantity>,
+    ) {
+        for (order_id, sibling) in [(first, second), (second, first)] {
+            self.0.insert(
+                order_id,
+                Link {
+                    sibling,
+                    cancel_after,
+                },
+            );
+        }
+    }
+
+    /// Unlinks the given order from its sibling, returning how they were
+    /// linked.
+    #[inline]
+    pub fn remove(&mut self, order_id: &OrderId) -> Option<Link> {
+        let link = self.0.remove(order_id)?;
+
+        // This prevents dangling links (link to an order that is gone).
+        self.0.remove(&link.sibling);
+
+        Some(link)
+    }
+
+    #[inline]
+    pub fn get(&self, order_id: &OrderId) -> Option<&Link> {
+        self.0.get(order_id)
+    }
+
+    #[inline]
+    pub fn contains_key(&self, order_id: &OrderId) -> bool {
+        self.0.contains_key(order_id)
+    }
+
+    /// Returns every linked pair once, the lesser order id first.
+    pub fn pairs(&self) -> impl Iterator<Item = (OrderId, &Link)> {
+        self.0
+            .iter()
+            .filter(|(order_id, link)| **order_id < link.sibling)
+            .map(|(order_id, link)| (*order_id, link))
+    }
+}
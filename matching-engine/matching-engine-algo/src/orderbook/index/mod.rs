@@ -1,3 +1,12 @@
+mod level;
+pub use level::*;
+
+mod linked_orders;
+pub use linked_orders::*;
+
+mod orders_by_account;
+pub use orders_by_account::*;
+
 mod orders_by_id;
 pub use orders_by_id::*;
 
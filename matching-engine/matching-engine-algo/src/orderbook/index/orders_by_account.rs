@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use exchange_core::Asset;
+use exchange_types::AccountId;
+use exchange_types::LimitOrder;
+use exchange_types::OrderId;
+
+/// The ids of the resting orders of each account, for orders that have one.
+#[derive(Default)]
+pub struct OrdersByAccount(HashMap<AccountId, HashSet<OrderId>>);
+
+impl OrdersByAccount {
+    #[inline]
+    pub fn insert(&mut self, order: &LimitOrder) {
+        if let Some(account_id) = order.account_id() {
+            self.0.entry(account_id).or_default().insert(order.id());
+        }
+    }
+
+    #[inline]
+    pub fn remove(&mut self, order: &LimitOrder) {
+        let Some(account_id) = order.account_id() else {
+            return;
+        };
+
+        if let Some(order_ids) = self.0.get_mut(&account_id) {
+            order_ids.remove(&order.id());
+
+            // This prevents dangling accounts (account with no orders).
+            if order_ids.is_empty() {
+                self.0.remove(&account_id);
+            }
+        }
+    }
+
+    /// Returns the ids of every resting order of the given account.
+    #[inline]
+    pub fn get(&self, account_id: &AccountId) -> Option<&HashSet<OrderId>> {
+        self.0.get(account_id)
+    }
+}
@@ -1,12 +1,13 @@
 use std::collections::BTreeMap;
-use std::collections::VecDeque;
 use std::ops::Deref;
 use std::ops::DerefMut;
 
 use exchange_core::Asset;
 
+use super::Level;
+
 pub struct OrdersByPrice<Order: Asset>(
-    BTreeMap<<Order as Asset>::OrderPrice, VecDeque<<Order as Asset>::OrderId>>,
+    BTreeMap<<Order as Asset>::OrderPrice, Level<<Order as Asset>::OrderId>>,
 );
 
 impl<Order: Asset> Default for OrdersByPrice<Order> {
@@ -19,7 +20,7 @@ impl<Order: Asset> Default for OrdersByPrice<Order> {
 impl<Order: Asset> Deref for OrdersByPrice<Order> {
     type Target = BTreeMap<
         <Order as Asset>::OrderPrice,
-        VecDeque<<Order as Asset>::OrderId>,
+        Level<<Order as Asset>::OrderId>,
     >;
 
     #[inline]
@@ -1,5 +1,4 @@
 use std::borrow::Borrow;
-use std::collections::VecDeque;
 use std::ops::Deref;
 use std::ops::Index;
 use std::ops::IndexMut;
@@ -8,6 +7,7 @@ use either::Either;
 use exchange_core::Asset;
 use exchange_types::OrderSide;
 
+use super::Level;
 use super::OrdersByPrice;
 
 pub struct OrdersBySide<Order: Asset> {
@@ -19,27 +19,73 @@ impl<Order: Asset> OrdersBySide<Order>
 where
     Order: Asset<OrderSide = OrderSide>,
 {
+    /// Returns an iterator over the displayed orders of the given side,
+    /// hidden ones left out.
     #[inline]
     pub fn iter(
         &self,
         side: &<Order as Asset>::OrderSide,
     ) -> impl Iterator<Item = &<Order as Asset>::OrderId> {
+        self.levels(side)
+            .flat_map(|(_, level)| level.displayed().iter())
+    }
+
+    /// Returns an iterator over every order of the given side, hidden ones
+    /// included, each after the displayed ones of its level.
+    #[inline]
+    pub fn iter_with_hidden(
+        &self,
+        side: &<Order as Asset>::OrderSide,
+    ) -> impl Iterator<Item = &<Order as Asset>::OrderId> {
+        self.levels(side).flat_map(|(_, level)| level.iter())
+    }
+
+    /// Returns an iterator over the price levels of the given side, from the
+    /// most to the least relevant one.
+    #[inline]
+    pub fn levels(
+        &self,
+        side: &<Order as Asset>::OrderSide,
+    ) -> impl Iterator<
+        Item = (
+            &<Order as Asset>::OrderPrice,
+            &Level<<Order as Asset>::OrderId>,
+        ),
+    > {
         match side {
-            OrderSide::Ask => Either::Left(
-                self[side].deref().values().flat_map(VecDeque::iter),
-            ),
-            OrderSide::Bid => Either::Right(
-                self[side].deref().values().rev().flat_map(VecDeque::iter),
-            ),
+            OrderSide::Ask => Either::Left(self[side].deref().iter()),
+            OrderSide::Bid => Either::Right(self[side].deref().iter().rev()),
         }
     }
 
+    /// Returns the most relevant order of the given side, which is hidden
+    /// only if its whole level is.
     #[inline]
     pub fn peek(
         &self,
         side: &<Order as Asset>::OrderSide,
     ) -> Option<&<Order as Asset>::OrderId> {
-        self.iter(side).next()
+        self.levels(side).next()?.1.front()
+    }
+
+    /// Returns the `n`-th displayed order id of the given side, skipping
+    /// whole levels at once instead of walking every order before it.
+    #[inline]
+    pub fn nth(
+        &self,
+        side: &<Order as Asset>::OrderSide,
+        mut n: usize,
+    ) -> Option<&<Order as Asset>::OrderId> {
+        for (_, level) in self.levels(side) {
+            let level = level.displayed();
+
+            match level.get(n) {
+                Some(order_id) => return Some(order_id),
+                None => n -= level.len(),
+            }
+        }
+
+        None
     }
 }
 
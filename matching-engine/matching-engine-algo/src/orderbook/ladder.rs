@@ -0,0 +1,72 @@
+use std::fmt::Write as _;
+
+use exchange_types::OrderSide;
+
+use super::Orderbook;
+use crate::MatchingStrategy;
+
+impl<A: MatchingStrategy> Orderbook<A> {
+    /// Renders the top `depth` price levels of both sides as an aligned
+    /// price ladder, in the fashion of a trading terminal's depth-of-market
+    /// view.
+    ///
+    /// Bids are laid out on the left and asks on the right, one level per
+    /// row, best prices first. Each level shows its price along with the
+    /// total remaining quantity resting at it.
+    pub fn ladder(&self, depth: usize) -> String {
+        let levels = |side| {
+            self.levels(&side)
+                .take(depth)
+                .map(|(price, quantity)| {
+                    [price.to_string(), quantity.to_string()]
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let bids = levels(OrderSide::Bid);
+        let asks = levels(OrderSide::Ask);
+
+        let width = |levels: &[[String; 2]], column: usize, header: &str| {
+            levels
+                .iter()
+                .map(|level| level[column].len())
+                .fold(header.len(), usize::max)
+        };
+
+        let bid_quantity_width = width(&bids, 1, "Qty");
+        let bid_price_width = width(&bids, 0, "Bid");
+        let ask_price_width = width(&asks, 0, "Ask");
+        let ask_quantity_width = width(&asks, 1, "Qty");
+
+        let mut ladder = String::new();
+        let mut row =
+            |[bid_price, bid_quantity]: [&str; 2],
+             [ask_price, ask_quantity]: [&str; 2]| {
+                let line = format!(
+                    "{bid_quantity:>bid_quantity_width$} \
+                     {bid_price:>bid_price_width$} | \
+                     {ask_price:<ask_price_width$} \
+                     {ask_quantity:<ask_quantity_width$}"
+                );
+
+                // Writing into a `String` never fails.
+                let _ = writeln!(ladder, "{}", line.trim_end());
+            };
+
+        row(["Bid", "Qty"], ["Ask", "Qty"]);
+        for i in 0..bids.len().max(asks.len()) {
+            row(cells(&bids, i), cells(&asks, i));
+        }
+
+        ladder
+    }
+}
+
+/// Returns the price and quantity cells of the `i`-th level, or blank cells
+/// if the side is not that deep.
+#[inline]
+fn cells(levels: &[[String; 2]], i: usize) -> [&str; 2] {
+    levels.get(i).map_or(["", ""], |[price, quantity]| {
+        [price.as_str(), quantity.as_str()]
+    })
+}
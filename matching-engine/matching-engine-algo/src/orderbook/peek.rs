@@ -0,0 +1,106 @@
+use std::ops::Deref;
+use std::ops::DerefMut;
+
+use exchange_core::Asset;
+use exchange_types::LimitOrder;
+use exchange_types::OrderId;
+use exchange_types::Price;
+use exchange_types::Quantity;
+
+use super::index::Level;
+
+/// A mutable reference to a resting order of an
+/// [`Orderbook`](super::Orderbook), e.g. the most relevant one of a side.
+///
+/// Once dropped, an iceberg order whose displayed slice was fully filled shows
+/// a new slice out of its reserve and is moved to the back of its level,
+/// losing its time priority. Any fill of the order is also recorded as the
+/// last trade of the book, at its limit price.
+///
+/// On a persistent book, the order is written through as well, unless it
+/// was closed, in which case it is removed from the storage at once, ahead
+/// of being removed from the book.
+pub struct PeekMut<'e> {
+    pub(super) order: &'e mut LimitOrder,
+    pub(super) level: &'e mut Level<OrderId>,
+    /// The running volume of the order's side.
+    pub(super) volume: &'e mut Quantity,
+    /// What the order showed when it was peeked.
+    pub(super) displayed: Quantity,
+    /// What was filled of the order when it was peeked.
+    pub(super) filled: Quantity,
+    pub(super) next_sequence: &'e mut u64,
+    pub(super) last_trade_price: &'e mut Option<Price>,
+    #[cfg(feature = "sled")]
+    pub(super) storage: Option<&'e mut super::persist::Storage>,
+}
+
+impl Deref for PeekMut<'_> {
+    type Target = LimitOrder;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.order
+    }
+}
+
+impl DerefMut for PeekMut<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.order
+    }
+}
+
+impl Drop for PeekMut<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        // Makers always trade at their own limit price.
+        if self.order.filled() != self.filled {
+            *self.last_trade_price = self.order.limit_price();
+        }
+
+        let replenished = !self.order.is_closed() && self.order.replenish();
+
+        // Whatever was filled, or shown anew, must be reflected on the
+        // running volume of the side, which hidden orders are no part of.
+        if !self.order.is_hidden() {
+            *self.volume += self.order.displayed();
+            *self.volume -= self.displayed;
+        }
+
+        if !replenished {
+            // A closed order is about to be removed from the book, so it is
+            // removed from the storage in a single step right away. Were it
+            // written back first, a crash before its removal would leave it
+            // behind on disk.
+            #[cfg(feature = "sled")]
+            if let Some(storage) = self.storage.as_deref_mut() {
+                if self.order.is_closed() {
+                    storage.remove(self.order);
+                } else {
+                    storage.update(self.order);
+                }
+            }
+
+            return;
+        }
+
+        let order_id = self
+            .level
+            .remove(&self.order.id())
+            .expect("peeked order must be in its level");
+
+        self.level.push_back(order_id, self.order.is_hidden());
+
+        #[cfg(feature = "sled")]
+        let previous = self.order.sequence();
+
+        self.order.set_sequence(*self.next_sequence);
+        *self.next_sequence += 1;
+
+        #[cfg(feature = "sled")]
+        if let Some(storage) = self.storage.as_deref_mut() {
+            storage.push_back(self.order, Some(previous));
+        }
+    }
+}
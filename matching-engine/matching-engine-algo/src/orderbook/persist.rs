@@ -0,0 +1,605 @@
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::path::Path;
+
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_types::LimitOrder;
+use exchange_types::OrderId;
+use exchange_types::Quantity;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+use sled::transaction::TransactionError;
+use sled::Transactional;
+use thiserror::Error;
+
+use super::Orderbook;
+use crate::MatchingStrategy;
+
+impl Orderbook {
+    /// Opens the orderbook persisted at `path`, creating it if there is none
+    /// yet, and writes every later change to it through.
+    ///
+    /// Resting orders are restored in their original price-time priority,
+    /// along with their one-cancels-other links. Entries left behind by a
+    /// write that did not complete, i.e. those that fail their checksum,
+    /// cannot be decoded or describe an order that could not be resting, or
+    /// a link to an order that is gone, are removed from the storage instead
+    /// of restored. How many of them were found is told by
+    /// [`Orderbook::recovery`], and the checkpoint the storage was last left
+    /// at, if any, by [`Orderbook::last_checkpoint`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage itself cannot be opened or read.
+    pub fn open_persistent(
+        path: impl AsRef<Path>,
+    ) -> Result<Self, PersistError> {
+        let db = sled::open(path)?;
+        let links = db.open_tree(LINKS)?;
+
+        let mut orderbook = Self::new();
+        let mut recovery = Recovery::default();
+        let mut stale = sled::Batch::default();
+
+        let next_sequence = db
+            .get(NEXT_SEQUENCE)?
+            .as_deref()
+            .and_then(decode_sequence)
+            .unwrap_or_default();
+        let checkpoint = db.get(CHECKPOINT)?.as_deref().and_then(decode);
+
+        // Keys are big-endian sequences, so orders come in the very order
+        // they were queued at their levels.
+        for entry in db.iter() {
+            let (key, value) = entry?;
+
+            if *key == *NEXT_SEQUENCE || *key == *CHECKPOINT {
+                continue;
+            }
+
+            let Some(sequence) = decode_sequence(&key) else {
+                stale.remove(key);
+                recovery.discarded += 1;
+                continue;
+            };
+
+            match decode::<LimitOrder>(&value) {
+                Some(order)
+                    if order.is_open()
+                        && orderbook.get(&order.id()).is_none()
+                        && !orderbook.crosses(&order) =>
+                {
+                    recovery.restored += 1;
+
+                    // Orders keep the sequence they were persisted under,
+                    // whatever the encoded order says.
+                    orderbook.next_sequence = sequence;
+
+                    // SAFETY: we just checked that the order does not
+                    // overlap the opposite side.
+                    unsafe { orderbook.insert(order) };
+                }
+                _ => {
+                    stale.remove(key);
+                    recovery.discarded += 1;
+                }
+            }
+        }
+
+        db.apply_batch(stale)?;
+
+        let mut stale = sled::Batch::default();
+
+        for entry in links.iter() {
+            let (key, value) = entry?;
+
+            // Both orders must have been restored, neither linked already.
+            let relinked = match decode(&value) {
+                Some((first, second, cancel_after)) => {
+                    orderbook.link(&first, &second, cancel_after).is_ok()
+                }
+                None => false,
+            };
+
+            if !relinked {
+                stale.remove(key);
+                recovery.discarded += 1;
+            }
+        }
+
+        links.apply_batch(stale)?;
+        db.flush()?;
+
+        // The counter is persisted as well, so sequences are never handed out
+        // twice, not even those of orders that are gone by now.
+        orderbook.next_sequence = orderbook.next_sequence.max(next_sequence);
+
+        orderbook.storage = Some(Storage {
+            db,
+            links,
+            recovery,
+            checkpoint,
+            error: None,
+            persistence: Persistence::default(),
+            pending: None,
+            writes: 0,
+        });
+
+        Ok(orderbook)
+    }
+}
+
+impl<A> Orderbook<A> {
+    /// Sets when changes are written through, see [`Persistence`].
+    ///
+    /// Changes held back until the next checkpoint, if they were, are
+    /// written through at once. This has no effect on an orderbook that is
+    /// not persistent.
+    #[inline]
+    pub fn with_persistence(mut self, persistence: Persistence) -> Self {
+        if let Some(storage) = self.storage.as_mut() {
+            storage.set_persistence(persistence, self.next_sequence);
+        }
+        self
+    }
+}
+
+impl<A: MatchingStrategy> Orderbook<A> {
+    /// Returns what was found in the storage when the orderbook was opened
+    /// with [`Orderbook::open_persistent`], if it was.
+    #[inline]
+    pub fn recovery(&self) -> Option<Recovery> {
+        self.storage.as_ref().map(|storage| storage.recovery)
+    }
+
+    /// Makes every change written through so far durable.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error any write through has run into since the last
+    /// flush, if any, or the error of flushing itself.
+    pub fn flush(&mut self) -> Result<(), PersistError> {
+        let Some(storage) = self.storage.as_mut() else {
+            return Ok(());
+        };
+
+        if let Some(error) = storage.error.take() {
+            return Err(error.into());
+        }
+
+        storage.db.flush()?;
+
+        Ok(())
+    }
+
+    /// Writes every change held back since the last checkpoint, along with
+    /// the given one, in a single transaction, and makes them durable.
+    ///
+    /// Only under [`Persistence::Checkpoint`] are changes held back, so that
+    /// the storage is never found with any change made past the offset it
+    /// tells. Otherwise, they were written through as they were made, and so
+    /// may be found along with an older offset. Either way, it is meant to be
+    /// called only once the input it tells is done with, not while matching.
+    /// This has no effect on an orderbook that is not persistent.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error any write through has run into since the last
+    /// flush, if any, or the error of writing the checkpoint itself, in which
+    /// case the storage is left as of the previous one.
+    pub fn checkpoint(
+        &mut self,
+        checkpoint: Checkpoint,
+    ) -> Result<(), PersistError> {
+        let Some(storage) = self.storage.as_mut() else {
+            return Ok(());
+        };
+
+        storage.checkpoint(checkpoint, self.next_sequence)
+    }
+
+    /// Returns the checkpoint the storage was last left at, either when the
+    /// orderbook was opened or since, if it is persistent and ever was.
+    #[inline]
+    pub fn last_checkpoint(&self) -> Option<Checkpoint> {
+        self.storage.as_ref().and_then(|storage| storage.checkpoint)
+    }
+
+    /// Returns how many order entries were written to the storage since the
+    /// orderbook was opened, removals included, if it is persistent.
+    #[inline]
+    pub fn storage_writes(&self) -> Option<u64> {
+        self.storage.as_ref().map(|storage| storage.writes)
+    }
+}
+
+/// When the changes a persistent orderbook goes through are written to its
+/// storage.
+///
+/// Incoming orders are only ever written once they rest on the book, so
+/// those that fill within a single matching call never are. Resting orders,
+/// though, may be written more than once in one call, e.g. an iceberg order
+/// that shows a new slice after every fill.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Persistence {
+    /// Every change is written through as it is made.
+    #[default]
+    Always,
+    /// Changes are held back until the matching call is done, and only what
+    /// is left resting by then is written, in a single batch. Anything done
+    /// outside of matching is still written through at once.
+    Resting,
+    /// Every change, links included, is held back until the next
+    /// [`Orderbook::checkpoint`], which writes them all in a single
+    /// transaction, along with the input offset it is given. Should the
+    /// process crash, the orderbook is thus restored as of the last
+    /// checkpoint, and so is the offset to resume the input from.
+    Checkpoint,
+}
+
+/// How far the input of a persistent orderbook was processed when its
+/// storage was last written, see [`Orderbook::checkpoint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// The offset of the first input not processed yet, in whatever unit the
+    /// input is read in.
+    pub offset: u64,
+    /// A hash of the state the book was in, as told by whoever checkpointed
+    /// it, for the restored book to be validated against.
+    pub state_hash: u32,
+}
+
+/// What was found in the storage of a persistent orderbook when it was
+/// opened.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Recovery {
+    /// How many resting orders were restored.
+    pub restored: usize,
+    /// How many entries were corrupt or stale and so removed.
+    pub discarded: usize,
+}
+
+#[derive(Debug, Error)]
+pub enum PersistError {
+    #[error(transparent)]
+    Storage(#[from] sled::Error),
+}
+
+impl From<TransactionError<Infallible>> for PersistError {
+    #[inline]
+    fn from(error: TransactionError<Infallible>) -> Self {
+        match error {
+            TransactionError::Abort(never) => match never {},
+            TransactionError::Storage(error) => error.into(),
+        }
+    }
+}
+
+/// The write-through side of a persistent orderbook.
+///
+/// Every resting order is kept under its sequence, next to a checksum of its
+/// encoding, along with the sequence the next queued order gets and the last
+/// checkpoint. Links are kept apart, under the id of the first order of each
+/// pair.
+pub(super) struct Storage {
+    db: sled::Db,
+    links: sled::Tree,
+    recovery: Recovery,
+    checkpoint: Option<Checkpoint>,
+    error: Option<sled::Error>,
+    persistence: Persistence,
+    /// The writes held back while matching, or until the next checkpoint, if
+    /// they are, see [`Persistence`].
+    pending: Option<Pending>,
+    writes: u64,
+}
+
+/// The writes held back while matching, or until the next checkpoint, a
+/// later one to the same key taking the place of the earlier.
+struct Pending {
+    entries: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+    /// Links are only ever held back until the next checkpoint.
+    links: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+    /// Whether every link written before was removed.
+    unlinked_all: bool,
+    /// Sequences from this one on were handed out since writes were held
+    /// back, so they were never written to the storage.
+    fresh: u64,
+}
+
+impl Pending {
+    #[inline]
+    fn new(next_sequence: u64) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            links: BTreeMap::new(),
+            unlinked_all: false,
+            fresh: next_sequence,
+        }
+    }
+}
+
+impl Storage {
+    /// Writes whatever was held back through, and holds writes back from now
+    /// on if they are to be until the next checkpoint.
+    fn set_persistence(
+        &mut self,
+        persistence: Persistence,
+        next_sequence: u64,
+    ) {
+        if let Some(pending) = self.pending.take() {
+            self.apply(pending.entries);
+            if pending.unlinked_all {
+                let result = self.links.clear();
+                self.record(result);
+            }
+            self.apply_links(pending.links);
+        }
+
+        self.persistence = persistence;
+        if persistence == Persistence::Checkpoint {
+            self.pending = Some(Pending::new(next_sequence));
+        }
+    }
+
+    /// Starts holding writes back until [`Storage::commit`], if they are to
+    /// be, `next_sequence` being the sequence the next queued order gets.
+    pub(super) fn begin(&mut self, next_sequence: u64) {
+        if self.persistence == Persistence::Resting {
+            self.pending = Some(Pending::new(next_sequence));
+        }
+    }
+
+    /// Writes everything held back since [`Storage::begin`] in a single
+    /// batch, unless it is held back until the next checkpoint.
+    pub(super) fn commit(&mut self) {
+        if self.persistence != Persistence::Resting {
+            return;
+        }
+
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+
+        self.apply(pending.entries);
+    }
+
+    /// Writes everything held back since the last checkpoint, along with the
+    /// new one, in a single transaction, then flushes.
+    fn checkpoint(
+        &mut self,
+        checkpoint: Checkpoint,
+        next_sequence: u64,
+    ) -> Result<(), PersistError> {
+        if let Some(error) = self.error.take() {
+            return Err(error.into());
+        }
+
+        let pending = match self.persistence {
+            Persistence::Checkpoint => {
+                self.pending.replace(Pending::new(next_sequence))
+            }
+            Persistence::Always | Persistence::Resting => None,
+        }
+        .unwrap_or_else(|| Pending::new(next_sequence));
+
+        let mut entries = pending.entries;
+        let writes = entries.len() as u64;
+        entries.insert(CHECKPOINT.to_vec(), Some(encode(&checkpoint)));
+
+        let mut links = BTreeMap::new();
+        if pending.unlinked_all {
+            for key in self.links.iter().keys() {
+                links.insert(key?.to_vec(), None);
+            }
+        }
+        links.extend(pending.links);
+
+        let (entries, links) = (batch(entries), batch(links));
+        (&*self.db, &self.links).transaction(|(db, tree)| {
+            db.apply_batch(&entries)?;
+            tree.apply_batch(&links)?;
+            Ok(())
+        })?;
+        self.db.flush()?;
+
+        self.writes += writes;
+        self.checkpoint = Some(checkpoint);
+
+        Ok(())
+    }
+
+    /// Writes the order through, keeping its place in the level.
+    pub(super) fn update(&mut self, order: &LimitOrder) {
+        self.write([(key(order.sequence()), Some(encode(order)))]);
+    }
+
+    /// Writes the order through at the back of its level, moving it away from
+    /// the `previous` sequence it was queued at, if any.
+    pub(super) fn push_back(
+        &mut self,
+        order: &LimitOrder,
+        previous: Option<u64>,
+    ) {
+        let sequence = order.sequence();
+
+        // Moving the order is a single batch, so it is never found twice
+        // (nor lost) on reopen.
+        let previous = previous.map(|previous| (key(previous), None));
+        self.write(previous.into_iter().chain([
+            (key(sequence), Some(encode(order))),
+            (NEXT_SEQUENCE.to_vec(), Some(key(sequence + 1))),
+        ]));
+    }
+
+    /// Writes the sequence the next queued order gets through, once trades
+    /// took some as their ids, so they are never handed out again on reopen.
+    pub(super) fn advance(&mut self, next_sequence: u64) {
+        self.write([(NEXT_SEQUENCE.to_vec(), Some(key(next_sequence)))]);
+    }
+
+    pub(super) fn remove(&mut self, order: &LimitOrder) {
+        self.write([(key(order.sequence()), None)]);
+    }
+
+    /// Writes the one-cancels-other link between both orders through.
+    pub(super) fn link(
+        &mut self,
+        first: &OrderId,
+        second: &OrderId,
+        cancel_after: Option<Quantity>,
+    ) {
+        let key = first.to_string().into_bytes();
+
+        self.write_links([(key, Some(encode(&(first, second, cancel_after))))]);
+    }
+
+    /// Removes the link between both orders, whichever came first.
+    pub(super) fn unlink(&mut self, order_id: &OrderId, sibling: &OrderId) {
+        self.write_links([
+            (order_id.to_string().into_bytes(), None),
+            (sibling.to_string().into_bytes(), None),
+        ]);
+    }
+
+    /// Removes every link at once.
+    pub(super) fn unlink_all(&mut self) {
+        if let Some(pending) = self.held_links() {
+            pending.links.clear();
+            pending.unlinked_all = true;
+            return;
+        }
+
+        let result = self.links.clear();
+        self.record(result);
+    }
+
+    /// Writes the given links, removing those without a value, in a single
+    /// batch, or holds them back until the next checkpoint.
+    fn write_links(
+        &mut self,
+        entries: impl IntoIterator<Item = (Vec<u8>, Option<Vec<u8>>)>,
+    ) {
+        match self.held_links() {
+            Some(pending) => pending.links.extend(entries),
+            None => self.apply_links(entries.into_iter().collect()),
+        }
+    }
+
+    /// Returns where links are held back, if they are.
+    #[inline]
+    fn held_links(&mut self) -> Option<&mut Pending> {
+        match self.persistence {
+            Persistence::Checkpoint => self.pending.as_mut(),
+            Persistence::Always | Persistence::Resting => None,
+        }
+    }
+
+    fn apply_links(&mut self, entries: BTreeMap<Vec<u8>, Option<Vec<u8>>>) {
+        if entries.is_empty() {
+            return;
+        }
+
+        let result = self.links.apply_batch(batch(entries));
+        self.record(result);
+    }
+
+    /// Writes the given entries, removing those without a value, in a single
+    /// batch, or holds them back if matching is under way.
+    fn write(
+        &mut self,
+        entries: impl IntoIterator<Item = (Vec<u8>, Option<Vec<u8>>)>,
+    ) {
+        let Some(pending) = self.pending.as_mut() else {
+            let entries = entries.into_iter().collect::<BTreeMap<_, _>>();
+            self.apply(entries);
+            return;
+        };
+
+        for (key, value) in entries {
+            let fresh =
+                decode_sequence(&key).is_some_and(|seq| seq >= pending.fresh);
+
+            // An order queued and gone since writes were held back has nothing
+            // to remove from the storage.
+            if value.is_none() && fresh {
+                pending.entries.remove(&key);
+            } else {
+                pending.entries.insert(key, value);
+            }
+        }
+    }
+
+    fn apply(&mut self, entries: BTreeMap<Vec<u8>, Option<Vec<u8>>>) {
+        if entries.is_empty() {
+            return;
+        }
+
+        self.writes += entries.len() as u64;
+
+        let result = self.db.apply_batch(batch(entries));
+        self.record(result);
+    }
+
+    #[inline]
+    fn record(&mut self, result: sled::Result<()>) {
+        if let Err(error) = result {
+            self.error.get_or_insert(error);
+        }
+    }
+}
+
+/// The key the sequence the next queued order gets is kept under, which can
+/// never be mistaken for the one of an order.
+const NEXT_SEQUENCE: &[u8] = b"next_sequence";
+
+/// The key the last checkpoint is kept under, which can never be mistaken for
+/// the one of an order either.
+const CHECKPOINT: &[u8] = b"checkpoint";
+
+/// The tree one-cancels-other links are kept in.
+const LINKS: &[u8] = b"links";
+
+/// Turns the given entries into a batch, removing those without a value.
+fn batch(entries: BTreeMap<Vec<u8>, Option<Vec<u8>>>) -> sled::Batch {
+    let mut batch = sled::Batch::default();
+    for (key, value) in entries {
+        match value {
+            Some(value) => batch.insert(key, value),
+            None => batch.remove(key),
+        }
+    }
+
+    batch
+}
+
+fn encode(value: &impl Serialize) -> Vec<u8> {
+    let payload = serde_json::to_vec(value)
+        .expect("orders and links must always be serializable");
+
+    let mut value = Vec::with_capacity(4 + payload.len());
+    value.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    value.extend_from_slice(&payload);
+
+    value
+}
+
+fn decode<T: DeserializeOwned>(value: &[u8]) -> Option<T> {
+    let (checksum, payload) = value.split_first_chunk::<4>()?;
+
+    if u32::from_le_bytes(*checksum) != crc32fast::hash(payload) {
+        return None;
+    }
+
+    serde_json::from_slice(payload).ok()
+}
+
+fn key(sequence: u64) -> Vec<u8> {
+    sequence.to_be_bytes().to_vec()
+}
+
+fn decode_sequence(key: &[u8]) -> Option<u64> {
+    key.try_into().ok().map(u64::from_be_bytes)
+}
@@ -0,0 +1,75 @@
+use exchange_core::Exchange;
+use exchange_types::LimitOrder;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::Price;
+use exchange_types::Quantity;
+use thiserror::Error;
+
+use super::Orderbook;
+
+impl Orderbook {
+    /// Builds an orderbook shaped after the given aggregated depth, placing a
+    /// single synthetic resting order on each `(price, quantity)` level.
+    ///
+    /// This is meant to quickly set up a specific book for demos and tests
+    /// without replaying the order flow that would lead to it. Synthetic
+    /// orders get sequential ids starting at `1`, in the order the levels are
+    /// given, bids first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any level has no positive quantity, or if the best
+    /// bid is not strictly lower than the best ask, since a crossed book
+    /// could never result from matching.
+    pub fn seed_from_depth(
+        bids: impl IntoIterator<Item = (Price, Quantity)>,
+        asks: impl IntoIterator<Item = (Price, Quantity)>,
+    ) -> Result<Self, SeedError> {
+        let bids = bids.into_iter().collect::<Vec<_>>();
+        let asks = asks.into_iter().collect::<Vec<_>>();
+
+        if let Some(&(price, _)) = bids
+            .iter()
+            .chain(asks.iter())
+            .find(|(_, quantity)| *quantity <= Quantity::default())
+        {
+            return Err(SeedError::EmptyLevel { price });
+        }
+
+        let best_bid = bids.iter().map(|&(price, _)| price).max();
+        let best_ask = asks.iter().map(|&(price, _)| price).min();
+        if let (Some(bid), Some(ask)) = (best_bid, best_ask) {
+            if bid >= ask {
+                return Err(SeedError::Crossed { bid, ask });
+            }
+        }
+
+        let mut orderbook = Self::new();
+
+        let levels = bids
+            .into_iter()
+            .map(|level| (OrderSide::Bid, level))
+            .chain(asks.into_iter().map(|level| (OrderSide::Ask, level)));
+
+        for ((side, (price, quantity)), id) in levels.zip(1..) {
+            let order =
+                LimitOrder::new(OrderId::from_u128(id), side, price, quantity);
+
+            // SAFETY: we already checked that the best bid is strictly lower
+            // than the best ask, so no inserted order overlaps the opposite
+            // side.
+            unsafe { orderbook.insert(order) };
+        }
+
+        Ok(orderbook)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SeedError {
+    #[error("crossed depth (bid={}, ask={})", .bid, .ask)]
+    Crossed { bid: Price, ask: Price },
+    #[error("level without quantity (price={})", .price)]
+    EmptyLevel { price: Price },
+}
@@ -0,0 +1,241 @@
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_types::AccountId;
+use exchange_types::LimitOrder;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::Price;
+use exchange_types::Quantity;
+use thiserror::Error;
+
+use super::LinkError;
+use super::Orderbook;
+use crate::MatchingStrategy;
+
+/// A self-contained copy of every resting order in an [`Orderbook`], meant
+/// to be serialized as a single document, e.g. for cold storage or to hand a
+/// book over to another process.
+///
+/// Orders keep everything about them, such as what was already filled, their
+/// status and where they stand in the time priority of their level. So does
+/// the book keep the price it last traded at, and the reference price it was
+/// given, if any, and so are one-cancels-other pairs kept linked. At-the-close
+/// orders, which are not on the book until the closing auction, are kept queued
+/// apart, and so is the net position of every account.
+///
+/// The default snapshot is that of an empty book.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderbookSnapshot {
+    /// Every resting order, in the order they were queued.
+    orders: Vec<LimitOrder>,
+    /// The sequence the next queued order gets.
+    next_sequence: u64,
+    /// The price of the last trade, if there was any.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    last_trade_price: Option<Price>,
+    /// The reference price the price collar is centred on, if one was set,
+    /// see [`Orderbook::set_reference_price`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    reference_price: Option<Price>,
+    /// Every one-cancels-other pair of resting orders.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    links: Vec<LinkedPair>,
+    /// Every at-the-close order, in the order they came in.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    closing: Vec<LimitOrder>,
+    /// The net position of every account holding any.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    positions: Vec<Position>,
+}
+
+/// A one-cancels-other pair of resting orders, see [`Orderbook::link`].
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct LinkedPair {
+    first: OrderId,
+    second: OrderId,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    cancel_after: Option<Quantity>,
+}
+
+/// The net position of an account, see [`Orderbook::set_position`].
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Position {
+    account_id: AccountId,
+    side: OrderSide,
+    quantity: Quantity,
+}
+
+impl OrderbookSnapshot {
+    /// Returns every resting order, in the order they were queued.
+    #[inline]
+    pub fn orders(&self) -> &[LimitOrder] {
+        &self.orders
+    }
+}
+
+impl<A: MatchingStrategy> Orderbook<A> {
+    /// Takes an [`OrderbookSnapshot`] of every resting order, hidden ones
+    /// included.
+    pub fn snapshot(&self) -> OrderbookSnapshot {
+        // Levels are walked in queue order, which the sort is stable to, so
+        // orders that tie on their sequence are restored just as they queue.
+        let mut orders = [OrderSide::Ask, OrderSide::Bid]
+            .iter()
+            .flat_map(|side| self.orders_by_side[side].values())
+            .flat_map(|level| level.iter())
+            .map(|order_id| {
+                *self
+                    .orders_by_id
+                    .get(order_id)
+                    .expect("every order in tree must also be in index")
+            })
+            .collect::<Vec<_>>();
+        orders.sort_by_key(LimitOrder::sequence);
+
+        let mut links = self
+            .linked_orders
+            .pairs()
+            .map(|(first, link)| LinkedPair {
+                first,
+                second: link.sibling,
+                cancel_after: link.cancel_after,
+            })
+            .collect::<Vec<_>>();
+        links.sort_by_key(|pair| pair.first);
+
+        let mut positions = self
+            .positions
+            .iter()
+            .map(|(&account_id, &(side, quantity))| Position {
+                account_id,
+                side,
+                quantity,
+            })
+            .collect::<Vec<_>>();
+        positions.sort_by_key(|position| position.account_id);
+
+        OrderbookSnapshot {
+            orders,
+            next_sequence: self.next_sequence,
+            last_trade_price: self.last_trade_price,
+            reference_price: self.reference_price,
+            links,
+            closing: self.closing.clone(),
+            positions,
+        }
+    }
+}
+
+impl Orderbook {
+    /// Rebuilds the orderbook an [`OrderbookSnapshot`] was taken of.
+    ///
+    /// Resting orders are restored in their original price-time priority,
+    /// keeping their sequences and their links, so taking a snapshot of the
+    /// rebuilt orderbook gives the very same snapshot back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any order could not be resting on the book, i.e.
+    /// if it is closed already, if its id is taken by another order or if it
+    /// overlaps the opposite side, or if any pair could not be linked.
+    pub fn from_snapshot(
+        snapshot: OrderbookSnapshot,
+    ) -> Result<Self, SnapshotError> {
+        let OrderbookSnapshot {
+            mut orders,
+            next_sequence,
+            last_trade_price,
+            reference_price,
+            links,
+            closing,
+            positions,
+        } = snapshot;
+
+        orders.sort_by_key(LimitOrder::sequence);
+
+        let mut orderbook = Self::new();
+
+        for order in orders {
+            let order_id = order.id();
+
+            if order.is_closed() {
+                return Err(SnapshotError::Closed { order_id });
+            }
+
+            if orderbook.get(&order_id).is_some() {
+                return Err(SnapshotError::Duplicate { order_id });
+            }
+
+            if orderbook.crosses(&order) {
+                return Err(SnapshotError::Crossed { order_id });
+            }
+
+            // Orders keep the sequence they were taken with.
+            orderbook.next_sequence = order.sequence();
+
+            // SAFETY: we just checked that the order does not overlap the
+            // opposite side.
+            unsafe { orderbook.insert(order) };
+        }
+
+        orderbook.next_sequence = orderbook.next_sequence.max(next_sequence);
+        orderbook.last_trade_price = last_trade_price;
+        orderbook.reference_price = reference_price;
+
+        for pair in links {
+            orderbook.link(&pair.first, &pair.second, pair.cancel_after)?;
+        }
+
+        for order in closing {
+            let order_id = order.id();
+            if orderbook.get(&order_id).is_some() {
+                return Err(SnapshotError::Duplicate { order_id });
+            }
+
+            orderbook.closing.push(order);
+        }
+
+        for position in positions {
+            orderbook.set_position(
+                position.account_id,
+                position.side,
+                position.quantity,
+            );
+        }
+
+        Ok(orderbook)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("order is closed (order_id={})", .order_id)]
+    Closed { order_id: OrderId },
+    #[error("order overlaps the opposite side (order_id={})", .order_id)]
+    Crossed { order_id: OrderId },
+    #[error("duplicate order (order_id={})", .order_id)]
+    Duplicate { order_id: OrderId },
+    #[error(transparent)]
+    Link(#[from] LinkError),
+}
@@ -0,0 +1,67 @@
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_types::LimitOrder;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::Price;
+use exchange_types::Quantity;
+
+use super::Orderbook;
+use crate::MatchingStrategy;
+
+/// An owned view of every resting order in an [`Orderbook`], per side and in
+/// price-time priority.
+///
+/// This has the very same shape as the debug output used throughout the
+/// snapshot tests, so tooling can consume it programmatically instead of
+/// parsing debug output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BookView {
+    #[cfg_attr(feature = "serde", serde(rename = "Ask"))]
+    pub ask: Vec<OrderView>,
+    #[cfg_attr(feature = "serde", serde(rename = "Bid"))]
+    pub bid: Vec<OrderView>,
+}
+
+/// A resting order, as shown by [`BookView`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderView {
+    pub limit_price: Price,
+    pub remaining: Quantity,
+    pub status: OrderStatus,
+    /// When the order was first queued, or zero if it never was stamped, see
+    /// [`LimitOrder::created_at`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "num::Zero::is_zero")
+    )]
+    pub created_at: u64,
+}
+
+impl From<&LimitOrder> for OrderView {
+    #[inline]
+    fn from(order: &LimitOrder) -> Self {
+        Self {
+            limit_price: order
+                .limit_price()
+                .expect("orderbook orders always have limit price"),
+            remaining: order.displayed(),
+            status: order.status(),
+            created_at: order.created_at(),
+        }
+    }
+}
+
+impl<A: MatchingStrategy> Orderbook<A> {
+    /// Returns an owned [`BookView`] of the orderbook.
+    pub fn view(&self) -> BookView {
+        let view = |side| self.iter(&side).map(OrderView::from).collect();
+
+        BookView {
+            ask: view(OrderSide::Ask),
+            bid: view(OrderSide::Bid),
+        }
+    }
+}
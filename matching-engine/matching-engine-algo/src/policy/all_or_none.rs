@@ -0,0 +1,46 @@
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::Trade;
+
+use super::crosses;
+use super::fill_or_kill::FillOrKill;
+use crate::MatchingConfig;
+use crate::RejectReason;
+
+/// Cancels the incoming order if it is all-or-none, cannot be filled whole
+/// right away, and yet crosses the opposite side, returning why it was
+/// cancelled.
+///
+/// It must not trade at all unless filled whole, nor rest crossing the
+/// orders that could not fill it, so there is nothing else left for it to
+/// do. One that does not cross anything rests whole until it can be filled,
+/// and only ever trades as a maker from then on.
+#[inline]
+pub(crate) fn cancel_unfillable<O, E>(
+    incoming_order: &mut O,
+    exchange: &E,
+) -> Option<RejectReason>
+where
+    E: MatchingConfig,
+    <E as Exchange>::Order: Trade<O>,
+    O: Asset<
+        OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+        OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+        OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+        OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+        OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+        OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+    >,
+{
+    if !incoming_order.is_all_or_none()
+        || incoming_order.is_closed()
+        || FillOrKill::can_fill(incoming_order, exchange)
+        || !crosses(incoming_order, exchange)
+    {
+        return None;
+    }
+
+    incoming_order.cancel();
+
+    Some(RejectReason::AllOrNone)
+}
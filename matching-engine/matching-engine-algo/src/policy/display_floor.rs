@@ -0,0 +1,55 @@
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::Trade;
+
+use super::seq;
+use super::Policy;
+use crate::MatchingConfig;
+use crate::RejectReason;
+
+pub(super) struct DisplayFloor;
+impl<O, E> Policy<O, E, seq::Before> for DisplayFloor
+where
+    E: MatchingConfig,
+    <E as Exchange>::Order: Trade<O>,
+    O: Asset<
+        OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+        OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+        OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+        OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+        OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+        OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+    >,
+{
+    #[inline]
+    fn enforce(
+        &self,
+        incoming_order: &mut O,
+        exchange: &E,
+    ) -> Option<RejectReason> {
+        let (Some(display_quantity), Some(display_floor)) =
+            (incoming_order.display_quantity(), exchange.display_floor())
+        else {
+            // Only iceberg orders show less than they have, and there may be
+            // no floor to hold them to either.
+            return None;
+        };
+
+        if display_quantity >= display_floor.min() {
+            return None;
+        }
+
+        match display_floor {
+            crate::DisplayFloor::Bump(min) => {
+                incoming_order.set_display_quantity(min);
+                None
+            }
+            crate::DisplayFloor::Reject(_) => {
+                // Orders asking to show too little are rejected as a whole,
+                // before they get the chance to trade.
+                incoming_order.cancel();
+                Some(RejectReason::DisplayFloor)
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use either::Either;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::Opposite as _;
+use exchange_core::Trade;
+
+use super::crosses;
+use crate::whole_lots;
+use crate::MatchingConfig;
+use crate::RejectReason;
+
+/// Returns the id of the most relevant resting order the incoming order may
+/// trade with next, if any is left, passing over those it is not eligible to
+/// trade with, see [`passes_over`].
+///
+/// Those keep their priority, and are matched once an incoming order large
+/// enough comes along. The order returned may be priced past the incoming
+/// one, in which case it is up to the caller to stop matching.
+#[inline]
+pub(crate) fn next_maker<O, E>(
+    incoming_order: &O,
+    exchange: &E,
+) -> Option<<O as Asset>::OrderId>
+where
+    E: MatchingConfig,
+    <E as Exchange>::Order: Trade<O>,
+    O: Asset<
+        OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+        OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+        OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+        OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+        OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+        OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+    >,
+{
+    let lot_size = exchange.lot_size();
+    let remaining = incoming_order.remaining();
+
+    exchange
+        .iter_with_hidden(&incoming_order.side().opposite())
+        .find(|order| {
+            order.matches(incoming_order).is_err()
+                || !passes_over::<O>(remaining, &**order, lot_size)
+        })
+        .map(|order| order.id())
+}
+
+/// Cancels whatever is left of the incoming order if it would rest crossing
+/// the opposite side, returning why it was cancelled.
+///
+/// Once matching is done, all that is left crossing it are the orders it
+/// was not eligible to trade with, see [`passes_over`]. Those keep their
+/// priority, so it would never trade with them as a maker either, and the
+/// book must not be left crossed.
+#[inline]
+pub(crate) fn cancel_ineligible<O, E>(
+    incoming_order: &mut O,
+    exchange: &E,
+) -> Option<RejectReason>
+where
+    E: MatchingConfig,
+    <E as Exchange>::Order: Trade<O>,
+    O: Asset<
+        OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+        OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+        OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+        OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+        OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+        OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+    >,
+{
+    if !incoming_order.is_open() || !crosses(incoming_order, exchange) {
+        return None;
+    }
+
+    incoming_order.cancel();
+
+    Some(RejectReason::Ineligible)
+}
+
+/// Returns `true` if `remaining`, what is left of an incoming order, is too
+/// little to trade with the resting order, i.e. to fill it whole if it is
+/// all-or-none, or to meet its minimum counterparty quantity, if it has one.
+#[inline]
+pub(crate) fn passes_over<O: Asset>(
+    remaining: Either<O::OrderNotional, O::OrderQuantity>,
+    resting_order: &impl Asset<
+        OrderNotional = O::OrderNotional,
+        OrderPrice = O::OrderPrice,
+        OrderQuantity = O::OrderQuantity,
+    >,
+    lot_size: Option<O::OrderQuantity>,
+) -> bool {
+    if !resting_order.is_all_or_none()
+        && resting_order.min_counterparty_qty().is_none()
+    {
+        return false;
+    }
+
+    let Either::Right(left) = resting_order.remaining() else {
+        unreachable!("resting orders are always priced by quantity");
+    };
+    let limit_price = resting_order
+        .limit_price()
+        .expect("maker orders always have a limit price");
+
+    let wanted = remaining.either(
+        |funds| {
+            whole_lots::<O>(funds, limit_price, lot_size)
+                .unwrap_or_else(|| funds / limit_price)
+        },
+        |quantity| quantity,
+    );
+
+    resting_order.is_all_or_none() && wanted < left
+        || resting_order
+            .min_counterparty_qty()
+            .is_some_and(|min_counterparty_qty| wanted < min_counterparty_qty)
+}
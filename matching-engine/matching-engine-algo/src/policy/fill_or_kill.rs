@@ -7,13 +7,18 @@ use exchange_core::Opposite;
 use exchange_core::Trade;
 use num::Zero;
 
+use super::eligible::passes_over;
 use super::seq;
+use super::slippage;
 use super::Policy;
+use crate::whole_lots;
+use crate::MatchingConfig;
+use crate::RejectReason;
 
 pub(super) struct FillOrKill;
 impl<O, E> Policy<O, E, seq::Before> for FillOrKill
 where
-    E: Exchange,
+    E: MatchingConfig,
     <E as Exchange>::Order: Trade<O>,
     O: Asset<
         OrderId = <<E as Exchange>::Order as Asset>::OrderId,
@@ -25,7 +30,11 @@ where
     >,
 {
     #[inline]
-    fn enforce(&self, incoming_order: &mut O, exchange: &E) {
+    fn enforce(
+        &self,
+        incoming_order: &mut O,
+        exchange: &E,
+    ) -> Option<RejectReason> {
         if incoming_order.is_fill_or_kill()
             && !FillOrKill::can_fill(incoming_order, exchange)
         {
@@ -33,7 +42,11 @@ where
             // execute an all-or-none order; otherwise, the all-or-none
             // order must be cancelled.
             incoming_order.cancel();
+
+            return Some(RejectReason::FillOrKill);
         }
+
+        None
     }
 }
 
@@ -45,14 +58,21 @@ impl FillOrKill {
     /// and `exchange` is the orderbook that we'll use to compare against
     /// the given order.
     ///
+    /// Hidden orders are walked as well, since they trade just like any
+    /// other, iceberg orders along with their hidden reserve, and funds-priced
+    /// orders only ever count the whole lots they may buy, if the exchange has
+    /// a lot size. Orders only count if what is left by then is eligible to
+    /// trade with them, as they are passed over otherwise, e.g. all-or-none
+    /// ones it cannot fill whole.
+    ///
     /// `can_fill()` is short-circuiting; in other words, it will stop
     /// processing as soon as it ensures the given order can be full-filled,
     /// given that no matter what else happens, the result will also be
     /// `true`.
     #[inline]
-    fn can_fill<O, E>(incoming_order: &O, exchange: &E) -> bool
+    pub(super) fn can_fill<O, E>(incoming_order: &O, exchange: &E) -> bool
     where
-        E: Exchange,
+        E: MatchingConfig,
         <E as Exchange>::Order: Trade<O>,
         O: Asset<
             OrderId = <<E as Exchange>::Order as Asset>::OrderId,
@@ -63,15 +83,33 @@ impl FillOrKill {
             OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
         >,
     {
+        let side = incoming_order.side().opposite();
+        let best_price =
+            exchange.peek(&side).and_then(|order| order.limit_price());
+        let max_slippage = incoming_order.max_slippage();
+        let lot_size = exchange.lot_size();
+
         let mut iter = exchange
-            .iter(&incoming_order.side().opposite())
+            .iter_with_hidden(&side)
             .take_while(|order| {
                 // Gather only the orders that are compatible to the
                 // `incoming_order`.
                 order.matches(incoming_order).is_ok()
             })
+            .take_while(|order| {
+                // Nor can it trade past its slippage cap, if any.
+                let (Some(max_slippage), Some(best_price), Some(limit_price)) =
+                    (max_slippage, best_price, order.limit_price())
+                else {
+                    return true;
+                };
+
+                slippage(best_price, limit_price) <= max_slippage
+            })
             .map(|order| {
-                let Either::Right(remaining) = order.remaining() else {
+                // Iceberg orders trade their hidden reserve as well, slice
+                // after slice.
+                let Either::Right(remaining) = order.total_remaining() else {
                     unreachable!();
                 };
 
@@ -80,6 +118,7 @@ impl FillOrKill {
                         .limit_price()
                         .expect("maker orders always have a limit price"),
                     remaining,
+                    order,
                 )
             });
 
@@ -87,7 +126,23 @@ impl FillOrKill {
             Either::Left(remaining) => {
                 iter.try_fold(
                     remaining,
-                    |mut remaining, (limit_price, available_to_trade)| {
+                    |mut remaining, (limit_price, available_to_trade, order)| {
+                        if passes_over::<O>(
+                            Either::Left(remaining),
+                            &*order,
+                            lot_size,
+                        ) {
+                            return ControlFlow::Continue(remaining);
+                        }
+
+                        let available_to_trade = match whole_lots::<O>(
+                            remaining,
+                            limit_price,
+                            lot_size,
+                        ) {
+                            Some(lots) => available_to_trade.min(lots),
+                            None => available_to_trade,
+                        };
                         let available_to_trade =
                             limit_price * available_to_trade;
 
@@ -112,7 +167,15 @@ impl FillOrKill {
             Either::Right(remaining) => {
                 iter.try_fold(
                     remaining,
-                    |mut remaining, (_limit_price, available_to_trade)| {
+                    |mut remaining, (_limit_price, available_to_trade, order)| {
+                        if passes_over::<O>(
+                            Either::Right(remaining),
+                            &*order,
+                            lot_size,
+                        ) {
+                            return ControlFlow::Continue(remaining);
+                        }
+
                         remaining =
                             remaining - available_to_trade.min(remaining);
 
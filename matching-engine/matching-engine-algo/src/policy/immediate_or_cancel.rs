@@ -4,6 +4,7 @@ use exchange_core::Trade;
 
 use super::seq;
 use super::Policy;
+use crate::RejectReason;
 
 pub(super) struct ImmediateOrCancel;
 impl<O, E> Policy<O, E, seq::Late> for ImmediateOrCancel
@@ -20,11 +21,14 @@ where
     >,
 {
     #[inline]
-    fn enforce(&self, incoming_order: &mut O, _: &E) {
+    fn enforce(&self, incoming_order: &mut O, _: &E) -> Option<RejectReason> {
         if incoming_order.is_immediate_or_cancel() {
             // If incoming order is immediate or cancel, it must be closed
             // at the end of matching.
-            incoming_order.cancel();
+            incoming_order.expire();
         }
+
+        // Expiring is just what they asked for, so they are not rejected.
+        None
     }
 }
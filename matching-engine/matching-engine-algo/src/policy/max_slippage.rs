@@ -0,0 +1,43 @@
+use std::ops::Sub;
+
+use exchange_core::Asset;
+
+/// Prevents the incoming order from trading at `limit_price` if that is
+/// farther from `best_price` than its slippage cap, cancelling whatever is
+/// left of it instead.
+///
+/// Returns `true` if the cap is exceeded, in which case the incoming order
+/// must not be traded any further.
+#[inline]
+pub(crate) fn prevent_slippage<O>(
+    incoming_order: &mut O,
+    best_price: O::OrderPrice,
+    limit_price: O::OrderPrice,
+) -> bool
+where
+    O: Asset,
+{
+    let Some(max_slippage) = incoming_order.max_slippage() else {
+        return false;
+    };
+
+    if slippage(best_price, limit_price) <= max_slippage {
+        return false;
+    }
+
+    incoming_order.cancel();
+
+    true
+}
+
+/// Returns how far `limit_price` is from `best_price`.
+///
+/// Opposite orders are walked from the best price onwards, so how far they
+/// get from it is all that matters, whatever the side.
+#[inline]
+pub(crate) fn slippage<P>(best_price: P, limit_price: P) -> P
+where
+    P: Sub<Output = P> + Ord + Copy,
+{
+    limit_price.max(best_price) - limit_price.min(best_price)
+}
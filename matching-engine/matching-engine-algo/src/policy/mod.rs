@@ -1,6 +1,14 @@
+mod all_or_none;
+mod display_floor;
+mod eligible;
 mod fill_or_kill;
 mod immediate_or_cancel;
+mod max_slippage;
+mod participation;
 mod post_only;
+mod price_collar;
+mod reduce_only;
+mod self_trade_prevention;
 mod seq {
     pub(in crate::policy) trait Seq {}
 
@@ -13,12 +21,27 @@ mod seq {
 
 use exchange_core::Asset;
 use exchange_core::Exchange;
-use exchange_core::ExchangeExt;
+use exchange_core::Opposite as _;
 use exchange_core::Trade;
 
+pub(crate) use self::all_or_none::cancel_unfillable;
+use self::display_floor::DisplayFloor;
+pub(crate) use self::eligible::cancel_ineligible;
+pub(crate) use self::eligible::next_maker;
+pub(crate) use self::eligible::passes_over;
 use self::fill_or_kill::FillOrKill;
 use self::immediate_or_cancel::ImmediateOrCancel;
+pub(crate) use self::max_slippage::prevent_slippage;
+use self::max_slippage::slippage;
+pub(crate) use self::participation::cancel_capped;
+pub(crate) use self::participation::participation_cap;
 use self::post_only::PostOnly;
+use self::price_collar::PriceCollar;
+pub(crate) use self::reduce_only::cap_reduce_only;
+use self::reduce_only::ReduceOnly;
+pub(crate) use self::self_trade_prevention::prevent_self_trade;
+use crate::MatchingConfig;
+use crate::RejectReason;
 
 #[allow(private_bounds)]
 pub(crate) trait Policy<O, E, S>
@@ -35,7 +58,9 @@ where
         OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
     >,
 {
-    fn enforce(&self, order: &mut O, exchange: &E);
+    /// Enforces the policy on the incoming order, returning why it was
+    /// cancelled, if it was.
+    fn enforce(&self, order: &mut O, exchange: &E) -> Option<RejectReason>;
 }
 
 /// Policies that should be run before matching.
@@ -43,7 +68,7 @@ where
 pub(super) fn before_policies<'e, O, E>(
 ) -> &'e [&'e dyn Policy<O, E, seq::Before>]
 where
-    E: Exchange + ExchangeExt + 'e,
+    E: MatchingConfig + 'e,
     <E as Exchange>::Order: Trade<O>,
     O: Asset<
         OrderId = <<E as Exchange>::Order as Asset>::OrderId,
@@ -54,10 +79,12 @@ where
         OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
     >,
 {
+    const DISPLAY_FLOOR: &DisplayFloor = &DisplayFloor;
+    const PRICE_COLLAR: &PriceCollar = &PriceCollar;
     const FILL_OR_KILL: &FillOrKill = &FillOrKill;
     const POST_ONLY: &PostOnly = &PostOnly;
 
-    &[FILL_OR_KILL, POST_ONLY]
+    &[DISPLAY_FLOOR, PRICE_COLLAR, FILL_OR_KILL, POST_ONLY]
 }
 
 /// Policies that should be run after matching.
@@ -65,7 +92,7 @@ where
 pub(super) const fn late_policies<'e, O, E>(
 ) -> &'e [&'e dyn Policy<O, E, seq::Late>]
 where
-    E: Exchange + ExchangeExt + 'e,
+    E: MatchingConfig + 'e,
     <E as Exchange>::Order: Trade<O>,
     O: Asset<
         OrderId = <<E as Exchange>::Order as Asset>::OrderId,
@@ -77,6 +104,32 @@ where
     >,
 {
     const IMMEDIATE_OR_CANCEL: &ImmediateOrCancel = &ImmediateOrCancel;
+    const REDUCE_ONLY: &ReduceOnly = &ReduceOnly;
 
-    &[IMMEDIATE_OR_CANCEL]
+    &[IMMEDIATE_OR_CANCEL, REDUCE_ONLY]
+}
+
+/// Returns `true` if the incoming order would cross the opposite side, were
+/// it left to rest as it is, hidden orders included.
+///
+/// Orders it was not eligible to trade with, or not allowed to take, cross
+/// it all the same, so it must not rest on the book unless this is `false`.
+#[inline]
+pub(crate) fn crosses<O, E>(incoming_order: &O, exchange: &E) -> bool
+where
+    E: Exchange,
+    <E as Exchange>::Order: Trade<O>,
+    O: Asset<
+        OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+        OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+        OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+        OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+        OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+        OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+    >,
+{
+    exchange
+        .iter_with_hidden(&incoming_order.side().opposite())
+        .next()
+        .is_some_and(|order| order.matches(incoming_order).is_ok())
 }
@@ -0,0 +1,104 @@
+use either::Either;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::Opposite as _;
+use exchange_core::Trade;
+use num::Zero;
+
+use super::crosses;
+use super::eligible::passes_over;
+use crate::MatchingConfig;
+use crate::RejectReason;
+
+/// Returns the most the incoming order may take as it comes in, if it has a
+/// maximum participation rate: its share of the liquidity it could trade
+/// with right away, rounded down to a whole number of lots, if the exchange
+/// has a lot size.
+///
+/// Whatever it may not take is cancelled once it is capped, see
+/// [`cancel_capped`], so a limit order only rests if nothing it could trade
+/// with was left to it.
+///
+/// All-or-none orders trade as a whole or not at all, so they are never
+/// capped.
+#[inline]
+pub(crate) fn participation_cap<O, E>(
+    incoming_order: &O,
+    exchange: &E,
+) -> Option<<O as Asset>::OrderQuantity>
+where
+    E: MatchingConfig,
+    <E as Exchange>::Order: Trade<O>,
+    O: Asset<
+        OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+        OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+        OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+        OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+        OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+        OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+    >,
+{
+    if incoming_order.is_all_or_none() || incoming_order.is_fill_or_kill() {
+        return None;
+    }
+
+    let lot_size = exchange.lot_size();
+    let remaining = incoming_order.remaining();
+
+    // Hidden orders count as well, since they trade just like any other,
+    // unlike those the incoming order is not eligible to trade with.
+    let available = exchange
+        .iter_with_hidden(&incoming_order.side().opposite())
+        .take_while(|order| order.matches(incoming_order).is_ok())
+        .filter(|order| !passes_over::<O>(remaining, &**order, lot_size))
+        .fold(Zero::zero(), |available, order| {
+            let Either::Right(left) = order.remaining() else {
+                unreachable!("resting orders are always priced by quantity");
+            };
+
+            available + left
+        });
+
+    let cap = incoming_order.max_participation(available)?;
+
+    Some(match lot_size {
+        Some(lot_size) => cap - cap % lot_size,
+        None => cap,
+    })
+}
+
+/// Cancels whatever is left of the incoming order, if it has a maximum
+/// participation rate and would rest crossing the orders it was not allowed
+/// to take, returning why it was cancelled.
+///
+/// Those orders stay ahead of it, so it would never trade with them as a
+/// maker either, and the book must not be left crossed.
+#[inline]
+pub(crate) fn cancel_capped<O, E>(
+    incoming_order: &mut O,
+    participation: Option<<O as Asset>::OrderQuantity>,
+    exchange: &E,
+) -> Option<RejectReason>
+where
+    E: MatchingConfig,
+    <E as Exchange>::Order: Trade<O>,
+    O: Asset<
+        OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+        OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+        OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+        OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+        OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+        OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+    >,
+{
+    if participation.is_none()
+        || !incoming_order.is_open()
+        || !crosses(incoming_order, exchange)
+    {
+        return None;
+    }
+
+    incoming_order.cancel();
+
+    Some(RejectReason::MaxParticipation)
+}
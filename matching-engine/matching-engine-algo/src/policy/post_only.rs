@@ -5,6 +5,7 @@ use exchange_core::Trade;
 
 use super::seq;
 use super::Policy;
+use crate::RejectReason;
 
 pub(super) struct PostOnly;
 impl<O, E> Policy<O, E, seq::Before> for PostOnly
@@ -21,7 +22,11 @@ where
     >,
 {
     #[inline]
-    fn enforce(&self, incoming_order: &mut O, exchange: &E) {
+    fn enforce(
+        &self,
+        incoming_order: &mut O,
+        exchange: &E,
+    ) -> Option<RejectReason> {
         if incoming_order.is_post_only()
             && exchange
                 .peek(&incoming_order.side().opposite())
@@ -33,6 +38,10 @@ where
             // executed as taker at all, otherwise it must be cancelled before
             // enter the book.
             incoming_order.cancel();
+
+            return Some(RejectReason::PostOnly);
         }
+
+        None
     }
 }
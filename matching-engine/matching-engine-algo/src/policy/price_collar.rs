@@ -0,0 +1,48 @@
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::Trade;
+
+use super::seq;
+use super::Policy;
+use crate::MatchingConfig;
+use crate::RejectReason;
+
+pub(super) struct PriceCollar;
+impl<O, E> Policy<O, E, seq::Before> for PriceCollar
+where
+    E: MatchingConfig,
+    <E as Exchange>::Order: Trade<O>,
+    O: Asset<
+        OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+        OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+        OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+        OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+        OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+        OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+    >,
+{
+    #[inline]
+    fn enforce(
+        &self,
+        incoming_order: &mut O,
+        exchange: &E,
+    ) -> Option<RejectReason> {
+        let (Some(limit_price), Some(price_collar)) =
+            (incoming_order.limit_price(), exchange.price_collar())
+        else {
+            // Market orders have no price to collar, and there may be no
+            // reference price to collar around either.
+            return None;
+        };
+
+        if price_collar.contains(&limit_price) {
+            return None;
+        }
+
+        // Orders priced too far off the market are rejected as a whole,
+        // before they get the chance to trade.
+        incoming_order.cancel();
+
+        Some(RejectReason::PriceCollar)
+    }
+}
@@ -0,0 +1,91 @@
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::Trade;
+use num::Zero as _;
+
+use super::seq;
+use super::Policy;
+use crate::MatchingConfig;
+use crate::RejectReason;
+
+pub(super) struct ReduceOnly;
+impl<O, E> Policy<O, E, seq::Late> for ReduceOnly
+where
+    E: Exchange,
+    <E as Exchange>::Order: Trade<O>,
+    O: Asset<
+        OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+        OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+        OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+        OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+        OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+        OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+    >,
+{
+    #[inline]
+    fn enforce(&self, incoming_order: &mut O, _: &E) -> Option<RejectReason> {
+        // A post-only one never takes liquidity in the first place, so it
+        // rests instead, capped by `cap_reduce_only`.
+        if incoming_order.is_reduce_only()
+            && !incoming_order.is_post_only()
+            && incoming_order.is_open()
+        {
+            // A reduce-only order must never add liquidity, so whatever is
+            // left of it at the end of matching is cancelled instead of
+            // resting on the book.
+            incoming_order.cancel();
+
+            return Some(RejectReason::ReduceOnly);
+        }
+
+        None
+    }
+}
+
+/// Caps an order that is both post-only and reduce-only to what it may
+/// reduce, see [`MatchingConfig::reducible`], rejecting it if that is nothing
+/// at all.
+///
+/// It runs once every policy before matching has, so a post-only order that
+/// would cross is rejected as such whatever its size, and only one that is
+/// bound to rest is ever capped.
+pub(crate) fn cap_reduce_only<O, E>(
+    incoming_order: &mut O,
+    exchange: &E,
+) -> Option<RejectReason>
+where
+    E: MatchingConfig,
+    <E as Exchange>::Order: Trade<O>,
+    O: Asset<
+        OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+        OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+        OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+        OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+        OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+        OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+    >,
+    O: Clone + TryInto<<E as Exchange>::Order>,
+{
+    if incoming_order.is_closed()
+        || !incoming_order.is_post_only()
+        || !incoming_order.is_reduce_only()
+    {
+        return None;
+    }
+
+    // An order that cannot rest as it is is cancelled once it would, anyway.
+    let Ok(order) = incoming_order.clone().try_into() else {
+        return None;
+    };
+
+    let reducible = exchange.reducible(&order);
+    if reducible.is_zero() {
+        incoming_order.cancel();
+
+        return Some(RejectReason::ReduceOnly);
+    }
+
+    incoming_order.cap(reducible);
+
+    None
+}
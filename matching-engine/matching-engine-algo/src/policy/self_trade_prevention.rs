@@ -0,0 +1,39 @@
+use exchange_core::Asset;
+use exchange_core::Trade;
+
+use crate::SelfTradePrevention;
+
+/// Prevents the incoming order from trading against a resting order of the
+/// same account, cancelling either or both of them according to `mode`.
+///
+/// Returns `true` if the orders belong to the same account and would trade
+/// otherwise, in which case they must not be traded.
+#[inline]
+pub(crate) fn prevent_self_trade<O, M>(
+    mode: SelfTradePrevention,
+    incoming_order: &mut O,
+    top_order: &mut M,
+) -> bool
+where
+    O: Asset,
+    M: Trade<O>,
+{
+    // Orders that would not trade anyway, e.g. an account's bid resting
+    // below its own ask, are left alone.
+    if !top_order.is_self_trade(incoming_order)
+        || top_order.matches(incoming_order).is_err()
+    {
+        return false;
+    }
+
+    match mode {
+        SelfTradePrevention::CancelNewest => incoming_order.cancel(),
+        SelfTradePrevention::CancelOldest => top_order.cancel(),
+        SelfTradePrevention::CancelBoth => {
+            incoming_order.cancel();
+            top_order.cancel();
+        }
+    }
+
+    true
+}
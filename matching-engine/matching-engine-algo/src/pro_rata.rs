@@ -0,0 +1,443 @@
+use std::cmp::Reverse;
+use std::ops::Add;
+use std::ops::Sub;
+
+use exchange_core::Algo;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_core::Opposite;
+use exchange_core::Prorate;
+use exchange_core::Trade;
+use num::Zero;
+
+use crate::policy;
+use crate::BookEventOf;
+use crate::DefaultExchangeError;
+use crate::MatchingConfig;
+use crate::MatchingReport;
+use crate::MatchingStrategy;
+use crate::RejectReason;
+use crate::Trades;
+
+/// Matches incoming orders against every resting order of a price level at
+/// once, in proportion to the quantity each of them shows, rather than
+/// strictly by time priority.
+///
+/// Levels are still swept from the most to the least relevant one, and an
+/// incoming order large enough to take a whole level fills every order of it.
+/// Otherwise, each resting order gets its share of the incoming quantity,
+/// rounded down to a whole number of lots or, if the book has no lot size, to
+/// the last decimal place the incoming and resting quantities need, so the
+/// split never depends on how they happen to be written. Whatever rounding
+/// leaves over goes to the largest orders first, the oldest of them breaking
+/// ties, so the level always fills exactly the incoming quantity.
+///
+/// The exchange may set only part of what is taken of a level to be split
+/// this way, see [`MatchingConfig::pro_rata_ratio`], in which case the rest
+/// of it is allocated by time priority first, filling the oldest orders of
+/// the level, and the pro-rata part is then split across what they still
+/// show. The pro-rata part is rounded down, leaving whatever is over to time
+/// priority, and with a ratio of zero, levels fill strictly by time priority,
+/// just like with [`MatchingAlgo`](crate::MatchingAlgo).
+///
+/// Hidden orders show nothing, so they get no share at all. They are only
+/// matched once no displayed order is left at their price, one at a time.
+/// All-or-none orders get no share either, and are only matched whole, one
+/// at a time, as they come first among what the incoming order may take.
+/// Orders whose minimum counterparty quantity it falls short of get none at
+/// all.
+pub struct ProRataAlgo;
+impl MatchingStrategy for ProRataAlgo {
+    type Algo<O: Asset> = Self;
+}
+
+impl<O, E> Algo<O, E> for ProRataAlgo
+where
+    O: Asset,
+    E: MatchingConfig,
+{
+    type Error = DefaultExchangeError;
+    type Output = MatchingReport<
+        <O as Asset>::Trade,
+        <O as Asset>::OrderStatus,
+        BookEventOf<O>,
+        <O as Asset>::OrderId,
+    >;
+
+    fn matching(
+        exchange: &mut E,
+        mut incoming_order: O,
+    ) -> Result<Self::Output, DefaultExchangeError>
+    where
+        E: Exchange + ExchangeExt,
+        <E as Exchange>::Order: Trade<O>,
+        O: Asset<
+            OrderId = <<E as Exchange>::Order as Asset>::OrderId,
+            OrderNotional = <<E as Exchange>::Order as Asset>::OrderNotional,
+            OrderPrice = <<E as Exchange>::Order as Asset>::OrderPrice,
+            OrderQuantity = <<E as Exchange>::Order as Asset>::OrderQuantity,
+            OrderSide = <<E as Exchange>::Order as Asset>::OrderSide,
+            OrderStatus = <<E as Exchange>::Order as Asset>::OrderStatus,
+            Trade = <<E as Exchange>::Order as Asset>::Trade,
+        >,
+        O: Clone + TryInto<<E as Exchange>::Order>,
+    {
+        // Just like for `MatchingAlgo`, at-the-close orders are kept apart
+        // from the book until the closing auction.
+        if incoming_order.is_at_the_close() {
+            return Ok(crate::queue_for_close(exchange, incoming_order));
+        }
+
+        // The first policy to reject the incoming order tells why it was.
+        let mut rejected_reason =
+            policy::before_policies()
+                .iter()
+                .fold(None, |reason, policy| {
+                    reason.or(policy.enforce(&mut incoming_order, exchange))
+                });
+
+        // Only an order that is bound to rest by now is capped to what it may
+        // reduce, if it is reduce-only.
+        rejected_reason = rejected_reason
+            .or_else(|| policy::cap_reduce_only(&mut incoming_order, exchange));
+
+        let self_trade_prevention = exchange.self_trade_prevention();
+        let lot_size = exchange.lot_size();
+        let pro_rata_ratio = exchange.pro_rata_ratio();
+        let side = incoming_order.side().opposite();
+        let mut best_price = None;
+        let mut trades = Trades::new();
+        let mut events = Vec::new();
+        let mut cancelled = Vec::new();
+
+        // Just like for `MatchingAlgo`, an all-or-none order that cannot be
+        // filled whole right away is cancelled if it crosses the book.
+        rejected_reason = rejected_reason.or_else(|| {
+            policy::cancel_unfillable(&mut incoming_order, exchange)
+        });
+
+        // So may an order with a maximum participation rate only take so
+        // much of what it could trade with right away.
+        let mut participation =
+            policy::participation_cap(&incoming_order, exchange);
+
+        while !incoming_order.is_closed() {
+            let Some(top_order) = policy::next_maker(&incoming_order, exchange)
+                .and_then(|order_id| exchange.get(&order_id))
+            else {
+                // Since there is no opposite order anymore, we can move on.
+                break;
+            };
+
+            if top_order.matches(&incoming_order).is_err() {
+                // Since incoming order is not matching to top level anymore,
+                // we can also move on.
+                break;
+            }
+
+            let limit_price = top_order
+                .limit_price()
+                .expect("market makers always have a limit price");
+            let best_price = *best_price.get_or_insert(limit_price);
+
+            // Market orders may cap how far from the best opposite price they
+            // sweep the book, so they stop short of the level past the cap.
+            if policy::prevent_slippage(
+                &mut incoming_order,
+                best_price,
+                limit_price,
+            ) {
+                rejected_reason.get_or_insert(RejectReason::MaxSlippage);
+                break;
+            }
+
+            let shown = |order: &<E as Exchange>::Order| {
+                (order.id(), crate::shown(order, limit_price))
+            };
+            let top = shown(&top_order);
+            let is_all_or_none = top_order.is_all_or_none();
+
+            drop(top_order);
+
+            // All-or-none orders get no share of the level, so they are only
+            // matched as they come first among what the incoming order may
+            // take, one at a time and as a whole. Nor do those it is not
+            // eligible to trade with at all.
+            let remaining = incoming_order.remaining();
+            let (mut order_ids, mut shown): (Vec<_>, Vec<_>) = exchange
+                .iter(&side)
+                .take_while(|order| order.limit_price() == Some(limit_price))
+                .filter(|order| {
+                    !is_all_or_none
+                        && !order.is_all_or_none()
+                        && !policy::passes_over::<O>(
+                            remaining, &**order, lot_size,
+                        )
+                })
+                .map(|order| shown(&order))
+                .unzip();
+
+            // Hidden orders are never iterated over, so once they are all
+            // that is left of the level, they are matched one at a time, in
+            // time priority.
+            if order_ids.is_empty() {
+                order_ids.push(top.0);
+                shown.push(top.1);
+            }
+
+            let wanted = incoming_order
+                .remaining()
+                .left()
+                .and_then(|funds| {
+                    crate::whole_lots::<O>(funds, limit_price, lot_size)
+                })
+                .unwrap_or_else(|| crate::shown(&incoming_order, limit_price));
+            let wanted = participation.map_or(wanted, |left| wanted.min(left));
+
+            // Leftover funds may be too little to buy anything at this price,
+            // or not a single whole lot, or the order may have taken all it
+            // may already, so there is nothing left to split.
+            if wanted.is_zero() {
+                break;
+            }
+
+            let increment = lot_size.unwrap_or_else(|| {
+                shown.iter().fold(wanted.precision(), |acc, &curr| {
+                    acc.min(curr.precision())
+                })
+            });
+            let allocations =
+                allocate(wanted, &shown, increment, pro_rata_ratio);
+
+            let mut exhausted = false;
+
+            for ((order_id, shown), allocation) in
+                order_ids.into_iter().zip(shown).zip(allocations)
+            {
+                // A sibling cancelled earlier in the level gets nothing, and
+                // whatever it was allocated is left for the next pass.
+                if cancelled.contains(&order_id) {
+                    continue;
+                }
+
+                // Orders are filled in place, so they keep their time
+                // priority, unless an iceberg one shows a new slice.
+                let mut maker = exchange
+                    .get_mut(&order_id)
+                    .expect("level was just looked up");
+
+                // Orders of the same account must never trade with each
+                // other, so one of them (or both) is cancelled instead.
+                let is_self_trade = !allocation.is_zero()
+                    && policy::prevent_self_trade(
+                        self_trade_prevention,
+                        &mut incoming_order,
+                        &mut *maker,
+                    );
+
+                if is_self_trade {
+                    if incoming_order.is_closed() {
+                        rejected_reason.get_or_insert(RejectReason::SelfTrade);
+                    }
+                } else if !allocation.is_zero() {
+                    match maker.trade_up_to(&mut incoming_order, allocation) {
+                        Ok(trade) => {
+                            if let Some(left) = participation.as_mut() {
+                                *left = *left - allocation;
+                            }
+
+                            trades.push(trade);
+                        }
+                        Err(_) => exhausted = true,
+                    }
+                }
+
+                let is_closed = maker.is_closed();
+                let is_hidden = maker.is_hidden();
+                let left = crate::shown(&*maker, limit_price);
+
+                // We must explicity drop to reuse the `exchange`.
+                drop(maker);
+
+                // Just like for `MatchingAlgo`, a linked sibling is gone
+                // before the order itself may be.
+                let sibling = exchange.cancel_linked(&order_id);
+
+                if !is_hidden {
+                    crate::maker_events(
+                        &mut events,
+                        exchange,
+                        order_id,
+                        is_closed,
+                        shown,
+                        left,
+                    );
+                }
+
+                if is_closed {
+                    exchange.remove(&order_id).expect("order should be `Some`");
+
+                    if is_self_trade {
+                        cancelled.push(order_id);
+                    }
+                }
+
+                crate::linked_cancelled(&mut events, &mut cancelled, sibling);
+            }
+
+            if exhausted {
+                break;
+            }
+        }
+
+        // Just like for `MatchingAlgo`, trades are given their ids in the
+        // order they were executed.
+        for trade in &mut trades {
+            exchange.assign_trade_id(trade);
+        }
+
+        rejected_reason = policy::late_policies().iter().fold(
+            rejected_reason,
+            |reason, policy| {
+                reason.or(policy.enforce(&mut incoming_order, exchange))
+            },
+        );
+
+        // Whatever is left of an order capped to its maximum participation
+        // must not rest crossing the orders it was not allowed to take.
+        rejected_reason = rejected_reason.or_else(|| {
+            policy::cancel_capped(&mut incoming_order, participation, exchange)
+        });
+
+        // So must whatever is left of any order crossing only resting orders
+        // it was not eligible to trade with.
+        rejected_reason = rejected_reason.or_else(|| {
+            policy::cancel_ineligible(&mut incoming_order, exchange)
+        });
+
+        // If incoming order is not full-filled and open, it must be inserted
+        // into the orderbook.
+        if incoming_order.is_open() {
+            let incoming_order_id = incoming_order.id();
+
+            match incoming_order.clone().try_into() {
+                Ok(order) => {
+                    // SAFETY: just like for `MatchingAlgo`, the incoming order
+                    // only enters the book once every order of the opposite
+                    // side it matches with has already been executed, or it
+                    // was cancelled above.
+                    unsafe {
+                        exchange.insert(order);
+                    }
+
+                    events.extend(crate::added(exchange, incoming_order_id));
+                }
+                // An order that cannot rest as it is, e.g. a hidden iceberg
+                // one, is cancelled rather than left out of the book silently.
+                Err(_) => {
+                    incoming_order.cancel();
+                    rejected_reason.get_or_insert(RejectReason::Malformed);
+                }
+            }
+        }
+
+        // Market orders are immediate-or-cancel, so whatever is left of them
+        // is closed by now and reported as such, since they could never rest
+        // on the book.
+        let status = incoming_order.status();
+
+        Ok(MatchingReport {
+            trades,
+            status,
+            events,
+            cancelled,
+            rejected_reason,
+        })
+    }
+}
+
+/// Allocates `quantity` across the resting orders of a level, given in time
+/// priority, splitting `pro_rata_ratio` percent of it in proportion to the
+/// quantity each of them shows, and the rest by time priority first.
+fn allocate<Q>(
+    quantity: Q,
+    shown: &[Q],
+    increment: Q,
+    pro_rata_ratio: u8,
+) -> Vec<Q>
+where
+    Q: Add<Output = Q>
+        + Sub<Output = Q>
+        + Prorate
+        + From<u8>
+        + Copy
+        + Ord
+        + Zero,
+{
+    let pro_rata =
+        quantity.prorate(Q::from(pro_rata_ratio), Q::from(100), increment);
+
+    let mut fifo = quantity - pro_rata;
+    let fifo = shown
+        .iter()
+        .map(|&part| {
+            let allocation = part.min(fifo);
+            fifo = fifo - allocation;
+            allocation
+        })
+        .collect::<Vec<_>>();
+
+    let left = shown
+        .iter()
+        .zip(&fifo)
+        .map(|(&part, &allocation)| part - allocation)
+        .collect::<Vec<_>>();
+
+    split(pro_rata, &left, increment)
+        .into_iter()
+        .zip(fifo)
+        .map(|(pro_rata, fifo)| pro_rata + fifo)
+        .collect()
+}
+
+/// Splits `quantity` across the resting orders of a level in proportion to
+/// the quantity each of them shows, in multiples of `increment`.
+fn split<Q>(quantity: Q, shown: &[Q], increment: Q) -> Vec<Q>
+where
+    Q: Add<Output = Q> + Sub<Output = Q> + Prorate + Copy + Ord + Zero,
+{
+    let total = shown.iter().fold(Q::zero(), |acc, &curr| acc + curr);
+
+    if quantity >= total {
+        return shown.to_vec();
+    }
+
+    let mut allocations = shown
+        .iter()
+        .map(|&part| quantity.prorate(part, total, increment))
+        .collect::<Vec<_>>();
+
+    let mut left = allocations
+        .iter()
+        .fold(quantity, |acc, &allocation| acc - allocation);
+
+    // Sorting is stable, so the oldest order comes first among those of the
+    // same size.
+    let mut by_size = (0..shown.len()).collect::<Vec<_>>();
+    by_size.sort_by_key(|&index| Reverse(shown[index]));
+
+    // Since `quantity` is less than `total`, there is always room left for
+    // whatever rounding left over.
+    for index in by_size {
+        if left.is_zero() {
+            break;
+        }
+
+        let extra = (shown[index] - allocations[index]).min(left);
+        allocations[index] = allocations[index] + extra;
+        left = left - extra;
+    }
+
+    allocations
+}
@@ -0,0 +1,42 @@
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::Trade;
+
+use crate::Orderbook;
+use crate::OrderbookSnapshot;
+use crate::SnapshotError;
+
+/// An owned book, as [`match_pure`] takes it and gives it back: every order
+/// it rests, along with whatever else the book keeps, see
+/// [`OrderbookSnapshot`].
+///
+/// The default state is that of an empty book.
+pub type BookState = OrderbookSnapshot;
+
+/// Matches an incoming order against the given book, in price-time priority,
+/// returning the book it leaves behind along with the trades it executed,
+/// in the order they happened.
+///
+/// This is the very matching of an [`Orderbook`], with no side effect
+/// whatsoever: the book is rebuilt from the state, with no clock and no
+/// storage, and the state is taken again once matched. The same state and
+/// order therefore always give the same result, e.g. to be driven by a
+/// fuzzer checking invariants.
+///
+/// # Errors
+///
+/// Fails if the book could not be rebuilt from the state, see
+/// [`Orderbook::from_snapshot`], leaving the order unmatched. Every state it
+/// returns can be.
+pub fn match_pure(
+    book: BookState,
+    order: Order,
+) -> Result<(BookState, Vec<Trade>), SnapshotError> {
+    let mut orderbook = Orderbook::from_snapshot(book)?;
+
+    let report = orderbook
+        .matching(order)
+        .unwrap_or_else(|error| match error {});
+
+    Ok((orderbook.snapshot(), report.into_trades().into_vec()))
+}
@@ -0,0 +1,100 @@
+use crate::Trades;
+
+/// What matching a single incoming order resulted in.
+#[derive(Debug)]
+pub struct MatchingReport<T, S, E, I> {
+    pub(crate) trades: Trades<T>,
+    pub(crate) status: S,
+    pub(crate) events: Vec<E>,
+    pub(crate) cancelled: Vec<I>,
+    pub(crate) rejected_reason: Option<RejectReason>,
+}
+
+/// Why the book itself cancelled an incoming order, rather than letting it
+/// trade or rest as it asked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
+#[non_exhaustive]
+pub enum RejectReason {
+    /// Its limit price was too far off the mid price.
+    PriceCollar,
+    /// It was post-only, yet it would have traded right away.
+    PostOnly,
+    /// It was fill-or-kill, yet the book could not fill it whole.
+    FillOrKill,
+    /// It was all-or-none, yet it crossed orders that could not fill it
+    /// whole, so it could neither trade nor rest.
+    AllOrNone,
+    /// It would have traded with a resting order of its own account.
+    SelfTrade,
+    /// It would have swept the book farther than its slippage cap.
+    MaxSlippage,
+    /// It was capped to its maximum participation, so whatever was left of
+    /// it could not rest without crossing the orders it was not allowed to
+    /// take.
+    MaxParticipation,
+    /// Whatever was left of it crossed only resting orders it was too small
+    /// to trade with, i.e. all-or-none ones or those with a minimum
+    /// counterparty quantity, so it could not rest.
+    Ineligible,
+    /// It was reduce-only, so whatever was left of it could not rest.
+    ReduceOnly,
+    /// It was iceberg, yet it asked to show less than the display floor.
+    DisplayFloor,
+    /// It could not rest on the book as it was, e.g. for being both hidden
+    /// and iceberg.
+    Malformed,
+}
+
+impl<T, S: Copy, E, I> MatchingReport<T, S, E, I> {
+    /// Returns the trades executed, in the order they happened.
+    #[inline]
+    pub fn trades(&self) -> &Trades<T> {
+        &self.trades
+    }
+
+    /// Returns the status the incoming order was left with.
+    ///
+    /// An order that is still open was left resting on the book. Any other
+    /// order is done with, either because it was fully filled or because
+    /// whatever was left of it could not rest, as it happens to market
+    /// orders once the opposite side runs out.
+    #[inline]
+    pub fn status(&self) -> S {
+        self.status
+    }
+
+    /// Returns the changes made to the orders shown by the book, in the
+    /// order they happened, as needed to keep an order-level copy of it.
+    #[inline]
+    pub fn events(&self) -> &[E] {
+        &self.events
+    }
+
+    /// Returns the ids of the resting orders cancelled while matching, in the
+    /// order they were cancelled: those whose linked order traded, as
+    /// one-cancels-other pairs do, and those self-trade prevention cancelled
+    /// instead of trading with the incoming order.
+    #[inline]
+    pub fn cancelled(&self) -> &[I] {
+        &self.cancelled
+    }
+
+    /// Returns why the incoming order was cancelled by the book, if it was.
+    ///
+    /// Some rules only kick in halfway through matching, e.g. a slippage cap,
+    /// so an order may well have traded before whatever was left of it was
+    /// cancelled. Immediate-or-cancel orders that could not be filled in
+    /// full are not rejected, but expired, as they asked.
+    #[inline]
+    pub fn rejected_reason(&self) -> Option<RejectReason> {
+        self.rejected_reason
+    }
+
+    /// Returns the trades executed, dropping the rest of the report.
+    #[inline]
+    pub fn into_trades(self) -> Trades<T> {
+        self.trades
+    }
+}
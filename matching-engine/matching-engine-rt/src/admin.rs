@@ -0,0 +1,156 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use exchange_types::CancelReason;
+use thiserror::Error;
+
+use crate::CancelReport;
+use crate::Engine;
+use crate::EngineStats;
+use crate::Halt;
+
+/// A command from an operator of an [`Engine`], dispatched to it apart from
+/// the order flow, e.g. as read from a control channel, one per line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AdminCommand {
+    /// `halt`: stops matching until resumed, see [`Engine::suspend`].
+    Halt,
+    /// `resume`: matches incoming orders again, see [`Engine::resume`].
+    Resume,
+    /// `snapshot <path>`: writes a snapshot of the book to the given file,
+    /// as JSON, see [`Orderbook::snapshot`].
+    ///
+    /// [`Orderbook::snapshot`]: matching_engine_algo::Orderbook::snapshot
+    Snapshot(PathBuf),
+    /// `cancel-all`: cancels every resting order, see
+    /// [`Engine::cancel_all`].
+    CancelAll,
+    /// `stats`: tells what the engine matched so far, see [`Engine::stats`].
+    Stats,
+    /// `state-hash`: tells the checksum of the whole book, see
+    /// [`Engine::state_hash`].
+    StateHash,
+}
+
+impl FromStr for AdminCommand {
+    type Err = AdminError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let command = words.next().ok_or(AdminError::Empty)?;
+
+        let command = match command {
+            "halt" => AdminCommand::Halt,
+            "resume" => AdminCommand::Resume,
+            "snapshot" => {
+                let path = words.next().ok_or(AdminError::MissingPath)?;
+                AdminCommand::Snapshot(path.into())
+            }
+            "cancel-all" => AdminCommand::CancelAll,
+            "stats" => AdminCommand::Stats,
+            "state-hash" => AdminCommand::StateHash,
+            command => return Err(AdminError::Unknown(command.to_owned())),
+        };
+
+        match words.next() {
+            Some(word) => Err(AdminError::Unexpected(word.to_owned())),
+            None => Ok(command),
+        }
+    }
+}
+
+/// What an [`AdminCommand`] did, displayed as a single line for the operator
+/// who sent it.
+#[derive(Debug)]
+pub enum AdminReply {
+    Halted,
+    /// Along with the circuit breaker halt it ended, if that is what halted
+    /// matching.
+    Resumed(Option<Halt>),
+    /// Along with how many resting orders the snapshot holds.
+    Snapshot(usize),
+    Cancelled(Vec<CancelReport>),
+    Stats(EngineStats),
+    StateHash(u32),
+}
+
+impl fmt::Display for AdminReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdminReply::Halted => write!(f, "halted"),
+            AdminReply::Resumed(None) => write!(f, "resumed"),
+            AdminReply::Resumed(Some(halt)) => write!(
+                f,
+                "resumed (reference={}, price={})",
+                halt.reference, halt.price
+            ),
+            AdminReply::Snapshot(orders) => {
+                write!(f, "snapshot taken (orders={orders})")
+            }
+            AdminReply::Cancelled(cancelled) => {
+                write!(f, "cancelled (orders={})", cancelled.len())
+            }
+            AdminReply::Stats(stats) => write!(
+                f,
+                "stats (trades={}, matched={}, notional={})",
+                stats.trades, stats.matched, stats.notional
+            ),
+            AdminReply::StateHash(hash) => write!(f, "state hash {hash:08x}"),
+        }
+    }
+}
+
+impl Engine {
+    /// Dispatches a command from an operator, returning what it did.
+    ///
+    /// # Errors
+    ///
+    /// Fails only if a snapshot could not be written, in which case the
+    /// engine is left untouched.
+    pub fn admin(
+        &mut self,
+        command: AdminCommand,
+    ) -> Result<AdminReply, AdminError> {
+        Ok(match command {
+            AdminCommand::Halt => {
+                self.suspend();
+                AdminReply::Halted
+            }
+            AdminCommand::Resume => AdminReply::Resumed(self.resume()),
+            AdminCommand::Snapshot(path) => {
+                let snapshot = self.orderbook().snapshot();
+
+                let mut writer = io::BufWriter::new(fs::File::create(path)?);
+                serde_json::to_writer(&mut writer, &snapshot)?;
+                writer.flush()?;
+
+                AdminReply::Snapshot(snapshot.orders().len())
+            }
+            AdminCommand::CancelAll => {
+                AdminReply::Cancelled(self.cancel_all(CancelReason::Admin))
+            }
+            AdminCommand::Stats => AdminReply::Stats(self.stats().clone()),
+            AdminCommand::StateHash => AdminReply::StateHash(self.state_hash()),
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AdminError {
+    #[error("empty command")]
+    Empty,
+    #[error("missing path")]
+    MissingPath,
+    #[error("unknown command (command={})", .0)]
+    Unknown(String),
+    #[error("unexpected argument (argument={})", .0)]
+    Unexpected(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Serialize(#[from] serde_json::Error),
+}
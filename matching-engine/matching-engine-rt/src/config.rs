@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+#[cfg(feature = "config")]
+use std::fs;
+#[cfg(feature = "config")]
+use std::io;
+#[cfg(feature = "config")]
+use std::path::Path;
+
+use exchange_types::AssetPair;
+use exchange_types::CircuitBreaker;
+use exchange_types::FeeSchedule;
+use exchange_types::Notional;
+use exchange_types::Price;
+use exchange_types::PriceCollar;
+use exchange_types::Quantity;
+use thiserror::Error;
+
+use crate::Engine;
+
+/// The parameters of a single symbol, applied to its [`Engine`] as it is
+/// created by a [`MultiEngine`](crate::MultiEngine).
+///
+/// Every parameter is optional, and one left out keeps the default of a
+/// plain [`Engine`]: any price or quantity goes, limit prices are not
+/// collared, orders may be worth any notional and matching is never halted.
+/// The fee schedule defaults to the one of the `MultiEngine` instead. A
+/// symbol with no configuration at all gets those defaults throughout.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(default, deny_unknown_fields)]
+pub struct SymbolConfig {
+    /// Every limit price must be a multiple of it, see
+    /// [`Engine::with_tick_size`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tick_size: Option<Price>,
+    /// Every quantity must be a multiple of it, see
+    /// [`Engine::with_lot_size`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lot_size: Option<Quantity>,
+    /// How far off the mid price limit orders may be, see
+    /// [`Engine::with_price_collar`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_collar: Option<PriceCollar>,
+    /// The largest value an order may have, see
+    /// [`Engine::with_max_order_notional`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_order_notional: Option<Notional>,
+    /// How far the market may move before matching is halted, see
+    /// [`Engine::with_circuit_breaker`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub circuit_breaker: Option<CircuitBreaker>,
+    /// The fees charged on every trade of the symbol, see
+    /// [`Engine::with_fee_schedule`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_schedule: Option<FeeSchedule>,
+}
+
+impl SymbolConfig {
+    /// Returns an error if any size it sets is not positive, as the engine
+    /// would panic on it.
+    fn check(&self, symbol: &AssetPair) -> Result<(), ConfigError> {
+        let not_positive = |field| ConfigError::NotPositive {
+            symbol: symbol.clone(),
+            field,
+        };
+
+        if self.tick_size.is_some_and(|size| size <= Price::default()) {
+            return Err(not_positive("tick_size"));
+        }
+        if self
+            .lot_size
+            .is_some_and(|size| size <= Quantity::default())
+        {
+            return Err(not_positive("lot_size"));
+        }
+        if self
+            .max_order_notional
+            .is_some_and(|notional| notional <= Notional::default())
+        {
+            return Err(not_positive("max_order_notional"));
+        }
+
+        Ok(())
+    }
+
+    /// Applies every parameter it sets to the engine.
+    pub(crate) fn apply(&self, mut engine: Engine) -> Engine {
+        if let Some(tick_size) = self.tick_size {
+            engine = engine.with_tick_size(tick_size);
+        }
+        if let Some(lot_size) = self.lot_size {
+            engine = engine.with_lot_size(lot_size);
+        }
+        if let Some(price_collar) = self.price_collar {
+            engine = engine.with_price_collar(price_collar);
+        }
+        if let Some(max_order_notional) = self.max_order_notional {
+            engine = engine.with_max_order_notional(max_order_notional);
+        }
+        if let Some(circuit_breaker) = self.circuit_breaker {
+            engine = engine.with_circuit_breaker(circuit_breaker);
+        }
+        if let Some(fee_schedule) = self.fee_schedule {
+            engine = engine.with_fee_schedule(fee_schedule);
+        }
+
+        engine
+    }
+}
+
+/// The configuration of every symbol, by symbol, as loaded from a file.
+///
+/// ```json
+/// {
+///     "BTC/USDC": { "tick_size": 0.5, "lot_size": 0.001 },
+///     "ETH/USDC": { "tick_size": 0.01 }
+/// }
+/// ```
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+#[serde(transparent)]
+pub struct SymbolConfigs(HashMap<AssetPair, SymbolConfig>);
+
+impl SymbolConfigs {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the configuration of the given symbol, replacing any it had.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any size it sets is not positive.
+    pub fn insert(
+        &mut self,
+        symbol: AssetPair,
+        config: SymbolConfig,
+    ) -> Result<(), ConfigError> {
+        config.check(&symbol)?;
+        self.0.insert(symbol, config);
+
+        Ok(())
+    }
+
+    /// Returns the configuration of the given symbol, if it has any.
+    #[inline]
+    pub fn get(&self, symbol: &AssetPair) -> Option<&SymbolConfig> {
+        self.0.get(symbol)
+    }
+
+    /// Creates the engine of the given symbol with its parameters, charging
+    /// the given fees unless it sets its own.
+    pub(crate) fn engine(
+        &self,
+        symbol: &AssetPair,
+        fee_schedule: FeeSchedule,
+    ) -> Engine {
+        let engine =
+            Engine::new(symbol.clone()).with_fee_schedule(fee_schedule);
+
+        match self.get(symbol) {
+            Some(config) => config.apply(engine),
+            None => engine,
+        }
+    }
+
+    /// Loads the configuration of every symbol from a JSON file, mapping
+    /// each symbol to its [`SymbolConfig`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be read or parsed, or if any size it sets
+    /// is not positive.
+    #[cfg(feature = "config")]
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let reader = io::BufReader::new(fs::File::open(path)?);
+        let configs: Self = serde_json::from_reader(reader)?;
+
+        for (symbol, config) in &configs.0 {
+            config.check(symbol)?;
+        }
+
+        Ok(configs)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("{field} must be positive (symbol={symbol})")]
+    NotPositive {
+        symbol: AssetPair,
+        field: &'static str,
+    },
+    #[cfg(feature = "config")]
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[cfg(feature = "config")]
+    #[error(transparent)]
+    Parse(#[from] serde_json::Error),
+}
@@ -0,0 +1,61 @@
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+
+use exchange_types::OrderRequest;
+
+use crate::Engine;
+use crate::EngineError;
+use crate::ProcessReport;
+
+impl Engine {
+    /// Processes an incoming request just like [`Engine::process`], though
+    /// should it panic, e.g. since the book broke one of its invariants, a
+    /// [`BookDump`](matching_engine_algo::BookDump) of the book is written to
+    /// `path` as JSON before the panic goes on unwinding.
+    ///
+    /// The book is dumped as the panic left it, which may be halfway through
+    /// matching the request, so that whatever state led to it is kept for
+    /// post-mortem analysis. Should the dump itself fail, why is printed to
+    /// stderr, lest it hide the panic.
+    ///
+    /// # Errors
+    ///
+    /// Fails just like [`Engine::process`] does.
+    pub fn process_or_dump(
+        &mut self,
+        incoming_order: OrderRequest,
+        path: impl AsRef<Path>,
+    ) -> Result<ProcessReport, EngineError> {
+        // Unwinding is caught rather than a panic hook set, since a hook runs
+        // while the book is still borrowed by whatever panicked.
+        match panic::catch_unwind(AssertUnwindSafe(|| {
+            self.process(incoming_order)
+        })) {
+            Ok(result) => result,
+            Err(payload) => {
+                let path = path.as_ref();
+                if let Err(error) = self.dump(path) {
+                    eprintln!("Failed to dump the book to {path:?}: {error}");
+                }
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+
+    /// Writes a [`BookDump`](matching_engine_algo::BookDump) of the book to
+    /// `path` as JSON, whatever state it is in.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be created or written.
+    pub fn dump(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, &self.orderbook.dump())?;
+        writer.flush()
+    }
+}
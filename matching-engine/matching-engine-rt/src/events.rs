@@ -0,0 +1,79 @@
+use std::io;
+
+use exchange_core::ExchangeExt;
+use exchange_types::Price;
+use exchange_types::Trade;
+use matching_engine_algo::Orderbook;
+
+/// Something that happened to an orderbook while processing a request.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "UPPERCASE")]
+pub enum Event {
+    /// A trade between an incoming order and a resting one.
+    Trade(Trade),
+    /// The best prices of the book, after they moved.
+    Quote {
+        ask: Option<Price>,
+        bid: Option<Price>,
+    },
+}
+
+/// Writes the events of an orderbook, one JSON event per line.
+///
+/// Every trade a request resulted in is written, in the order it happened,
+/// followed by the new best prices of the book if the request moved them.
+pub struct EventWriter<W> {
+    writer: W,
+    quote: (Option<Price>, Option<Price>),
+}
+
+impl<W: io::Write> EventWriter<W> {
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            quote: (None, None),
+        }
+    }
+
+    /// Writes the events of a request that was just processed against
+    /// `orderbook`, returning how many were written.
+    pub fn write(
+        &mut self,
+        trades: impl IntoIterator<Item = Trade>,
+        orderbook: &Orderbook,
+    ) -> io::Result<usize> {
+        let mut written = 0;
+
+        for trade in trades {
+            self.write_event(&Event::Trade(trade))?;
+            written += 1;
+        }
+
+        let quote = (orderbook.best_ask(), orderbook.best_bid());
+        if quote == self.quote {
+            return Ok(written);
+        }
+
+        self.quote = quote;
+        self.write_event(&Event::Quote {
+            ask: quote.0,
+            bid: quote.1,
+        })?;
+
+        Ok(written + 1)
+    }
+
+    /// Flushes and returns the underlying writer.
+    #[inline]
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    #[inline]
+    fn write_event(&mut self, event: &Event) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, event)?;
+        self.writer.write_all(b"\n")
+    }
+}
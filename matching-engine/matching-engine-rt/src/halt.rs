@@ -0,0 +1,68 @@
+use std::collections::VecDeque;
+
+use exchange_types::CircuitBreaker;
+use exchange_types::Price;
+
+/// Whether an [`Engine`](crate::Engine) matches incoming orders at all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EngineState {
+    /// Orders are matched as they come.
+    #[default]
+    Running,
+    /// The circuit breaker tripped, so nothing that may trade is accepted
+    /// until matching is resumed. Resting orders may still be cancelled.
+    Halted(Halt),
+    /// An operator halted matching, just like the circuit breaker would, see
+    /// [`Engine::suspend`](crate::Engine::suspend).
+    Suspended,
+}
+
+/// The move that tripped the circuit breaker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Halt {
+    /// The price the move was measured from.
+    pub reference: Price,
+    /// The price traded past the threshold.
+    pub price: Price,
+}
+
+/// A circuit breaker along with the prices of the trades in its window.
+pub(crate) struct Breaker {
+    circuit_breaker: CircuitBreaker,
+    prices: VecDeque<Price>,
+}
+
+impl Breaker {
+    #[inline]
+    pub(crate) fn new(circuit_breaker: CircuitBreaker) -> Self {
+        Self {
+            circuit_breaker,
+            prices: VecDeque::with_capacity(circuit_breaker.window().get()),
+        }
+    }
+
+    /// Records the price of a trade, returning the halt it trips, if any.
+    pub(crate) fn record(&mut self, price: Price) -> Option<Halt> {
+        let halt = self
+            .prices
+            .front()
+            .filter(|&&reference| {
+                !self.circuit_breaker.band(reference).contains(&price)
+            })
+            .map(|&reference| Halt { reference, price });
+
+        if self.prices.len() == self.circuit_breaker.window().get() {
+            self.prices.pop_front();
+        }
+        self.prices.push_back(price);
+
+        halt
+    }
+
+    /// Forgets every price seen so far, so that the next trade is the new
+    /// reference.
+    #[inline]
+    pub(crate) fn reset(&mut self) {
+        self.prices.clear();
+    }
+}
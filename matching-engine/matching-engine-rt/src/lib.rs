@@ -1,57 +1,960 @@
-use compact_str::CompactString;
+use std::collections::HashMap;
+use std::mem;
+
+use exchange_core::Asset;
 use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_core::Opposite;
+use exchange_types::AssetPair;
+use exchange_types::CancelReason;
+use exchange_types::CircuitBreaker;
+use exchange_types::FeeSchedule;
+use exchange_types::Notional;
 use exchange_types::Order;
 use exchange_types::OrderId;
 use exchange_types::OrderRequest;
+use exchange_types::OrderRequestError;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::Price;
+use exchange_types::PriceCollar;
+use exchange_types::Quantity;
+use exchange_types::Trade;
+use matching_engine_algo::AmendError;
+use matching_engine_algo::Clock;
+use matching_engine_algo::DefaultExchangeError;
+use matching_engine_algo::IncrementError;
+use matching_engine_algo::InsertError;
 use matching_engine_algo::Orderbook;
+use matching_engine_algo::RejectReason;
+use matching_engine_algo::SelfTradePrevention;
+use matching_engine_algo::Trades;
 use thiserror::Error;
 
+#[cfg(feature = "admin")]
+mod admin;
+#[cfg(feature = "admin")]
+pub use admin::AdminCommand;
+#[cfg(feature = "admin")]
+pub use admin::AdminError;
+#[cfg(feature = "admin")]
+pub use admin::AdminReply;
+mod config;
+pub use config::ConfigError;
+pub use config::SymbolConfig;
+pub use config::SymbolConfigs;
+#[cfg(feature = "dump")]
+mod dump;
+#[cfg(feature = "events")]
+mod events;
+#[cfg(feature = "events")]
+pub use events::Event;
+#[cfg(feature = "events")]
+pub use events::EventWriter;
+mod halt;
+use halt::Breaker;
+pub use halt::EngineState;
+pub use halt::Halt;
+mod multi_engine;
+pub use multi_engine::MultiEngine;
+#[cfg(feature = "persist")]
+mod persist;
+#[cfg(feature = "persist")]
+pub use persist::WarmUp;
+mod quoter;
+pub use quoter::AutoQuoter;
+#[cfg(feature = "record")]
+mod recorder;
+#[cfg(feature = "record")]
+pub use recorder::Recorder;
+#[cfg(feature = "rejections")]
+mod rejections;
+#[cfg(feature = "rejections")]
+pub use rejections::Payload;
+#[cfg(feature = "rejections")]
+pub use rejections::Rejection;
+#[cfg(feature = "rejections")]
+pub use rejections::RejectionLog;
+
 pub struct Engine {
-    symbol: CompactString,
+    symbol: AssetPair,
     orderbook: Orderbook,
+    fee_schedule: FeeSchedule,
+    /// The largest value an order may have, if capped.
+    max_order_notional: Option<Notional>,
+    circuit_breaker: Option<Breaker>,
+    state: EngineState,
+    stats: EngineStats,
+}
+
+/// Cumulative figures about what an [`Engine`] has matched so far.
+///
+/// Amounts are kept as decimals, just like the trades they add up, so they
+/// lose no precision however long the engine runs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EngineStats {
+    /// Total quantity exchanged.
+    pub matched: Quantity,
+    /// Total value exchanged.
+    pub notional: Notional,
+    /// Number of trades executed.
+    pub trades: u64,
+    /// Number of orders the book cancelled rather than let them trade or
+    /// rest as they asked, by why it did, even if they traded in part first.
+    /// Requests the engine turns down before they reach the book are not
+    /// counted.
+    pub rejected: HashMap<RejectReason, u64>,
+}
+
+impl EngineStats {
+    #[inline]
+    fn count_rejection(&mut self, reason: Option<RejectReason>) {
+        if let Some(reason) = reason {
+            *self.rejected.entry(reason).or_default() += 1;
+        }
+    }
+}
+
+/// What processing a single request did to the book, as told by
+/// [`Engine::process`].
+#[derive(Debug, Default)]
+pub struct ProcessReport {
+    /// Trades executed, in the order they happened, with their fees already
+    /// charged.
+    pub trades: Trades<Trade>,
+    /// Resting orders the book cancelled along the way, in the order it did:
+    /// one-cancels-other siblings, once their linked order traded, and orders
+    /// self-trade prevention cancelled instead of trading with their own
+    /// account. Orders deleted or cancelled on request are not listed.
+    pub cancelled: Vec<OrderId>,
+}
+
+/// How far a single request filled an order, be it the incoming one or any
+/// resting order it traded with, as a settlement system consumes it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FillReport {
+    pub order_id: OrderId,
+    pub side: OrderSide,
+    /// Quantity filled by this very request.
+    pub filled_this_pass: Quantity,
+    /// Quantity filled in total, earlier requests included.
+    pub cumulative_filled: Quantity,
+    /// Average price of what this very request filled.
+    pub avg_price: Price,
+    /// Status the order was left with.
+    pub status: OrderStatus,
+}
+
+/// What matching a single incoming order did to it and to every resting order
+/// it traded with, telling the order that took liquidity apart from those
+/// that made it, e.g. to charge taker fees and pay maker rebates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchOutcome {
+    /// The incoming order, reported even if it traded nothing.
+    pub taker: OrderReport,
+    /// Every resting order it traded with, in the order they first traded.
+    pub makers: Vec<OrderReport>,
+}
+
+/// How far a single request filled an order, on either side of its trades.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OrderReport {
+    pub order_id: OrderId,
+    pub side: OrderSide,
+    /// Quantity filled by this very request.
+    pub filled: Quantity,
+    /// Status the order was left with.
+    pub status: OrderStatus,
+    /// Fees charged on what this very request filled, negative for a rebate.
+    pub fee: Notional,
+}
+
+/// An incoming order as it was before it was matched, along with the status
+/// matching left it with, if known.
+struct Taker {
+    order_id: OrderId,
+    side: OrderSide,
+    filled: Quantity,
+    status: Option<OrderStatus>,
+}
+
+/// What was left of an order once cancelled, confirming the cancel to
+/// whoever placed it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CancelReport {
+    pub order_id: OrderId,
+    pub reason: CancelReason,
+    /// Either cancelled, if nothing of it was ever filled, or closed.
+    pub status: OrderStatus,
+    /// Quantity filled before it was cancelled.
+    pub filled: Quantity,
+    /// Quantity that was left unfilled, including any hidden reserve.
+    pub remaining: Quantity,
 }
 
 impl Engine {
     #[inline]
-    pub fn new(symbol: &str) -> Self {
+    pub fn new(symbol: AssetPair) -> Self {
+        Self::from_orderbook(symbol, Orderbook::new())
+    }
+
+    /// Creates an engine serving an existing book, e.g. one restored from
+    /// storage, with default fees and no caps, halts nor stats yet.
+    #[inline]
+    pub fn from_orderbook(symbol: AssetPair, orderbook: Orderbook) -> Self {
         Self {
-            symbol: CompactString::new_inline(symbol),
-            orderbook: Orderbook::new(),
+            symbol,
+            orderbook,
+            fee_schedule: FeeSchedule::default(),
+            max_order_notional: None,
+            circuit_breaker: None,
+            state: EngineState::Running,
+            stats: EngineStats::default(),
         }
     }
 
+    /// Sets the fees charged on every trade.
+    #[inline]
+    pub fn with_fee_schedule(mut self, fee_schedule: FeeSchedule) -> Self {
+        self.fee_schedule = fee_schedule;
+        self
+    }
+
+    /// Sets the tick size every limit price must be a multiple of.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tick_size` is not positive.
+    #[inline]
+    pub fn with_tick_size(self, tick_size: Price) -> Self {
+        Self {
+            orderbook: self.orderbook.with_tick_size(tick_size),
+            ..self
+        }
+    }
+
+    /// Sets the lot size every quantity must be a multiple of.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lot_size` is not positive.
+    #[inline]
+    pub fn with_lot_size(self, lot_size: Quantity) -> Self {
+        Self {
+            orderbook: self.orderbook.with_lot_size(lot_size),
+            ..self
+        }
+    }
+
+    /// Sets how orders of the same account are kept from trading with each
+    /// other.
+    #[inline]
+    pub fn with_self_trade_prevention(
+        self,
+        self_trade_prevention: SelfTradePrevention,
+    ) -> Self {
+        Self {
+            orderbook: self
+                .orderbook
+                .with_self_trade_prevention(self_trade_prevention),
+            ..self
+        }
+    }
+
+    /// Sets the clock resting orders are stamped with as they are queued.
+    #[inline]
+    pub fn with_clock(self, clock: Clock) -> Self {
+        Self {
+            orderbook: self.orderbook.with_clock(clock),
+            ..self
+        }
+    }
+
+    /// Sets how far off the mid price incoming limit orders may be priced,
+    /// cancelling those beyond it rather than matching them.
+    #[inline]
+    pub fn with_price_collar(self, price_collar: PriceCollar) -> Self {
+        Self {
+            orderbook: self.orderbook.with_price_collar(price_collar),
+            ..self
+        }
+    }
+
+    /// Sets the largest value, i.e. limit price times quantity, an order may
+    /// have, so that fat-finger orders are rejected rather than matched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_order_notional` is not positive.
+    #[inline]
+    pub fn with_max_order_notional(
+        mut self,
+        max_order_notional: Notional,
+    ) -> Self {
+        assert!(
+            max_order_notional > Notional::default(),
+            "max order notional must be positive"
+        );
+
+        self.max_order_notional = Some(max_order_notional);
+        self
+    }
+
+    /// Sets how far the market may move before matching is halted, until it
+    /// is resumed with [`Engine::resume`].
+    ///
+    /// The request whose trades trip it is processed in full, whereas those
+    /// that follow and may trade are rejected while halted.
+    #[inline]
+    pub fn with_circuit_breaker(
+        mut self,
+        circuit_breaker: CircuitBreaker,
+    ) -> Self {
+        self.circuit_breaker = Some(Breaker::new(circuit_breaker));
+        self
+    }
+
+    /// Sets the reference price the price collar is centred on instead of
+    /// the mid price, e.g. an index fed by another system, which may then be
+    /// set again as it changes, see [`Orderbook::set_reference_price`].
+    #[inline]
+    pub fn set_reference_price(&mut self, reference_price: Price) {
+        self.orderbook.set_reference_price(reference_price);
+    }
+
+    /// Processes an incoming request, returning the trades it resulted in,
+    /// with their fees already charged, along with the resting orders it
+    /// cancelled.
+    ///
+    /// Deleting or cancelling an order that is not resting in the book, e.g.
+    /// because it was already filled, is an error, and so is creating an
+    /// order with the id of a resting one, or creating or amending an order
+    /// off the tick or lot size, or worth more than the max order notional.
+    /// So is creating or amending an order while matching is halted.
     pub fn process(
         &mut self,
         incoming_order: OrderRequest,
-    ) -> Result<(), EngineError> {
-        match incoming_order {
-            OrderRequest::Create { ref symbol, .. } => {
-                if symbol != &self.symbol {
-                    Err(SymbolError::Mismatch {
-                        expected: self.symbol.clone(),
-                        found: symbol.clone(),
+    ) -> Result<ProcessReport, EngineError> {
+        self.execute(incoming_order, &mut Vec::new())
+    }
+
+    /// Processes an incoming request just like [`Engine::process`], also
+    /// reporting how far it filled every order that traded, the incoming
+    /// one first, then every resting one, in the order they first traded.
+    ///
+    /// # Errors
+    ///
+    /// Fails just like [`Engine::process`] does.
+    pub fn process_with_fills(
+        &mut self,
+        incoming_order: OrderRequest,
+    ) -> Result<(ProcessReport, Vec<FillReport>), EngineError> {
+        let mut takers = Vec::new();
+        let report = self.execute(incoming_order, &mut takers)?;
+        let fills = self.fill_reports(&report.trades, &takers);
+
+        Ok((report, fills))
+    }
+
+    /// Processes an incoming request just like [`Engine::process`], also
+    /// telling, for every order it matched, how far it filled that order, as
+    /// the taker, and every resting order it traded with, as makers.
+    ///
+    /// Creating an order matches that one order, and creating a
+    /// one-cancels-other pair matches both its legs, one after the other.
+    /// Amending an order matches it again only if it is repriced, though it
+    /// is reported either way. Deletes and cancels match nothing at all.
+    ///
+    /// # Errors
+    ///
+    /// Fails just like [`Engine::process`] does.
+    pub fn process_with_outcomes(
+        &mut self,
+        incoming_order: OrderRequest,
+    ) -> Result<(ProcessReport, Vec<MatchOutcome>), EngineError> {
+        let mut takers = Vec::new();
+        let report = self.execute(incoming_order, &mut takers)?;
+        let fills = self.fill_reports(&report.trades, &takers);
+
+        let report_of = |order_id: OrderId, side, fee| {
+            let fill = fills.iter().find(|fill| fill.order_id == order_id);
+
+            OrderReport {
+                order_id,
+                side,
+                filled: fill.map_or_else(Quantity::default, |fill| {
+                    fill.filled_this_pass
+                }),
+                status: fill
+                    .map(|fill| fill.status)
+                    .unwrap_or_else(|| self.status_of(order_id, &takers)),
+                fee,
+            }
+        };
+
+        let outcomes = takers
+            .iter()
+            .map(|taker| {
+                let trades = report
+                    .trades
+                    .iter()
+                    .filter(|trade| trade.taker() == taker.order_id);
+
+                let mut makers = Vec::<OrderReport>::new();
+                let mut taker_fee = Notional::default();
+                for trade in trades {
+                    taker_fee += trade.taker_fee();
+
+                    match makers
+                        .iter_mut()
+                        .find(|maker| maker.order_id == trade.maker())
+                    {
+                        Some(maker) => maker.fee += trade.maker_fee(),
+                        None => makers.push(report_of(
+                            trade.maker(),
+                            taker.side.opposite(),
+                            trade.maker_fee(),
+                        )),
+                    }
+                }
+
+                MatchOutcome {
+                    taker: report_of(taker.order_id, taker.side, taker_fee),
+                    makers,
+                }
+            })
+            .collect();
+
+        Ok((report, outcomes))
+    }
+
+    fn execute(
+        &mut self,
+        incoming_order: OrderRequest,
+        takers: &mut Vec<Taker>,
+    ) -> Result<ProcessReport, EngineError> {
+        // Cancels are still let through, so traders may pull their orders
+        // while halted.
+        if !matches!(
+            incoming_order,
+            OrderRequest::Delete { .. } | OrderRequest::Cancel { .. }
+        ) {
+            match self.state {
+                EngineState::Running => {}
+                EngineState::Halted(halt) => Err(EngineError::Halted(halt))?,
+                EngineState::Suspended => Err(EngineError::Suspended)?,
+            }
+        }
+
+        let mut report = match incoming_order {
+            OrderRequest::Create { ref symbol, .. }
+            | OrderRequest::CreateAtClose { ref symbol, .. } => {
+                self.check_symbol(symbol)?;
+
+                let order = Order::try_from(incoming_order)?;
+                self.check_order(&order)?;
+
+                let order_id = order.id();
+                let side = order.side();
+                let report = self.orderbook.matching(order)?;
+                self.stats.count_rejection(report.rejected_reason());
+
+                takers.push(Taker {
+                    order_id,
+                    side,
+                    filled: Quantity::default(),
+                    status: Some(report.status()),
+                });
+
+                // Orders that traded, or cancelled resting orders, before
+                // being cancelled are not rejected as a whole, so what they
+                // did to the book is still handed back.
+                if let Some(reason) = report.rejected_reason().filter(|_| {
+                    report.trades().is_empty() && report.cancelled().is_empty()
+                }) {
+                    Err(EngineError::Rejected { order_id, reason })?;
+                }
+
+                ProcessReport {
+                    cancelled: report.cancelled().to_vec(),
+                    trades: report.into_trades(),
+                }
+            }
+            OrderRequest::CreateOco {
+                account_id,
+                ref symbol,
+                legs,
+                cancel_after,
+            } => {
+                self.check_symbol(symbol)?;
+
+                let [first, second] =
+                    legs.map(|leg| leg.into_order(account_id));
+                let (first, second) = (first?, second?);
+
+                if first.id() == second.id() {
+                    Err(InsertError::Duplicate {
+                        order_id: second.id(),
                     })?;
                 }
 
-                let order = Order::try_from(incoming_order).unwrap();
-                let _ = self.orderbook.matching(order);
+                self.check_order(&first)?;
+                self.check_order(&second)?;
+
+                self.create_oco(first, second, cancel_after, takers)?
             }
             OrderRequest::Delete { order_id } => {
-                self.orderbook.remove(&OrderId::new(order_id));
+                let order_id = OrderId::new(order_id);
+                if self.orderbook.remove(&order_id).is_none() {
+                    Err(EngineError::OrderNotFound { order_id })?;
+                }
+
+                ProcessReport::default()
+            }
+            OrderRequest::Cancel { order_id, reason } => {
+                let order_id = OrderId::new(order_id);
+                if self.cancel(&order_id, reason).is_none() {
+                    Err(EngineError::OrderNotFound { order_id })?;
+                }
+
+                ProcessReport::default()
+            }
+            OrderRequest::Amend {
+                order_id,
+                new_price,
+                new_quantity,
+            } => {
+                self.check_notional(new_price * new_quantity)?;
+
+                let order_id = OrderId::new(order_id);
+                if let Some(order) = self.orderbook.get(&order_id) {
+                    takers.push(Taker {
+                        order_id,
+                        side: order.side(),
+                        filled: order.filled(),
+                        status: None,
+                    });
+                }
+
+                let report =
+                    self.orderbook.amend(&order_id, new_price, new_quantity)?;
+                self.stats.count_rejection(report.rejected_reason());
+
+                ProcessReport {
+                    cancelled: report.cancelled().to_vec(),
+                    trades: report.into_trades(),
+                }
             }
         };
 
-        Ok(())
+        self.record(&mut report.trades);
+
+        Ok(report)
+    }
+
+    /// Charges fees on every trade executed, and keeps the stats and the
+    /// circuit breaker, if any, up to date with them.
+    fn record(&mut self, trades: &mut [Trade]) {
+        for trade in trades {
+            self.fee_schedule.apply(trade);
+
+            self.stats.matched += trade.quantity();
+            self.stats.notional += trade.notional();
+            self.stats.trades += 1;
+
+            if let Some(breaker) = self.circuit_breaker.as_mut() {
+                if let Some(halt) = breaker.record(trade.price()) {
+                    self.state = EngineState::Halted(halt);
+                }
+            }
+        }
+    }
+
+    /// Cancels a resting order for the given reason, reporting what was left
+    /// of it, unless it is neither resting in the book nor queued for the
+    /// closing auction.
+    pub fn cancel(
+        &mut self,
+        order_id: &OrderId,
+        reason: CancelReason,
+    ) -> Option<CancelReport> {
+        let mut order = self.orderbook.remove(order_id)?;
+        order.cancel();
+
+        Some(CancelReport {
+            order_id: *order_id,
+            reason,
+            status: order.status(),
+            filled: order.filled(),
+            remaining: order.remaining(),
+        })
+    }
+
+    /// Cancels every resting order for the given reason, hidden ones
+    /// included, reporting what was left of each of them, in price-time
+    /// priority, asks first, and then every at-the-close order queued, in
+    /// the order they came in.
+    pub fn cancel_all(&mut self, reason: CancelReason) -> Vec<CancelReport> {
+        let order_ids = [OrderSide::Ask, OrderSide::Bid]
+            .iter()
+            .flat_map(|side| self.orderbook.iter_with_hidden(side))
+            .chain(self.orderbook.closing())
+            .map(|order| order.id())
+            .collect::<Vec<_>>();
+
+        order_ids
+            .iter()
+            .filter_map(|order_id| self.cancel(order_id, reason))
+            .collect()
+    }
+
+    /// Returns a checksum of every level of the book, just like
+    /// [`ExchangeExt::checksum`] does of its top ones, so that two engines
+    /// fed the same requests may be told apart once they diverge.
+    ///
+    /// At-the-close orders queued, if any, are accounted for as well: each
+    /// is formatted as `id:price:quantity`, in the order they came in, and
+    /// appended to the checksum of the book after a `|`.
+    pub fn state_hash(&self) -> u32 {
+        let checksum = self.orderbook.checksum(usize::MAX);
+        let closing = self.orderbook.closing();
+        if closing.is_empty() {
+            return checksum;
+        }
+
+        let closing = closing
+            .iter()
+            .map(|order| {
+                format!(
+                    "{}:{}:{}",
+                    order.id(),
+                    order.limit_price().unwrap_or_default().normalize(),
+                    order.remaining().normalize()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        crc32fast::hash(format!("{checksum}|{closing}").as_bytes())
+    }
+
+    /// Runs the closing auction of the book, see
+    /// [`Orderbook::run_closing_auction`], charging fees on the trades it
+    /// crossed just like on any other, and returning along with them the
+    /// at-the-close orders it expired.
+    pub fn run_closing_auction(&mut self) -> (ProcessReport, Vec<Order>) {
+        let (cross, expired) = self.orderbook.run_closing_auction();
+        let mut report =
+            cross.map_or_else(ProcessReport::default, |cross| ProcessReport {
+                cancelled: cross.cancelled().to_vec(),
+                trades: Trades::from_vec(cross.into_trades()),
+            });
+        self.record(&mut report.trades);
+
+        (report, expired)
     }
 
     #[inline]
     pub fn orderbook(&self) -> &Orderbook {
         &self.orderbook
     }
+
+    #[inline]
+    pub fn symbol(&self) -> &AssetPair {
+        &self.symbol
+    }
+
+    #[inline]
+    pub fn fee_schedule(&self) -> &FeeSchedule {
+        &self.fee_schedule
+    }
+
+    /// Returns whether the engine matches incoming orders at all.
+    #[inline]
+    pub fn state(&self) -> EngineState {
+        self.state
+    }
+
+    /// Halts matching on an operator's request, until it is resumed, just
+    /// like the circuit breaker does once it trips, unless it already did.
+    #[inline]
+    pub fn suspend(&mut self) {
+        if self.state == EngineState::Running {
+            self.state = EngineState::Suspended;
+        }
+    }
+
+    /// Resumes matching once halted or suspended, returning the halt it
+    /// ends, if it was the circuit breaker that tripped.
+    ///
+    /// The circuit breaker starts over, so the first trade after resuming is
+    /// the new reference price.
+    pub fn resume(&mut self) -> Option<Halt> {
+        let EngineState::Halted(halt) = mem::take(&mut self.state) else {
+            return None;
+        };
+
+        if let Some(breaker) = self.circuit_breaker.as_mut() {
+            breaker.reset();
+        }
+
+        Some(halt)
+    }
+
+    /// Returns what the engine has matched since it was created.
+    #[inline]
+    pub fn stats(&self) -> &EngineStats {
+        &self.stats
+    }
+
+    #[inline]
+    fn check_symbol(&self, symbol: &AssetPair) -> Result<(), SymbolError> {
+        if symbol != &self.symbol {
+            return Err(SymbolError::Mismatch {
+                expected: self.symbol.clone(),
+                found: symbol.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks an incoming order before it is matched at all.
+    fn check_order(&self, order: &Order) -> Result<(), EngineError> {
+        // The order is matched before it may rest, so an id already taken
+        // must be turned down before anything trades.
+        let order_id = order.id();
+        if self.orderbook.get(&order_id).is_some() {
+            Err(InsertError::Duplicate { order_id })?;
+        }
+
+        self.orderbook.validate(order)?;
+        if let Some(notional) = order.notional() {
+            self.check_notional(notional)?;
+        }
+
+        Ok(())
+    }
+
+    /// Matches both legs of a one-cancels-other pair, one after the other,
+    /// linking whatever is left of them once both rest on the book.
+    ///
+    /// Should the first leg trade enough right away, the second one is
+    /// cancelled before it ever reaches the book, and should the second one
+    /// do, the first one is cancelled in its place.
+    fn create_oco(
+        &mut self,
+        first: Order,
+        second: Order,
+        cancel_after: Option<Quantity>,
+        takers: &mut Vec<Taker>,
+    ) -> Result<ProcessReport, EngineError> {
+        let triggered = |report: &ProcessReport| {
+            let filled = report
+                .trades
+                .iter()
+                .fold(Quantity::default(), |acc, trade| acc + trade.quantity());
+
+            match cancel_after {
+                Some(cancel_after) => filled >= cancel_after,
+                None => !filled.is_zero(),
+            }
+        };
+
+        let (first_id, second_id) = (first.id(), second.id());
+        let mut matching = |order: Order| {
+            let (order_id, side) = (order.id(), order.side());
+            let report = self.orderbook.matching(order)?;
+            self.stats.count_rejection(report.rejected_reason());
+
+            takers.push(Taker {
+                order_id,
+                side,
+                filled: Quantity::default(),
+                status: Some(report.status()),
+            });
+
+            Ok::<_, EngineError>(ProcessReport {
+                cancelled: report.cancelled().to_vec(),
+                trades: report.into_trades(),
+            })
+        };
+
+        let mut report = matching(first)?;
+        if triggered(&report) {
+            return Ok(report);
+        }
+
+        let second_report = matching(second)?;
+        if triggered(&second_report) {
+            if self.orderbook.remove(&first_id).is_some() {
+                report.cancelled.push(first_id);
+            }
+        } else if self.orderbook.get(&first_id).is_some()
+            && self.orderbook.get(&second_id).is_some()
+        {
+            self.orderbook
+                .link(&first_id, &second_id, cancel_after)
+                .expect("both legs rest on the book, unlinked");
+        }
+
+        report.trades.extend(second_report.trades);
+        report.cancelled.extend(second_report.cancelled);
+
+        Ok(report)
+    }
+
+    /// Aggregates the trades of a single request per order, as told by
+    /// [`Engine::process_with_fills`].
+    fn fill_reports(
+        &self,
+        trades: &[Trade],
+        takers: &[Taker],
+    ) -> Vec<FillReport> {
+        let mut fills = Vec::<(FillReport, Notional, Option<Quantity>)>::new();
+
+        for trade in trades {
+            let taker = takers
+                .iter()
+                .find(|taker| taker.order_id == trade.taker())
+                .expect("every trade must be taken by a known order");
+
+            for (order_id, side, left) in [
+                (trade.taker(), taker.side, trade.taker_remaining()),
+                (
+                    trade.maker(),
+                    taker.side.opposite(),
+                    Some(trade.maker_total_remaining()),
+                ),
+            ] {
+                let index = fills
+                    .iter()
+                    .position(|(fill, ..)| fill.order_id == order_id)
+                    .unwrap_or_else(|| {
+                        fills.push((
+                            FillReport {
+                                order_id,
+                                side,
+                                filled_this_pass: Quantity::default(),
+                                cumulative_filled: Quantity::default(),
+                                avg_price: Price::default(),
+                                status: OrderStatus::Partial,
+                            },
+                            Notional::default(),
+                            None,
+                        ));
+
+                        fills.len() - 1
+                    });
+
+                let (fill, notional, remaining) = &mut fills[index];
+                fill.filled_this_pass += trade.quantity();
+                fill.cumulative_filled = match order_id == trade.taker() {
+                    true => taker.filled + fill.filled_this_pass,
+                    false => trade.maker_filled(),
+                };
+                *notional += trade.notional();
+                *remaining = left;
+            }
+        }
+
+        fills
+            .into_iter()
+            .map(|(mut fill, notional, remaining)| {
+                fill.avg_price = notional / fill.filled_this_pass;
+                fill.status = match self.orderbook.get(&fill.order_id) {
+                    Some(order) => order.status(),
+                    // Orders gone from the book are either done with or were
+                    // taken off it, as one-cancels-other legs are.
+                    None => match takers
+                        .iter()
+                        .find(|taker| taker.order_id == fill.order_id)
+                        .and_then(|taker| taker.status)
+                    {
+                        Some(status)
+                            if !matches!(
+                                status,
+                                OrderStatus::Open | OrderStatus::Partial
+                            ) =>
+                        {
+                            status
+                        }
+                        _ if remaining.is_some_and(|left| left.is_zero()) => {
+                            OrderStatus::Completed
+                        }
+                        _ => OrderStatus::Closed,
+                    },
+                };
+
+                fill
+            })
+            .collect()
+    }
+
+    /// Returns the status an incoming order that traded nothing was left
+    /// with.
+    fn status_of(&self, order_id: OrderId, takers: &[Taker]) -> OrderStatus {
+        self.orderbook.get(&order_id).map_or_else(
+            || {
+                takers
+                    .iter()
+                    .find(|taker| taker.order_id == order_id)
+                    .and_then(|taker| taker.status)
+                    .unwrap_or(OrderStatus::Closed)
+            },
+            |order| order.status(),
+        )
+    }
+
+    fn check_notional(&self, notional: Notional) -> Result<(), EngineError> {
+        match self.max_order_notional {
+            Some(max_order_notional) if notional > max_order_notional => {
+                Err(EngineError::OrderTooLarge {
+                    notional,
+                    max_order_notional,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum EngineError {
+    #[error(transparent)]
+    AmendError(#[from] AmendError),
+    #[error(
+        "matching is halted (reference={}, price={})",
+        .0.reference,
+        .0.price
+    )]
+    Halted(Halt),
+    #[error("matching is suspended")]
+    Suspended,
+    #[error(transparent)]
+    IncrementError(#[from] IncrementError),
+    #[error(transparent)]
+    InsertError(#[from] InsertError),
+    #[error(transparent)]
+    MatchingError(#[from] DefaultExchangeError),
+    #[error("order not found (order_id={})", .order_id)]
+    OrderNotFound { order_id: OrderId },
+    #[error(
+        "order too large (notional={}, max_order_notional={})",
+        .notional,
+        .max_order_notional
+    )]
+    OrderTooLarge {
+        notional: Notional,
+        max_order_notional: Notional,
+    },
+    #[error("order rejected (order_id={}, reason={:?})", .order_id, .reason)]
+    Rejected {
+        order_id: OrderId,
+        reason: RejectReason,
+    },
+    #[error(transparent)]
+    RequestError(#[from] OrderRequestError),
     #[error(transparent)]
     SymbolError(#[from] SymbolError),
 }
@@ -60,7 +963,7 @@ pub enum EngineError {
 pub enum SymbolError {
     #[error("symbol mismatch (expected={}, found={})", .expected, .found)]
     Mismatch {
-        expected: CompactString,
-        found: CompactString,
+        expected: AssetPair,
+        found: AssetPair,
     },
 }
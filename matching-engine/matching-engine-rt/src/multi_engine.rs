@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use exchange_core::Asset;
+use exchange_types::AssetPair;
+use exchange_types::FeeSchedule;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::Price;
+use exchange_types::Trade;
+use matching_engine_algo::AmendError;
+use matching_engine_algo::InsertError;
+
+use crate::Engine;
+use crate::EngineError;
+use crate::ProcessReport;
+use crate::SymbolConfigs;
+
+/// Hosts one [`Engine`] per symbol in a single process, routing every
+/// request to the engine of the book it belongs to.
+///
+/// Engines are created lazily, as soon as the first order of their symbol
+/// comes in. Deletes, cancels and amends only carry an order id, so the
+/// symbol of every resting order is tracked to route them.
+///
+/// Each engine is given the parameters of its symbol as it is created, see
+/// [`MultiEngine::with_configs`], so they must be set beforehand.
+#[derive(Default)]
+pub struct MultiEngine {
+    engines: HashMap<AssetPair, Engine>,
+    symbols: HashMap<OrderId, AssetPair>,
+    fee_schedule: FeeSchedule,
+    configs: SymbolConfigs,
+}
+
+impl MultiEngine {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the fees charged on every trade, of every symbol.
+    #[inline]
+    pub fn with_fee_schedule(mut self, fee_schedule: FeeSchedule) -> Self {
+        self.fee_schedule = fee_schedule;
+        self
+    }
+
+    /// Sets the parameters of every symbol, such as its tick and lot sizes,
+    /// applied to its engine as it is created. Symbols left out get the
+    /// defaults of a [`SymbolConfig`](crate::SymbolConfig).
+    #[inline]
+    pub fn with_configs(mut self, configs: SymbolConfigs) -> Self {
+        self.configs = configs;
+        self
+    }
+
+    /// Processes an incoming request against the book of its symbol,
+    /// returning the trades it resulted in, with their fees already charged,
+    /// along with the resting orders it cancelled.
+    ///
+    /// Just like with a single [`Engine`], deleting, cancelling or amending
+    /// an order that is not resting in any book is an error, and so is
+    /// creating an order with the id of one resting in any book.
+    pub fn process(
+        &mut self,
+        incoming_order: OrderRequest,
+    ) -> Result<ProcessReport, EngineError> {
+        // Only one-cancels-other pairs create a second order at once.
+        let (symbol, order_id, sibling_id) = match incoming_order {
+            OrderRequest::Create {
+                ref symbol,
+                order_id,
+                ..
+            }
+            | OrderRequest::CreateAtClose {
+                ref symbol,
+                order_id,
+                ..
+            } => {
+                let order_id = OrderId::new(order_id);
+                self.check_unique(&order_id)?;
+
+                (symbol.clone(), order_id, None)
+            }
+            OrderRequest::CreateOco {
+                ref symbol,
+                legs: [first, second],
+                ..
+            } => {
+                let order_id = OrderId::new(first.order_id);
+                let sibling_id = OrderId::new(second.order_id);
+                self.check_unique(&order_id)?;
+                self.check_unique(&sibling_id)?;
+
+                (symbol.clone(), order_id, Some(sibling_id))
+            }
+            OrderRequest::Delete { order_id }
+            | OrderRequest::Cancel { order_id, .. } => {
+                let order_id = OrderId::new(order_id);
+                let Some(symbol) = self.symbols.get(&order_id) else {
+                    return Err(EngineError::OrderNotFound { order_id });
+                };
+
+                (symbol.clone(), order_id, None)
+            }
+            OrderRequest::Amend { order_id, .. } => {
+                let order_id = OrderId::new(order_id);
+                let Some(symbol) = self.symbols.get(&order_id) else {
+                    return Err(AmendError::NotFound.into());
+                };
+
+                (symbol.clone(), order_id, None)
+            }
+        };
+
+        let engine = self
+            .engines
+            .entry(symbol.clone())
+            .or_insert_with(|| self.configs.engine(&symbol, self.fee_schedule));
+
+        let report = engine.process(incoming_order)?;
+
+        // Only orders still resting in the book, or queued for the closing
+        // auction, are kept track of.
+        let orderbook = engine.orderbook();
+        let order_ids = report
+            .trades
+            .iter()
+            .map(Trade::maker)
+            .chain(report.cancelled.iter().copied())
+            .chain(Some(order_id))
+            .chain(sibling_id);
+        for order_id in order_ids {
+            if orderbook.get(&order_id).is_some() {
+                self.symbols.insert(order_id, symbol.clone());
+            } else {
+                self.symbols.remove(&order_id);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Sets the reference price the price collar of the given symbol is
+    /// centred on, see [`Engine::set_reference_price`], creating its engine
+    /// if no order of it was processed yet.
+    pub fn set_reference_price(
+        &mut self,
+        symbol: &AssetPair,
+        reference_price: Price,
+    ) {
+        self.engines
+            .entry(symbol.clone())
+            .or_insert_with(|| self.configs.engine(symbol, self.fee_schedule))
+            .set_reference_price(reference_price);
+    }
+
+    /// Runs the closing auction of the given symbol, see
+    /// [`Engine::run_closing_auction`], unless no order of it was ever
+    /// processed.
+    pub fn run_closing_auction(
+        &mut self,
+        symbol: &AssetPair,
+    ) -> Option<(ProcessReport, Vec<Order>)> {
+        let engine = self.engines.get_mut(symbol)?;
+        let (report, expired) = engine.run_closing_auction();
+
+        // Filled and expired orders, be they at-the-close or resting ones,
+        // are no longer kept track of.
+        let orderbook = engine.orderbook();
+        let order_ids = report
+            .trades
+            .iter()
+            .flat_map(|trade| [trade.maker(), trade.taker()])
+            .chain(report.cancelled.iter().copied())
+            .chain(expired.iter().map(Asset::id));
+        for order_id in order_ids {
+            if orderbook.get(&order_id).is_none() {
+                self.symbols.remove(&order_id);
+            }
+        }
+
+        Some((report, expired))
+    }
+
+    /// Ids must be unique across every book, as they alone route later
+    /// requests.
+    fn check_unique(&self, order_id: &OrderId) -> Result<(), InsertError> {
+        if self.symbols.contains_key(order_id) {
+            return Err(InsertError::Duplicate {
+                order_id: *order_id,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the engine of the given symbol, if any order of it was ever
+    /// processed.
+    #[inline]
+    pub fn engine(&self, symbol: &AssetPair) -> Option<&Engine> {
+        self.engines.get(symbol)
+    }
+
+    /// Returns an iterator over every engine, in arbitrary order.
+    #[inline]
+    pub fn engines(&self) -> impl Iterator<Item = &Engine> {
+        self.engines.values()
+    }
+}
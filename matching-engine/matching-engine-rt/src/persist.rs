@@ -0,0 +1,130 @@
+use std::path::Path;
+
+use exchange_types::AssetPair;
+use matching_engine_algo::Checkpoint;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::PersistError;
+use matching_engine_algo::Persistence;
+
+use crate::Engine;
+
+impl Engine {
+    /// Opens the engine persisted at `path`, creating it if there is none
+    /// yet, see [`Orderbook::open_persistent`].
+    ///
+    /// Changes to the book are only ever written at checkpoints, see
+    /// [`Engine::checkpoint`], so it is restored as of the last one, which
+    /// tells the offset to resume the input from, see
+    /// [`Orderbook::last_checkpoint`]. Only resting orders and their links
+    /// are persisted, so parameters, such as the tick size, are set again
+    /// once opened, and stats start over. The book is trusted as it is
+    /// restored, see [`Engine::open_validated`] otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the storage itself cannot be opened or read.
+    pub fn open_persistent(
+        symbol: AssetPair,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, PersistError> {
+        let orderbook = Orderbook::open_persistent(path)?
+            .with_persistence(Persistence::Checkpoint);
+
+        Ok(Self::from_orderbook(symbol, orderbook))
+    }
+
+    /// Opens the engine persisted at `path` just like
+    /// [`Engine::open_persistent`], then validates the book it restored
+    /// against the state hash of the last checkpoint, if any, see
+    /// [`Engine::state_hash`].
+    ///
+    /// Should they disagree, e.g. since entries of the storage were corrupt
+    /// and discarded, or since at-the-close orders were queued, which are
+    /// never persisted, the book is not served. It is emptied instead, and
+    /// the storage along with it, so that the input, the log of every
+    /// request, is replayed from its very start, as the [`WarmUp`] returned
+    /// tells.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the storage itself cannot be opened, read or, should the book
+    /// be emptied, written.
+    pub fn open_validated(
+        symbol: AssetPair,
+        path: impl AsRef<Path>,
+    ) -> Result<(Self, WarmUp), PersistError> {
+        let mut engine = Self::open_persistent(symbol, path)?;
+
+        let Some(checkpoint) = engine.orderbook.last_checkpoint() else {
+            return Ok((engine, WarmUp::Resume { offset: 0 }));
+        };
+
+        let found = engine.state_hash();
+        if found == checkpoint.state_hash {
+            let offset = checkpoint.offset;
+            return Ok((engine, WarmUp::Resume { offset }));
+        }
+
+        // Nothing the book restored is kept, and the input is checkpointed as
+        // not processed at all, lest a crash while replaying it resume from
+        // the stale book.
+        engine.orderbook.drain().for_each(drop);
+        engine.checkpoint(0)?;
+
+        Ok((
+            engine,
+            WarmUp::Replay {
+                expected: checkpoint.state_hash,
+                found,
+            },
+        ))
+    }
+
+    /// Writes every change made to the book since the last checkpoint, along
+    /// with the offset of the first input not processed yet and the state
+    /// hash of the book, in a single transaction, see
+    /// [`Orderbook::checkpoint`].
+    ///
+    /// Requests are processed in full, so calling it between any two of
+    /// them checkpoints the book at a trade boundary. Should the process
+    /// crash, it resumes from the very input that follows, with the book as
+    /// it was then, so no input is ever processed twice, nor skipped.
+    ///
+    /// # Errors
+    ///
+    /// Fails if anything could not be written, in which case the storage is
+    /// left as of the previous checkpoint.
+    #[inline]
+    pub fn checkpoint(&mut self, offset: u64) -> Result<(), PersistError> {
+        let state_hash = self.state_hash();
+        self.orderbook.checkpoint(Checkpoint { offset, state_hash })
+    }
+}
+
+/// Where the input of an engine is processed from once it is opened, as told
+/// by [`Engine::open_validated`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WarmUp {
+    /// The book is just as it was at the last checkpoint, so the input
+    /// resumes from its offset, or from its start if there is none.
+    Resume { offset: u64 },
+    /// The book disagreed with the last checkpoint, so it was emptied, and
+    /// the input is replayed from its start.
+    Replay {
+        /// The state hash of the book at the last checkpoint.
+        expected: u32,
+        /// The state hash of the book as it was restored.
+        found: u32,
+    },
+}
+
+impl WarmUp {
+    /// Returns the offset of the first input to process.
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        match self {
+            WarmUp::Resume { offset } => *offset,
+            WarmUp::Replay { .. } => 0,
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use exchange_core::Asset;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use exchange_types::Price;
+use exchange_types::Quantity;
+use exchange_types::Trade;
+use uuid::Uuid;
+
+use crate::Engine;
+use crate::EngineError;
+use crate::ProcessReport;
+
+/// Keeps a two-sided quote around a reference price on behalf of a single
+/// account, e.g. to seed a demo market with liquidity.
+///
+/// Its bid rests `half_spread` below the reference price and its ask as far
+/// above it, each showing `size`. It reacts to the reference price moving,
+/// see [`AutoQuoter::on_reference_price`], by amending its quotes to the new
+/// prices, and to its own quotes trading, see [`AutoQuoter::on_trades`], by
+/// restoring their size, or placing them anew once filled.
+///
+/// The quoter only ever acts when told to, through the engine it is given,
+/// so it is left to whoever drives the engine to call it.
+#[derive(Clone, Debug)]
+pub struct AutoQuoter {
+    account_id: Uuid,
+    /// Id of the next quote to be placed.
+    next_order_id: u128,
+    half_spread: Price,
+    size: Quantity,
+    reference_price: Option<Price>,
+    bid: Option<Uuid>,
+    ask: Option<Uuid>,
+}
+
+impl AutoQuoter {
+    /// Creates a quoter placing quotes on behalf of `account_id`, with ids
+    /// counting up from `first_order_id`, which must be kept apart from
+    /// those of any other order. Nothing is quoted until a reference price
+    /// is set.
+    #[inline]
+    pub fn new(
+        account_id: Uuid,
+        first_order_id: Uuid,
+        half_spread: Price,
+        size: Quantity,
+    ) -> Self {
+        Self {
+            account_id,
+            next_order_id: first_order_id.as_u128(),
+            half_spread,
+            size,
+            reference_price: None,
+            bid: None,
+            ask: None,
+        }
+    }
+
+    /// Returns the prices the quoter bids and asks at, if it quotes at all.
+    #[inline]
+    pub fn quote(&self) -> Option<(Price, Price)> {
+        self.reference_price.map(|reference_price| {
+            (
+                reference_price - self.half_spread,
+                reference_price + self.half_spread,
+            )
+        })
+    }
+
+    /// Sets the reference price of the engine, see
+    /// [`Engine::set_reference_price`], and reprices both quotes around it,
+    /// returning what that resulted in.
+    ///
+    /// Of both quotes, the one the reference price moves away from is
+    /// repriced first, lest the other one trade against it on the way.
+    ///
+    /// # Errors
+    ///
+    /// Fails just like [`Engine::process`] does, e.g. if the new prices are
+    /// off the tick size, leaving any quote not repriced yet as it was.
+    pub fn on_reference_price(
+        &mut self,
+        engine: &mut Engine,
+        reference_price: Price,
+    ) -> Result<ProcessReport, EngineError> {
+        let rising = self
+            .reference_price
+            .is_some_and(|previous| reference_price > previous);
+
+        engine.set_reference_price(reference_price);
+        self.reference_price = Some(reference_price);
+
+        if rising {
+            self.requote(engine, [OrderSide::Ask, OrderSide::Bid])
+        } else {
+            self.requote(engine, [OrderSide::Bid, OrderSide::Ask])
+        }
+    }
+
+    /// Replenishes both quotes if any of them took part in the given trades,
+    /// e.g. those of the report of any request the engine processed,
+    /// returning what that resulted in.
+    ///
+    /// # Errors
+    ///
+    /// Fails just like [`Engine::process`] does.
+    pub fn on_trades(
+        &mut self,
+        engine: &mut Engine,
+        trades: &[Trade],
+    ) -> Result<ProcessReport, EngineError> {
+        let quoted = |order_id| {
+            [self.bid, self.ask]
+                .into_iter()
+                .flatten()
+                .any(|quote| OrderId::from(quote) == order_id)
+        };
+        if !trades
+            .iter()
+            .any(|trade| quoted(trade.maker()) || quoted(trade.taker()))
+        {
+            return Ok(ProcessReport::default());
+        }
+
+        self.requote(engine, [OrderSide::Bid, OrderSide::Ask])
+    }
+
+    /// Makes both quotes rest at their prices with their whole size, one
+    /// side after the other, amending those still resting and placing anew
+    /// those that are not.
+    fn requote(
+        &mut self,
+        engine: &mut Engine,
+        sides: [OrderSide; 2],
+    ) -> Result<ProcessReport, EngineError> {
+        let mut report = ProcessReport::default();
+        let Some((bid_price, ask_price)) = self.quote() else {
+            return Ok(report);
+        };
+
+        for side in sides {
+            let (order_id, price) = match side {
+                OrderSide::Bid => (self.bid, bid_price),
+                OrderSide::Ask => (self.ask, ask_price),
+            };
+
+            let resting = order_id.and_then(|order_id| {
+                engine
+                    .orderbook()
+                    .get(&order_id.into())
+                    .map(|order| (order_id, order))
+            });
+            let request = match resting {
+                Some((_, order))
+                    if order.limit_price() == Some(price)
+                        && order.remaining() == self.size =>
+                {
+                    continue;
+                }
+                // Whatever was filled counts towards the quantity amended to,
+                // so that the size shown is whole again.
+                Some((order_id, order)) => OrderRequest::Amend {
+                    order_id,
+                    new_price: price,
+                    new_quantity: order.filled() + self.size,
+                },
+                None => {
+                    let order_id = Uuid::from_u128(self.next_order_id);
+                    self.next_order_id += 1;
+                    match side {
+                        OrderSide::Bid => self.bid = Some(order_id),
+                        OrderSide::Ask => self.ask = Some(order_id),
+                    }
+
+                    OrderRequest::Create {
+                        account_id: self.account_id,
+                        amount: self.size,
+                        order_id,
+                        symbol: engine.symbol().clone(),
+                        limit_price: price,
+                        side,
+                    }
+                }
+            };
+
+            let processed = engine.process(request)?;
+            report.trades.extend(processed.trades);
+            report.cancelled.extend(processed.cancelled);
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,49 @@
+use std::io;
+use std::num::NonZeroUsize;
+
+use exchange_types::OrderRequest;
+
+/// Records a deterministic sample of the order flow, one JSON request per
+/// line, in the very same format the engine consumes.
+///
+/// Every `every`-th request is recorded, starting with the first one, so
+/// that recorded flow can later be replayed as a realistic test fixture.
+/// Requests that are not sampled cost a single counter increment.
+pub struct Recorder<W> {
+    writer: W,
+    every: NonZeroUsize,
+    seen: usize,
+}
+
+impl<W: io::Write> Recorder<W> {
+    #[inline]
+    pub fn new(writer: W, every: NonZeroUsize) -> Self {
+        Self {
+            writer,
+            every,
+            seen: 0,
+        }
+    }
+
+    /// Records `request` if it belongs to the sample, returning whether it
+    /// was recorded.
+    #[inline]
+    pub fn record(&mut self, request: &OrderRequest) -> io::Result<bool> {
+        let sampled = self.seen % self.every == 0;
+        self.seen += 1;
+
+        if sampled {
+            serde_json::to_writer(&mut self.writer, request)?;
+            self.writer.write_all(b"\n")?;
+        }
+
+        Ok(sampled)
+    }
+
+    /// Flushes and returns the underlying writer.
+    #[inline]
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+use std::io;
+
+use exchange_types::OrderRequest;
+use matching_engine_algo::RejectReason;
+
+use crate::EngineError;
+
+/// A request that was turned down, as written by a [`RejectionLog`].
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Rejection {
+    /// Why the book cancelled it, if a matching policy did, rather than it
+    /// being turned down before reaching the book.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<RejectReason>,
+    /// The error it was turned down with.
+    pub error: String,
+    /// The request itself, as it came in.
+    pub payload: Payload,
+}
+
+/// A rejected request, as it came in.
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Payload {
+    /// A request that was parsed, though not processed.
+    Request(OrderRequest),
+    /// A request that could not be parsed, as the text it was read as.
+    Raw(String),
+    /// A request that could not be parsed, nor read as text.
+    Bytes(Vec<u8>),
+}
+
+/// Writes every rejected request, one JSON [`Rejection`] per line, along
+/// with why it was rejected, so that client integration problems may be
+/// diagnosed with the very payload that caused them.
+///
+/// Both requests the engine turned down and those that could not even be
+/// parsed are written, the latter as they were read.
+pub struct RejectionLog<W> {
+    writer: W,
+}
+
+impl<W: io::Write> RejectionLog<W> {
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Writes a request the engine turned down with `error`.
+    pub fn rejected(
+        &mut self,
+        request: OrderRequest,
+        error: &EngineError,
+    ) -> io::Result<()> {
+        let reason = match *error {
+            EngineError::Rejected { reason, .. } => Some(reason),
+            _ => None,
+        };
+
+        self.write(&Rejection {
+            reason,
+            error: error.to_string(),
+            payload: Payload::Request(request),
+        })
+    }
+
+    /// Writes the raw payload of a request that could not be parsed.
+    pub fn malformed(
+        &mut self,
+        raw: &[u8],
+        error: &dyn fmt::Display,
+    ) -> io::Result<()> {
+        let payload = match std::str::from_utf8(raw) {
+            Ok(text) => Payload::Raw(text.trim_end().to_owned()),
+            Err(_) => Payload::Bytes(raw.to_vec()),
+        };
+
+        self.write(&Rejection {
+            reason: None,
+            error: error.to_string(),
+            payload,
+        })
+    }
+
+    /// Flushes the underlying writer.
+    #[inline]
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Flushes and returns the underlying writer.
+    #[inline]
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    #[inline]
+    fn write(&mut self, rejection: &Rejection) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, rejection)?;
+        self.writer.write_all(b"\n")
+    }
+}
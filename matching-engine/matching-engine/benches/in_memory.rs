@@ -1,17 +1,15 @@
-use compact_str::CompactString;
 use criterion::black_box;
 use criterion::criterion_group;
 use criterion::criterion_main;
 use criterion::BatchSize;
 use criterion::Criterion;
+use exchange_types::AssetPair;
 use exchange_types::OrderRequest;
 use exchange_types::OrderSide;
 use matching_engine_rt::Engine;
 use rand::Rng;
 use uuid::Uuid;
 
-const SYMBOL: &'static str = "BENCH";
-
 pub fn in_memory(c: &mut Criterion) {
     let mut rng = rand::thread_rng();
 
@@ -23,7 +21,7 @@ pub fn in_memory(c: &mut Criterion) {
             account_id: Uuid::new_v4(),
             amount: rng.gen_range(100..10_000).into(),
             order_id: Uuid::new_v4(),
-            symbol: CompactString::new_inline(SYMBOL),
+            symbol: AssetPair::new("BENCH", "USD"),
             limit_price: rng.gen_range(100..10_000).into(),
             side: match rng.gen_range(0..2) {
                 0 => OrderSide::Ask,
@@ -34,7 +32,7 @@ pub fn in_memory(c: &mut Criterion) {
 
     c.bench_function("process", |b| {
         b.iter_batched(
-            || Engine::new(SYMBOL),
+            || Engine::new(AssetPair::new("BENCH", "USD")),
             |mut engine| {
                 let incoming_order = black_box(orders.next().unwrap());
                 black_box(engine.process(incoming_order))
@@ -0,0 +1,69 @@
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BatchSize;
+use criterion::Criterion;
+use exchange_core::Exchange;
+use exchange_types::LimitOrder;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use rand::Rng;
+
+/// A non-crossing snapshot: asks rest above 5_000 and bids below it.
+fn snapshot(len: u128) -> Vec<LimitOrder> {
+    let mut rng = rand::thread_rng();
+
+    (0..len)
+        .map(|id| {
+            let (side, limit_price) = match rng.gen_range(0..2) {
+                0 => (OrderSide::Ask, rng.gen_range(5_001..10_000)),
+                _ => (OrderSide::Bid, rng.gen_range(100..5_000)),
+            };
+
+            LimitOrder::new(
+                OrderId::from_u128(id),
+                side,
+                limit_price,
+                rng.gen_range(100..10_000),
+            )
+        })
+        .collect()
+}
+
+pub fn insert_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load_snapshot");
+
+    group.bench_function("matching", |b| {
+        b.iter_batched(
+            || snapshot(10_000),
+            |orders| {
+                let mut orderbook = Orderbook::new();
+                for order in orders {
+                    black_box(orderbook.matching(Order::from(order))).ok();
+                }
+                orderbook
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.bench_function("insert_batch", |b| {
+        b.iter_batched(
+            || snapshot(10_000),
+            |orders| {
+                let mut orderbook = Orderbook::new();
+                // SAFETY: asks and bids of the snapshot never overlap.
+                unsafe { orderbook.insert_batch(black_box(orders)) };
+                orderbook
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, insert_batch);
+criterion_main!(benches);
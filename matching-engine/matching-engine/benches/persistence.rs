@@ -0,0 +1,110 @@
+//! Processes a stream where most orders fill at once against resting iceberg
+//! orders, so that persisting every change can be compared against only
+//! persisting where orders rest once matching is done:
+//!
+//! ```sh
+//! cargo bench --bench persistence
+//! ```
+
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BatchSize;
+use criterion::Criterion;
+use criterion::Throughput;
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::Persistence;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use tempfile::TempDir;
+
+const LEN: usize = 1_000;
+
+/// Iceberg asks deep enough for the whole stream to fill against.
+fn book(persistence: Persistence) -> (TempDir, Orderbook) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut orderbook = Orderbook::open_persistent(dir.path())
+        .unwrap()
+        .with_persistence(persistence);
+
+    for limit_price in 100..110 {
+        let iceberg_order = Order::builder()
+            .side(OrderSide::Ask)
+            .limit(limit_price, 100_000)
+            .iceberg(10)
+            .build();
+        orderbook.matching(iceberg_order).ok();
+    }
+
+    (dir, orderbook)
+}
+
+/// Bids, seeded so that both modes process the very same ones, nine in ten of
+/// them marketable.
+fn stream(len: usize) -> Vec<Order> {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+
+    (0..len)
+        .map(|_| {
+            let limit_price = match rng.gen_bool(0.9) {
+                true => 110,
+                false => rng.gen_range(90..100),
+            };
+
+            Order::builder()
+                .side(OrderSide::Bid)
+                .limit(limit_price, rng.gen_range(1..100))
+                .build()
+        })
+        .collect()
+}
+
+fn writes(persistence: Persistence) -> u64 {
+    let (_dir, mut orderbook) = book(persistence);
+    let placed = orderbook.storage_writes().unwrap_or_default();
+
+    for order in stream(LEN) {
+        orderbook.matching(order).ok();
+    }
+
+    orderbook.storage_writes().unwrap_or_default() - placed
+}
+
+pub fn persistence(c: &mut Criterion) {
+    let modes = [
+        ("always", Persistence::Always),
+        ("resting", Persistence::Resting),
+    ];
+
+    for (name, persistence) in modes {
+        println!("{name}: {} writes", writes(persistence));
+    }
+
+    let mut group = c.benchmark_group("persistence");
+    group.throughput(Throughput::Elements(LEN as u64));
+    group.sample_size(10);
+
+    for (name, persistence) in modes {
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || (book(persistence), stream(LEN)),
+                |((dir, mut orderbook), orders)| {
+                    for order in orders {
+                        black_box(orderbook.matching(order)).ok();
+                    }
+                    (dir, orderbook)
+                },
+                BatchSize::PerIteration,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, persistence);
+criterion_main!(benches);
@@ -0,0 +1,79 @@
+//! Processes the same stream the generator writes out, so that decimal and
+//! integer amounts can be compared against each other:
+//!
+//! ```sh
+//! cargo bench --bench price_mode
+//! cargo bench --bench price_mode --features integer
+//! ```
+
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BatchSize;
+use criterion::Criterion;
+use criterion::Throughput;
+use exchange_types::AssetPair;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use matching_engine_rt::Engine;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use uuid::Uuid;
+
+const LEN: usize = 100_000;
+
+const PRICE_MODE: &str = if cfg!(feature = "integer") {
+    "integer"
+} else {
+    "decimal"
+};
+
+/// Orders as the generator draws them, though seeded, so that both modes
+/// process the very same ones.
+fn stream(len: usize) -> Vec<OrderRequest> {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+
+    (0..len)
+        .map(|_| match rng.gen_range(0..1_000) {
+            0 => OrderRequest::Delete {
+                order_id: Uuid::from_bytes(rng.gen()),
+            },
+            _ => OrderRequest::Create {
+                account_id: Uuid::from_bytes(rng.gen()),
+                amount: rng.gen_range(100..10_000_u32).into(),
+                order_id: Uuid::from_bytes(rng.gen()),
+                symbol: AssetPair::new("BTC", "USDC"),
+                limit_price: rng.gen_range(100..10_000_u32).into(),
+                side: match rng.gen_bool(0.5) {
+                    true => OrderSide::Ask,
+                    false => OrderSide::Bid,
+                },
+            },
+        })
+        .collect()
+}
+
+pub fn price_mode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("process");
+    group.throughput(Throughput::Elements(LEN as u64));
+
+    group.bench_function(PRICE_MODE, |b| {
+        b.iter_batched(
+            || stream(LEN),
+            |orders| {
+                let mut engine = Engine::new(AssetPair::new("BTC", "USDC"));
+                for order in orders {
+                    black_box(engine.process(order)).ok();
+                }
+                engine
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, price_mode);
+criterion_main!(benches);
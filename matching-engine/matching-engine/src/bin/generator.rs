@@ -5,19 +5,26 @@ use std::io::Write;
 use std::sync::OnceLock;
 use std::thread;
 
-use arrayvec::ArrayVec;
 use clap::Parser;
-use compact_str::CompactString;
+use clap::ValueEnum;
+use crossbeam_channel::Receiver;
 use crossbeam_channel::Sender;
+use exchange_types::AssetPair;
 use exchange_types::OrderRequest;
 use exchange_types::OrderSide;
 use rand::distributions::Bernoulli;
 use rand::distributions::Distribution;
 use rand::Rng;
-use rust_decimal::Decimal;
 use uuid::Uuid;
 
-type Message = ArrayVec<u8, 512>;
+/// Newline delimited orders, as many as a batch holds.
+type Message = Vec<u8>;
+
+/// Roughly the most a single serialized order takes.
+const ORDER_CAPACITY: usize = 512;
+
+/// How many messages may be in flight at once, both ways.
+const CHANNEL_CAPACITY: usize = 1024 * 4;
 
 #[derive(Parser)]
 struct Args {
@@ -25,36 +32,69 @@ struct Args {
     total: usize,
     #[clap(short = 'j', long = "jobs", default_value_t = num_cpus::get())]
     workers: usize,
+    /// How many orders each worker serializes into a single message before
+    /// sending it, sparing the channel a send for each and every order.
+    #[clap(short = 'b', long = "batch", default_value_t = 1)]
+    batch: usize,
+    #[clap(long, value_enum, default_value_t = Format::default())]
+    format: Format,
+}
+
+/// How orders are written out.
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum Format {
+    /// Newline delimited JSON.
+    #[default]
+    Json,
+    /// `postcard`, each order prefixed by its length, as a little-endian
+    /// `u32`.
+    Bin,
 }
 
 fn main() -> Result<()> {
     let Args {
         total: jobs,
         workers,
+        batch,
+        format,
     } = Args::parse();
 
-    let (tx, rx) = crossbeam_channel::bounded::<Message>(1024 * 4);
+    let batch = 1.max(batch);
+    let (tx, rx) = crossbeam_channel::bounded::<Message>(CHANNEL_CAPACITY);
+
+    // Messages are sent back once written, so that workers reuse their
+    // buffers rather than allocating new ones for every batch.
+    let (recycle_tx, recycle_rx) =
+        crossbeam_channel::bounded::<Message>(CHANNEL_CAPACITY);
 
     let workers = 1.max(workers - 1);
     for jobs_per_worker in fair_division(jobs, workers) {
         let tx = tx.clone();
+        let recycle_rx = recycle_rx.clone();
         thread::spawn(move || {
             let mut rng = rand::thread_rng();
-            for _ in 0..jobs_per_worker {
-                worker(&tx, &mut rng);
+            let mut remaining = jobs_per_worker;
+            while remaining > 0 {
+                let batch = batch.min(remaining);
+                worker(&tx, &recycle_rx, &mut rng, batch, format);
+                remaining -= batch;
             }
         });
     }
 
     drop(tx);
+    drop(recycle_rx);
 
     let mut out = {
         let stdout = io::stdout().lock();
         BufWriter::new(stdout)
     };
 
-    while let Ok(order) = rx.recv() {
-        out.write_all(order.as_slice())?;
+    while let Ok(mut orders) = rx.recv() {
+        out.write_all(orders.as_slice())?;
+
+        orders.clear();
+        let _ = recycle_tx.try_send(orders);
     }
 
     out.flush()?;
@@ -67,9 +107,26 @@ thread_local! {
 }
 
 #[inline(always)]
-fn worker(tx: &Sender<Message>, rng: &mut rand::rngs::ThreadRng) {
-    let mut buf = Message::new_const();
+fn worker(
+    tx: &Sender<Message>,
+    recycle_rx: &Receiver<Message>,
+    rng: &mut rand::rngs::ThreadRng,
+    batch: usize,
+    format: Format,
+) {
+    let mut buf = recycle_rx
+        .try_recv()
+        .unwrap_or_else(|_| Message::with_capacity(batch * ORDER_CAPACITY));
+
+    for _ in 0..batch {
+        order(&mut buf, rng, format);
+    }
+
+    tx.send(buf).unwrap();
+}
 
+#[inline(always)]
+fn order(buf: &mut Message, rng: &mut rand::rngs::ThreadRng, format: Format) {
     let side_distribution = SIDE_DISTRIBUTION.with(|side_dist| {
         *side_dist.get_or_init(move || unsafe {
             Bernoulli::from_ratio(1, 2).unwrap_unchecked()
@@ -82,10 +139,10 @@ fn worker(tx: &Sender<Message>, rng: &mut rand::rngs::ThreadRng) {
         },
         _ => OrderRequest::Create {
             account_id: Uuid::from_bytes(rng.gen::<[u8; 16]>()),
-            amount: Decimal::from(rng.gen_range(100..10_000)).into(),
+            amount: rng.gen_range(100..10_000_u32).into(),
             order_id: Uuid::from_bytes(rng.gen::<[u8; 16]>()),
-            symbol: CompactString::new_inline("BTC/USDC"),
-            limit_price: Decimal::from(rng.gen_range(100..10_000)).into(),
+            symbol: AssetPair::new("BTC", "USDC"),
+            limit_price: rng.gen_range(100..10_000_u32).into(),
             side: match side_distribution.sample(rng) {
                 true => OrderSide::Ask,
                 false => OrderSide::Bid,
@@ -93,14 +150,28 @@ fn worker(tx: &Sender<Message>, rng: &mut rand::rngs::ThreadRng) {
         },
     };
 
-    let Ok(_) = serde_json::to_writer(&mut buf, &order) else {
-        return;
-    };
-
-    buf.write_all(b"\n").unwrap();
-    buf.flush().unwrap();
-
-    tx.send(buf).unwrap();
+    let len = buf.len();
+    match format {
+        Format::Json => {
+            let Ok(_) = serde_json::to_writer(&mut *buf, &order) else {
+                buf.truncate(len);
+                return;
+            };
+
+            buf.push(b'\n');
+        }
+        Format::Bin => {
+            // The length is only known once encoded, so room is left for it.
+            buf.extend_from_slice(&[0; 4]);
+            let Ok(_) = order.write_postcard(buf) else {
+                buf.truncate(len);
+                return;
+            };
+
+            let size = (buf.len() - len - 4) as u32;
+            buf[len..len + 4].copy_from_slice(&size.to_le_bytes());
+        }
+    }
 }
 
 #[inline(always)]
@@ -1,6 +1,10 @@
+use std::cmp::Reverse;
 use std::fmt;
 use std::fs;
 use std::io;
+use std::io::BufRead;
+use std::io::Read;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
@@ -8,10 +12,17 @@ use std::time::Instant;
 use anyhow::Result;
 use arrayvec::ArrayVec;
 use clap::Parser;
-use compact_str::CompactString;
+use clap::ValueEnum;
 use exchange_core::ExchangeExt;
+use exchange_types::AssetPair;
 use exchange_types::OrderRequest;
+use matching_engine_algo::system_clock;
+use matching_engine_rt::AdminCommand;
 use matching_engine_rt::Engine;
+use matching_engine_rt::EventWriter;
+use matching_engine_rt::Recorder;
+use matching_engine_rt::RejectionLog;
+use matching_engine_rt::WarmUp;
 use owo_colors::OwoColorize;
 use parking_lot::Mutex;
 
@@ -22,7 +33,7 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 #[clap(author, version, about)]
 struct Args {
     #[clap(short, long, default_value = "BTC/USDC")]
-    symbol: CompactString,
+    symbol: AssetPair,
     #[clap(
         short,
         long,
@@ -30,6 +41,13 @@ struct Args {
         help = "Orders source"
     )]
     input: Input,
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = Format::default(),
+        help = "Orders format"
+    )]
+    format: Format,
     #[clap(
         short,
         long,
@@ -39,6 +57,48 @@ struct Args {
     output: Output,
     #[clap(short = 'j', long = "jobs", default_value_t = num_cpus::get())]
     workers: usize,
+    #[clap(long, help = "Record a sample of the orders into this file")]
+    record: Option<PathBuf>,
+    #[clap(long, default_value = "1", help = "Record every n-th order")]
+    sample_every: NonZeroUsize,
+    #[clap(
+        long,
+        help = "Log rejected orders into this file rather than to stderr, one \
+                JSON record per line"
+    )]
+    rejections: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "Read admin commands from this file, e.g. a named pipe, one \
+                per line: halt, resume, snapshot <path>, cancel-all, stats or \
+                state-hash"
+    )]
+    control: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "Persist the book into this directory, resuming the input from \
+                its last checkpoint"
+    )]
+    persist: Option<PathBuf>,
+    #[clap(
+        long,
+        requires = "persist",
+        help = "Validate the persisted book against its last checkpoint, \
+                replaying the input from its start should they disagree"
+    )]
+    validate: bool,
+    #[clap(
+        long,
+        default_value = "1000",
+        help = "Checkpoint the persisted book every n-th order"
+    )]
+    checkpoint_every: NonZeroUsize,
+    #[clap(
+        long,
+        help = "Dump the book into this file, as JSON, should processing an \
+                order panic"
+    )]
+    dump: Option<PathBuf>,
 }
 
 fn main() -> Result<()> {
@@ -46,45 +106,186 @@ fn main() -> Result<()> {
 
     let (tx, rx) = crossbeam_channel::bounded(128 * 1024);
 
-    let reader = Arc::new(Mutex::new(io::BufReader::with_capacity(
-        1024 * 32,
-        args.input,
-    )));
+    // Workers log the orders they cannot parse, and the engine those it
+    // turns down, so the log is shared by every thread.
+    let rejections: Box<dyn io::Write + Send> = match &args.rejections {
+        Some(path) => Box::new(io::BufWriter::new(fs::File::create(path)?)),
+        None => Box::new(io::stderr()),
+    };
+    let rejections = Arc::new(Mutex::new(RejectionLog::new(rejections)));
+
+    // Orders up to the last checkpoint are in the book already, so they are
+    // read past rather than processed again.
+    let (engine, resume) = match &args.persist {
+        Some(path) if args.validate => {
+            let (engine, warm_up) = Engine::open_validated(args.symbol, path)?;
+            if let WarmUp::Replay { expected, found } = warm_up {
+                eprintln!(
+                    "{:>12} book disagrees with its checkpoint (expected \
+                     {expected:08x}, found {found:08x})",
+                    "Replaying".bold().yellow(),
+                );
+            }
+
+            (engine, warm_up.offset())
+        }
+        Some(path) => {
+            let engine = Engine::open_persistent(args.symbol, path)?;
+            let resume = engine
+                .orderbook()
+                .last_checkpoint()
+                .map_or(0, |checkpoint| checkpoint.offset);
+
+            (engine, resume)
+        }
+        None => (Engine::new(args.symbol), 0),
+    };
+    let mut engine = engine.with_clock(system_clock);
+
+    let reader = Arc::new(Mutex::new(Source {
+        reader: io::BufReader::with_capacity(1024 * 32, args.input),
+        read: 0,
+    }));
 
-    for _ in 0..1.max(args.workers - 1) {
+    // Offsets only tell how far the input was processed if orders are
+    // processed in the very order they are read, so a single worker reads
+    // them when persisting.
+    let workers = match args.persist {
+        Some(_) => 1,
+        None => 1.max(args.workers - 1),
+    };
+
+    for _ in 0..workers {
         let reader = Arc::clone(&reader);
+        let rejections = Arc::clone(&rejections);
         let tx = tx.clone();
-        std::thread::spawn(|| worker(reader, tx));
+        let format = args.format;
+        std::thread::spawn(move || match format {
+            Format::Json => worker(reader, resume, rejections, tx),
+            Format::Bin => bin_worker(reader, resume, rejections, tx),
+        });
     }
 
     drop(tx);
 
-    let mut engine = Engine::new(&args.symbol);
+    // Admin commands come in apart from the order flow, so they are only
+    // ever read on a thread of their own.
+    let mut control_rx = match args.control.clone() {
+        Some(path) => {
+            let (tx, rx) = crossbeam_channel::unbounded();
+            std::thread::spawn(move || control_worker(path, tx));
+            rx
+        }
+        None => crossbeam_channel::never(),
+    };
+
+    let mut recorder = match &args.record {
+        Some(path) => Some(Recorder::new(
+            io::BufWriter::new(fs::File::create(path)?),
+            args.sample_every,
+        )),
+        None => None,
+    };
+
+    let mut events = EventWriter::new(args.output.open()?);
 
     let mut i = 0.0f64;
+    let mut rejected = 0usize;
+    // Orders processed since the last checkpoint, if persisting.
+    let mut unchecked = 0usize;
     let begin = Instant::now();
-    while let Ok(order) = rx.recv() {
-        if let Err(err) = engine.process(order) {
-            eprintln!("something went wrong: {}", err);
+    loop {
+        let order = crossbeam_channel::select! {
+            recv(rx) -> order => order,
+            recv(control_rx) -> command => {
+                match command {
+                    Ok(command) => admin(&mut engine, command),
+                    // Once the control channel is gone, only orders are left
+                    // to wait for.
+                    Err(_) => control_rx = crossbeam_channel::never(),
+                }
+                continue;
+            }
+        };
+        let Ok((offset, order)) = order else {
+            break;
+        };
+
+        if let Some(recorder) = &mut recorder {
+            recorder.record(&order)?;
+        }
+
+        let processed = match &args.dump {
+            Some(path) => engine.process_or_dump(order.clone(), path),
+            None => engine.process(order.clone()),
+        };
+        match processed {
+            Ok(report) => {
+                events.write(report.trades, engine.orderbook())?;
+            }
+            Err(err) => {
+                rejections.lock().rejected(order, &err)?;
+                rejected += 1;
+            }
         };
         i += 1.0;
+
+        if args.persist.is_some() {
+            unchecked += 1;
+            if unchecked == args.checkpoint_every.get() {
+                engine.checkpoint(offset + 1)?;
+                unchecked = 0;
+            }
+        }
     }
     let end = Instant::now();
 
+    // Every worker is done by now, so the whole input was read.
+    if args.persist.is_some() {
+        engine.checkpoint(reader.lock().read)?;
+    }
+
+    events.into_inner()?;
+
+    if let Some(recorder) = recorder {
+        recorder.into_inner()?;
+    }
+
+    rejections.lock().flush()?;
+
     let elapsed = end - begin;
     let (ask_length, bid_length) = engine.orderbook().len();
+    let stats = engine.stats();
 
+    if resume > 0 {
+        eprintln!("{:>12} after {} order(s)", "Resumed".bold().green(), resume);
+    }
     eprintln!(
         "{:>12} {} order(s) in {:.2}s",
         "Total".bold().green(),
         i.round() as i64,
         elapsed.as_secs_f64(),
     );
+    eprintln!("{:>12} {} order(s)", "Rejected".bold().green(), rejected);
     eprintln!(
         "{:>12} {:.2} orders/s",
         "Average".bold().green(),
         i / elapsed.as_secs_f64(),
     );
+    eprintln!("{:>12} {} trade(s)", "Matched".bold().green(), stats.trades);
+    eprintln!("{:>12} {}", "Volume".bold().green(), stats.matched);
+    eprintln!("{:>12} {}", "Notional".bold().green(), stats.notional);
+    // Most frequent first, which is what points at a misconfiguration.
+    let mut reasons = stats.rejected.iter().collect::<Vec<_>>();
+    reasons.sort_by_key(|&(_, count)| Reverse(*count));
+    for (reason, count) in reasons {
+        eprintln!(
+            "{:>12} {} order(s) cancelled for {:?}",
+            "Rejected".bold().green(),
+            count,
+            reason,
+        );
+    }
     eprintln!();
     eprintln!("{}", " Orderbook info ".bold().white().on_black());
     if let Some((ask_price, bid_price)) = engine.orderbook().spread() {
@@ -96,15 +297,29 @@ fn main() -> Result<()> {
     eprintln!("{:>8} {}", "Ask".bold().green(), ask_length);
     eprintln!("{:>8} {}", "Bid".bold().green(), bid_length);
 
-    // TODO: use this as `io::Write` instead relying on `(e)println`s.
-    match &args.output {
-        Output::Stdout => {}
-        Output::File(_path) => {}
-    };
-
     Ok(())
 }
 
+/// Dispatches an admin command to the engine, telling the operator what it
+/// did.
+fn admin(engine: &mut Engine, command: AdminCommand) {
+    match engine.admin(command) {
+        Ok(reply) => eprintln!("{:>12} {}", "Admin".bold().green(), reply),
+        Err(error) => eprintln!("admin command failed: {error}"),
+    }
+}
+
+/// How orders are read in.
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+enum Format {
+    /// Newline delimited JSON.
+    #[default]
+    Json,
+    /// `postcard`, each order prefixed by its length, as a little-endian
+    /// `u32`.
+    Bin,
+}
+
 #[derive(Clone, Debug, Default)]
 enum Input {
     #[default]
@@ -157,6 +372,18 @@ impl fmt::Display for Output {
     }
 }
 
+impl Output {
+    /// Opens the destination for writing, truncating it if it is a file.
+    fn open(&self) -> io::Result<Box<dyn io::Write>> {
+        Ok(match self {
+            Output::Stdout => Box::new(io::BufWriter::new(io::stdout().lock())),
+            Output::File(path) => {
+                Box::new(io::BufWriter::new(fs::File::create(path)?))
+            }
+        })
+    }
+}
+
 impl From<&str> for Output {
     #[inline]
     fn from(s: &str) -> Self {
@@ -167,23 +394,124 @@ impl From<&str> for Output {
     }
 }
 
+/// The log of rejected orders, shared by every thread.
+type Rejections = Arc<Mutex<RejectionLog<Box<dyn io::Write + Send>>>>;
+
+/// The input, shared by every worker, along with how many orders were read
+/// from it so far, malformed ones included.
+struct Source {
+    reader: io::BufReader<Input>,
+    read: u64,
+}
+
+impl Source {
+    /// Counts an order as read, returning its offset in the input.
+    #[inline]
+    fn next(&mut self) -> u64 {
+        self.read += 1;
+        self.read - 1
+    }
+}
+
+/// Reads orders in, sending each along with its offset in the input, unless
+/// it comes before `resume`.
 #[inline(never)]
 fn worker(
-    reader: Arc<Mutex<io::BufReader<Input>>>,
-    tx: crossbeam_channel::Sender<OrderRequest>,
+    source: Arc<Mutex<Source>>,
+    resume: u64,
+    rejections: Rejections,
+    tx: crossbeam_channel::Sender<(u64, OrderRequest)>,
 ) -> Result<()> {
     let mut buf = ArrayVec::<u8, 512>::new_const();
 
-    while read_until(&mut *reader.lock(), b'\n', &mut buf).is_ok() {
-        let order = serde_json::from_slice(&buf);
-        match order {
-            Ok(order) => tx.send(order)?,
-            Err(error) if error.is_eof() => break,
+    loop {
+        let offset = {
+            let mut source = source.lock();
+            match read_until(&mut source.reader, b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => source.next(),
+            }
+        };
+
+        if offset >= resume {
+            let order = serde_json::from_slice(&buf);
+            match order {
+                Ok(order) => tx.send((offset, order))?,
+                Err(error) if error.is_eof() => break,
+                Err(error) => {
+                    rejections.lock().malformed(&buf, &error)?;
+                }
+            }
+        }
+        buf.clear();
+    }
+
+    Ok(())
+}
+
+#[inline(never)]
+fn bin_worker(
+    source: Arc<Mutex<Source>>,
+    resume: u64,
+    rejections: Rejections,
+    tx: crossbeam_channel::Sender<(u64, OrderRequest)>,
+) -> Result<()> {
+    let mut buf = Vec::with_capacity(512);
+
+    loop {
+        let offset = {
+            let mut source = source.lock();
+            let reader = &mut source.reader;
+
+            let mut len = [0; 4];
+            match reader.read_exact(&mut len) {
+                Ok(()) => {}
+                Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+                    break
+                }
+                Err(error) => return Err(error.into()),
+            }
+
+            buf.resize(u32::from_le_bytes(len) as usize, 0);
+            reader.read_exact(&mut buf)?;
+
+            source.next()
+        };
+
+        if offset < resume {
+            continue;
+        }
+
+        match OrderRequest::from_postcard(&buf) {
+            Ok(order) => tx.send((offset, order))?,
+            Err(error) => {
+                rejections.lock().malformed(&buf, &error)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[inline(never)]
+fn control_worker(
+    path: PathBuf,
+    tx: crossbeam_channel::Sender<AdminCommand>,
+) -> Result<()> {
+    let reader = io::BufReader::new(fs::File::open(path)?);
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match line.parse() {
+            Ok(command) => tx.send(command)?,
             Err(error) => {
                 eprintln!("{error}");
             }
         }
-        buf.clear();
     }
 
     Ok(())
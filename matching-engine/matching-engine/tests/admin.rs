@@ -0,0 +1,144 @@
+//! Operators run an engine through admin commands, one per line, dispatched
+//! to it apart from the order flow.
+
+use std::fs;
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::ExchangeExt;
+use exchange_types::AssetPair;
+use exchange_types::CancelReason;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::OrderbookSnapshot;
+use matching_engine_rt::AdminCommand;
+use matching_engine_rt::AdminError;
+use matching_engine_rt::AdminReply;
+use matching_engine_rt::Engine;
+use matching_engine_rt::EngineError;
+use matching_engine_rt::EngineState;
+use uuid::Uuid;
+
+fn create(
+    order_id: u128,
+    side: OrderSide,
+    limit_price: u32,
+    amount: u32,
+) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: AssetPair::new("BTC", "USDC"),
+        limit_price: limit_price.into(),
+        side,
+    }
+}
+
+/// An engine that traded 5 at 100, resting an ask and a bid.
+fn engine() -> Engine {
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"));
+
+    for request in [
+        create(1, OrderSide::Ask, 100, 10),
+        create(2, OrderSide::Bid, 100, 5),
+        create(3, OrderSide::Bid, 99, 10),
+    ] {
+        assert!(let Ok(_) = engine.process(request));
+    }
+
+    engine
+}
+
+fn admin(engine: &mut Engine, line: &str) -> AdminReply {
+    let_assert!(Ok(command) = line.parse::<AdminCommand>());
+    let_assert!(Ok(reply) = engine.admin(command));
+    reply
+}
+
+#[test]
+fn parse() {
+    for (line, command) in [
+        ("halt", AdminCommand::Halt),
+        ("resume", AdminCommand::Resume),
+        (
+            "snapshot /tmp/book.json",
+            AdminCommand::Snapshot("/tmp/book.json".into()),
+        ),
+        ("  cancel-all ", AdminCommand::CancelAll),
+        ("stats", AdminCommand::Stats),
+        ("state-hash", AdminCommand::StateHash),
+    ] {
+        let_assert!(Ok(parsed) = line.parse::<AdminCommand>());
+        assert!(parsed == command);
+    }
+
+    let_assert!(Err(AdminError::Empty) = "".parse::<AdminCommand>());
+    let_assert!(
+        Err(AdminError::MissingPath) = "snapshot".parse::<AdminCommand>()
+    );
+    let_assert!(
+        Err(AdminError::Unknown(_)) = "shutdown".parse::<AdminCommand>()
+    );
+    let_assert!(
+        Err(AdminError::Unexpected(_)) = "halt now".parse::<AdminCommand>()
+    );
+}
+
+#[test]
+fn halt_and_resume() {
+    let mut engine = engine();
+
+    let_assert!(AdminReply::Halted = admin(&mut engine, "halt"));
+    assert!(engine.state() == EngineState::Suspended);
+    let_assert!(
+        Err(EngineError::Suspended) =
+            engine.process(create(4, OrderSide::Bid, 100, 1))
+    );
+
+    let_assert!(AdminReply::Resumed(None) = admin(&mut engine, "resume"));
+    assert!(engine.state() == EngineState::Running);
+    assert!(let Ok(_) = engine.process(create(4, OrderSide::Bid, 100, 1)));
+}
+
+#[test]
+fn stats_and_state_hash() {
+    let mut engine = engine();
+
+    let reply = admin(&mut engine, "stats");
+    assert!(reply.to_string() == "stats (trades=1, matched=5, notional=500)");
+
+    let_assert!(AdminReply::StateHash(hash) = admin(&mut engine, "state-hash"));
+    assert!(hash == engine.orderbook().checksum(usize::MAX));
+}
+
+#[test]
+fn snapshot() {
+    let mut engine = engine();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("book.json");
+
+    let reply = admin(&mut engine, &format!("snapshot {}", path.display()));
+    let_assert!(AdminReply::Snapshot(2) = reply);
+
+    // The very same book may be rebuilt out of it.
+    let snapshot: OrderbookSnapshot =
+        serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+    let_assert!(Ok(orderbook) = Orderbook::from_snapshot(snapshot));
+    assert!(orderbook.checksum(usize::MAX) == engine.state_hash());
+}
+
+#[test]
+fn cancel_all() {
+    let mut engine = engine();
+
+    let_assert!(
+        AdminReply::Cancelled(cancelled) = admin(&mut engine, "cancel-all")
+    );
+    assert!(cancelled.len() == 2);
+    assert!(cancelled
+        .iter()
+        .all(|report| report.reason == CancelReason::Admin));
+    assert!(engine.orderbook().len() == (0, 0));
+}
@@ -0,0 +1,54 @@
+use assert2::assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+
+#[test]
+fn swap_algo() {
+    let mut exchange = Orderbook::new().with_algo::<ProRataAlgo>();
+
+    for limit_price in [100, 101] {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .limit(limit_price, 10)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(15).build();
+    let trades = exchange.matching(market_order).unwrap().into_trades();
+
+    assert!(trades.len() == 2);
+    assert!(exchange.len() == (1, 0));
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 101,
+                remaining: 5,
+                status: Partial,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+fn keep_resting_orders() {
+    let mut exchange = Orderbook::new();
+
+    let limit_order =
+        Order::builder().side(OrderSide::Ask).limit(100, 10).build();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    let exchange = exchange.with_algo::<ProRataAlgo>();
+
+    assert!(exchange.len() == (1, 0));
+    assert!(exchange.best_ask() == Some(100.into()));
+}
@@ -0,0 +1,233 @@
+//! An All-Or-None order is an order that only ever trades as a whole. Unlike
+//! a Fill-Or-Kill order, it is not cancelled when there is nothing to trade
+//! with right away, but rests whole until it can, and incoming orders too
+//! small to fill it meanwhile pass it over, trading with whatever comes
+//! after it instead. One that crosses orders which cannot fill it whole is
+//! cancelled, though, as it could not rest without crossing the book.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::Quantity;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use matching_engine_algo::RejectReason;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for limit_price in [100, 101] {
+            let limit_order = Order::builder()
+                .side(OrderSide::Ask)
+                .limit(limit_price, 10)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+fn all_or_none(order_id: u128, side: OrderSide, limit_price: u32) -> Order {
+    Order::builder()
+        .side(side)
+        .id(OrderId::from_u128(order_id))
+        .limit(limit_price, 20)
+        .all_or_none()
+        .build()
+}
+
+#[test]
+fn fills_whole_right_away() {
+    let mut exchange = orderbook();
+
+    let all_or_none = all_or_none(1, OrderSide::Bid, 101);
+    let_assert!(Ok(report) = exchange.matching(all_or_none));
+    assert!(report.trades().len() == 2);
+    assert!(report.status() == OrderStatus::Completed);
+}
+
+#[test]
+fn rests_whole_until_it_can() {
+    let mut exchange = orderbook();
+
+    // Nothing is asked at its price, so it rests whole.
+    let all_or_none = all_or_none(1, OrderSide::Bid, 99);
+    let_assert!(Ok(report) = exchange.matching(all_or_none));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Open);
+    assert!(report.rejected_reason() == None);
+
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(1)));
+    assert!(order.remaining() == Quantity::from(20));
+    assert!(exchange.best_bid() < exchange.best_ask());
+
+    // Large enough, an incoming ask fills it whole.
+    let limit_order =
+        Order::builder().side(OrderSide::Ask).limit(99, 20).build();
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.maker() == OrderId::from_u128(1));
+    assert!(trade.quantity() == 20.into());
+}
+
+#[test]
+fn cancelled_if_it_crosses() {
+    let mut exchange = orderbook();
+
+    // There are 20 asks in total, but only 10 at its price, so it could
+    // neither trade nor rest without crossing them.
+    let all_or_none = all_or_none(1, OrderSide::Bid, 100);
+    let_assert!(Ok(report) = exchange.matching(all_or_none));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Cancelled);
+    assert!(report.rejected_reason() == Some(RejectReason::AllOrNone));
+
+    assert!(exchange.get(&OrderId::from_u128(1)).is_none());
+    assert!(exchange.best_bid() < exchange.best_ask());
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 100,
+                remaining: 10,
+                status: Open,
+            },
+            Order {
+                limit_price: 101,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+fn unlike_fill_or_kill() {
+    let mut exchange = orderbook();
+
+    let fill_or_kill = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(100, 20)
+        .ioc()
+        .all_or_none()
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(fill_or_kill));
+    assert!(report.status() == OrderStatus::Cancelled);
+    assert!(report.rejected_reason() == Some(RejectReason::FillOrKill));
+    assert!(exchange.len() == (2, 0));
+}
+
+#[test]
+fn passed_over_by_smaller_orders() {
+    let mut exchange = Orderbook::new();
+    assert!(exchange
+        .matching(all_or_none(1, OrderSide::Ask, 100))
+        .is_ok());
+
+    let limit_order =
+        Order::builder().side(OrderSide::Ask).limit(101, 10).build();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    // Too small for the all-or-none ask, it trades with the one behind it.
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(101, 10).build();
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.price() == 101.into());
+
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(1)));
+    assert!(order.remaining() == Quantity::from(20));
+    assert!(order.status() == OrderStatus::Open);
+
+    // Large enough, another one fills it whole, resting what is left.
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(100, 25).build();
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.quantity() == 20.into());
+    assert!(report.status() == OrderStatus::Partial);
+    assert!(exchange.get(&OrderId::from_u128(1)).is_none());
+}
+
+#[test]
+fn smaller_crossing_cancelled() {
+    let mut exchange = Orderbook::new();
+    assert!(exchange
+        .matching(all_or_none(1, OrderSide::Ask, 100))
+        .is_ok());
+
+    // Too small for the all-or-none ask, it could not rest without crossing
+    // it either.
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(100, 10).build();
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.trades().is_empty());
+    assert!(report.rejected_reason() == Some(RejectReason::Ineligible));
+    assert!(exchange.len() == (1, 0));
+}
+
+#[test]
+fn not_counted_by_fill_or_kill() {
+    let mut exchange = Orderbook::new();
+    assert!(exchange
+        .matching(all_or_none(1, OrderSide::Ask, 100))
+        .is_ok());
+
+    let limit_order =
+        Order::builder().side(OrderSide::Ask).limit(101, 10).build();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    // Without the all-or-none ask, which it is too small for, there is not
+    // enough to fill it.
+    let fill_or_kill = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(101, 15)
+        .ioc()
+        .all_or_none()
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(fill_or_kill));
+    assert!(report.trades().is_empty());
+    assert!(report.rejected_reason() == Some(RejectReason::FillOrKill));
+}
+
+#[test]
+fn no_share_of_pro_rata_level() {
+    let mut exchange = Orderbook::new().with_algo::<ProRataAlgo>();
+    assert!(exchange
+        .matching(all_or_none(1, OrderSide::Ask, 100))
+        .is_ok());
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Ask)
+        .id(OrderId::from_u128(2))
+        .limit(100, 20)
+        .build();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    // The whole of it goes to the order that is not all-or-none.
+    let market_order = Order::builder().side(OrderSide::Bid).market(10).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.maker() == OrderId::from_u128(2));
+
+    // Large enough, the next one fills the all-or-none order whole, as it
+    // was queued first, and only then the other one.
+    let market_order = Order::builder().side(OrderSide::Bid).market(30).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let_assert!([first, second] = report.trades().as_slice());
+    assert!(first.maker() == OrderId::from_u128(1));
+    assert!(first.quantity() == 20.into());
+    assert!(second.maker() == OrderId::from_u128(2));
+    assert!(exchange.len() == (0, 0));
+}
@@ -0,0 +1,308 @@
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::error::OrderError;
+use exchange_types::AssetPair;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use matching_engine_algo::AmendError;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::RejectReason;
+use matching_engine_rt::Engine;
+
+fn place(
+    exchange: &mut Orderbook,
+    side: OrderSide,
+    limit_price: u32,
+    quantity: u32,
+) -> OrderId {
+    let limit_order = Order::builder()
+        .side(side)
+        .limit(limit_price, quantity)
+        .build();
+    let order_id = limit_order.id();
+
+    assert!(exchange.matching(limit_order).is_ok());
+
+    order_id
+}
+
+#[test]
+fn reprice_loses_priority() {
+    let mut exchange = Orderbook::new();
+    let order_id = place(&mut exchange, OrderSide::Ask, 100, 10);
+    place(&mut exchange, OrderSide::Ask, 101, 20);
+
+    let_assert!(Ok(report) = exchange.amend(&order_id, 101.into(), 10.into()));
+    assert!(report.trades().is_empty());
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 101,
+                remaining: 20,
+                status: Open,
+            },
+            Order {
+                limit_price: 101,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+fn reduce_keeps_priority() {
+    let mut exchange = Orderbook::new();
+    let order_id = place(&mut exchange, OrderSide::Bid, 100, 10);
+    place(&mut exchange, OrderSide::Bid, 100, 20);
+
+    let_assert!(Ok(report) = exchange.amend(&order_id, 100.into(), 5.into()));
+    assert!(report.trades().is_empty());
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [],
+        Bid: [
+            Order {
+                limit_price: 100,
+                remaining: 5,
+                status: Open,
+            },
+            Order {
+                limit_price: 100,
+                remaining: 20,
+                status: Open,
+            },
+        ],
+    }
+    "###);
+}
+
+#[test]
+fn increase_loses_priority() {
+    let mut exchange = Orderbook::new();
+    let order_id = place(&mut exchange, OrderSide::Bid, 100, 10);
+    place(&mut exchange, OrderSide::Bid, 100, 20);
+
+    let_assert!(Ok(report) = exchange.amend(&order_id, 100.into(), 15.into()));
+    assert!(report.trades().is_empty());
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [],
+        Bid: [
+            Order {
+                limit_price: 100,
+                remaining: 20,
+                status: Open,
+            },
+            Order {
+                limit_price: 100,
+                remaining: 15,
+                status: Open,
+            },
+        ],
+    }
+    "###);
+
+    // It is matched last, just like any order queued after the other one.
+    let market_order = Order::builder().side(OrderSide::Ask).market(25).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(
+        report.trades().last().map(|trade| trade.maker()) == Some(order_id)
+    );
+}
+
+#[test]
+fn reject_below_filled() {
+    let mut exchange = Orderbook::new();
+    let order_id = place(&mut exchange, OrderSide::Ask, 100, 10);
+    place(&mut exchange, OrderSide::Ask, 100, 20);
+    place(&mut exchange, OrderSide::Bid, 100, 6);
+
+    let_assert!(
+        Err(AmendError::Order(OrderError::BelowFilled)) =
+            exchange.amend(&order_id, 101.into(), 4.into())
+    );
+
+    // A rejected amend leaves the order exactly as it was.
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 100,
+                remaining: 4,
+                status: Partial,
+            },
+            Order {
+                limit_price: 100,
+                remaining: 20,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+fn reject_non_positive_price() {
+    let mut exchange = Orderbook::new();
+    let order_id = place(&mut exchange, OrderSide::Ask, 100, 10);
+
+    for limit_price in [0, -5] {
+        let_assert!(
+            Err(AmendError::Order(OrderError::NonPositivePrice)) =
+                exchange.amend(&order_id, limit_price.into(), 10.into())
+        );
+    }
+
+    // The order still rests where it was.
+    place(&mut exchange, OrderSide::Bid, 100, 10);
+    assert!(exchange.len() == (0, 0));
+}
+
+#[test]
+fn reprice_through_the_book() {
+    let mut exchange = Orderbook::new();
+    let order_id = place(&mut exchange, OrderSide::Ask, 105, 30);
+    let maker_id = place(&mut exchange, OrderSide::Bid, 100, 10);
+
+    let_assert!(Ok(report) = exchange.amend(&order_id, 100.into(), 30.into()));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.taker() == order_id);
+    assert!(trade.maker() == maker_id);
+    assert!(trade.quantity() == 10.into());
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 100,
+                remaining: 20,
+                status: Partial,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+fn reject_post_only_reprice() {
+    let mut exchange = Orderbook::new();
+    place(&mut exchange, OrderSide::Ask, 100, 10);
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(99, 10)
+        .post_only()
+        .build();
+    let order_id = limit_order.id();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    // A post-only order repriced through the book would trade right away,
+    // so it is left resting as it was instead.
+    let_assert!(
+        Err(AmendError::Rejected(RejectReason::PostOnly)) =
+            exchange.amend(&order_id, 101.into(), 10.into())
+    );
+
+    let_assert!(Some(order) = exchange.get(&order_id));
+    assert!(order.limit_price() == Some(99.into()));
+    assert!(order.is_open());
+    assert!(exchange.len() == (1, 1));
+    assert!(exchange.volume() == (10.into(), 10.into()));
+}
+
+#[test]
+fn rejected_amend_keeps_priority() {
+    let mut exchange = Orderbook::new().with_tick_size(5.into());
+    place(&mut exchange, OrderSide::Ask, 110, 10);
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(100, 10)
+        .post_only()
+        .build();
+    let order_id = limit_order.id();
+    assert!(exchange.matching(limit_order).is_ok());
+    let behind = place(&mut exchange, OrderSide::Bid, 100, 10);
+
+    let_assert!(Some(&before) = exchange.get(&order_id));
+
+    // Neither off the tick nor through the book for a post-only order.
+    let_assert!(
+        Err(AmendError::Increment(_)) =
+            exchange.amend(&order_id, 102.into(), 20.into())
+    );
+    let_assert!(
+        Err(AmendError::Rejected(RejectReason::PostOnly)) =
+            exchange.amend(&order_id, 110.into(), 20.into())
+    );
+
+    let_assert!(Some(order) = exchange.get(&order_id));
+    assert!(order.limit_price() == before.limit_price());
+    assert!(order.remaining() == before.remaining());
+    assert!(order.sequence() == before.sequence());
+    assert!(exchange.volume() == (10.into(), 20.into()));
+
+    // It is still first in line at its level.
+    let queue = exchange
+        .iter(&OrderSide::Bid)
+        .map(|order| order.id())
+        .collect::<Vec<_>>();
+    assert!(queue == [order_id, behind]);
+
+    let market_order = Order::builder().side(OrderSide::Ask).market(10).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.maker() == order_id);
+}
+
+#[test]
+fn not_found() {
+    let mut exchange = Orderbook::new();
+
+    let_assert!(
+        Err(AmendError::NotFound) =
+            exchange.amend(&OrderId::random(), 100.into(), 10.into())
+    );
+}
+
+#[test]
+fn amend_request() {
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"));
+
+    let requests = r#"
+        {"type_op":"CREATE","account_id":"d4f79484-fe48-41e9-9bc5-45bb4cfedaf4","amount":"10","order_id":"75637317-8d86-436e-93bc-befc4a4ed830","symbol":"BTC/USDC","limit_price":"100","side":"SELL"}
+        {"type_op":"AMEND","order_id":"75637317-8d86-436e-93bc-befc4a4ed830","new_price":"110","new_quantity":"15"}
+    "#;
+
+    for request in requests.lines().filter(|line| !line.trim().is_empty()) {
+        let request: OrderRequest = serde_json::from_str(request).unwrap();
+        assert!(let Ok(_) = engine.process(request));
+    }
+
+    insta::assert_debug_snapshot!(engine.orderbook(), @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 110,
+                remaining: 15,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
@@ -0,0 +1,177 @@
+//! A call auction queues orders without matching them, and then crosses them
+//! all at once at the single price matching the most quantity.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use matching_engine_algo::AuctionAlgo;
+use matching_engine_algo::MatchingAlgo;
+use matching_engine_algo::Orderbook;
+
+fn limit_order(
+    id: u128,
+    side: OrderSide,
+    limit_price: u32,
+    quantity: u32,
+) -> Order {
+    Order::builder()
+        .side(side)
+        .id(OrderId::from_u128(id))
+        .limit(limit_price, quantity)
+        .build()
+}
+
+fn queue(exchange: &mut Orderbook<AuctionAlgo>, orders: &[(u128, u32, u32)]) {
+    for &(id, limit_price, quantity) in orders {
+        let side = if id < 10 {
+            OrderSide::Bid
+        } else {
+            OrderSide::Ask
+        };
+        let order = limit_order(id, side, limit_price, quantity);
+
+        let_assert!(Ok(report) = exchange.matching(order));
+        assert!(report.trades().is_empty());
+        assert!(report.status() == OrderStatus::Open);
+    }
+}
+
+#[test]
+fn uniform_clearing_price() {
+    let mut exchange = Orderbook::new().with_algo::<AuctionAlgo>();
+
+    // Bids are numbered below 10, asks from 10 on. The book is crossed while
+    // the auction is on.
+    queue(
+        &mut exchange,
+        &[
+            (1, 102, 5),
+            (2, 101, 5),
+            (3, 100, 8),
+            (10, 99, 4),
+            (11, 100, 6),
+            (12, 101, 10),
+            (13, 103, 5),
+        ],
+    );
+    assert!(exchange.best_bid() > exchange.best_ask());
+
+    // Both 100 and 101 match 10, though 100 leaves less unmatched.
+    let_assert!(Some(cross) = exchange.cross());
+    assert!(cross.price() == 100.into());
+    assert!(cross.quantity() == 10.into());
+
+    // Seven orders were queued before, so trades are numbered on from there.
+    let trade_ids = cross.trades().iter().map(|trade| trade.trade_id());
+    assert!(trade_ids.eq(7..7 + cross.trades().len() as u64));
+
+    let trades = cross
+        .trades()
+        .iter()
+        .map(|trade| {
+            assert!(trade.price() == 100.into());
+            (trade.maker(), trade.taker(), trade.quantity())
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        trades
+            == [
+                (OrderId::from_u128(1), OrderId::from_u128(10), 4.into()),
+                (OrderId::from_u128(1), OrderId::from_u128(11), 1.into()),
+                (OrderId::from_u128(2), OrderId::from_u128(11), 5.into()),
+            ]
+    );
+    assert!(exchange.last_price() == Some(100.into()));
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 101,
+                remaining: 10,
+                status: Open,
+            },
+            Order {
+                limit_price: 103,
+                remaining: 5,
+                status: Open,
+            },
+        ],
+        Bid: [
+            Order {
+                limit_price: 100,
+                remaining: 8,
+                status: Open,
+            },
+        ],
+    }
+    "###);
+
+    // Once the auction is over, orders are matched as they come again.
+    let mut exchange = exchange.with_algo::<MatchingAlgo>();
+    let order = limit_order(4, OrderSide::Bid, 101, 2);
+    let_assert!(Ok(report) = exchange.matching(order));
+    assert!(report.trades().len() == 1);
+}
+
+#[test]
+fn closest_to_last_price() {
+    let orders = [
+        (1, 102, 5),
+        (2, 101, 5),
+        (3, 100, 10),
+        (10, 99, 4),
+        (11, 100, 6),
+        (12, 101, 10),
+        (13, 103, 5),
+    ];
+
+    // Both 100 and 101 match 10 and leave 10 unmatched, so the lowest wins
+    // unless something traded before.
+    let mut exchange = Orderbook::new().with_algo::<AuctionAlgo>();
+    queue(&mut exchange, &orders);
+    let_assert!(Some(cross) = exchange.cross());
+    assert!(cross.price() == 100.into());
+
+    let mut exchange = Orderbook::new();
+    for order in [
+        limit_order(20, OrderSide::Ask, 101, 1),
+        limit_order(21, OrderSide::Bid, 101, 1),
+    ] {
+        assert!(exchange.matching(order).is_ok());
+    }
+
+    let mut exchange = exchange.with_algo::<AuctionAlgo>();
+    queue(&mut exchange, &orders);
+    let_assert!(Some(cross) = exchange.cross());
+    assert!(cross.price() == 101.into());
+    assert!(cross.quantity() == 10.into());
+}
+
+#[test]
+fn nothing_crosses() {
+    let mut exchange = Orderbook::new().with_algo::<AuctionAlgo>();
+    queue(&mut exchange, &[(1, 99, 5), (10, 100, 5)]);
+
+    assert!(exchange.cross().is_none());
+    assert!(exchange.len() == (1, 1));
+}
+
+#[test]
+fn immediate_orders_expire() {
+    let mut exchange = Orderbook::new().with_algo::<AuctionAlgo>();
+    queue(&mut exchange, &[(10, 100, 5)]);
+
+    // There is nothing to match them against until the cross, and they
+    // could never wait for it.
+    let market_order = Order::builder().side(OrderSide::Bid).market(5).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Expired);
+    assert!(exchange.len() == (1, 0));
+}
@@ -0,0 +1,119 @@
+//! An auto-quoter keeps a two-sided quote at its target spread around the
+//! reference price, repricing it as the reference moves, and replenishing
+//! it once it trades.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::ExchangeExt;
+use exchange_types::AssetPair;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use exchange_types::Quantity;
+use matching_engine_rt::AutoQuoter;
+use matching_engine_rt::Engine;
+use uuid::Uuid;
+
+const QUOTER: u128 = 1000;
+
+fn create(
+    order_id: u128,
+    symbol: &AssetPair,
+    side: OrderSide,
+    limit_price: u32,
+    amount: u32,
+) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: symbol.clone(),
+        limit_price: limit_price.into(),
+        side,
+    }
+}
+
+fn quoter() -> AutoQuoter {
+    AutoQuoter::new(
+        Uuid::from_u128(QUOTER),
+        Uuid::from_u128(QUOTER),
+        1.into(),
+        10.into(),
+    )
+}
+
+/// Returns what is left of the quote with the given id, if resting.
+fn remaining(engine: &Engine, order_id: u128) -> Option<Quantity> {
+    let order = engine.orderbook().get(&OrderId::from_u128(order_id))?;
+    Some(order.remaining())
+}
+
+#[test]
+fn quote_around_reference() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine = Engine::new(symbol);
+    let mut quoter = quoter();
+
+    // Nothing is quoted without a reference price.
+    let_assert!(Ok(_) = quoter.on_trades(&mut engine, &[]));
+    assert!(quoter.quote().is_none());
+    assert!(engine.orderbook().is_empty());
+
+    let_assert!(
+        Ok(report) = quoter.on_reference_price(&mut engine, 100.into())
+    );
+    assert!(report.trades.is_empty());
+    assert!(quoter.quote() == Some((99.into(), 101.into())));
+    assert!(engine.orderbook().best_bid() == Some(99.into()));
+    assert!(engine.orderbook().best_ask() == Some(101.into()));
+    assert!(engine.orderbook().len() == (1, 1));
+
+    // Moving past the quoted ask reprices it first, so the quotes never
+    // trade with each other.
+    for reference_price in [105, 102] {
+        let_assert!(
+            Ok(report) =
+                quoter.on_reference_price(&mut engine, reference_price.into())
+        );
+        assert!(report.trades.is_empty());
+        assert!(
+            engine.orderbook().best_bid() == Some((reference_price - 1).into())
+        );
+        assert!(
+            engine.orderbook().best_ask() == Some((reference_price + 1).into())
+        );
+        // The very same quotes are amended rather than placed anew.
+        assert!(remaining(&engine, QUOTER) == Some(10.into()));
+        assert!(remaining(&engine, QUOTER + 1) == Some(10.into()));
+        assert!(engine.orderbook().len() == (1, 1));
+    }
+}
+
+#[test]
+fn replenish_after_fill() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine = Engine::new(symbol.clone());
+    let mut quoter = quoter();
+    let_assert!(Ok(_) = quoter.on_reference_price(&mut engine, 100.into()));
+
+    // A partial fill has the quote amended back to its whole size.
+    let_assert!(
+        Ok(report) = engine.process(create(1, &symbol, OrderSide::Bid, 101, 4))
+    );
+    assert!(report.trades.len() == 1);
+    assert!(remaining(&engine, QUOTER + 1) == Some(6.into()));
+    let_assert!(Ok(_) = quoter.on_trades(&mut engine, &report.trades));
+    assert!(remaining(&engine, QUOTER + 1) == Some(10.into()));
+
+    // A full fill has a new quote placed at the same price.
+    let_assert!(
+        Ok(report) = engine.process(create(2, &symbol, OrderSide::Ask, 99, 10))
+    );
+    assert!(report.trades.len() == 1);
+    assert!(remaining(&engine, QUOTER).is_none());
+    let_assert!(Ok(_) = quoter.on_trades(&mut engine, &report.trades));
+    assert!(remaining(&engine, QUOTER + 2) == Some(10.into()));
+    assert!(engine.orderbook().best_bid() == Some(99.into()));
+    assert!(engine.orderbook().best_ask() == Some(101.into()));
+    assert!(engine.orderbook().len() == (1, 1));
+}
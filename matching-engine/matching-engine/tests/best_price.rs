@@ -0,0 +1,54 @@
+use assert2::assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+
+#[test]
+fn empty() {
+    let exchange = Orderbook::new();
+
+    assert!(exchange.best_bid().is_none());
+    assert!(exchange.best_ask().is_none());
+    assert!(exchange.spread().is_none());
+}
+
+#[test]
+fn one_sided() {
+    let mut exchange = Orderbook::new();
+
+    for limit_price in [99, 100, 98] {
+        let limit_order = Order::builder()
+            .side(OrderSide::Bid)
+            .limit(limit_price, 10)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+
+    assert!(exchange.best_bid() == Some(100.into()));
+    assert!(exchange.best_ask().is_none());
+    assert!(exchange.spread().is_none());
+}
+
+#[test]
+fn both_sides() {
+    let mut exchange = Orderbook::new();
+
+    for (side, limit_price) in [
+        (OrderSide::Ask, 102),
+        (OrderSide::Ask, 101),
+        (OrderSide::Bid, 100),
+        (OrderSide::Bid, 99),
+    ] {
+        let limit_order =
+            Order::builder().side(side).limit(limit_price, 10).build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+
+    assert!(exchange.best_bid() == Some(100.into()));
+    assert!(exchange.best_ask() == Some(101.into()));
+    assert!(exchange.spread() == Some((101.into(), 100.into())));
+}
@@ -0,0 +1,322 @@
+//! Matching reports every change it makes to the orders shown by the book, so
+//! that an order-level (L3) copy of the book can be kept from these events
+//! alone, without ever taking a snapshot again.
+
+use std::collections::HashMap;
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::AccountId;
+use exchange_types::BookEvent;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::Price;
+use exchange_types::Quantity;
+use matching_engine_algo::MatchingStrategy;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use matching_engine_algo::SelfTradePrevention;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+
+fn order_id(id: u128) -> OrderId {
+    OrderId::from_u128(id)
+}
+
+fn added(
+    id: u128,
+    side: OrderSide,
+    limit_price: u32,
+    quantity: u32,
+) -> BookEvent {
+    BookEvent::OrderAdded {
+        order_id: order_id(id),
+        side,
+        limit_price: limit_price.into(),
+        quantity: quantity.into(),
+    }
+}
+
+fn removed(id: u128) -> BookEvent {
+    BookEvent::OrderRemoved {
+        order_id: order_id(id),
+    }
+}
+
+fn reduced(id: u128, delta: u32) -> BookEvent {
+    BookEvent::OrderReduced {
+        order_id: order_id(id),
+        delta: delta.into(),
+    }
+}
+
+fn orderbook() -> Orderbook {
+    let mut exchange = Orderbook::new();
+
+    for (id, limit_price) in [(1, 100), (2, 101)] {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .id(order_id(id))
+            .limit(limit_price, 10)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+
+    exchange
+}
+
+#[test]
+fn resting_order_is_added() {
+    let mut exchange = orderbook();
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .id(order_id(3))
+        .limit(99, 5)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.events() == [added(3, OrderSide::Bid, 99, 5)]);
+}
+
+#[test]
+fn makers_are_reduced_then_removed() {
+    let mut exchange = orderbook();
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .id(order_id(3))
+        .limit(101, 15)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.events() == [removed(1), reduced(2, 5)]);
+
+    // Whatever is left of the incoming order rests once it is done trading.
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .id(order_id(4))
+        .limit(101, 8)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.events() == [removed(2), added(4, OrderSide::Bid, 101, 3)]);
+}
+
+#[test]
+fn market_orders_are_never_added() {
+    let mut exchange = orderbook();
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(30).build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.events() == [removed(1), removed(2)]);
+    assert!(exchange.is_empty());
+}
+
+#[test]
+fn iceberg_slices_lose_priority() {
+    let mut exchange = Orderbook::new();
+
+    let iceberg_order = Order::builder()
+        .side(OrderSide::Ask)
+        .id(order_id(1))
+        .limit(100, 10)
+        .iceberg(4)
+        .build();
+
+    // Only the first slice is shown.
+    let_assert!(Ok(report) = exchange.matching(iceberg_order));
+    assert!(report.events() == [added(1, OrderSide::Ask, 100, 4)]);
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(1).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.events() == [reduced(1, 1)]);
+
+    // Once the slice is fully filled, the next one is shown at the back of
+    // the level, just like a brand new order.
+    let market_order = Order::builder().side(OrderSide::Bid).market(3).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.events() == [removed(1), added(1, OrderSide::Ask, 100, 4)]);
+}
+
+#[test]
+fn hidden_orders_never_come_up() {
+    let mut exchange = Orderbook::new();
+
+    let hidden_order = Order::builder()
+        .side(OrderSide::Ask)
+        .id(order_id(1))
+        .limit(100, 10)
+        .hidden()
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(hidden_order));
+    assert!(report.events().is_empty());
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(10).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().len() == 1);
+    assert!(report.events().is_empty());
+}
+
+#[test]
+fn self_trade_prevention_removes() {
+    let account_id = AccountId::from_u128(1);
+    let mut exchange = Orderbook::new()
+        .with_self_trade_prevention(SelfTradePrevention::CancelOldest);
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Ask)
+        .id(order_id(1))
+        .account_id(account_id)
+        .limit(100, 10)
+        .build();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    // The resting order is cancelled instead, letting the incoming one rest
+    // in its place.
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .id(order_id(2))
+        .account_id(account_id)
+        .limit(100, 5)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.trades().is_empty());
+    assert!(report.events() == [removed(1), added(2, OrderSide::Bid, 100, 5)]);
+}
+
+#[test]
+fn pro_rata() {
+    let mut exchange = Orderbook::new().with_algo::<ProRataAlgo>();
+
+    for (id, quantity) in [(1, 10), (2, 30)] {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .id(order_id(id))
+            .limit(100, quantity)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(20).build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.events() == [reduced(1, 5), reduced(2, 15)]);
+}
+
+/// An order-level copy of the book, kept from events alone.
+#[derive(Default)]
+struct Replica(HashMap<OrderId, (OrderSide, Price, Quantity)>);
+
+impl Replica {
+    fn apply(&mut self, event: &BookEvent) {
+        match *event {
+            BookEvent::OrderAdded {
+                order_id,
+                side,
+                limit_price,
+                quantity,
+            } => {
+                let previous =
+                    self.0.insert(order_id, (side, limit_price, quantity));
+                assert!(previous.is_none());
+            }
+            BookEvent::OrderRemoved { order_id } => {
+                assert!(self.0.remove(&order_id).is_some());
+            }
+            BookEvent::OrderReduced { order_id, delta } => {
+                let_assert!(Some((_, _, quantity)) = self.0.get_mut(&order_id));
+                assert!(*quantity > delta);
+                *quantity -= delta;
+            }
+        }
+    }
+
+    fn assert_matches<A: MatchingStrategy>(&self, exchange: &Orderbook<A>) {
+        let mut replica = self
+            .0
+            .iter()
+            .map(|(&order_id, &(side, limit_price, quantity))| {
+                (order_id, side, Some(limit_price), quantity)
+            })
+            .collect::<Vec<_>>();
+        replica.sort_by_key(|&(order_id, ..)| order_id);
+
+        let mut shown = [OrderSide::Ask, OrderSide::Bid]
+            .iter()
+            .flat_map(|side| exchange.iter(side))
+            .map(|order| {
+                (
+                    order.id(),
+                    order.side(),
+                    order.limit_price(),
+                    order.displayed(),
+                )
+            })
+            .collect::<Vec<_>>();
+        shown.sort_by_key(|&(order_id, ..)| order_id);
+
+        assert!(replica == shown);
+    }
+}
+
+fn replay<A: MatchingStrategy>(mut exchange: Orderbook<A>) {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    let mut replica = Replica::default();
+
+    for _ in 0..10_000 {
+        let side = if rng.gen_bool(0.5) {
+            OrderSide::Ask
+        } else {
+            OrderSide::Bid
+        };
+
+        let order = if rng.gen_bool(0.7) {
+            let limit_price = rng.gen_range(95..=105);
+            let quantity = rng.gen_range(1..=50);
+
+            let builder = Order::builder()
+                .side(side)
+                .account_id(AccountId::from_u128(rng.gen_range(1..=5)))
+                .limit(limit_price, quantity);
+
+            match rng.gen_range(0..10) {
+                0 | 1 => builder.iceberg(rng.gen_range(1..=quantity)).build(),
+                2 => builder.hidden().build(),
+                _ => builder.build(),
+            }
+        } else {
+            let quantity = rng.gen_range(1..=80);
+
+            Order::builder().side(side).market(quantity).build()
+        };
+
+        let_assert!(Ok(report) = exchange.matching(order));
+        report
+            .events()
+            .iter()
+            .for_each(|event| replica.apply(event));
+
+        replica.assert_matches(&exchange);
+    }
+}
+
+#[test]
+fn randomized_replay() {
+    replay(Orderbook::new());
+}
+
+#[test]
+fn randomized_replay_pro_rata() {
+    replay(Orderbook::new().with_algo::<ProRataAlgo>());
+}
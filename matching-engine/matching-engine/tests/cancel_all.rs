@@ -0,0 +1,100 @@
+use assert2::assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price, quantity) in [
+            (OrderSide::Ask, 102, 10),
+            (OrderSide::Ask, 101, 10),
+            (OrderSide::Ask, 101, 5),
+            (OrderSide::Bid, 99, 10),
+            (OrderSide::Bid, 100, 10),
+            (OrderSide::Bid, 102, 4),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn cancel_all() {
+    let mut exchange = orderbook();
+
+    let cancelled = exchange.cancel_all();
+
+    assert!(cancelled.len() == 5);
+    assert!(exchange.len() == (0, 0));
+    assert!(exchange.spread().is_none());
+    assert!(exchange.peek(&OrderSide::Ask).is_none());
+    assert!(exchange.peek(&OrderSide::Bid).is_none());
+
+    // The ask partially filled by the crossing bid is closed rather than
+    // cancelled.
+    let statuses = cancelled.iter().map(Order::status).collect::<Vec<_>>();
+    assert!(
+        statuses
+            == [
+                OrderStatus::Closed,
+                OrderStatus::Cancelled,
+                OrderStatus::Cancelled,
+                OrderStatus::Cancelled,
+                OrderStatus::Cancelled,
+            ]
+    );
+}
+
+#[test]
+fn cancel_all_side() {
+    let mut exchange = orderbook();
+
+    let cancelled = exchange.cancel_all_side(&OrderSide::Bid);
+
+    let prices = cancelled
+        .iter()
+        .map(|order| order.limit_price().unwrap())
+        .collect::<Vec<_>>();
+    assert!(prices == [100.into(), 99.into()]);
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 101,
+                remaining: 6,
+                status: Partial,
+            },
+            Order {
+                limit_price: 101,
+                remaining: 5,
+                status: Open,
+            },
+            Order {
+                limit_price: 102,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+    assert!(exchange.depth(&OrderSide::Bid, 10).is_empty());
+
+    // The side can be used again right away.
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(100, 1).build();
+
+    assert!(exchange.matching(limit_order).is_ok());
+    assert!(exchange.len() == (3, 1));
+}
@@ -0,0 +1,107 @@
+use assert2::assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::AccountId;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+const ALICE: AccountId = AccountId::from_u128(1);
+const BOB: AccountId = AccountId::from_u128(2);
+const CAROL: AccountId = AccountId::from_u128(3);
+const DAVE: AccountId = AccountId::from_u128(4);
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        // Carol and Dave keep the top of the book, so nobody else ever meets
+        // an order of their own there.
+        for (id, account_id, side, limit_price) in [
+            (1, CAROL, OrderSide::Ask, 101),
+            (2, DAVE, OrderSide::Bid, 100),
+            (3, ALICE, OrderSide::Ask, 102),
+            (4, ALICE, OrderSide::Bid, 99),
+            (5, BOB, OrderSide::Ask, 103),
+            (6, BOB, OrderSide::Bid, 98),
+            (7, ALICE, OrderSide::Ask, 104),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .id(OrderId::from_u128(id))
+                .account_id(account_id)
+                .limit(limit_price, 10)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn both_sides() {
+    let mut exchange = orderbook();
+
+    let cancelled = exchange.cancel_by_account(&ALICE);
+
+    // Orders come back in the order they were queued, whatever their side.
+    let cancelled = cancelled
+        .iter()
+        .map(|order| (order.id(), order.status()))
+        .collect::<Vec<_>>();
+    assert!(
+        cancelled
+            == [
+                (OrderId::from_u128(3), OrderStatus::Cancelled),
+                (OrderId::from_u128(4), OrderStatus::Cancelled),
+                (OrderId::from_u128(7), OrderStatus::Cancelled),
+            ]
+    );
+
+    assert!(exchange.len() == (2, 2));
+    assert!(exchange.spread() == Some((101.into(), 100.into())));
+    assert!(exchange.cancel_by_account(&ALICE).is_empty());
+}
+
+#[test]
+fn filled_orders_are_gone() {
+    let mut exchange = orderbook();
+
+    // Carol's ask is filled, and Alice's next one only partially.
+    let market_order = Order::builder().side(OrderSide::Bid).market(15).build();
+    assert!(exchange.matching(market_order).is_ok());
+
+    assert!(exchange.cancel_by_account(&CAROL).is_empty());
+
+    let cancelled = exchange.cancel_by_account(&ALICE);
+    let cancelled = cancelled
+        .iter()
+        .map(|order| (order.id(), order.status()))
+        .collect::<Vec<_>>();
+    assert!(
+        cancelled
+            == [
+                (OrderId::from_u128(3), OrderStatus::Closed),
+                (OrderId::from_u128(4), OrderStatus::Cancelled),
+                (OrderId::from_u128(7), OrderStatus::Cancelled),
+            ]
+    );
+
+    assert!(exchange.cancel_by_account(&BOB).len() == 2);
+    assert!(exchange.len() == (0, 1));
+}
+
+#[test]
+fn cancel_all_side() {
+    let mut exchange = orderbook();
+
+    exchange.cancel_all_side(&OrderSide::Ask);
+
+    let cancelled = exchange.cancel_by_account(&ALICE);
+    assert!(
+        cancelled.iter().map(Order::id).collect::<Vec<_>>()
+            == [OrderId::from_u128(4)]
+    );
+}
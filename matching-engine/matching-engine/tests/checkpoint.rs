@@ -0,0 +1,204 @@
+//! Engines persisted with checkpoints resume from the very input that
+//! follows the last one, with the book as it was then, whatever they
+//! processed after it before crashing. Unless the book they restore
+//! disagrees with the checkpoint, in which case they replay the input from
+//! its start instead, if they validate it.
+
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::ExchangeExt;
+use exchange_types::AssetPair;
+use exchange_types::CancelReason;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use matching_engine_algo::Checkpoint;
+use matching_engine_algo::PersistError;
+use matching_engine_rt::Engine;
+use matching_engine_rt::WarmUp;
+use uuid::Uuid;
+
+/// Retries for a while, since sled releases the lock on its files from a
+/// background thread once a previous handle is dropped.
+fn retry<T, E>(mut open: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+    for _ in 0..50 {
+        if let Ok(opened) = open() {
+            return Ok(opened);
+        }
+
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    open()
+}
+
+fn open(symbol: &AssetPair, path: &Path) -> Result<Engine, PersistError> {
+    retry(|| Engine::open_persistent(symbol.clone(), path))
+}
+
+fn open_validated(
+    symbol: &AssetPair,
+    path: &Path,
+) -> Result<(Engine, WarmUp), PersistError> {
+    retry(|| Engine::open_validated(symbol.clone(), path))
+}
+
+fn create(
+    order_id: u128,
+    symbol: &AssetPair,
+    side: OrderSide,
+    limit_price: u32,
+    amount: u32,
+) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: symbol.clone(),
+        limit_price: limit_price.into(),
+        side,
+    }
+}
+
+fn input(symbol: &AssetPair) -> Vec<OrderRequest> {
+    vec![
+        create(1, symbol, OrderSide::Ask, 101, 10),
+        create(2, symbol, OrderSide::Ask, 102, 10),
+        create(3, symbol, OrderSide::Bid, 99, 10),
+        // Trades 10 at 101 and 5 at 102.
+        create(4, symbol, OrderSide::Bid, 102, 15),
+        OrderRequest::Cancel {
+            order_id: Uuid::from_u128(3),
+            reason: CancelReason::UserRequested,
+        },
+        create(5, symbol, OrderSide::Ask, 98, 5),
+    ]
+}
+
+/// Returns the state hash of an engine after every input.
+fn state_hashes(symbol: &AssetPair, input: &[OrderRequest]) -> Vec<u32> {
+    let mut engine = Engine::new(symbol.clone());
+
+    input
+        .iter()
+        .map(|request| {
+            assert!(engine.process(request.clone()).is_ok());
+            engine.state_hash()
+        })
+        .collect()
+}
+
+#[test]
+fn resume_after_crash() {
+    let dir = tempfile::tempdir().unwrap();
+    let symbol = AssetPair::new("BTC", "USDC");
+    let input = input(&symbol);
+    let state_hashes = state_hashes(&symbol, &input);
+
+    {
+        let_assert!(Ok(mut engine) = open(&symbol, dir.path()));
+        assert!(engine.orderbook().last_checkpoint() == None);
+
+        for request in &input[..3] {
+            assert!(engine.process(request.clone()).is_ok());
+        }
+        assert!(let Ok(()) = engine.checkpoint(3));
+
+        // Crashes once the next two are processed, before they are
+        // checkpointed.
+        for request in &input[3..5] {
+            assert!(engine.process(request.clone()).is_ok());
+        }
+        assert!(engine.state_hash() == state_hashes[4]);
+    }
+
+    // Nothing past the checkpoint is found, so the bid it cancelled still
+    // rests, and the orders it traded with are whole again.
+    let_assert!(Ok(mut engine) = open(&symbol, dir.path()));
+    assert!(
+        engine.orderbook().last_checkpoint()
+            == Some(Checkpoint {
+                offset: 3,
+                state_hash: state_hashes[2]
+            })
+    );
+    assert!(engine.state_hash() == state_hashes[2]);
+    assert!(engine.orderbook().get(&OrderId::from_u128(3)).is_some());
+
+    // Resuming right after it, every input is processed exactly once.
+    let_assert!(Ok(report) = engine.process(input[3].clone()));
+    assert!(report.trades.len() == 2);
+    for request in &input[4..] {
+        assert!(engine.process(request.clone()).is_ok());
+    }
+    assert!(engine.state_hash() == state_hashes[5]);
+    assert!(let Ok(()) = engine.checkpoint(6));
+    drop(engine);
+
+    let_assert!(Ok((engine, warm_up)) = open_validated(&symbol, dir.path()));
+    assert!(warm_up == WarmUp::Resume { offset: 6 });
+    assert!(engine.state_hash() == state_hashes[5]);
+}
+
+#[test]
+fn replay_corrupt_book() {
+    let dir = tempfile::tempdir().unwrap();
+    let symbol = AssetPair::new("BTC", "USDC");
+    let input = input(&symbol);
+    let state_hashes = state_hashes(&symbol, &input);
+
+    {
+        let_assert!(Ok(mut engine) = open(&symbol, dir.path()));
+        for request in &input[..3] {
+            assert!(engine.process(request.clone()).is_ok());
+        }
+        assert!(let Ok(()) = engine.checkpoint(3));
+    }
+
+    {
+        // Simulates a write that did not complete, so the first ask fails
+        // its checksum.
+        let db = retry(|| sled::open(dir.path())).unwrap();
+        let (key, value) = db.first().unwrap().unwrap();
+        let mut value = value.to_vec();
+        *value.last_mut().unwrap() ^= 0xff;
+        db.insert(key, value).unwrap();
+        db.flush().unwrap();
+    }
+
+    // The book is restored without it, which validating catches.
+    let_assert!(Ok((mut engine, warm_up)) = open_validated(&symbol, dir.path()));
+    let_assert!(WarmUp::Replay { expected, found } = warm_up);
+    assert!(expected == state_hashes[2]);
+    assert!(found != expected);
+    assert!(warm_up.offset() == 0);
+    assert!(engine.orderbook().get(&OrderId::from_u128(1)).is_none());
+    assert!(engine.orderbook().is_empty());
+
+    // Replaying a part of the input only, the storage no longer holds
+    // anything of the stale book, should it crash again.
+    for request in &input[..2] {
+        assert!(engine.process(request.clone()).is_ok());
+    }
+    drop(engine);
+
+    let_assert!(Ok((mut engine, warm_up)) = open_validated(&symbol, dir.path()));
+    assert!(warm_up == WarmUp::Resume { offset: 0 });
+    assert!(engine.orderbook().is_empty());
+
+    // Replayed in full, the book is just as if nothing was ever corrupt.
+    for request in &input {
+        assert!(engine.process(request.clone()).is_ok());
+    }
+    assert!(engine.state_hash() == state_hashes[5]);
+    assert!(let Ok(()) = engine.checkpoint(6));
+    drop(engine);
+
+    let_assert!(Ok((engine, warm_up)) = open_validated(&symbol, dir.path()));
+    assert!(warm_up == WarmUp::Resume { offset: 6 });
+    assert!(engine.state_hash() == state_hashes[5]);
+}
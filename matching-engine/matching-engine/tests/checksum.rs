@@ -0,0 +1,106 @@
+//! The checksum is computed over `100:20,101:40|99:50,98:20` for the book
+//! below, so these values can be reproduced with any CRC32 implementation.
+
+use assert2::assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+#[cfg(not(feature = "integer"))]
+use exchange_types::Price;
+#[cfg(not(feature = "integer"))]
+use exchange_types::Quantity;
+use matching_engine_algo::Orderbook;
+#[cfg(not(feature = "integer"))]
+use rust_decimal_macros::dec;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price, quantity) in [
+            (OrderSide::Ask, 101, 10),
+            (OrderSide::Ask, 100, 20),
+            (OrderSide::Ask, 101, 30),
+            (OrderSide::Ask, 102, 40),
+            (OrderSide::Bid, 98, 20),
+            (OrderSide::Bid, 99, 20),
+            (OrderSide::Bid, 99, 30),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn top_levels() {
+    let exchange = orderbook();
+
+    assert!(exchange.checksum(2) == 1130946235);
+    assert!(exchange.checksum(1) == 2427649028);
+}
+
+#[test]
+fn empty_book() {
+    assert!(Orderbook::new().checksum(10) == 2343686810);
+    assert!(orderbook().checksum(0) == 2343686810);
+}
+
+#[test]
+fn detects_changes() {
+    let mut exchange = orderbook();
+    let checksum = exchange.checksum(2);
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(5).build();
+    assert!(exchange.matching(market_order).is_ok());
+
+    assert!(exchange.checksum(2) != checksum);
+}
+
+#[test]
+#[cfg(not(feature = "integer"))]
+fn regardless_of_scale() {
+    let book = |orders: &[(OrderSide, Price, Quantity)], fill: Quantity| {
+        Orderbook::new().tap_mut(|exchange| {
+            for &(side, limit_price, quantity) in orders {
+                let limit_order = Order::builder()
+                    .side(side)
+                    .limit(limit_price, quantity)
+                    .build();
+
+                assert!(exchange.matching(limit_order).is_ok());
+            }
+
+            let market_order =
+                Order::builder().side(OrderSide::Bid).market(fill).build();
+            assert!(exchange.matching(market_order).is_ok());
+        })
+    };
+
+    // Both books end up with 10 at 100 and 5 at 99, though one of them got
+    // there through fractional fills, at prices written with more places.
+    let exchange = book(
+        &[
+            (OrderSide::Ask, 100.into(), 12.into()),
+            (OrderSide::Bid, 99.into(), 5.into()),
+        ],
+        2.into(),
+    );
+    let other = book(
+        &[
+            (OrderSide::Ask, dec!(100.00).into(), dec!(4.5).into()),
+            (OrderSide::Ask, dec!(100.0).into(), dec!(8.0).into()),
+            (OrderSide::Bid, dec!(99.0).into(), dec!(5.00).into()),
+        ],
+        dec!(2.5).into(),
+    );
+
+    assert!(
+        exchange.depth(&OrderSide::Ask, 1) == other.depth(&OrderSide::Ask, 1)
+    );
+    assert!(exchange.checksum(10) == other.checksum(10));
+}
@@ -0,0 +1,119 @@
+//! A circuit breaker halts matching once the market moves too far within a
+//! window of trades, until it is resumed by hand.
+
+use std::num::NonZeroUsize;
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_types::AssetPair;
+use exchange_types::CircuitBreaker;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use matching_engine_rt::Engine;
+use matching_engine_rt::EngineError;
+use matching_engine_rt::EngineState;
+use matching_engine_rt::Halt;
+use uuid::Uuid;
+
+fn create(
+    order_id: u128,
+    side: OrderSide,
+    limit_price: u32,
+    amount: u32,
+) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: AssetPair::new("BTC", "USDC"),
+        limit_price: limit_price.into(),
+        side,
+    }
+}
+
+/// An engine halted at 10% off the price of up to two trades ago, with a
+/// thin ask side above the last trade, at 100.
+fn engine() -> Engine {
+    let circuit_breaker =
+        CircuitBreaker::new(10, NonZeroUsize::new(2).unwrap());
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"))
+        .with_circuit_breaker(circuit_breaker);
+
+    for request in [
+        create(1, OrderSide::Ask, 100, 2),
+        create(2, OrderSide::Ask, 108, 1),
+        create(3, OrderSide::Ask, 108, 1),
+        create(4, OrderSide::Ask, 115, 1),
+        create(5, OrderSide::Ask, 120, 10),
+        create(6, OrderSide::Bid, 100, 1),
+    ] {
+        assert!(let Ok(_) = engine.process(request));
+    }
+
+    assert!(engine.state() == EngineState::Running);
+
+    engine
+}
+
+/// The move from the second to last trade at 108 up to 120.
+fn halt() -> Halt {
+    Halt {
+        reference: 108.into(),
+        price: 120.into(),
+    }
+}
+
+#[test]
+fn halts_past_the_threshold() {
+    let mut engine = engine();
+
+    // The request that trips the breaker still trades in full.
+    let_assert!(Ok(report) = engine.process(create(7, OrderSide::Bid, 120, 5)));
+    assert!(report.trades.len() == 5);
+    assert!(engine.state() == EngineState::Halted(halt()));
+
+    let_assert!(
+        Err(EngineError::Halted(halted)) =
+            engine.process(create(8, OrderSide::Bid, 120, 1))
+    );
+    assert!(halted == halt());
+
+    // Resting orders may still be pulled.
+    let delete = OrderRequest::Delete {
+        order_id: Uuid::from_u128(5),
+    };
+    assert!(let Ok(_) = engine.process(delete));
+}
+
+#[test]
+fn resume_restores_matching() {
+    let mut engine = engine();
+    assert!(let Ok(_) = engine.process(create(7, OrderSide::Bid, 120, 5)));
+    assert!(engine.resume() == Some(halt()));
+    assert!(engine.state() == EngineState::Running);
+
+    // The breaker starts over from the first trade after resuming.
+    let_assert!(Ok(report) = engine.process(create(8, OrderSide::Bid, 120, 1)));
+    assert!(report.trades.len() == 1);
+    assert!(engine.state() == EngineState::Running);
+
+    // Resuming a running engine does nothing at all.
+    assert!(engine.resume().is_none());
+}
+
+#[test]
+fn drift_within_the_window() {
+    let mut engine = engine();
+
+    // Every trade is within 10% of the one two trades before, so the market
+    // may drift further than that as a whole, from 100 up to 115.
+    for (order_id, limit_price, amount) in [(7, 108, 3), (8, 115, 1)] {
+        let request = create(order_id, OrderSide::Bid, limit_price, amount);
+        assert!(let Ok(_) = engine.process(request));
+        assert!(engine.state() == EngineState::Running);
+    }
+
+    // Though 120 is too far off 108, two trades before.
+    assert!(let Ok(_) = engine.process(create(9, OrderSide::Bid, 120, 1)));
+    assert!(engine.state() == EngineState::Halted(halt()));
+}
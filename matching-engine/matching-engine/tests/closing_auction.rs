@@ -0,0 +1,340 @@
+//! At-the-close orders are kept apart from the book during the session, out
+//! of continuous matching, until the closing auction crosses them, along
+//! with every resting order, at a single clearing price.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::AssetPair;
+use exchange_types::CancelReason;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::Quantity;
+use matching_engine_algo::InsertError;
+use matching_engine_algo::MatchingStrategy;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use matching_engine_rt::Engine;
+use matching_engine_rt::EngineError;
+use matching_engine_rt::MultiEngine;
+use uuid::Uuid;
+
+fn limit_order(
+    id: u128,
+    side: OrderSide,
+    limit_price: u32,
+    quantity: u32,
+) -> Order {
+    Order::builder()
+        .side(side)
+        .id(OrderId::from_u128(id))
+        .limit(limit_price, quantity)
+        .build()
+}
+
+fn at_the_close(
+    id: u128,
+    side: OrderSide,
+    limit_price: u32,
+    quantity: u32,
+) -> Order {
+    Order::builder()
+        .side(side)
+        .id(OrderId::from_u128(id))
+        .limit(limit_price, quantity)
+        .atc()
+        .build()
+}
+
+fn create(
+    order_id: u128,
+    symbol: &AssetPair,
+    side: OrderSide,
+    limit_price: u32,
+    amount: u32,
+) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: symbol.clone(),
+        limit_price: limit_price.into(),
+        side,
+    }
+}
+
+fn create_at_close(
+    order_id: u128,
+    symbol: &AssetPair,
+    side: OrderSide,
+    limit_price: u32,
+    amount: u32,
+) -> OrderRequest {
+    OrderRequest::CreateAtClose {
+        account_id: Uuid::from_u128(order_id),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: symbol.clone(),
+        limit_price: limit_price.into(),
+        side,
+    }
+}
+
+fn delete(order_id: u128) -> OrderRequest {
+    OrderRequest::Delete {
+        order_id: Uuid::from_u128(order_id),
+    }
+}
+
+/// Rests a bid at 98 and an ask at 100, queueing at-the-close orders that
+/// would trade with them right away otherwise.
+fn orderbook<A: MatchingStrategy>(mut exchange: Orderbook<A>) -> Orderbook<A> {
+    for order in [
+        limit_order(1, OrderSide::Bid, 98, 5),
+        limit_order(10, OrderSide::Ask, 100, 10),
+    ] {
+        assert!(exchange.matching(order).is_ok());
+    }
+
+    for order in [
+        at_the_close(2, OrderSide::Bid, 101, 6),
+        at_the_close(3, OrderSide::Bid, 100, 6),
+        at_the_close(11, OrderSide::Ask, 97, 4),
+    ] {
+        let_assert!(Ok(report) = exchange.matching(order));
+        assert!(report.trades().is_empty());
+        assert!(report.events().is_empty());
+        assert!(report.status() == OrderStatus::Open);
+        assert!(report.rejected_reason() == None);
+    }
+
+    exchange
+}
+
+/// Checks that none of the at-the-close orders trades, nor is on the book.
+fn continuous<A: MatchingStrategy>(mut exchange: Orderbook<A>) {
+    // None of them is on the book, though they are all queued, and so may
+    // still be looked up by id.
+    assert!(exchange.len() == (1, 1));
+    assert!(exchange.spread() == Some((100.into(), 98.into())));
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(2)));
+    assert!(order.is_at_the_close());
+    assert!(exchange.closing().len() == 3);
+
+    // An incoming order trades with the resting ones only.
+    let_assert!(
+        Ok(report) = exchange.matching(limit_order(12, OrderSide::Ask, 98, 5))
+    );
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.maker() == OrderId::from_u128(1));
+}
+
+#[test]
+fn no_continuous_trades() {
+    continuous(orderbook(Orderbook::new()));
+    continuous(orderbook(Orderbook::new().with_algo::<ProRataAlgo>()));
+}
+
+#[test]
+fn uncrossed_at_clearing_price() {
+    let mut exchange = orderbook(Orderbook::new());
+
+    // At 100, the bids at 101 and 100 buy 12 out of the 14 sold at 97 and
+    // 100, more than at any other price.
+    let (cross, expired) = exchange.run_closing_auction();
+    let_assert!(Some(cross) = cross);
+    assert!(cross.price() == 100.into());
+    assert!(cross.quantity() == 12.into());
+    assert!(expired.is_empty());
+
+    let trades = cross
+        .trades()
+        .iter()
+        .map(|trade| {
+            assert!(trade.price() == 100.into());
+            trade.quantity()
+        })
+        .collect::<Vec<_>>();
+    assert!(trades == [4.into(), 2.into(), 6.into()]);
+
+    // The resting ask keeps what is left of it, the resting bid all of it.
+    assert!(exchange.closing().is_empty());
+    assert!(exchange.last_price() == Some(100.into()));
+    let_assert!(Some(ask) = exchange.get(&OrderId::from_u128(10)));
+    assert!(ask.remaining() == Quantity::from(2));
+    let_assert!(Some(bid) = exchange.get(&OrderId::from_u128(1)));
+    assert!(bid.remaining() == Quantity::from(5));
+    assert!(exchange.best_bid() < exchange.best_ask());
+}
+
+#[test]
+fn unfilled_expired() {
+    let mut exchange = Orderbook::new();
+    assert!(exchange
+        .matching(limit_order(10, OrderSide::Ask, 100, 10))
+        .is_ok());
+    assert!(exchange
+        .matching(at_the_close(1, OrderSide::Bid, 95, 5))
+        .is_ok());
+
+    // Nothing crosses, so the bid is expired rather than left to rest.
+    let (cross, expired) = exchange.run_closing_auction();
+    assert!(cross.is_none());
+    let_assert!([order] = expired.as_slice());
+    assert!(order.id() == OrderId::from_u128(1));
+    assert!(order.status() == OrderStatus::Expired);
+
+    assert!(exchange.len() == (1, 0));
+    assert!(exchange.get(&OrderId::from_u128(1)).is_none());
+}
+
+#[test]
+fn cancelled_before_close() {
+    let mut exchange = orderbook(Orderbook::new());
+
+    let_assert!(Some(order) = exchange.remove(&OrderId::from_u128(2)));
+    assert!(order.id() == OrderId::from_u128(2));
+    assert!(exchange.closing().len() == 2);
+
+    // Without the bid at 101, the bid at 100 buys 6 of the 14 sold.
+    let (cross, _) = exchange.run_closing_auction();
+    let_assert!(Some(cross) = cross);
+    assert!(cross.price() == 100.into());
+    assert!(cross.quantity() == 6.into());
+}
+
+#[test]
+fn duplicate_expired_as_is() {
+    let mut exchange = Orderbook::new();
+    assert!(exchange
+        .matching(limit_order(1, OrderSide::Ask, 100, 10))
+        .is_ok());
+    assert!(exchange
+        .matching(at_the_close(1, OrderSide::Bid, 100, 5))
+        .is_ok());
+
+    // The at-the-close order never joins the book, so the resting one keeps
+    // its id, untouched.
+    let (cross, expired) = exchange.run_closing_auction();
+    assert!(cross.is_none());
+    let_assert!([order] = expired.as_slice());
+    assert!(order.side() == OrderSide::Bid);
+    assert!(order.status() == OrderStatus::Expired);
+
+    let_assert!(Some(ask) = exchange.get(&OrderId::from_u128(1)));
+    assert!(ask.remaining() == Quantity::from(10));
+    let_assert!(Some(ask) = exchange.remove(&OrderId::from_u128(1)));
+    assert!(ask.side() == OrderSide::Ask);
+    assert!(exchange.is_empty());
+}
+
+#[test]
+fn engine_rejects_duplicates() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine = Engine::new(symbol.clone());
+
+    assert!(let Ok(_) = engine.process(create(1, &symbol, OrderSide::Ask, 100, 10)));
+    assert!(let Ok(_) = engine.process(create_at_close(2, &symbol, OrderSide::Bid, 100, 5)));
+
+    // Ids are unique whether taken by a resting order or a queued one, and
+    // either way around.
+    for request in [
+        create_at_close(1, &symbol, OrderSide::Bid, 100, 5),
+        create_at_close(2, &symbol, OrderSide::Bid, 100, 5),
+        create(2, &symbol, OrderSide::Bid, 90, 5),
+    ] {
+        let_assert!(
+            Err(EngineError::InsertError(InsertError::Duplicate { .. })) =
+                engine.process(request)
+        );
+    }
+
+    assert!(engine.orderbook().len() == (1, 0));
+    assert!(engine.orderbook().closing().len() == 1);
+}
+
+#[test]
+fn engine_cancels_queued() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine = Engine::new(symbol.clone());
+
+    assert!(let Ok(_) = engine.process(create(1, &symbol, OrderSide::Ask, 100, 10)));
+    let state_hash = engine.state_hash();
+
+    // Queued orders tell engines apart, though the book is the same.
+    assert!(let Ok(_) = engine.process(create_at_close(2, &symbol, OrderSide::Bid, 101, 5)));
+    assert!(engine.state_hash() != state_hash);
+
+    assert!(let Ok(_) = engine.process(OrderRequest::Cancel {
+        order_id: Uuid::from_u128(2),
+        reason: CancelReason::UserRequested,
+    }));
+    assert!(engine.orderbook().closing().is_empty());
+    assert!(engine.state_hash() == state_hash);
+
+    // Cancelling every order leaves none queued either.
+    assert!(let Ok(_) = engine.process(create_at_close(3, &symbol, OrderSide::Bid, 101, 5)));
+    let cancelled = engine
+        .cancel_all(CancelReason::Admin)
+        .into_iter()
+        .map(|cancel| (cancel.order_id, cancel.status))
+        .collect::<Vec<_>>();
+    assert!(
+        cancelled
+            == [
+                (OrderId::from_u128(1), OrderStatus::Cancelled),
+                (OrderId::from_u128(3), OrderStatus::Cancelled),
+            ]
+    );
+    assert!(engine.orderbook().is_empty());
+    assert!(engine.orderbook().closing().is_empty());
+}
+
+#[test]
+fn multi_engine_routes_queued() {
+    let btc = AssetPair::new("BTC", "USDC");
+    let eth = AssetPair::new("ETH", "USDC");
+    let mut engine = MultiEngine::new();
+
+    for request in [
+        create(1, &btc, OrderSide::Ask, 100, 10),
+        create_at_close(2, &btc, OrderSide::Bid, 100, 4),
+        create_at_close(3, &btc, OrderSide::Bid, 95, 5),
+        create_at_close(4, &btc, OrderSide::Bid, 95, 5),
+    ] {
+        assert!(engine.process(request).is_ok());
+    }
+
+    // Queued orders take their id across every book, and may be deleted or
+    // cancelled just like resting ones.
+    let_assert!(
+        Err(EngineError::InsertError(InsertError::Duplicate { .. })) =
+            engine.process(create(2, &eth, OrderSide::Ask, 100, 10))
+    );
+    assert!(engine.process(delete(3)).is_ok());
+    assert!(engine
+        .process(OrderRequest::Cancel {
+            order_id: Uuid::from_u128(4),
+            reason: CancelReason::UserRequested,
+        })
+        .is_ok());
+
+    // Once crossed, or expired, they are forgotten.
+    let_assert!(Some((report, expired)) = engine.run_closing_auction(&btc));
+    assert!(report.trades.len() == 1);
+    assert!(expired.is_empty());
+    let_assert!(
+        Err(EngineError::OrderNotFound { .. }) = engine.process(delete(2))
+    );
+    assert!(engine
+        .process(create(2, &eth, OrderSide::Ask, 100, 10))
+        .is_ok());
+    assert!(engine
+        .run_closing_auction(&AssetPair::new("SOL", "USDC"))
+        .is_none());
+}
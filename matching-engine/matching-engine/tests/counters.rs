@@ -0,0 +1,101 @@
+//! The number of resting orders and the volume they show are kept up to date
+//! as the book changes, rather than recounted on every call, so they must
+//! always agree with what actually rests on the book.
+
+use assert2::assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::Quantity;
+use matching_engine_algo::Orderbook;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+
+fn assert_consistent(exchange: &Orderbook) {
+    let recount = |side| {
+        exchange
+            .iter(&side)
+            .fold((0, Quantity::default()), |(count, volume), order| {
+                (count + 1, volume + order.displayed())
+            })
+    };
+
+    let (asks, ask_volume) = recount(OrderSide::Ask);
+    let (bids, bid_volume) = recount(OrderSide::Bid);
+
+    // Both are also checked against a full recount by debug assertions.
+    assert!(exchange.len() == (asks, bids));
+    assert!(exchange.volume() == (ask_volume, bid_volume));
+}
+
+#[test]
+fn randomized_operations() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    let mut exchange = Orderbook::new();
+    let mut order_ids = Vec::<OrderId>::new();
+
+    for _ in 0..10_000 {
+        let side = if rng.gen_bool(0.5) {
+            OrderSide::Ask
+        } else {
+            OrderSide::Bid
+        };
+
+        match rng.gen_range(0..100) {
+            0..=49 => {
+                let limit_price = rng.gen_range(95..=105);
+                let quantity = rng.gen_range(1..=50);
+
+                let builder =
+                    Order::builder().side(side).limit(limit_price, quantity);
+                let limit_order = match rng.gen_range(0..10) {
+                    0 | 1 => {
+                        builder.iceberg(rng.gen_range(1..=quantity)).build()
+                    }
+                    2 => builder.hidden().build(),
+                    _ => builder.build(),
+                };
+
+                order_ids.push(limit_order.id());
+                assert!(exchange.matching(limit_order).is_ok());
+            }
+            50..=69 => {
+                let quantity = rng.gen_range(1..=80);
+                let market_order =
+                    Order::builder().side(side).market(quantity).build();
+
+                assert!(exchange.matching(market_order).is_ok());
+            }
+            70..=89 if !order_ids.is_empty() => {
+                let order_id =
+                    order_ids.swap_remove(rng.gen_range(0..order_ids.len()));
+
+                exchange.remove(&order_id);
+            }
+            90..=97 if !order_ids.is_empty() => {
+                let order_id = order_ids[rng.gen_range(0..order_ids.len())];
+                let limit_price = rng.gen_range(95..=105);
+                let quantity = rng.gen_range(1..=50);
+
+                let _ = exchange.amend(
+                    &order_id,
+                    limit_price.into(),
+                    quantity.into(),
+                );
+            }
+            98 => {
+                exchange.pop(&side);
+            }
+            99 => {
+                exchange.cancel_all_side(&side);
+            }
+            _ => {}
+        }
+
+        assert_consistent(&exchange);
+    }
+}
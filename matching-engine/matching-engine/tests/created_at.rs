@@ -0,0 +1,123 @@
+//! Resting orders are stamped with the time they were first queued at, by the
+//! clock the book was given, if any.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+const NOW: u64 = 1_700_000_000;
+
+fn clock() -> u64 {
+    NOW
+}
+
+fn later() -> u64 {
+    NOW + 60
+}
+
+fn orderbook(exchange: Orderbook) -> Orderbook {
+    exchange.tap_mut(|exchange| {
+        for (id, side, limit_price) in
+            [(1, OrderSide::Ask, 101), (2, OrderSide::Bid, 99)]
+        {
+            let limit_order = Order::builder()
+                .side(side)
+                .id(OrderId::from_u128(id))
+                .limit(limit_price, 10)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn stamped_as_queued() {
+    let mut exchange = orderbook(Orderbook::new().with_clock(clock));
+
+    // Being traded with does not make an order any younger.
+    let market_order = Order::builder().side(OrderSide::Bid).market(4).build();
+    assert!(exchange.matching(market_order).is_ok());
+
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(1)));
+    assert!(order.created_at() == NOW);
+    assert!(order.age(later()) == Some(60));
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 101,
+                remaining: 6,
+                status: Partial,
+                created_at: 1700000000,
+            },
+        ],
+        Bid: [
+            Order {
+                limit_price: 99,
+                remaining: 10,
+                status: Open,
+                created_at: 1700000000,
+            },
+        ],
+    }
+    "###);
+}
+
+#[test]
+fn unstamped_without_clock() {
+    let exchange = orderbook(Orderbook::new());
+
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(1)));
+    assert!(order.created_at() == 0);
+    assert!(order.age(later()).is_none());
+}
+
+#[test]
+fn kept_across_snapshots() {
+    let exchange = orderbook(Orderbook::new().with_clock(clock));
+
+    let snapshot = serde_json::to_value(exchange.snapshot()).unwrap();
+    assert!(snapshot["orders"][0]["created_at"] == NOW);
+
+    // Restored orders keep their time, whereas those queued afterwards are
+    // stamped by the clock of the restored book.
+    let snapshot = serde_json::from_value(snapshot).unwrap();
+    let_assert!(Ok(restored) = Orderbook::from_snapshot(snapshot));
+    let mut restored = restored.with_clock(later);
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .id(OrderId::from_u128(3))
+        .limit(98, 10)
+        .build();
+    assert!(restored.matching(limit_order).is_ok());
+
+    for (id, created_at) in [(2, NOW), (3, later())] {
+        let_assert!(Some(order) = restored.get(&OrderId::from_u128(id)));
+        assert!(order.created_at() == created_at);
+    }
+}
+
+#[test]
+fn kept_across_reprices() {
+    let exchange = orderbook(Orderbook::new().with_clock(clock));
+    let mut exchange = exchange.with_clock(later);
+
+    // The order loses its priority, though not the time it was placed at.
+    let order_id = OrderId::from_u128(1);
+    let_assert!(Ok(report) = exchange.amend(&order_id, 102.into(), 10.into()));
+    assert!(report.trades().is_empty());
+
+    let_assert!(Some(order) = exchange.get(&order_id));
+    assert!(order.limit_price() == Some(102.into()));
+    assert!(order.created_at() == NOW);
+    assert!(order.age(later()) == Some(60));
+}
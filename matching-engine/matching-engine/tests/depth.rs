@@ -0,0 +1,66 @@
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price, quantity) in [
+            (OrderSide::Ask, 102, 20),
+            (OrderSide::Ask, 101, 10),
+            (OrderSide::Ask, 101, 5),
+            (OrderSide::Ask, 103, 1),
+            (OrderSide::Bid, 99, 5),
+            (OrderSide::Bid, 100, 30),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn top_levels() {
+    let exchange = orderbook();
+
+    assert_eq!(
+        exchange.depth(&OrderSide::Ask, 2),
+        [(101.into(), 15.into()), (102.into(), 20.into())],
+    );
+    assert_eq!(
+        exchange.depth(&OrderSide::Bid, 1),
+        [(100.into(), 30.into())],
+    );
+}
+
+#[test]
+fn fewer_levels_than_requested() {
+    let exchange = orderbook();
+
+    assert_eq!(
+        exchange.depth(&OrderSide::Bid, 10),
+        [(100.into(), 30.into()), (99.into(), 5.into())],
+    );
+    assert!(Orderbook::new().depth(&OrderSide::Ask, 10).is_empty());
+}
+
+#[test]
+fn partially_filled_level() {
+    let mut exchange = orderbook();
+
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(101, 12).build();
+
+    assert!(exchange.matching(limit_order).is_ok());
+
+    assert_eq!(
+        exchange.depth(&OrderSide::Ask, 2),
+        [(101.into(), 3.into()), (102.into(), 20.into())],
+    );
+}
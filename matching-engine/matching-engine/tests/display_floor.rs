@@ -0,0 +1,108 @@
+//! A display floor keeps iceberg orders from showing a misleadingly small
+//! slice of themselves, either bumping what they show up to the floor or
+//! rejecting them outright.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::Quantity;
+use matching_engine_algo::DisplayFloor;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::RejectReason;
+use tap::Tap;
+
+/// A book with a single ask at 101, holding iceberg orders to show at least
+/// 10 at a time.
+fn orderbook(
+    display_floor: fn(Quantity) -> DisplayFloor<Quantity>,
+) -> Orderbook {
+    Orderbook::new()
+        .with_display_floor(display_floor(10.into()))
+        .tap_mut(|exchange| {
+            let limit_order =
+                Order::builder().side(OrderSide::Ask).limit(101, 10).build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        })
+}
+
+fn iceberg(limit_price: u32, display_quantity: u32) -> Order {
+    Order::builder()
+        .side(OrderSide::Bid)
+        .id(OrderId::from_u128(1))
+        .limit(limit_price, 50)
+        .iceberg(display_quantity)
+        .build()
+}
+
+#[test]
+fn bump() {
+    let mut exchange = orderbook(DisplayFloor::Bump);
+
+    let_assert!(Ok(report) = exchange.matching(iceberg(100, 2)));
+    assert!(report.rejected_reason().is_none());
+
+    let_assert!(Some(iceberg_order) = exchange.get(&OrderId::from_u128(1)));
+    assert!(iceberg_order.display_quantity() == Some(10.into()));
+    assert!(exchange.depth(&OrderSide::Bid, 1) == [(100.into(), 10.into())]);
+}
+
+#[test]
+fn bump_before_trading() {
+    let mut exchange = orderbook(DisplayFloor::Bump);
+
+    // Takes the whole ask, then rests showing the floor.
+    let_assert!(Ok(report) = exchange.matching(iceberg(101, 2)));
+    assert!(report.trades().len() == 1);
+    assert!(exchange.depth(&OrderSide::Bid, 1) == [(101.into(), 10.into())]);
+}
+
+#[test]
+fn reject() {
+    let mut exchange = orderbook(DisplayFloor::Reject);
+
+    for limit_price in [100, 101] {
+        let_assert!(Ok(report) = exchange.matching(iceberg(limit_price, 2)));
+        assert!(report.rejected_reason() == Some(RejectReason::DisplayFloor));
+        assert!(report.status() == OrderStatus::Cancelled);
+        assert!(report.trades().is_empty());
+    }
+
+    assert!(exchange.get(&OrderId::from_u128(1)).is_none());
+    assert!(exchange.depth(&OrderSide::Ask, 1) == [(101.into(), 10.into())]);
+}
+
+#[test]
+fn at_or_above_floor() {
+    for display_floor in [DisplayFloor::Bump, DisplayFloor::Reject] {
+        for display_quantity in [10, 20] {
+            let mut exchange = orderbook(display_floor);
+
+            let_assert!(
+                Ok(report) = exchange.matching(iceberg(100, display_quantity))
+            );
+            assert!(report.rejected_reason().is_none());
+            assert!(
+                exchange.depth(&OrderSide::Bid, 1)
+                    == [(100.into(), display_quantity.into())]
+            );
+        }
+    }
+}
+
+#[test]
+fn not_iceberg() {
+    let mut exchange = orderbook(DisplayFloor::Reject);
+
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(100, 5).build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.rejected_reason().is_none());
+    assert!(exchange.depth(&OrderSide::Bid, 1) == [(100.into(), 5.into())]);
+}
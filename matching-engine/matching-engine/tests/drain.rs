@@ -0,0 +1,101 @@
+//! Draining a book moves every resting order out of it exactly once, as it
+//! rested, e.g. to hand the book over to another engine.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::LimitOrder;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (id, side, limit_price, quantity) in [
+            (1, OrderSide::Ask, 102, 10),
+            (2, OrderSide::Ask, 101, 10),
+            (3, OrderSide::Ask, 101, 5),
+            (4, OrderSide::Bid, 99, 10),
+            (5, OrderSide::Bid, 100, 10),
+            (6, OrderSide::Bid, 102, 4),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .id(OrderId::from_u128(id))
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+
+        let hidden_order = Order::builder()
+            .side(OrderSide::Bid)
+            .id(OrderId::from_u128(7))
+            .limit(100, 3)
+            .hidden()
+            .build();
+
+        assert!(exchange.matching(hidden_order).is_ok());
+    })
+}
+
+#[test]
+fn moves_every_order_out() {
+    let mut exchange = orderbook();
+
+    let drained = exchange.drain().collect::<Vec<_>>();
+
+    assert!(exchange.is_empty());
+    assert!(exchange.volume() == (0.into(), 0.into()));
+    assert!(exchange.peek(&OrderSide::Ask).is_none());
+    assert!(exchange.peek(&OrderSide::Bid).is_none());
+
+    // Orders come out in price-time priority, asks first, hidden ones
+    // included, without being cancelled.
+    let drained = drained
+        .iter()
+        .map(|order| (order.id(), order.status()))
+        .collect::<Vec<_>>();
+    assert!(
+        drained
+            == [
+                (OrderId::from_u128(2), OrderStatus::Partial),
+                (OrderId::from_u128(3), OrderStatus::Open),
+                (OrderId::from_u128(1), OrderStatus::Open),
+                (OrderId::from_u128(5), OrderStatus::Open),
+                (OrderId::from_u128(7), OrderStatus::Open),
+                (OrderId::from_u128(4), OrderStatus::Open),
+            ]
+    );
+}
+
+#[test]
+fn emptied_right_away() {
+    let mut exchange = orderbook();
+
+    // Even an iterator that is never consumed leaves nothing behind.
+    drop(exchange.drain());
+    assert!(exchange.is_empty());
+    assert!(exchange.get(&OrderId::from_u128(1)).is_none());
+    assert!(exchange.drain().next().is_none());
+}
+
+#[test]
+fn handed_over() {
+    let mut exchange = orderbook();
+    let volume = exchange.volume();
+
+    let mut other = Orderbook::new();
+    for order in exchange.drain() {
+        let_assert!(Ok(order) = LimitOrder::try_from(order));
+        assert!(other.insert_checked(order).is_ok());
+    }
+
+    assert!(other.len() == (3, 2));
+    assert!(other.volume() == volume);
+}
@@ -0,0 +1,96 @@
+//! Engines that panic while processing a request dump the book as it was
+//! left, even if its indices disagree, so that what broke can be told.
+
+use std::fs;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_types::AssetPair;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use matching_engine_rt::Engine;
+use serde_json::json;
+use serde_json::Value;
+use tap::Tap;
+use uuid::Uuid;
+
+fn create(
+    order_id: u128,
+    symbol: &AssetPair,
+    side: OrderSide,
+    limit_price: u32,
+    amount: u32,
+) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: symbol.clone(),
+        limit_price: limit_price.into(),
+        side,
+    }
+}
+
+fn id(order_id: u128) -> Value {
+    json!(OrderId::from_u128(order_id))
+}
+
+#[test]
+fn dump_on_panic() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("book.json");
+    let symbol: AssetPair = "BTC/USDC".parse().unwrap();
+
+    let mut orderbook = Orderbook::new().tap_mut(|exchange| {
+        for (id, side, limit_price, quantity) in [
+            (1, OrderSide::Ask, 100, 10),
+            (2, OrderSide::Ask, 101, 10),
+            (3, OrderSide::Bid, 99, 10),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .id(OrderId::from_u128(id))
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    });
+    // The best ask is left queued, though no longer indexed.
+    assert!(let Some(_) = orderbook.unindex(&OrderId::from_u128(1)));
+    let mut engine = Engine::from_orderbook(symbol.clone(), orderbook);
+
+    let crossing = create(4, &symbol, OrderSide::Bid, 100, 5);
+    let processed = panic::catch_unwind(AssertUnwindSafe(|| {
+        engine.process_or_dump(crossing, &path)
+    }));
+    assert!(let Err(_) = processed);
+
+    let_assert!(Ok(dump) = fs::read_to_string(&path));
+    let_assert!(Ok(dump) = serde_json::from_str::<Value>(&dump));
+    assert!(dump["unindexed"] == json!([id(1)]));
+    assert!(dump["unqueued"] == json!([]));
+    assert!(dump["asks"][0]["order_ids"] == json!([id(1)]));
+    assert!(dump["asks"][1]["order_ids"] == json!([id(2)]));
+    assert!(dump["bids"][0]["order_ids"] == json!([id(3)]));
+    let_assert!(Some(orders) = dump["orders"].as_array());
+    assert!(orders.len() == 2);
+}
+
+#[test]
+fn no_dump_without_panic() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("book.json");
+    let symbol: AssetPair = "BTC/USDC".parse().unwrap();
+    let mut engine = Engine::new(symbol.clone());
+
+    let request = create(1, &symbol, OrderSide::Ask, 100, 10);
+    assert!(let Ok(_) = engine.process_or_dump(request, &path));
+    assert!(!path.exists());
+}
@@ -0,0 +1,68 @@
+//! Market orders can never rest on the book, so whatever is left of them once
+//! the opposite side runs out is closed and reported as such, rather than
+//! silently dropped.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use matching_engine_algo::Orderbook;
+
+#[test]
+fn market_order() {
+    let mut exchange = Orderbook::new();
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(10).build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Expired);
+    assert!(exchange.is_empty());
+}
+
+#[test]
+fn market_funds_order() {
+    let mut exchange = Orderbook::new();
+
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market_funds(1000)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Expired);
+    assert!(exchange.is_empty());
+}
+
+#[test]
+fn partially_filled_market_order() {
+    let mut exchange = Orderbook::new();
+
+    let limit_order =
+        Order::builder().side(OrderSide::Ask).limit(100, 10).build();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(15).build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().len() == 1);
+    assert!(report.status() == OrderStatus::Expired);
+    assert!(exchange.is_empty());
+}
+
+#[test]
+fn resting_limit_order() {
+    let mut exchange = Orderbook::new();
+
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(100, 10).build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Open);
+    assert!(exchange.len() == (0, 1));
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::ExchangeExt;
+use exchange_types::error::OrderError;
+use exchange_types::AssetPair;
+use exchange_types::CancelReason;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::OrderRequestError;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::PriceCollar;
+use matching_engine_algo::InsertError;
+use matching_engine_algo::RejectReason;
+use matching_engine_rt::CancelReport;
+use matching_engine_rt::Engine;
+use matching_engine_rt::EngineError;
+use matching_engine_rt::EngineStats;
+use matching_engine_rt::SymbolError;
+use uuid::Uuid;
+
+fn create(order_id: u128, symbol: AssetPair, amount: i32) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol,
+        limit_price: 100.into(),
+        side: OrderSide::Ask,
+    }
+}
+
+fn delete(order_id: u128) -> OrderRequest {
+    OrderRequest::Delete {
+        order_id: Uuid::from_u128(order_id),
+    }
+}
+
+#[test]
+fn reject_malformed_requests() {
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"));
+
+    let_assert!(
+        Err(EngineError::SymbolError(SymbolError::Mismatch { .. })) =
+            engine.process(create(1, AssetPair::new("ETH", "USDC"), 10))
+    );
+    let_assert!(
+        Err(EngineError::RequestError(OrderRequestError::Order(
+            OrderError::NonPositiveQuantity
+        ))) = engine.process(create(2, AssetPair::new("BTC", "USDC"), 0))
+    );
+
+    // Rejected requests leave the engine able to process the next ones.
+    assert!(let Ok(_) = engine.process(create(3, AssetPair::new("BTC", "USDC"), 10)));
+    assert!(engine.orderbook().len() == (1, 0));
+}
+
+#[test]
+fn reject_unknown_deletes() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine = Engine::new(symbol.clone());
+
+    assert!(let Ok(_) = engine.process(create(1, symbol.clone(), 10)));
+    assert!(let Ok(_) = engine.process(delete(1)));
+
+    // Neither an order that is gone already nor one that never existed can
+    // be deleted.
+    for order_id in [1, 2] {
+        let_assert!(
+            Err(EngineError::OrderNotFound {
+                order_id: not_found
+            }) = engine.process(delete(order_id))
+        );
+        assert!(not_found == OrderId::from_u128(order_id));
+    }
+}
+
+#[test]
+fn reject_duplicate_order_ids() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine = Engine::new(symbol.clone());
+
+    assert!(let Ok(_) = engine.process(create(1, symbol.clone(), 10)));
+
+    // Even a request that would match is turned down before it trades,
+    // leaving the resting order untouched.
+    let bid = OrderRequest::Create {
+        account_id: Uuid::from_u128(2),
+        amount: 5.into(),
+        order_id: Uuid::from_u128(1),
+        symbol: symbol.clone(),
+        limit_price: 100.into(),
+        side: OrderSide::Bid,
+    };
+    let_assert!(
+        Err(EngineError::InsertError(InsertError::Duplicate {
+            order_id
+        })) = engine.process(bid)
+    );
+    assert!(order_id == OrderId::from_u128(1));
+
+    assert!(*engine.stats() == EngineStats::default());
+    assert!(engine.orderbook().len() == (1, 0));
+    assert!(engine.orderbook().volume().0 == 10.into());
+
+    // Once it is gone, its id may be used again.
+    assert!(let Ok(_) = engine.process(delete(1)));
+    assert!(let Ok(_) = engine.process(create(1, symbol.clone(), 10)));
+    assert!(engine.orderbook().len() == (1, 0));
+}
+
+#[test]
+fn cancel_reports_what_was_left() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine = Engine::new(symbol.clone());
+
+    assert!(let Ok(_) = engine.process(create(1, symbol.clone(), 10)));
+    assert!(let Ok(_) = engine.process(create(2, symbol.clone(), 10)));
+
+    let bid = OrderRequest::Create {
+        account_id: Uuid::from_u128(3),
+        amount: 4.into(),
+        order_id: Uuid::from_u128(3),
+        symbol: symbol.clone(),
+        limit_price: 100.into(),
+        side: OrderSide::Bid,
+    };
+    assert!(let Ok(_) = engine.process(bid));
+
+    let_assert!(
+        Some(report) =
+            engine.cancel(&OrderId::from_u128(1), CancelReason::UserRequested)
+    );
+    assert!(
+        report
+            == CancelReport {
+                order_id: OrderId::from_u128(1),
+                reason: CancelReason::UserRequested,
+                status: OrderStatus::Closed,
+                filled: 4.into(),
+                remaining: 6.into(),
+            }
+    );
+
+    let_assert!(
+        Some(report) =
+            engine.cancel(&OrderId::from_u128(2), CancelReason::Risk)
+    );
+    assert!(report.status == OrderStatus::Cancelled);
+    assert!(report.filled == 0.into());
+    assert!(report.remaining == 10.into());
+
+    // Once cancelled, orders are gone for good.
+    assert!(engine.orderbook().is_empty());
+    assert!(engine
+        .cancel(&OrderId::from_u128(1), CancelReason::UserRequested)
+        .is_none());
+
+    let cancel = OrderRequest::Cancel {
+        order_id: Uuid::from_u128(2),
+        reason: CancelReason::Admin,
+    };
+    let_assert!(
+        Err(EngineError::OrderNotFound { .. }) = engine.process(cancel)
+    );
+}
+
+#[test]
+fn stats() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine = Engine::new(symbol.clone());
+
+    assert!(*engine.stats() == EngineStats::default());
+
+    assert!(let Ok(_) = engine.process(create(1, symbol.clone(), 10)));
+    assert!(let Ok(_) = engine.process(create(2, symbol.clone(), 5)));
+
+    let bid = OrderRequest::Create {
+        account_id: Uuid::from_u128(3),
+        amount: 12.into(),
+        order_id: Uuid::from_u128(3),
+        symbol: symbol.clone(),
+        limit_price: 100.into(),
+        side: OrderSide::Bid,
+    };
+    assert!(let Ok(_) = engine.process(bid));
+
+    // Rejected requests count for nothing.
+    assert!(let Err(_) = engine.process(delete(4)));
+
+    assert!(
+        *engine.stats()
+            == EngineStats {
+                matched: 12.into(),
+                notional: 1200.into(),
+                trades: 2,
+                rejected: HashMap::new(),
+            }
+    );
+}
+
+#[test]
+fn rejection_counts() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine =
+        Engine::new(symbol.clone()).with_price_collar(PriceCollar::new(10));
+
+    let create =
+        |order_id, account_id, side, limit_price: u32| OrderRequest::Create {
+            account_id: Uuid::from_u128(account_id),
+            amount: 10.into(),
+            order_id: Uuid::from_u128(order_id),
+            symbol: symbol.clone(),
+            limit_price: limit_price.into(),
+            side,
+        };
+
+    // Orders are collared within 90 and 110 from now on.
+    assert!(let Ok(_) = engine.process(create(1, 1, OrderSide::Ask, 101)));
+    assert!(let Ok(_) = engine.process(create(2, 2, OrderSide::Bid, 99)));
+
+    for request in [
+        create(3, 1, OrderSide::Bid, 101),
+        create(4, 2, OrderSide::Ask, 99),
+        create(5, 3, OrderSide::Bid, 150),
+    ] {
+        let_assert!(
+            Err(EngineError::Rejected { .. }) = engine.process(request)
+        );
+    }
+
+    // Requests turned down before reaching the book are not counted.
+    assert!(let Err(_) = engine.process(create(1, 1, OrderSide::Ask, 101)));
+
+    assert!(
+        engine.stats().rejected
+            == HashMap::from([
+                (RejectReason::SelfTrade, 2),
+                (RejectReason::PriceCollar, 1),
+            ])
+    );
+}
+
+#[test]
+fn reject_oversized_orders() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine =
+        Engine::new(symbol.clone()).with_max_order_notional(1000.into());
+
+    // Right at the cap is still fine.
+    assert!(let Ok(_) = engine.process(create(1, symbol.clone(), 10)));
+
+    let_assert!(
+        Err(EngineError::OrderTooLarge {
+            notional,
+            max_order_notional,
+        }) = engine.process(create(2, symbol.clone(), 11))
+    );
+    assert!(notional == 1100.into());
+    assert!(max_order_notional == 1000.into());
+
+    // Nor can a resting order be amended past it.
+    let amend = OrderRequest::Amend {
+        order_id: Uuid::from_u128(1),
+        new_price: 101.into(),
+        new_quantity: 10.into(),
+    };
+    let_assert!(Err(EngineError::OrderTooLarge { .. }) = engine.process(amend));
+
+    // Rejected orders never reach the book.
+    assert!(engine.orderbook().len() == (1, 0));
+    assert!(engine.orderbook().volume().0 == 10.into());
+}
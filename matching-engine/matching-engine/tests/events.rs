@@ -0,0 +1,85 @@
+use std::fs;
+use std::io;
+use std::io::BufRead;
+
+use exchange_types::AssetPair;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use matching_engine_rt::Engine;
+use matching_engine_rt::Event;
+use matching_engine_rt::EventWriter;
+use uuid::Uuid;
+
+fn create(
+    i: u32,
+    side: OrderSide,
+    limit_price: u32,
+    amount: u32,
+) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(u128::from(i)),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(u128::from(i)),
+        symbol: AssetPair::new("BTC", "USDC"),
+        limit_price: limit_price.into(),
+        side,
+    }
+}
+
+#[test]
+fn round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("events.jsonl");
+
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"));
+    let mut events =
+        EventWriter::new(io::BufWriter::new(fs::File::create(&path).unwrap()));
+
+    let requests = [
+        // Moves the best ask.
+        create(1, OrderSide::Ask, 100, 10),
+        create(2, OrderSide::Ask, 101, 10),
+        // Moves the best bid.
+        create(3, OrderSide::Bid, 99, 10),
+        // Trades twice and moves the best ask.
+        create(4, OrderSide::Bid, 101, 15),
+        // Leaves the best prices alone.
+        create(5, OrderSide::Bid, 98, 5),
+        // Empties the bid side, then places it back.
+        OrderRequest::Delete {
+            order_id: Uuid::from_u128(3),
+        },
+        OrderRequest::Delete {
+            order_id: Uuid::from_u128(5),
+        },
+        create(6, OrderSide::Bid, 99, 5),
+    ];
+
+    let mut written = 0;
+    for request in requests {
+        let report = engine.process(request).unwrap();
+        written += events.write(report.trades, engine.orderbook()).unwrap();
+    }
+    events.into_inner().unwrap();
+
+    let events = io::BufReader::new(fs::File::open(&path).unwrap())
+        .lines()
+        .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+        .collect::<Vec<Event>>();
+
+    assert_eq!(events.len(), written);
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| matches!(event, Event::Trade(_)))
+            .count(),
+        2
+    );
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| matches!(event, Event::Quote { .. }))
+            .count(),
+        6
+    );
+}
@@ -0,0 +1,141 @@
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for limit_order in [
+            Order::builder()
+                .side(OrderSide::Ask)
+                .limit(101, 10)
+                .gtd(100)
+                .build(),
+            Order::builder().side(OrderSide::Ask).limit(102, 10).build(),
+            Order::builder()
+                .side(OrderSide::Bid)
+                .limit(99, 10)
+                .gtd(200)
+                .build(),
+            Order::builder()
+                .side(OrderSide::Bid)
+                .limit(98, 10)
+                .gtd(100)
+                .build(),
+        ] {
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn nothing_expired() {
+    let mut exchange = orderbook();
+
+    assert!(exchange.expire(99).is_empty());
+}
+
+#[test]
+fn sweep_expired() {
+    let mut exchange = orderbook();
+
+    let expired = exchange.expire(100);
+
+    assert!(expired.len() == 2);
+    assert!(expired
+        .iter()
+        .all(|order| order.status() == OrderStatus::Expired));
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 102,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+        Bid: [
+            Order {
+                limit_price: 99,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+    }
+    "###);
+
+    assert!(exchange.expire(200).len() == 1);
+    assert!(exchange.expire(u64::MAX).is_empty());
+}
+
+#[test]
+fn partially_filled() {
+    let mut exchange = orderbook();
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(4).build();
+    assert!(exchange.matching(market_order).is_ok());
+
+    let expired = exchange.expire(100);
+
+    // A partially filled order expires as well, unlike when it is cancelled.
+    assert!(expired
+        .iter()
+        .all(|order| order.status() == OrderStatus::Expired));
+}
+
+#[test]
+fn expire_in_queue_order() {
+    let mut exchange = Orderbook::new();
+
+    // Ids are descending, so neither they nor the hash of them follow the
+    // order in which the orders were queued.
+    let order_ids = (0..32u128)
+        .rev()
+        .map(|order_id| {
+            let limit_order = Order::builder()
+                .side(OrderSide::Ask)
+                .id(OrderId::from_u128(order_id))
+                .limit(101 + order_id as u32 % 3, 10)
+                .gtd(100)
+                .build();
+            assert!(exchange.matching(limit_order).is_ok());
+
+            OrderId::from_u128(order_id)
+        })
+        .collect::<Vec<_>>();
+
+    let expired = exchange.expire(100);
+
+    assert!(expired.iter().map(Order::id).collect::<Vec<_>>() == order_ids);
+}
+
+#[test]
+fn good_for_duration() {
+    let mut exchange = Orderbook::new().with_clock(|| 1_000);
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Ask)
+        .limit(101, 10)
+        .gfd(5_500)
+        .build();
+    let order_id = limit_order.id();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    // Its duration starts once accepted, rounded up to whole seconds.
+    let_assert!(Some(order) = exchange.get(&order_id));
+    assert!(order.expire_at() == Some(1_006));
+
+    assert!(exchange.expire(1_005).is_empty());
+
+    let expired = exchange.expire(1_006);
+    let_assert!([order] = expired.as_slice());
+    assert!(order.id() == order_id);
+    assert!(order.status() == OrderStatus::Expired);
+}
@@ -0,0 +1,299 @@
+//! Property tests of the invariants matching must hold whatever it is fed.
+//!
+//! Random sequences of limit orders, market orders and deletions are played
+//! against the book and against a plain model of it, which only knows about
+//! price-time priority. After every step, the book must never be crossed,
+//! trades must never fill more than what was submitted, every price level
+//! must hold at least one order, and the book must rest exactly what the
+//! model does, in the very same order.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::Price;
+use exchange_types::Quantity;
+use matching_engine_algo::MatchingStrategy;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Limit {
+        side: OrderSide,
+        limit_price: u32,
+        quantity: u32,
+    },
+    Market {
+        side: OrderSide,
+        quantity: u32,
+    },
+    /// Deletes one of the orders submitted so far, which may well be gone
+    /// already.
+    Delete(Index),
+}
+
+fn side() -> impl Strategy<Value = OrderSide> {
+    prop_oneof![Just(OrderSide::Ask), Just(OrderSide::Bid)]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        6 => (side(), 95..=105_u32, 1..=50_u32).prop_map(
+            |(side, limit_price, quantity)| Op::Limit {
+                side,
+                limit_price,
+                quantity,
+            }
+        ),
+        2 => (side(), 1..=80_u32)
+            .prop_map(|(side, quantity)| Op::Market { side, quantity }),
+        2 => any::<Index>().prop_map(Op::Delete),
+    ]
+}
+
+/// A resting order, as the model sees it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Resting {
+    order_id: OrderId,
+    side: OrderSide,
+    limit_price: Price,
+    remaining: Quantity,
+}
+
+/// The book as a plain list of resting orders, in arrival order.
+#[derive(Default)]
+struct Model(Vec<Resting>);
+
+impl Model {
+    /// Returns the orders resting on the given side, in price-time priority.
+    fn queue(&self, side: OrderSide) -> Vec<Resting> {
+        let mut queue = self
+            .0
+            .iter()
+            .filter(|resting| resting.side == side)
+            .copied()
+            .collect::<Vec<_>>();
+
+        // Sorting is stable, so orders at the same price keep their arrival
+        // order.
+        match side {
+            OrderSide::Ask => queue.sort_by_key(|resting| resting.limit_price),
+            OrderSide::Bid => queue
+                .sort_by_key(|resting| std::cmp::Reverse(resting.limit_price)),
+        }
+
+        queue
+    }
+
+    /// Returns the orders an incoming order would trade with, in price-time
+    /// priority.
+    fn crossing(
+        &self,
+        side: OrderSide,
+        limit_price: Option<Price>,
+    ) -> Vec<Resting> {
+        self.queue(opposite(side))
+            .into_iter()
+            .filter(|resting| match (side, limit_price) {
+                (_, None) => true,
+                (OrderSide::Ask, Some(limit_price)) => {
+                    resting.limit_price >= limit_price
+                }
+                (OrderSide::Bid, Some(limit_price)) => {
+                    resting.limit_price <= limit_price
+                }
+            })
+            .collect()
+    }
+
+    fn fill(&mut self, order_id: OrderId, quantity: Quantity) -> Quantity {
+        let_assert!(
+            Some(position) = self
+                .0
+                .iter()
+                .position(|resting| resting.order_id == order_id)
+        );
+
+        let resting = &mut self.0[position];
+        assert!(quantity <= resting.remaining);
+        resting.remaining -= quantity;
+
+        let remaining = resting.remaining;
+        if remaining.is_zero() {
+            self.0.remove(position);
+        }
+
+        remaining
+    }
+
+    fn remove(&mut self, order_id: OrderId) -> Option<Resting> {
+        let position = self
+            .0
+            .iter()
+            .position(|resting| resting.order_id == order_id)?;
+
+        Some(self.0.remove(position))
+    }
+}
+
+fn opposite(side: OrderSide) -> OrderSide {
+    match side {
+        OrderSide::Ask => OrderSide::Bid,
+        OrderSide::Bid => OrderSide::Ask,
+    }
+}
+
+fn run<A: MatchingStrategy>(
+    mut exchange: Orderbook<A>,
+    ops: Vec<Op>,
+    price_time_priority: bool,
+) {
+    let mut model = Model::default();
+    let mut submitted = Vec::<OrderId>::new();
+
+    for (id, op) in ops.into_iter().enumerate() {
+        let order_id = OrderId::from_u128(id as u128 + 1);
+
+        let (side, limit_price, quantity) = match op {
+            Op::Limit {
+                side,
+                limit_price,
+                quantity,
+            } => (side, Some(Price::from(limit_price)), quantity),
+            Op::Market { side, quantity } => (side, None, quantity),
+            Op::Delete(index) => {
+                if submitted.is_empty() {
+                    continue;
+                }
+
+                let order_id = submitted[index.index(submitted.len())];
+                let removed =
+                    exchange.remove(&order_id).map(|order| order.id());
+                let expected =
+                    model.remove(order_id).map(|resting| resting.order_id);
+                assert!(removed == expected);
+
+                check(&exchange, &model);
+                continue;
+            }
+        };
+
+        let builder = Order::builder().side(side).id(order_id);
+        let order = match limit_price {
+            Some(limit_price) => builder.limit(limit_price, quantity).build(),
+            None => builder.market(quantity).build(),
+        };
+
+        let crossing = model.crossing(side, limit_price);
+        let submitted_quantity = Quantity::from(quantity);
+
+        let_assert!(Ok(report) = exchange.matching(order));
+        let trades = report.trades();
+
+        // Nothing is ever filled out of thin air.
+        let mut filled = Quantity::default();
+        for trade in trades {
+            assert!(trade.taker() == order_id);
+            assert!(!trade.quantity().is_zero());
+
+            filled += trade.quantity();
+            assert!(filled <= submitted_quantity);
+            assert!(
+                trade.taker_remaining() == Some(submitted_quantity - filled)
+            );
+
+            let remaining = model.fill(trade.maker(), trade.quantity());
+            assert!(trade.maker_remaining() == remaining);
+        }
+
+        if price_time_priority {
+            // Makers are met strictly in price-time priority: every one but
+            // the last is used up, and none is skipped.
+            assert!(trades.len() <= crossing.len());
+            for (trade, maker) in trades.iter().zip(&crossing) {
+                assert!(trade.maker() == maker.order_id);
+                assert!(trade.price() == maker.limit_price);
+            }
+
+            if let Some((_, makers)) = trades.split_last() {
+                assert!(makers
+                    .iter()
+                    .all(|trade| trade.maker_remaining().is_zero()));
+            }
+        }
+
+        // The incoming order stops short only once nothing crosses anymore.
+        let left = submitted_quantity - filled;
+        if !left.is_zero() {
+            assert!(model.crossing(side, limit_price).is_empty());
+        }
+
+        if let Some(limit_price) = limit_price.filter(|_| !left.is_zero()) {
+            model.0.push(Resting {
+                order_id,
+                side,
+                limit_price,
+                remaining: left,
+            });
+        }
+
+        submitted.push(order_id);
+        check(&exchange, &model);
+    }
+}
+
+fn check<A: MatchingStrategy>(exchange: &Orderbook<A>, model: &Model) {
+    // The book is never left crossed.
+    if let (Some(best_ask), Some(best_bid)) =
+        (exchange.best_ask(), exchange.best_bid())
+    {
+        assert!(best_bid < best_ask);
+    }
+
+    let (asks, bids) = exchange.len();
+    for (side, len) in [(OrderSide::Ask, asks), (OrderSide::Bid, bids)] {
+        // Every price level holds something, and levels come in order.
+        let levels = exchange.level_summary(&side).collect::<Vec<_>>();
+        assert!(levels
+            .iter()
+            .all(|&(_, count, quantity)| { count > 0 && !quantity.is_zero() }));
+        assert!(
+            levels.iter().map(|&(_, count, _)| count).sum::<usize>() == len
+        );
+        assert!(levels.windows(2).all(|pair| match side {
+            OrderSide::Ask => pair[0].0 < pair[1].0,
+            OrderSide::Bid => pair[0].0 > pair[1].0,
+        }));
+
+        // The book rests exactly what the model does, in the same order.
+        let resting = exchange
+            .iter(&side)
+            .map(|order| Resting {
+                order_id: order.id(),
+                side: order.side(),
+                limit_price: order.limit_price().unwrap(),
+                remaining: order.remaining(),
+            })
+            .collect::<Vec<_>>();
+        assert!(resting == model.queue(side));
+    }
+}
+
+proptest! {
+    #[test]
+    fn price_time_priority(ops in prop::collection::vec(op(), 1..200)) {
+        run(Orderbook::new(), ops, true);
+    }
+
+    #[test]
+    fn pro_rata(ops in prop::collection::vec(op(), 1..200)) {
+        run(Orderbook::new().with_algo::<ProRataAlgo>(), ops, false);
+    }
+}
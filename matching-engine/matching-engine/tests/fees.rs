@@ -0,0 +1,52 @@
+// Fees are charged in fractions of the quote asset.
+#![cfg(not(feature = "integer"))]
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_types::AssetPair;
+use exchange_types::FeeSchedule;
+use exchange_types::OrderRequest;
+use exchange_types::Trade;
+use matching_engine_rt::Engine;
+use rust_decimal_macros::dec;
+
+const MAKER: &str = r#"{"type_op":"CREATE","account_id":"d4f79484-fe48-41e9-9bc5-45bb4cfedaf4","amount":"3","order_id":"75637317-8d86-436e-93bc-befc4a4ed830","symbol":"BTC/USDC","limit_price":"100.05","side":"SELL"}"#;
+const TAKER: &str = r#"{"type_op":"CREATE","account_id":"1b1e0c4e-9b4f-4b43-8c2e-4b0a3bb8e0b2","amount":"2","order_id":"5e0f2b8c-6a4f-4e55-9d0e-1f1e4a2b9c3d","symbol":"BTC/USDC","limit_price":"101","side":"BUY"}"#;
+
+fn process(engine: &mut Engine, request: &str) -> Vec<Trade> {
+    let request: OrderRequest = serde_json::from_str(request).unwrap();
+
+    let_assert!(Ok(report) = engine.process(request));
+
+    report.trades.into_vec()
+}
+
+#[test]
+fn charged_per_trade() {
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"))
+        .with_fee_schedule(FeeSchedule::new(dec!(-1), dec!(7.5), 2));
+
+    assert!(process(&mut engine, MAKER).is_empty());
+
+    let trades = process(&mut engine, TAKER);
+    let_assert!([trade] = trades.as_slice());
+
+    // 2 * 100.05 = 200.10, so the taker pays 0.150075 rounded half-up and
+    // the maker is rebated 0.02001 rounded half-up.
+    assert!(trade.notional() == dec!(200.10).into());
+    assert!(trade.taker_fee() == dec!(0.15).into());
+    assert!(trade.maker_fee() == dec!(-0.02).into());
+}
+
+#[test]
+fn free_by_default() {
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"));
+
+    assert!(process(&mut engine, MAKER).is_empty());
+
+    let trades = process(&mut engine, TAKER);
+    let_assert!([trade] = trades.as_slice());
+
+    assert!(trade.taker_fee().is_zero());
+    assert!(trade.maker_fee().is_zero());
+}
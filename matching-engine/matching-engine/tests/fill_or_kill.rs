@@ -4,9 +4,11 @@
 //! words, FOK orders are a combination of AON and IOC orders.
 
 use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
 use exchange_types::Order;
 use exchange_types::OrderSide;
 use matching_engine_algo::Orderbook;
+use matching_engine_algo::RejectReason;
 use tap::Tap;
 
 mod valid {
@@ -499,9 +501,90 @@ mod valid {
     }
 }
 
+#[test]
+fn hidden_liquidity() {
+    let mut exchange = Orderbook::new().tap_mut(|exchange| {
+        for limit_order in [
+            Order::builder().side(OrderSide::Ask).limit(100, 50).build(),
+            Order::builder()
+                .side(OrderSide::Ask)
+                .limit(100, 50)
+                .hidden()
+                .build(),
+        ] {
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    });
+
+    let fill_or_kill = Order::builder()
+        .side(OrderSide::Bid)
+        .market(100)
+        .all_or_none()
+        .build();
+
+    // Hidden orders are not shown, though they trade all the same.
+    let report = exchange.matching(fill_or_kill).unwrap();
+    assert!(report.rejected_reason().is_none());
+    assert_eq!(report.trades().len(), 2);
+    assert!(exchange.is_empty());
+}
+
+#[test]
+fn iceberg_reserve() {
+    let mut exchange = Orderbook::new().tap_mut(|exchange| {
+        let iceberg = Order::builder()
+            .side(OrderSide::Ask)
+            .limit(100, 50)
+            .iceberg(10)
+            .build();
+        assert!(exchange.matching(iceberg).is_ok());
+    });
+
+    let fill_or_kill = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(100, 30)
+        .ioc()
+        .all_or_none()
+        .build();
+
+    // Only a slice is shown, though the reserve trades all the same.
+    let report = exchange.matching(fill_or_kill).unwrap();
+    assert!(report.rejected_reason().is_none());
+    assert_eq!(report.trades().len(), 3);
+    let ask = exchange.peek(&OrderSide::Ask).unwrap();
+    assert!(ask.remaining() == 20.into());
+}
+
 mod invalid {
     use super::*;
 
+    #[test]
+    fn whole_lots_only() {
+        let mut exchange =
+            Orderbook::new()
+                .with_lot_size(10.into())
+                .tap_mut(|exchange| {
+                    let limit_order = Order::builder()
+                        .side(OrderSide::Ask)
+                        .limit(10, 100)
+                        .build();
+
+                    assert!(exchange.matching(limit_order).is_ok());
+                });
+
+        // The funds buy 95 units, though only 90 of them in whole lots.
+        let fill_or_kill = Order::builder()
+            .side(OrderSide::Bid)
+            .market_funds(950)
+            .all_or_none()
+            .build();
+
+        let report = exchange.matching(fill_or_kill).unwrap();
+        assert_eq!(report.rejected_reason(), Some(RejectReason::FillOrKill));
+        assert!(report.trades().is_empty());
+        assert_eq!(exchange.len(), (1, 0));
+    }
+
     #[test]
     fn amount_mismatch() {
         let mut exchange = Orderbook::new().tap_mut(|exchange| {
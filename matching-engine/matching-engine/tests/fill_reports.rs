@@ -0,0 +1,173 @@
+//! Every order a request trades, the incoming one and every resting one it
+//! trades with, is reported once, with how far the request filled it.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_types::AssetPair;
+use exchange_types::FeeSchedule;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use matching_engine_rt::Engine;
+use matching_engine_rt::FillReport;
+use matching_engine_rt::MatchOutcome;
+use matching_engine_rt::OrderReport;
+use uuid::Uuid;
+
+fn create(
+    order_id: u128,
+    side: OrderSide,
+    limit_price: u32,
+    amount: u32,
+) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: AssetPair::new("BTC", "USDC"),
+        limit_price: limit_price.into(),
+        side,
+    }
+}
+
+/// A book whose best ask was already filled in part.
+fn engine() -> Engine {
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"));
+
+    for request in [
+        create(1, OrderSide::Ask, 100, 10),
+        create(2, OrderSide::Ask, 102, 10),
+        create(3, OrderSide::Bid, 100, 4),
+    ] {
+        assert!(let Ok(_) = engine.process(request));
+    }
+
+    engine
+}
+
+#[test]
+fn taker_and_makers() {
+    let mut engine = engine();
+
+    let request = create(4, OrderSide::Bid, 102, 12);
+    let_assert!(Ok((report, fills)) = engine.process_with_fills(request));
+    assert!(report.trades.len() == 2);
+    assert!(
+        fills
+            == [
+                FillReport {
+                    order_id: OrderId::from_u128(4),
+                    side: OrderSide::Bid,
+                    filled_this_pass: 12.into(),
+                    cumulative_filled: 12.into(),
+                    avg_price: 101.into(),
+                    status: OrderStatus::Completed,
+                },
+                FillReport {
+                    order_id: OrderId::from_u128(1),
+                    side: OrderSide::Ask,
+                    filled_this_pass: 6.into(),
+                    cumulative_filled: 10.into(),
+                    avg_price: 100.into(),
+                    status: OrderStatus::Completed,
+                },
+                FillReport {
+                    order_id: OrderId::from_u128(2),
+                    side: OrderSide::Ask,
+                    filled_this_pass: 6.into(),
+                    cumulative_filled: 6.into(),
+                    avg_price: 102.into(),
+                    status: OrderStatus::Partial,
+                },
+            ]
+    );
+}
+
+#[test]
+fn amend_through_the_book() {
+    let mut engine = engine();
+
+    for request in [
+        create(5, OrderSide::Bid, 99, 10),
+        create(6, OrderSide::Ask, 99, 3),
+    ] {
+        assert!(let Ok(_) = engine.process(request));
+    }
+
+    // The bid amended through the book keeps what it filled before.
+    let amend = OrderRequest::Amend {
+        order_id: Uuid::from_u128(5),
+        new_price: 102.into(),
+        new_quantity: 20.into(),
+    };
+    let_assert!(Ok((_, fills)) = engine.process_with_fills(amend));
+    let_assert!([taker, ..] = fills.as_slice());
+    assert!(taker.order_id == OrderId::from_u128(5));
+    assert!(taker.filled_this_pass == 16.into());
+    assert!(taker.cumulative_filled == 19.into());
+    assert!(taker.status == OrderStatus::Partial);
+}
+
+#[test]
+fn nothing_traded() {
+    let mut engine = engine();
+
+    let request = create(4, OrderSide::Bid, 90, 10);
+    let_assert!(Ok((report, fills)) = engine.process_with_fills(request));
+    assert!(report.trades.is_empty());
+    assert!(fills.is_empty());
+}
+
+#[test]
+fn outcome_of_taker_and_makers() {
+    // Takers pay 1%, and makers get 0.5% back.
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"))
+        .with_fee_schedule(FeeSchedule::new(-50, 100, 2));
+    for request in [
+        create(1, OrderSide::Ask, 100, 10),
+        create(2, OrderSide::Ask, 200, 10),
+    ] {
+        assert!(let Ok(_) = engine.process(request));
+    }
+
+    let request = create(3, OrderSide::Bid, 200, 15);
+    let_assert!(Ok((report, outcomes)) = engine.process_with_outcomes(request));
+    assert!(report.trades.len() == 2);
+    assert!(
+        outcomes
+            == [MatchOutcome {
+                taker: OrderReport {
+                    order_id: OrderId::from_u128(3),
+                    side: OrderSide::Bid,
+                    filled: 15.into(),
+                    status: OrderStatus::Completed,
+                    fee: 20.into(),
+                },
+                makers: vec![
+                    OrderReport {
+                        order_id: OrderId::from_u128(1),
+                        side: OrderSide::Ask,
+                        filled: 10.into(),
+                        status: OrderStatus::Completed,
+                        fee: (-5).into(),
+                    },
+                    OrderReport {
+                        order_id: OrderId::from_u128(2),
+                        side: OrderSide::Ask,
+                        filled: 5.into(),
+                        status: OrderStatus::Partial,
+                        fee: (-5).into(),
+                    },
+                ],
+            }]
+    );
+
+    // An order trading nothing is still the taker of its own outcome.
+    let request = create(4, OrderSide::Bid, 150, 10);
+    let_assert!(Ok((_, outcomes)) = engine.process_with_outcomes(request));
+    let_assert!([outcome] = outcomes.as_slice());
+    assert!(outcome.taker.filled == 0.into());
+    assert!(outcome.taker.status == OrderStatus::Open);
+    assert!(outcome.makers.is_empty());
+}
@@ -0,0 +1,198 @@
+//! Hidden orders rest on the book without ever being shown by it, unlike
+//! iceberg orders, which still show a slice. They trade just like any other
+//! order, but only after every displayed order at the same price.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::error::ConversionError;
+use exchange_types::error::OrderError;
+use exchange_types::LimitOrder;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::Quantity;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use matching_engine_algo::RejectReason;
+
+fn orderbook() -> Orderbook {
+    let mut exchange = Orderbook::new();
+
+    for limit_order in [
+        Order::builder()
+            .side(OrderSide::Ask)
+            .id(OrderId::from_u128(1))
+            .limit(100, 10)
+            .hidden()
+            .build(),
+        Order::builder()
+            .side(OrderSide::Ask)
+            .id(OrderId::from_u128(2))
+            .limit(101, 20)
+            .hidden()
+            .build(),
+        Order::builder()
+            .side(OrderSide::Ask)
+            .id(OrderId::from_u128(3))
+            .limit(101, 5)
+            .build(),
+    ] {
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+
+    exchange
+}
+
+#[test]
+fn never_shown() {
+    let exchange = orderbook();
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 101,
+                remaining: 5,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+    assert!(exchange.len() == (1, 0));
+    assert!(exchange.volume() == (5.into(), 0.into()));
+    assert!(exchange.best_ask() == Some(101.into()));
+    assert!(
+        exchange.depth(&OrderSide::Ask, usize::MAX) == [(101.into(), 5.into())]
+    );
+    assert!(exchange.level_summary(&OrderSide::Ask).count() == 1);
+    assert!(
+        exchange
+            .peek_nth(&OrderSide::Ask, 0)
+            .map(|order| order.id())
+            == Some(OrderId::from_u128(3))
+    );
+
+    // They still rest on the book, though.
+    assert!(exchange.get(&OrderId::from_u128(1)).is_some());
+}
+
+#[test]
+fn still_trade() {
+    let mut exchange = orderbook();
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(20).build();
+
+    // The hidden order at the better price comes first, whereas the one at
+    // the same price as a displayed order comes after it, despite being
+    // older.
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let trades = report
+        .trades()
+        .iter()
+        .map(|trade| (trade.maker(), trade.quantity()))
+        .collect::<Vec<(OrderId, Quantity)>>();
+
+    assert!(
+        trades
+            == [
+                (OrderId::from_u128(1), 10.into()),
+                (OrderId::from_u128(3), 5.into()),
+                (OrderId::from_u128(2), 5.into()),
+            ]
+    );
+
+    assert!(exchange.is_empty());
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(2)));
+    assert!(order.remaining() == 15.into());
+}
+
+#[test]
+fn pro_rata() {
+    let mut exchange = orderbook().with_algo::<ProRataAlgo>();
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(13).build();
+
+    // Hidden orders show nothing, so they get no share of their level until
+    // every displayed order of it is filled.
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let trades = report
+        .trades()
+        .iter()
+        .map(|trade| (trade.maker(), trade.quantity()))
+        .collect::<Vec<(OrderId, Quantity)>>();
+
+    assert!(
+        trades
+            == [
+                (OrderId::from_u128(1), 10.into()),
+                (OrderId::from_u128(3), 3.into()),
+            ]
+    );
+}
+
+#[test]
+fn amended_in_place() {
+    let mut exchange = orderbook();
+
+    // Neither showing more nor less of a hidden order shows anything.
+    for quantity in [15, 4] {
+        let_assert!(
+            Ok(report) = exchange.amend(
+                &OrderId::from_u128(1),
+                100.into(),
+                quantity.into()
+            )
+        );
+        assert!(report.trades().is_empty());
+        assert!(exchange.volume() == (5.into(), 0.into()));
+    }
+
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(1)));
+    assert!(order.remaining() == 4.into());
+}
+
+#[test]
+fn cannot_be_iceberg() {
+    let_assert!(
+        Err(OrderError::Conversion(ConversionError::HiddenIceberg)) =
+            Order::builder()
+                .side(OrderSide::Ask)
+                .limit(100, 10)
+                .iceberg(2)
+                .hidden()
+                .try_build()
+    );
+}
+
+#[test]
+fn malformed_never_rests() {
+    let mut exchange = orderbook();
+
+    // Such an order cannot be built, though it may still be deserialized.
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .id(OrderId::from_u128(4))
+        .limit(100, 15)
+        .hidden()
+        .build();
+    let mut value = serde_json::to_value(limit_order).unwrap();
+    value["display_quantity"] = serde_json::json!("2");
+    let limit_order = serde_json::from_value::<Order>(value).unwrap();
+    let_assert!(
+        Err(ConversionError::HiddenIceberg) = LimitOrder::try_from(limit_order)
+    );
+
+    // Whatever is left of it once it traded is cancelled, rather than
+    // reported as resting while it is nowhere to be found.
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.trades().len() == 1);
+    assert!(report.status() == OrderStatus::Closed);
+    assert!(report.rejected_reason() == Some(RejectReason::Malformed));
+    assert!(exchange.get(&OrderId::from_u128(4)).is_none());
+    assert!(exchange.len() == (1, 0));
+}
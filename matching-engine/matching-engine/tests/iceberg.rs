@@ -0,0 +1,211 @@
+//! Iceberg orders only show a slice of their quantity on the book, keeping the
+//! rest hidden in reserve. Whenever the displayed slice is fully filled, a new
+//! one is shown out of the reserve at the back of the level, as if it was a
+//! brand new order.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for limit_order in [
+            Order::builder()
+                .side(OrderSide::Ask)
+                .limit(100, 50)
+                .iceberg(10)
+                .build(),
+            Order::builder().side(OrderSide::Ask).limit(100, 20).build(),
+        ] {
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn only_display_is_shown() {
+    let exchange = orderbook();
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 100,
+                remaining: 10,
+                status: Open,
+            },
+            Order {
+                limit_price: 100,
+                remaining: 20,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+    assert!(exchange.depth(&OrderSide::Ask, 1) == [(100.into(), 30.into())]);
+    assert!(exchange.volume() == (30.into(), 0.into()));
+}
+
+#[test]
+fn replenish_loses_priority() {
+    let mut exchange = orderbook();
+
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(100, 15).build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    let trades = report.into_trades();
+    assert!(trades.len() == 2);
+    assert!(trades[0].quantity() == 10.into());
+    assert!(trades[1].quantity() == 5.into());
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 100,
+                remaining: 15,
+                status: Partial,
+            },
+            Order {
+                limit_price: 100,
+                remaining: 10,
+                status: Partial,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+fn reserve_is_tradable() {
+    let mut exchange = orderbook();
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(80).build();
+
+    // The whole reserve is traded, one displayed slice at a time.
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let trades = report.into_trades();
+    let quantities = trades
+        .iter()
+        .map(|trade| trade.quantity())
+        .collect::<Vec<_>>();
+
+    assert!(quantities == [10, 20, 10, 10, 10, 10].map(Into::into).to_vec());
+    assert!(exchange.is_empty());
+}
+
+#[test]
+fn smaller_last_slice() {
+    let mut exchange = Orderbook::new();
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(100, 25)
+        .iceberg(10)
+        .build();
+
+    assert!(exchange.matching(limit_order).is_ok());
+
+    let market_order = Order::builder().side(OrderSide::Ask).market(20).build();
+
+    assert!(exchange.matching(market_order).is_ok());
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [],
+        Bid: [
+            Order {
+                limit_price: 100,
+                remaining: 5,
+                status: Partial,
+            },
+        ],
+    }
+    "###);
+}
+
+#[test]
+fn reprice_relocates_reserve() {
+    const ICEBERG: OrderId = OrderId::from_u128(1);
+
+    let mut exchange = Orderbook::new();
+    for limit_order in [
+        Order::builder()
+            .side(OrderSide::Ask)
+            .id(ICEBERG)
+            .limit(100, 50)
+            .iceberg(10)
+            .build(),
+        Order::builder().side(OrderSide::Ask).limit(100, 20).build(),
+        Order::builder().side(OrderSide::Ask).limit(101, 5).build(),
+        Order::builder().side(OrderSide::Bid).limit(100, 4).build(),
+    ] {
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+    let_assert!(Some(iceberg) = exchange.get(&ICEBERG));
+    assert!(iceberg.displayed() == 6.into());
+
+    // Its reserve follows it to the back of the new level, where it shows a
+    // whole slice again.
+    let_assert!(Ok(report) = exchange.amend(&ICEBERG, 101.into(), 50.into()));
+    assert!(report.trades().is_empty());
+    let_assert!(Some(iceberg) = exchange.get(&ICEBERG));
+    assert!(iceberg.displayed() == 10.into());
+    assert!(iceberg.remaining() == 46.into());
+    assert!(
+        exchange.depth(&OrderSide::Ask, 2)
+            == [(100.into(), 20.into()), (101.into(), 15.into())]
+    );
+
+    // The order already resting at the new level still goes first.
+    let market_order = Order::builder().side(OrderSide::Bid).market(71).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let quantities = report
+        .trades()
+        .iter()
+        .map(|trade| trade.quantity())
+        .collect::<Vec<_>>();
+
+    assert!(quantities == [20, 5, 10, 10, 10, 10, 6].map(Into::into).to_vec());
+    assert!(exchange.is_empty());
+}
+
+#[test]
+fn crossing_reprice_takes_liquidity() {
+    const ICEBERG: OrderId = OrderId::from_u128(1);
+
+    let mut exchange = Orderbook::new();
+    for limit_order in [
+        Order::builder()
+            .side(OrderSide::Ask)
+            .id(ICEBERG)
+            .limit(105, 50)
+            .iceberg(10)
+            .build(),
+        Order::builder().side(OrderSide::Bid).limit(102, 15).build(),
+    ] {
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+
+    // Just like an incoming iceberg order, it takes liquidity with all it
+    // has, not just the slice it showed, then rests what is left.
+    let_assert!(Ok(report) = exchange.amend(&ICEBERG, 102.into(), 50.into()));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.taker() == ICEBERG);
+    assert!(trade.quantity() == 15.into());
+
+    let_assert!(Some(iceberg) = exchange.get(&ICEBERG));
+    assert!(iceberg.displayed() == 10.into());
+    assert!(iceberg.remaining() == 35.into());
+    assert!(exchange.volume() == (10.into(), 0.into()));
+    assert!(exchange.best_ask() == Some(102.into()));
+}
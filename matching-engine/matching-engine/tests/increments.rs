@@ -0,0 +1,154 @@
+//! Limit prices and quantities can be restricted to multiples of a tick and a
+//! lot size, respectively, so that the book only ever holds round figures.
+
+// Ticks and lots below a whole unit need decimal amounts.
+#![cfg(not(feature = "integer"))]
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::AssetPair;
+use exchange_types::Order;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use matching_engine_algo::AmendError;
+use matching_engine_algo::IncrementError;
+use matching_engine_algo::Orderbook;
+use matching_engine_rt::Engine;
+use matching_engine_rt::EngineError;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+fn create(
+    order_id: u128,
+    limit_price: Decimal,
+    amount: Decimal,
+) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: AssetPair::new("BTC", "USDC"),
+        limit_price: limit_price.into(),
+        side: OrderSide::Ask,
+    }
+}
+
+#[test]
+fn any_increment_by_default() {
+    let exchange = Orderbook::new();
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Ask)
+        .limit(dec!(100.05), dec!(0.001))
+        .build();
+
+    assert!(exchange.tick_size().is_none());
+    assert!(exchange.lot_size().is_none());
+    assert!(let Ok(()) = exchange.validate(&limit_order));
+}
+
+#[test]
+fn tick_size() {
+    let exchange = Orderbook::new().with_tick_size(dec!(0.5).into());
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Ask)
+        .limit(dec!(100.5), 10)
+        .build();
+    assert!(let Ok(()) = exchange.validate(&limit_order));
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Ask)
+        .limit(dec!(100.25), 10)
+        .build();
+    let_assert!(
+        Err(IncrementError::Tick { price, tick_size }) =
+            exchange.validate(&limit_order)
+    );
+    assert!(price == dec!(100.25).into());
+    assert!(tick_size == dec!(0.5).into());
+
+    // Market orders have no limit price to check.
+    let market_order = Order::builder().side(OrderSide::Bid).market(10).build();
+    assert!(let Ok(()) = exchange.validate(&market_order));
+}
+
+#[test]
+fn lot_size() {
+    let exchange = Orderbook::new().with_lot_size(dec!(0.1).into());
+
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market(dec!(0.3))
+        .build();
+    assert!(let Ok(()) = exchange.validate(&market_order));
+
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market(dec!(0.35))
+        .build();
+    let_assert!(
+        Err(IncrementError::Lot { quantity, lot_size }) =
+            exchange.validate(&market_order)
+    );
+    assert!(quantity == dec!(0.35).into());
+    assert!(lot_size == dec!(0.1).into());
+
+    // Orders priced by funds have no quantity to check.
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market_funds(dec!(10.05))
+        .build();
+    assert!(let Ok(()) = exchange.validate(&market_order));
+}
+
+#[test]
+fn reject_amends() {
+    let mut exchange = Orderbook::new()
+        .with_tick_size(1.into())
+        .with_lot_size(1.into());
+
+    let limit_order =
+        Order::builder().side(OrderSide::Ask).limit(100, 10).build();
+    let order_id = limit_order.id();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    let_assert!(
+        Err(AmendError::Increment(IncrementError::Tick { .. })) =
+            exchange.amend(&order_id, dec!(100.5).into(), 10.into())
+    );
+    let_assert!(
+        Err(AmendError::Increment(IncrementError::Lot { .. })) =
+            exchange.amend(&order_id, 100.into(), dec!(10.5).into())
+    );
+
+    // Rejected amends leave the order untouched.
+    let_assert!(Some(order) = exchange.get(&order_id));
+    assert!(order.limit_price() == Some(100.into()));
+    assert!(order.remaining() == 10.into());
+}
+
+#[test]
+fn reject_requests() {
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"))
+        .with_tick_size(dec!(0.01).into())
+        .with_lot_size(dec!(0.001).into());
+
+    let_assert!(
+        Err(EngineError::IncrementError(IncrementError::Tick { .. })) =
+            engine.process(create(1, dec!(100.005), dec!(1)))
+    );
+    let_assert!(
+        Err(EngineError::IncrementError(IncrementError::Lot { .. })) =
+            engine.process(create(2, dec!(100.05), dec!(0.0005)))
+    );
+
+    assert!(engine.orderbook().is_empty());
+
+    assert!(let Ok(_) = engine.process(create(3, dec!(100.05), dec!(0.005))));
+    assert!(engine.orderbook().len() == (1, 0));
+}
@@ -0,0 +1,103 @@
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::LimitOrder;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+
+fn orders() -> Vec<LimitOrder> {
+    [
+        (OrderSide::Ask, 101, 10),
+        (OrderSide::Bid, 99, 10),
+        (OrderSide::Ask, 100, 20),
+        (OrderSide::Ask, 101, 30),
+        (OrderSide::Bid, 98, 20),
+        (OrderSide::Bid, 99, 30),
+    ]
+    .into_iter()
+    .zip(1..)
+    .map(|((side, limit_price, quantity), id)| {
+        LimitOrder::new(OrderId::from_u128(id), side, limit_price, quantity)
+    })
+    .collect()
+}
+
+fn ids(exchange: &Orderbook, side: OrderSide) -> Vec<OrderId> {
+    exchange.iter(&side).map(|order| order.id()).collect()
+}
+
+#[test]
+fn same_as_matching() {
+    let mut batched = Orderbook::new();
+    // SAFETY: asks and bids never overlap.
+    unsafe { batched.insert_batch(orders()) };
+
+    let mut matched = Orderbook::new();
+    for order in orders() {
+        assert!(matched.matching(Order::from(order)).is_ok());
+    }
+
+    assert_eq!(batched.view(), matched.view());
+    for side in [OrderSide::Ask, OrderSide::Bid] {
+        assert_eq!(ids(&batched, side), ids(&matched, side));
+    }
+}
+
+#[test]
+fn queue_behind_resting_orders() {
+    let mut exchange = Orderbook::new();
+    let limit_order =
+        Order::builder().side(OrderSide::Ask).limit(100, 5).build();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    // SAFETY: asks and bids never overlap.
+    unsafe { exchange.insert_batch(orders()) };
+
+    assert_eq!(exchange.len(), (4, 3));
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 100,
+                remaining: 5,
+                status: Open,
+            },
+            Order {
+                limit_price: 100,
+                remaining: 20,
+                status: Open,
+            },
+            Order {
+                limit_price: 101,
+                remaining: 10,
+                status: Open,
+            },
+            Order {
+                limit_price: 101,
+                remaining: 30,
+                status: Open,
+            },
+        ],
+        Bid: [
+            Order {
+                limit_price: 99,
+                remaining: 10,
+                status: Open,
+            },
+            Order {
+                limit_price: 99,
+                remaining: 30,
+                status: Open,
+            },
+            Order {
+                limit_price: 98,
+                remaining: 20,
+                status: Open,
+            },
+        ],
+    }
+    "###);
+}
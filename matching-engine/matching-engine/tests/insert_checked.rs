@@ -0,0 +1,64 @@
+//! Unlike `Exchange::insert`, which trusts its caller, checked inserts turn
+//! down whatever would corrupt the book.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::LimitOrder;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use matching_engine_algo::InsertError;
+use matching_engine_algo::Orderbook;
+
+fn limit_order(id: u128, side: OrderSide, limit_price: u32) -> LimitOrder {
+    LimitOrder::new(OrderId::from_u128(id), side, limit_price, 10)
+}
+
+#[test]
+fn insert() {
+    let mut exchange = Orderbook::new();
+
+    assert!(let Ok(()) = exchange.insert_checked(limit_order(1, OrderSide::Ask, 101)));
+    assert!(let Ok(()) = exchange.insert_checked(limit_order(2, OrderSide::Bid, 100)));
+
+    assert!(exchange.spread() == Some((101.into(), 100.into())));
+}
+
+#[test]
+fn reject_duplicate_ids() {
+    let mut exchange = Orderbook::new();
+
+    assert!(let Ok(()) = exchange.insert_checked(limit_order(1, OrderSide::Ask, 101)));
+
+    // Not even at another price or side.
+    let_assert!(
+        Err(InsertError::Duplicate { order_id }) =
+            exchange.insert_checked(limit_order(1, OrderSide::Bid, 99))
+    );
+    assert!(order_id == OrderId::from_u128(1));
+
+    // The resting order is left untouched.
+    assert!(exchange.len() == (1, 0));
+    assert!(exchange.remove(&order_id).is_some());
+    assert!(exchange.is_empty());
+}
+
+#[test]
+fn reject_crossing_orders() {
+    let mut exchange = Orderbook::new();
+
+    assert!(let Ok(()) = exchange.insert_checked(limit_order(1, OrderSide::Ask, 101)));
+
+    for limit_price in [101, 102] {
+        let_assert!(
+            Err(InsertError::Crossed) = exchange.insert_checked(limit_order(
+                2,
+                OrderSide::Bid,
+                limit_price
+            ))
+        );
+    }
+
+    assert!(exchange.len() == (1, 0));
+}
@@ -29,11 +29,14 @@ fn simple_match() {
         },
         @r###"
     Trade(
+      trade_id: 0,
       taker: "[uuid]",
       maker: "[uuid]",
       quantity: "100",
       price: "50000",
       notional: "5000000",
+      taker_remaining: Some("0"),
+      maker_remaining: "0",
     )
     "###
     }
@@ -56,11 +59,14 @@ fn partial_match() {
         },
         @r###"
     Trade(
+      trade_id: 0,
       taker: "[uuid]",
       maker: "[uuid]",
       quantity: "100",
       price: "50000",
       notional: "5000000",
+      taker_remaining: Some("0"),
+      maker_remaining: "100",
     )
     "###
     }
@@ -82,11 +88,14 @@ fn taker_advantage_for_ask() {
         },
         @r###"
     Trade(
+      trade_id: 0,
       taker: "[uuid]",
       maker: "[uuid]",
       quantity: "100",
       price: "50000",
       notional: "5000000",
+      taker_remaining: Some("0"),
+      maker_remaining: "100",
     )
     "###
     }
@@ -108,11 +117,14 @@ fn taker_advantage_for_bid() {
         },
         @r###"
     Trade(
+      trade_id: 0,
       taker: "[uuid]",
       maker: "[uuid]",
       quantity: "100",
       price: "60000",
       notional: "6000000",
+      taker_remaining: Some("100"),
+      maker_remaining: "0",
     )
     "###
     }
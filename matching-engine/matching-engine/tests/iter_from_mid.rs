@@ -0,0 +1,93 @@
+use assert2::assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use exchange_types::Price;
+use exchange_types::Quantity;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price, quantity) in [
+            (OrderSide::Ask, 101, 10),
+            (OrderSide::Ask, 100, 20),
+            (OrderSide::Ask, 101, 30),
+            (OrderSide::Ask, 102, 40),
+            (OrderSide::Bid, 98, 10),
+            (OrderSide::Bid, 99, 20),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+fn from_mid(exchange: &Orderbook) -> Vec<(OrderSide, Price, Quantity)> {
+    exchange
+        .iter_from_mid()
+        .map(|(side, order)| {
+            (side, order.limit_price().unwrap(), order.displayed())
+        })
+        .collect()
+}
+
+#[test]
+fn sides_take_turns() {
+    let exchange = orderbook();
+
+    // Bids run out first, so the remaining asks follow in a row.
+    assert!(
+        from_mid(&exchange)
+            == [
+                (OrderSide::Ask, 100.into(), 20.into()),
+                (OrderSide::Bid, 99.into(), 20.into()),
+                (OrderSide::Ask, 101.into(), 10.into()),
+                (OrderSide::Bid, 98.into(), 10.into()),
+                (OrderSide::Ask, 101.into(), 30.into()),
+                (OrderSide::Ask, 102.into(), 40.into()),
+            ]
+    );
+}
+
+#[test]
+fn one_sided() {
+    let mut exchange = orderbook();
+    exchange.cancel_all_side(&OrderSide::Ask);
+
+    assert!(
+        from_mid(&exchange)
+            == [
+                (OrderSide::Bid, 99.into(), 20.into()),
+                (OrderSide::Bid, 98.into(), 10.into()),
+            ]
+    );
+    assert!(from_mid(&Orderbook::new()).is_empty());
+}
+
+#[test]
+fn same_as_iter() {
+    let exchange = orderbook();
+
+    for side in [OrderSide::Ask, OrderSide::Bid] {
+        let from_mid = exchange
+            .iter_from_mid()
+            .filter(|&(order_side, _)| order_side == side)
+            .map(|(_, order)| order.id())
+            .collect::<Vec<_>>();
+
+        assert!(
+            from_mid
+                == exchange
+                    .iter(&side)
+                    .map(|order| order.id())
+                    .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price, quantity) in [
+            (OrderSide::Ask, 101, 10),
+            (OrderSide::Ask, 101, 5),
+            (OrderSide::Ask, 102, 20),
+            (OrderSide::Ask, 1000, 1),
+            (OrderSide::Bid, 100, 30),
+            (OrderSide::Bid, 99, 5),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn aggregates_levels() {
+    let exchange = orderbook();
+
+    insta::assert_snapshot!(exchange.ladder(2), @r###"
+    Qty Bid | Ask Qty
+     30 100 | 101 15
+      5  99 | 102 20
+    "###);
+}
+
+#[test]
+fn uneven_sides() {
+    let exchange = orderbook();
+
+    insta::assert_snapshot!(exchange.ladder(10), @r###"
+    Qty Bid | Ask  Qty
+     30 100 | 101  15
+      5  99 | 102  20
+            | 1000 1
+    "###);
+}
+
+#[test]
+fn empty() {
+    let exchange = Orderbook::new();
+
+    insta::assert_snapshot!(exchange.ladder(10), @"Qty Bid | Ask Qty");
+}
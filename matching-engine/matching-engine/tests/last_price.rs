@@ -0,0 +1,127 @@
+//! The price of the last trade is kept on the book, as a reference price, well
+//! after the trade itself is gone.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::AccountId;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price) in [
+            (OrderSide::Ask, 101),
+            (OrderSide::Ask, 102),
+            (OrderSide::Bid, 99),
+        ] {
+            let limit_order =
+                Order::builder().side(side).limit(limit_price, 10).build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn no_trade_yet() {
+    let exchange = orderbook();
+
+    assert!(exchange.last_price().is_none());
+}
+
+#[test]
+fn last_of_many() {
+    let mut exchange = orderbook();
+
+    // Sweeps the first level and part of the second one.
+    let market_order = Order::builder().side(OrderSide::Bid).market(15).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().len() == 2);
+
+    assert!(exchange.last_price() == Some(102.into()));
+
+    let market_order = Order::builder().side(OrderSide::Ask).market(1).build();
+    assert!(exchange.matching(market_order).is_ok());
+
+    assert!(exchange.last_price() == Some(99.into()));
+}
+
+#[test]
+fn kept_without_trades() {
+    let mut exchange = orderbook();
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(5).build();
+    assert!(exchange.matching(market_order).is_ok());
+
+    // Resting, cancelling and emptying the book trade nothing, so the last
+    // price is left as is.
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(100, 10).build();
+    assert!(exchange.matching(limit_order).is_ok());
+    exchange.cancel_all();
+
+    assert!(exchange.is_empty());
+    assert!(exchange.last_price() == Some(101.into()));
+}
+
+#[test]
+fn self_trade_prevention() {
+    let account_id = AccountId::from_u128(1);
+    let mut exchange = Orderbook::new().tap_mut(|exchange| {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .id(OrderId::from_u128(1))
+            .account_id(account_id)
+            .limit(101, 10)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    });
+
+    // Cancelled orders are not traded.
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .account_id(account_id)
+        .market(5)
+        .build();
+    assert!(exchange.matching(market_order).is_ok());
+
+    assert!(exchange.last_price().is_none());
+}
+
+#[test]
+fn pro_rata() {
+    let mut exchange = orderbook().with_algo::<ProRataAlgo>();
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(15).build();
+    assert!(exchange.matching(market_order).is_ok());
+
+    assert!(exchange.last_price() == Some(102.into()));
+}
+
+#[test]
+fn amended_into_a_trade() {
+    let mut exchange = orderbook().tap_mut(|exchange| {
+        let limit_order = Order::builder()
+            .side(OrderSide::Bid)
+            .id(OrderId::from_u128(1))
+            .limit(98, 10)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    });
+
+    let_assert!(
+        Ok(report) =
+            exchange.amend(&OrderId::from_u128(1), 101.into(), 10.into())
+    );
+    assert!(report.trades().len() == 1);
+
+    assert!(exchange.last_price() == Some(101.into()));
+}
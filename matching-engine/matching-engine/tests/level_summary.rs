@@ -0,0 +1,88 @@
+use assert2::assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use exchange_types::Price;
+use exchange_types::Quantity;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price, quantity) in [
+            (OrderSide::Ask, 101, 10),
+            (OrderSide::Ask, 100, 20),
+            (OrderSide::Ask, 101, 30),
+            (OrderSide::Bid, 98, 10),
+            (OrderSide::Bid, 99, 20),
+            (OrderSide::Bid, 98, 30),
+            (OrderSide::Bid, 98, 5),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+fn summary(
+    exchange: &Orderbook,
+    side: OrderSide,
+) -> Vec<(Price, usize, Quantity)> {
+    exchange.level_summary(&side).collect()
+}
+
+#[test]
+fn book_order() {
+    let exchange = orderbook();
+
+    assert!(
+        summary(&exchange, OrderSide::Ask)
+            == [(100.into(), 1, 20.into()), (101.into(), 2, 40.into())]
+    );
+    assert!(
+        summary(&exchange, OrderSide::Bid)
+            == [(99.into(), 1, 20.into()), (98.into(), 3, 45.into())]
+    );
+}
+
+#[test]
+fn same_as_len_and_depth() {
+    let exchange = orderbook();
+
+    let (asks, bids) = exchange.len();
+    let count = |side| {
+        summary(&exchange, side)
+            .iter()
+            .map(|&(_, count, _)| count)
+            .sum::<usize>()
+    };
+
+    assert!(count(OrderSide::Ask) == asks);
+    assert!(count(OrderSide::Bid) == bids);
+
+    for side in [OrderSide::Ask, OrderSide::Bid] {
+        assert!(
+            summary(&exchange, side)
+                .into_iter()
+                .map(|(price, _, quantity)| (price, quantity))
+                .collect::<Vec<_>>()
+                == exchange.depth(&side, usize::MAX)
+        );
+    }
+}
+
+#[test]
+fn partially_filled() {
+    let mut exchange = orderbook();
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(25).build();
+    assert!(exchange.matching(market_order).is_ok());
+
+    assert!(summary(&exchange, OrderSide::Ask) == [(101.into(), 2, 35.into())]);
+    assert!(summary(&Orderbook::new(), OrderSide::Ask).is_empty());
+}
@@ -0,0 +1,188 @@
+//! Funds-priced market orders spend a given notional instead of buying a given
+//! quantity, sweeping as many levels as the funds allow.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+#[cfg(not(feature = "integer"))]
+use exchange_types::Notional;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+#[cfg(not(feature = "integer"))]
+use exchange_types::Quantity;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+#[cfg(not(feature = "integer"))]
+use rust_decimal_macros::dec;
+use tap::Tap;
+
+fn orderbook(orders: &[(u32, u32)]) -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for &(limit_price, quantity) in orders {
+            let limit_order = Order::builder()
+                .side(OrderSide::Ask)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+#[cfg(not(feature = "integer"))]
+fn sweep_levels() {
+    let mut exchange = orderbook(&[(100, 1), (101, 1), (102, 5)]);
+
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market_funds(252)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let trades = report.into_trades();
+
+    let trades = trades
+        .iter()
+        .map(|trade| (trade.price(), trade.quantity()))
+        .collect::<Vec<_>>();
+    assert!(
+        trades
+            == [
+                (100.into(), 1.into()),
+                (101.into(), 1.into()),
+                (102.into(), dec!(0.5).into()),
+            ]
+    );
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 102,
+                remaining: 4.50,
+                status: Partial,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+fn stop_when_funds_exhausted() {
+    let mut exchange = orderbook(&[(30, 10), (31, 10)]);
+
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market_funds(300)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let trades = report.into_trades();
+    assert!(trades.len() == 1);
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 31,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+#[cfg(not(feature = "integer"))]
+fn partial_last_fill() {
+    let mut exchange = orderbook(&[(30, 10), (31, 10)]);
+
+    // The 50 left after the first level do not buy a whole unit at 31, nor
+    // a quantity that can be exactly represented.
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market_funds(350)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let trades = report.into_trades();
+    let_assert!([first, last] = trades.as_slice());
+
+    assert!(first.notional() == 300.into());
+    assert!(last.quantity() < 2.into());
+    assert!(last.notional() <= 50.into());
+
+    let spent = trades
+        .iter()
+        .fold(Notional::from(0), |acc, trade| acc + trade.notional());
+    assert!(spent <= 350.into());
+    assert!(spent > dec!(349.999).into());
+
+    let_assert!(Some(top_order) = exchange.peek(&OrderSide::Ask));
+    assert!(top_order.remaining() == Quantity::from(10) - last.quantity());
+}
+
+#[test]
+#[cfg(not(feature = "integer"))]
+fn fractional_last_fill() {
+    let mut exchange = orderbook(&[(10, 20)]);
+
+    // Without a lot size, the funds left after 10 whole units buy a fraction
+    // of the next one.
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market_funds(101)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.status() == OrderStatus::Completed);
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.quantity() == dec!(10.1).into());
+    assert!(trade.notional() == 101.into());
+}
+
+#[test]
+fn whole_lots_only() {
+    let mut exchange = orderbook(&[(10, 20)]).with_lot_size(1.into());
+
+    // The funds left after 10 lots cannot afford the 11th, so they are left
+    // unspent, and returned as the order expires.
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market_funds(101)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.status() == OrderStatus::Expired);
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.quantity() == 10.into());
+    assert!(trade.notional() == 100.into());
+
+    let_assert!(Some(top_order) = exchange.peek(&OrderSide::Ask));
+    assert!(top_order.remaining() == 10.into());
+}
+
+#[test]
+fn pro_rata_whole_lots_only() {
+    let mut exchange = orderbook(&[(10, 20)])
+        .with_lot_size(1.into())
+        .with_algo::<ProRataAlgo>();
+
+    // Just like in time priority, the funds that cannot afford another lot
+    // are left unspent rather than split.
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market_funds(101)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.status() == OrderStatus::Expired);
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.quantity() == 10.into());
+    assert!(trade.notional() == 100.into());
+}
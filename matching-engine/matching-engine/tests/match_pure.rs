@@ -0,0 +1,212 @@
+//! Property tests of the invariants [`match_pure`] must hold whatever it is
+//! fed.
+//!
+//! Random sequences of orders are matched one after the other, each against
+//! the book the previous one left. After every step, the book must not be
+//! crossed, and quantity must be conserved: whatever the opposite side lost
+//! was traded, and the incoming order rests either all it did not trade or
+//! nothing at all.
+//!
+//! The same invariants are checked by the `match_pure` fuzz target, see
+//! `fuzz/`.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::ExchangeExt;
+use exchange_core::Opposite;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::Quantity;
+use matching_engine_algo::match_pure;
+use matching_engine_algo::BookState;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::OrderbookSnapshot;
+use matching_engine_algo::SnapshotError;
+use proptest::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+enum Kind {
+    Limit,
+    PostOnly,
+    ImmediateOrCancel,
+    Iceberg(u32),
+    Market,
+    AllOrNone,
+    Participation(u8),
+    MinCounterparty(u32),
+}
+
+#[derive(Clone, Debug)]
+struct Op {
+    side: OrderSide,
+    kind: Kind,
+    limit_price: u32,
+    quantity: u32,
+}
+
+impl Op {
+    fn order(&self, id: u128) -> Order {
+        let builder =
+            Order::builder().side(self.side).id(OrderId::from_u128(id));
+        let limit = builder.limit(self.limit_price, self.quantity);
+
+        match self.kind {
+            Kind::Limit => limit.build(),
+            Kind::PostOnly => limit.post_only().build(),
+            Kind::ImmediateOrCancel => limit.ioc().build(),
+            Kind::Iceberg(display_quantity) => {
+                limit.iceberg(display_quantity.min(self.quantity)).build()
+            }
+            Kind::Market => builder.market(self.quantity).build(),
+            Kind::AllOrNone => limit.all_or_none().build(),
+            Kind::Participation(max_participation) => {
+                limit.build().with_max_participation(max_participation)
+            }
+            Kind::MinCounterparty(quantity) => limit
+                .build()
+                .with_min_counterparty_qty(Quantity::from(quantity)),
+        }
+    }
+}
+
+fn side() -> impl Strategy<Value = OrderSide> {
+    prop_oneof![Just(OrderSide::Ask), Just(OrderSide::Bid)]
+}
+
+fn kind() -> impl Strategy<Value = Kind> {
+    prop_oneof![
+        6 => Just(Kind::Limit),
+        1 => Just(Kind::PostOnly),
+        1 => Just(Kind::ImmediateOrCancel),
+        1 => (1..=10_u32).prop_map(Kind::Iceberg),
+        2 => Just(Kind::Market),
+        1 => Just(Kind::AllOrNone),
+        1 => (1..=100_u8).prop_map(Kind::Participation),
+        1 => (1..=20_u32).prop_map(Kind::MinCounterparty),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    (side(), kind(), 95..=105_u32, 1..=50_u32).prop_map(
+        |(side, kind, limit_price, quantity)| Op {
+            side,
+            kind,
+            limit_price,
+            quantity,
+        },
+    )
+}
+
+/// Returns the quantity left of every order resting on the given side,
+/// hidden reserves included.
+fn resting(book: &BookState, side: OrderSide) -> Quantity {
+    book.orders()
+        .iter()
+        .filter(|order| order.side() == side)
+        .fold(Quantity::default(), |acc, order| acc + order.remaining())
+}
+
+fn run(ops: Vec<Op>) {
+    let mut book = BookState::default();
+
+    for (id, op) in ops.iter().enumerate() {
+        let quantity = Quantity::from(op.quantity);
+        let opposite = op.side.opposite();
+        let same_before = resting(&book, op.side);
+        let opposite_before = resting(&book, opposite);
+
+        let_assert!(
+            Ok((after, trades)) = match_pure(book, op.order(id as u128))
+        );
+
+        let traded = trades.iter().fold(Quantity::default(), |acc, trade| {
+            assert!(trade.taker() == OrderId::from_u128(id as u128));
+            if !matches!(op.kind, Kind::Market) {
+                match op.side {
+                    OrderSide::Ask => {
+                        assert!(trade.price() >= op.limit_price.into())
+                    }
+                    OrderSide::Bid => {
+                        assert!(trade.price() <= op.limit_price.into())
+                    }
+                }
+            }
+
+            acc + trade.quantity()
+        });
+        assert!(traded <= quantity);
+
+        // Whatever the opposite side lost was traded, and the incoming order
+        // rests either all it did not trade or, if cancelled, nothing at all.
+        let same_after = resting(&after, op.side);
+        assert!(opposite_before - resting(&after, opposite) == traded);
+        assert!(same_after >= same_before);
+        let rested = same_after - same_before;
+        assert!(rested == Quantity::default() || rested + traded == quantity);
+        match op.kind {
+            Kind::ImmediateOrCancel | Kind::Market => {
+                assert!(rested == Quantity::default())
+            }
+            Kind::AllOrNone => {
+                assert!(traded == Quantity::default() || traded == quantity)
+            }
+            _ => {}
+        }
+
+        // The book it leaves can be rebuilt, and is never crossed.
+        let_assert!(Ok(orderbook) = Orderbook::from_snapshot(after.clone()));
+        if let (Some(bid), Some(ask)) =
+            (orderbook.best_bid(), orderbook.best_ask())
+        {
+            assert!(bid < ask);
+        }
+
+        book = after;
+    }
+}
+
+proptest! {
+    #[test]
+    fn invariants(ops in prop::collection::vec(op(), 1..200)) {
+        run(ops);
+    }
+}
+
+#[test]
+fn empty_book() {
+    let order = Order::builder()
+        .side(OrderSide::Bid)
+        .id(OrderId::from_u128(1))
+        .limit(100, 10)
+        .build();
+
+    let_assert!(Ok((book, trades)) = match_pure(BookState::default(), order));
+    assert!(trades.is_empty());
+    let_assert!([resting] = book.orders());
+    assert!(resting.remaining() == Quantity::from(10));
+}
+
+#[test]
+fn invalid_state() {
+    let order = |id| {
+        Order::builder()
+            .side(OrderSide::Bid)
+            .id(OrderId::from_u128(id))
+            .limit(100, 10)
+            .build()
+    };
+
+    let_assert!(Ok((book, _)) = match_pure(BookState::default(), order(1)));
+    let mut value = serde_json::to_value(&book).unwrap();
+    let orders = value["orders"].as_array_mut().unwrap();
+    orders.push(orders[0].clone());
+    let_assert!(Ok(book) = serde_json::from_value::<OrderbookSnapshot>(value));
+
+    // A state no book could be in is turned down rather than matched.
+    let_assert!(
+        Err(SnapshotError::Duplicate { order_id }) = match_pure(book, order(2))
+    );
+    assert!(order_id == OrderId::from_u128(1));
+}
@@ -0,0 +1,142 @@
+//! Market orders may cap how far from the best opposite price, as it stands
+//! when they arrive, they are willing to trade. Rather than sweeping a thin
+//! book at whatever price is left, they stop at the cap and whatever is left
+//! of them is cancelled.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::Price;
+use exchange_types::Quantity;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use tap::Tap;
+
+/// A book with a gap on each side, past two close levels.
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price, quantity) in [
+            (OrderSide::Ask, 100, 10),
+            (OrderSide::Ask, 101, 10),
+            (OrderSide::Ask, 150, 100),
+            (OrderSide::Bid, 99, 10),
+            (OrderSide::Bid, 98, 10),
+            (OrderSide::Bid, 50, 100),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn stops_at_the_gap() {
+    let mut exchange = orderbook();
+
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market(50)
+        .max_slippage(5)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let trades = report
+        .trades()
+        .iter()
+        .map(|trade| (trade.price(), trade.quantity()))
+        .collect::<Vec<(Price, Quantity)>>();
+
+    assert!(trades == [(100.into(), 10.into()), (101.into(), 10.into())]);
+    assert!(report.status() == OrderStatus::Closed);
+
+    // The order at the far side of the gap is left untouched.
+    assert!(exchange.best_ask() == Some(150.into()));
+    assert!(exchange.volume().0 == 100.into());
+}
+
+#[test]
+fn sells_too() {
+    let mut exchange = orderbook();
+
+    let market_order = Order::builder()
+        .side(OrderSide::Ask)
+        .market(50)
+        .max_slippage(1)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().len() == 2);
+    assert!(report.status() == OrderStatus::Closed);
+    assert!(exchange.best_bid() == Some(50.into()));
+}
+
+#[test]
+fn uncapped() {
+    let mut exchange = orderbook();
+
+    // Without a cap, the very same order fills at the far side of the gap.
+    let market_order = Order::builder().side(OrderSide::Bid).market(50).build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let_assert!(Some(trade) = report.trades().last());
+    assert!(trade.price() == 150.into());
+    assert!(report.status() == OrderStatus::Completed);
+}
+
+#[test]
+fn nothing_within_the_cap() {
+    let mut exchange = orderbook();
+
+    // The cap is off the best price, so the order can always trade there.
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market(5)
+        .max_slippage(0)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().len() == 1);
+    assert!(report.status() == OrderStatus::Completed);
+}
+
+#[test]
+fn all_or_none() {
+    let mut exchange = orderbook();
+
+    // The book holds enough for the whole order, just not within the cap.
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market(50)
+        .all_or_none()
+        .max_slippage(5)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Cancelled);
+    assert!(exchange.volume().0 == 120.into());
+}
+
+#[test]
+fn pro_rata() {
+    let mut exchange = orderbook().with_algo::<ProRataAlgo>();
+
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market(50)
+        .max_slippage(5)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().len() == 2);
+    assert!(report.status() == OrderStatus::Closed);
+    assert!(exchange.best_ask() == Some(150.into()));
+}
@@ -0,0 +1,158 @@
+//! A resting order may require a minimum counterparty quantity, so that it
+//! is not picked off by tiny orders: incoming orders with less than that left
+//! pass it over, trading with the next resting order instead, while it keeps
+//! its priority for those large enough. Whatever is left of them is
+//! cancelled rather than left to rest crossing it.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::Quantity;
+use matching_engine_algo::MatchingStrategy;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use matching_engine_algo::RejectReason;
+
+const PROTECTED: OrderId = OrderId::from_u128(1);
+const PLAIN: OrderId = OrderId::from_u128(2);
+
+/// Rests an ask that only trades with orders of at least 5, ahead of a
+/// plain one priced at `limit_price`.
+fn orderbook<A: MatchingStrategy>(
+    mut exchange: Orderbook<A>,
+    limit_price: u32,
+) -> Orderbook<A> {
+    let protected = Order::builder()
+        .side(OrderSide::Ask)
+        .id(PROTECTED)
+        .limit(100, 10)
+        .build()
+        .with_min_counterparty_qty(Quantity::from(5));
+    assert!(exchange.matching(protected).is_ok());
+
+    let plain = Order::builder()
+        .side(OrderSide::Ask)
+        .id(PLAIN)
+        .limit(limit_price, 10)
+        .build();
+    assert!(exchange.matching(plain).is_ok());
+
+    exchange
+}
+
+fn bid(limit_price: u32, quantity: u32) -> Order {
+    Order::builder()
+        .side(OrderSide::Bid)
+        .limit(limit_price, quantity)
+        .build()
+}
+
+#[test]
+fn too_small_passes_over() {
+    let mut exchange = orderbook(Orderbook::new(), 101);
+
+    let_assert!(Ok(report) = exchange.matching(bid(101, 3)));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.maker() == PLAIN);
+    assert!(trade.price() == 101.into());
+
+    let_assert!(Some(order) = exchange.get(&PROTECTED));
+    assert!(order.remaining() == Quantity::from(10));
+}
+
+#[test]
+fn cancelled_rather_than_crossing() {
+    let mut exchange = orderbook(Orderbook::new(), 101);
+
+    // The only ask at its price is the protected one, which it is too small
+    // to trade with, so it could not rest without crossing it.
+    let_assert!(Ok(report) = exchange.matching(bid(100, 3)));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Cancelled);
+    assert!(report.rejected_reason() == Some(RejectReason::Ineligible));
+    assert!(exchange.best_bid() < exchange.best_ask());
+
+    let_assert!(Some(order) = exchange.get(&PROTECTED));
+    assert!(order.remaining() == Quantity::from(10));
+}
+
+#[test]
+fn large_enough_fills() {
+    let mut exchange = orderbook(Orderbook::new(), 101);
+
+    let_assert!(Ok(report) = exchange.matching(bid(100, 5)));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.maker() == PROTECTED);
+    assert!(trade.quantity() == 5.into());
+}
+
+#[test]
+fn keeps_priority() {
+    let mut exchange = orderbook(Orderbook::new(), 100);
+
+    // Passed over at its very price, it is still first for larger orders.
+    let_assert!(Ok(report) = exchange.matching(bid(100, 3)));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.maker() == PLAIN);
+
+    let_assert!(Ok(report) = exchange.matching(bid(100, 12)));
+    let_assert!([first, second] = report.trades().as_slice());
+    assert!((first.maker(), first.quantity()) == (PROTECTED, 10.into()));
+    assert!((second.maker(), second.quantity()) == (PLAIN, 2.into()));
+}
+
+#[test]
+fn checked_against_what_is_left() {
+    let mut exchange = orderbook(Orderbook::new(), 100);
+
+    let protected = Order::builder()
+        .side(OrderSide::Ask)
+        .id(OrderId::from_u128(3))
+        .limit(100, 10)
+        .build()
+        .with_min_counterparty_qty(Quantity::from(5));
+    assert!(exchange.matching(protected).is_ok());
+
+    // Once the first two asks took 14 of it, only 2 is left, too little for
+    // the last one.
+    let_assert!(Ok(report) = exchange.matching(bid(100, 16)));
+    assert!(report.trades().len() == 2);
+
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(3)));
+    assert!(order.remaining() == Quantity::from(10));
+}
+
+#[test]
+fn not_counted_by_fill_or_kill() {
+    let mut exchange = orderbook(Orderbook::new(), 101);
+
+    let fill_or_kill = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(100, 3)
+        .ioc()
+        .all_or_none()
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(fill_or_kill));
+    assert!(report.trades().is_empty());
+    assert!(report.rejected_reason() == Some(RejectReason::FillOrKill));
+}
+
+#[test]
+fn no_share_of_pro_rata_level() {
+    let mut exchange =
+        orderbook(Orderbook::new().with_algo::<ProRataAlgo>(), 100);
+
+    let_assert!(Ok(report) = exchange.matching(bid(100, 4)));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!((trade.maker(), trade.quantity()) == (PLAIN, 4.into()));
+
+    // Large enough, it is split across both.
+    let_assert!(Ok(report) = exchange.matching(bid(100, 8)));
+    assert!(report.trades().len() == 2);
+}
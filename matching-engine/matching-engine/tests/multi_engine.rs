@@ -0,0 +1,216 @@
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::ExchangeExt;
+use exchange_types::AssetPair;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use matching_engine_algo::AmendError;
+use matching_engine_algo::IncrementError;
+use matching_engine_algo::InsertError;
+use matching_engine_rt::ConfigError;
+use matching_engine_rt::EngineError;
+use matching_engine_rt::MultiEngine;
+use matching_engine_rt::SymbolConfigs;
+use uuid::Uuid;
+
+fn create(
+    order_id: u128,
+    symbol: &AssetPair,
+    side: OrderSide,
+    limit_price: u32,
+    amount: u32,
+) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: amount.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: symbol.clone(),
+        limit_price: limit_price.into(),
+        side,
+    }
+}
+
+#[test]
+fn route_by_symbol() {
+    let btc = AssetPair::new("BTC", "USDC");
+    let eth = AssetPair::new("ETH", "USDC");
+    let mut engine = MultiEngine::new();
+
+    for request in [
+        create(1, &btc, OrderSide::Ask, 100, 10),
+        create(2, &eth, OrderSide::Bid, 100, 10),
+        create(3, &eth, OrderSide::Bid, 99, 10),
+    ] {
+        let_assert!(Ok(report) = engine.process(request));
+        assert!(report.trades.is_empty());
+    }
+
+    assert!(engine.engines().count() == 2);
+    let_assert!(Some(btc_engine) = engine.engine(&btc));
+    assert!(btc_engine.orderbook().len() == (1, 0));
+    let_assert!(Some(eth_engine) = engine.engine(&eth));
+    assert!(eth_engine.orderbook().len() == (0, 2));
+
+    // The very same price does not cross another symbol's book.
+    let_assert!(
+        Ok(report) = engine.process(create(4, &eth, OrderSide::Ask, 100, 5))
+    );
+    assert!(report.trades.len() == 1);
+    assert!(engine.engine(&btc).unwrap().orderbook().len() == (1, 0));
+}
+
+#[test]
+fn route_deletes() {
+    let btc = AssetPair::new("BTC", "USDC");
+    let eth = AssetPair::new("ETH", "USDC");
+    let mut engine = MultiEngine::new();
+
+    for request in [
+        create(1, &btc, OrderSide::Ask, 100, 10),
+        create(2, &eth, OrderSide::Ask, 100, 10),
+        OrderRequest::Delete {
+            order_id: Uuid::from_u128(2),
+        },
+    ] {
+        assert!(engine.process(request).is_ok());
+    }
+
+    // Unknown orders cannot be routed anywhere.
+    let_assert!(
+        Err(EngineError::OrderNotFound { .. }) =
+            engine.process(OrderRequest::Delete {
+                order_id: Uuid::from_u128(3),
+            })
+    );
+
+    assert!(engine.engine(&btc).unwrap().orderbook().len() == (1, 0));
+    assert!(engine.engine(&eth).unwrap().orderbook().is_empty());
+}
+
+#[test]
+fn forget_filled_orders() {
+    let btc = AssetPair::new("BTC", "USDC");
+    let mut engine = MultiEngine::new();
+
+    for request in [
+        create(1, &btc, OrderSide::Ask, 100, 10),
+        create(2, &btc, OrderSide::Bid, 100, 15),
+    ] {
+        assert!(engine.process(request).is_ok());
+    }
+
+    // The filled maker is gone, so it can no longer be amended.
+    let_assert!(
+        Err(EngineError::AmendError(AmendError::NotFound)) =
+            engine.process(OrderRequest::Amend {
+                order_id: Uuid::from_u128(1),
+                new_price: 90.into(),
+                new_quantity: 20.into(),
+            })
+    );
+
+    // Whereas the taker rests and can still be amended.
+    let_assert!(
+        Ok(report) = engine.process(OrderRequest::Amend {
+            order_id: Uuid::from_u128(2),
+            new_price: 90.into(),
+            new_quantity: 20.into(),
+        })
+    );
+    assert!(report.trades.is_empty());
+
+    let orderbook = engine.engine(&btc).unwrap().orderbook();
+    assert!(orderbook.len() == (0, 1));
+    assert!(orderbook.best_bid() == Some(90.into()));
+}
+
+#[test]
+fn reject_duplicates_across_symbols() {
+    let btc = AssetPair::new("BTC", "USDC");
+    let eth = AssetPair::new("ETH", "USDC");
+    let mut engine = MultiEngine::new();
+
+    assert!(engine
+        .process(create(1, &btc, OrderSide::Ask, 100, 10))
+        .is_ok());
+
+    // Another book would take it, but later requests could no longer tell
+    // which one they are meant for.
+    let_assert!(
+        Err(EngineError::InsertError(InsertError::Duplicate { .. })) =
+            engine.process(create(1, &eth, OrderSide::Ask, 100, 10))
+    );
+    assert!(engine.engine(&eth).is_none());
+    assert!(engine.engine(&btc).unwrap().orderbook().len() == (1, 0));
+}
+
+/// Loads the configuration of every symbol from a file holding `json`.
+fn load(json: &str) -> Result<SymbolConfigs, ConfigError> {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("symbols.json");
+    std::fs::write(&path, json).unwrap();
+
+    SymbolConfigs::from_path(path)
+}
+
+#[test]
+fn validate_per_symbol() {
+    let btc = AssetPair::new("BTC", "USDC");
+    let eth = AssetPair::new("ETH", "USDC");
+    let sol = AssetPair::new("SOL", "USDC");
+
+    let_assert!(
+        Ok(configs) = load(
+            r#"{
+                "BTC/USDC": { "tick_size": 5, "lot_size": 2 },
+                "ETH/USDC": { "tick_size": 1, "lot_size": 5 }
+            }"#
+        )
+    );
+    let mut engine = MultiEngine::new().with_configs(configs);
+
+    let_assert!(
+        Err(EngineError::IncrementError(IncrementError::Tick {
+            tick_size,
+            ..
+        })) = engine.process(create(1, &btc, OrderSide::Ask, 101, 10))
+    );
+    assert!(tick_size == 5.into());
+    let_assert!(
+        Err(EngineError::IncrementError(IncrementError::Lot {
+            lot_size,
+            ..
+        })) = engine.process(create(2, &eth, OrderSide::Ask, 101, 4))
+    );
+    assert!(lot_size == 5.into());
+
+    // The very same order is fine by the other symbol.
+    assert!(engine
+        .process(create(3, &eth, OrderSide::Ask, 101, 10))
+        .is_ok());
+    assert!(engine
+        .process(create(4, &btc, OrderSide::Ask, 100, 4))
+        .is_ok());
+
+    // A symbol left out of the file takes any price and quantity.
+    assert!(engine
+        .process(create(5, &sol, OrderSide::Ask, 101, 3))
+        .is_ok());
+    let_assert!(Some(sol_engine) = engine.engine(&sol));
+    assert!(sol_engine.orderbook().tick_size().is_none());
+    assert!(sol_engine.orderbook().lot_size().is_none());
+}
+
+#[test]
+fn reject_non_positive_sizes() {
+    let_assert!(
+        Err(ConfigError::NotPositive { symbol, field }) =
+            load(r#"{ "BTC/USDC": { "tick_size": 5, "lot_size": 0 } }"#)
+    );
+    assert!(symbol == AssetPair::new("BTC", "USDC"));
+    assert!(field == "lot_size");
+
+    let_assert!(
+        Err(ConfigError::Parse(_)) = load(r#"{ "BTC/USDC": { "tick": 5 } }"#)
+    );
+}
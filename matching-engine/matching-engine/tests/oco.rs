@@ -0,0 +1,306 @@
+//! One-cancels-other pairs: once either order of a pair trades enough, the
+//! other one is cancelled, before anything else may ever trade with it.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::AssetPair;
+use exchange_types::BookEvent;
+use exchange_types::OcoLeg;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use exchange_types::Quantity;
+use matching_engine_algo::AmendError;
+use matching_engine_algo::LinkError;
+use matching_engine_algo::MatchingStrategy;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use matching_engine_rt::Engine;
+use tap::Tap;
+use uuid::Uuid;
+
+fn order_id(id: u128) -> OrderId {
+    OrderId::from_u128(id)
+}
+
+fn rest<A: MatchingStrategy>(
+    exchange: &mut Orderbook<A>,
+    id: u128,
+    limit_price: u32,
+) {
+    let limit_order = Order::builder()
+        .side(OrderSide::Ask)
+        .id(order_id(id))
+        .limit(limit_price, 10)
+        .build();
+
+    assert!(exchange.matching(limit_order).is_ok());
+}
+
+#[test]
+fn filling_one_cancels_the_other() {
+    let mut exchange = Orderbook::new().tap_mut(|exchange| {
+        rest(exchange, 1, 100);
+        rest(exchange, 2, 105);
+    });
+    assert!(let Ok(()) = exchange.link(&order_id(1), &order_id(2), None));
+    assert!(exchange.linked(&order_id(2)) == Some(order_id(1)));
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(1).build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().len() == 1);
+    assert!(report.cancelled() == [order_id(2)]);
+    assert!(
+        report.events()
+            == [
+                BookEvent::OrderReduced {
+                    order_id: order_id(1),
+                    delta: 1.into(),
+                },
+                BookEvent::OrderRemoved {
+                    order_id: order_id(2),
+                },
+            ]
+    );
+
+    assert!(exchange.get(&order_id(2)).is_none());
+    assert!(exchange.linked(&order_id(1)).is_none());
+}
+
+#[test]
+fn only_one_survives_the_same_pass() {
+    let mut exchange = Orderbook::new().tap_mut(|exchange| {
+        rest(exchange, 1, 100);
+        rest(exchange, 2, 101);
+        rest(exchange, 3, 102);
+    });
+    assert!(let Ok(()) = exchange.link(&order_id(1), &order_id(2), None));
+
+    // The incoming order would sweep both, though the second one is gone
+    // as soon as the first one trades, so the sweep goes on past it.
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .id(order_id(4))
+        .limit(102, 15)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(
+        report
+            .trades()
+            .iter()
+            .map(|trade| (trade.maker(), trade.quantity()))
+            .collect::<Vec<_>>()
+            == [(order_id(1), 10.into()), (order_id(3), 5.into())]
+    );
+    assert!(report.cancelled() == [order_id(2)]);
+    assert!(exchange.len() == (1, 0));
+}
+
+#[test]
+fn cancel_after_some_quantity() {
+    let mut exchange = Orderbook::new().tap_mut(|exchange| {
+        rest(exchange, 1, 100);
+        rest(exchange, 2, 105);
+    });
+    let cancel_after = Some(Quantity::from(5));
+    assert!(let Ok(()) = exchange.link(&order_id(1), &order_id(2), cancel_after));
+
+    for (quantity, cancelled) in [(3, &[][..]), (2, &[order_id(2)][..])] {
+        let market_order = Order::builder()
+            .side(OrderSide::Bid)
+            .market(quantity)
+            .build();
+
+        let_assert!(Ok(report) = exchange.matching(market_order));
+        assert!(report.cancelled() == cancelled);
+    }
+
+    assert!(exchange.len() == (1, 0));
+}
+
+#[test]
+fn removing_one_unlinks_the_other() {
+    let mut exchange = Orderbook::new().tap_mut(|exchange| {
+        rest(exchange, 1, 100);
+        rest(exchange, 2, 105);
+    });
+    assert!(let Ok(()) = exchange.link(&order_id(1), &order_id(2), None));
+
+    assert!(exchange.remove(&order_id(1)).is_some());
+    assert!(exchange.linked(&order_id(2)).is_none());
+
+    // The other one is left resting on its own.
+    let market_order = Order::builder().side(OrderSide::Bid).market(1).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.cancelled().is_empty());
+    assert!(exchange.len() == (1, 0));
+}
+
+#[test]
+fn amending_keeps_the_link() {
+    let mut exchange = Orderbook::new().tap_mut(|exchange| {
+        rest(exchange, 1, 100);
+        rest(exchange, 2, 105);
+    });
+    assert!(let Ok(()) = exchange.link(&order_id(1), &order_id(2), None));
+
+    // Repricing would take the order off the book, and so unlink it.
+    let_assert!(
+        Err(AmendError::Linked) =
+            exchange.amend(&order_id(1), 101.into(), 10.into())
+    );
+    let_assert!(Some(order) = exchange.get(&order_id(1)));
+    assert!(order.limit_price() == Some(100.into()));
+
+    let_assert!(
+        Ok(report) = exchange.amend(&order_id(1), 100.into(), 20.into())
+    );
+    assert!(report.trades().is_empty());
+    assert!(exchange.linked(&order_id(1)) == Some(order_id(2)));
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(1).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.trades().len() == 1);
+    assert!(report.cancelled() == [order_id(2)]);
+    assert!(exchange.len() == (1, 0));
+}
+
+#[test]
+fn reject_invalid_links() {
+    let mut exchange = Orderbook::new().tap_mut(|exchange| {
+        rest(exchange, 1, 100);
+        rest(exchange, 2, 105);
+        rest(exchange, 3, 110);
+    });
+
+    assert!(
+        exchange.link(&order_id(1), &order_id(1), None)
+            == Err(LinkError::SameOrder {
+                order_id: order_id(1)
+            })
+    );
+    assert!(
+        exchange.link(&order_id(1), &order_id(4), None)
+            == Err(LinkError::NotFound {
+                order_id: order_id(4)
+            })
+    );
+
+    assert!(let Ok(()) = exchange.link(&order_id(1), &order_id(2), None));
+    assert!(
+        exchange.link(&order_id(3), &order_id(2), None)
+            == Err(LinkError::AlreadyLinked {
+                order_id: order_id(2)
+            })
+    );
+}
+
+#[test]
+fn pro_rata() {
+    let mut exchange =
+        Orderbook::new()
+            .with_algo::<ProRataAlgo>()
+            .tap_mut(|exchange| {
+                rest(exchange, 1, 100);
+                rest(exchange, 2, 100);
+                rest(exchange, 3, 100);
+            });
+    assert!(let Ok(()) = exchange.link(&order_id(1), &order_id(2), None));
+
+    // The second order was allocated its share, though it is cancelled
+    // before it gets it, so the rest of the level makes up for it.
+    let market_order = Order::builder().side(OrderSide::Bid).market(15).build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    assert!(report.cancelled() == [order_id(2)]);
+    assert!(report
+        .trades()
+        .iter()
+        .all(|trade| trade.maker() != order_id(2)));
+    assert!(
+        report
+            .trades()
+            .iter()
+            .fold(Quantity::default(), |acc, trade| acc + trade.quantity())
+            == 15.into()
+    );
+    assert!(exchange.volume() == (5.into(), 0.into()));
+}
+
+fn leg(id: u128, side: OrderSide, limit_price: u32) -> OcoLeg {
+    OcoLeg {
+        order_id: Uuid::from_u128(id),
+        side,
+        limit_price: limit_price.into(),
+        amount: 10.into(),
+    }
+}
+
+fn create_oco(first: OcoLeg, second: OcoLeg) -> OrderRequest {
+    OrderRequest::CreateOco {
+        account_id: Uuid::from_u128(1),
+        symbol: AssetPair::new("BTC", "USDC"),
+        legs: [first, second],
+        cancel_after: None,
+    }
+}
+
+fn create(id: u128, side: OrderSide, limit_price: u32) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(2),
+        amount: 10.into(),
+        order_id: Uuid::from_u128(id),
+        symbol: AssetPair::new("BTC", "USDC"),
+        limit_price: limit_price.into(),
+        side,
+    }
+}
+
+#[test]
+fn engine_links_both_legs() {
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"));
+
+    let request =
+        create_oco(leg(1, OrderSide::Ask, 110), leg(2, OrderSide::Bid, 90));
+    let_assert!(Ok(report) = engine.process(request));
+    assert!(report.trades.is_empty());
+    assert!(report.cancelled.is_empty());
+    assert!(engine.orderbook().linked(&order_id(1)) == Some(order_id(2)));
+
+    let_assert!(Ok(report) = engine.process(create(3, OrderSide::Bid, 110)));
+    assert!(report.trades.len() == 1);
+    assert!(report.cancelled == [order_id(2)]);
+    assert!(engine.orderbook().get(&order_id(2)).is_none());
+    assert!(engine.orderbook().len() == (0, 0));
+}
+
+#[test]
+fn engine_legs_trading_on_entry() {
+    // The first leg fills right away, so the second one never rests.
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"));
+    assert!(let Ok(_) = engine.process(create(1, OrderSide::Ask, 100)));
+
+    let request =
+        create_oco(leg(2, OrderSide::Bid, 100), leg(3, OrderSide::Bid, 90));
+    let_assert!(Ok(report) = engine.process(request));
+    assert!(report.trades.len() == 1);
+    assert!(report.cancelled.is_empty());
+    assert!(engine.orderbook().is_empty());
+
+    // The second leg fills right away, so the first one is cancelled.
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"));
+    assert!(let Ok(_) = engine.process(create(1, OrderSide::Ask, 100)));
+
+    let request =
+        create_oco(leg(2, OrderSide::Bid, 90), leg(3, OrderSide::Bid, 100));
+    let_assert!(Ok(report) = engine.process(request));
+    assert!(report.trades.len() == 1);
+    assert!(report.cancelled == [order_id(2)]);
+    assert!(engine.orderbook().is_empty());
+}
@@ -0,0 +1,170 @@
+//! An order may cap its participation, i.e. take at most a given share of
+//! the liquidity it could trade with right away as it comes in, so that a
+//! large order trades gradually. Whatever it may not take is cancelled, as
+//! it would cross the orders it was not allowed to take.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use matching_engine_algo::RejectReason;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for limit_price in [100, 101, 102] {
+            let limit_order = Order::builder()
+                .side(OrderSide::Ask)
+                .limit(limit_price, 10)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn consumes_half_cancelling_the_rest() {
+    let mut exchange = orderbook();
+
+    // It could trade with the 20 asked up to its price, so it takes 10.
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .id(OrderId::from_u128(1))
+        .limit(101, 40)
+        .build()
+        .with_max_participation(50);
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.quantity() == 10.into());
+    assert!(trade.price() == 100.into());
+    assert!(report.status() == OrderStatus::Closed);
+    assert!(report.rejected_reason() == Some(RejectReason::MaxParticipation));
+
+    // The rest of it would cross the ask it was not allowed to take, so it
+    // does not rest, and the book is left uncrossed.
+    assert!(exchange.get(&OrderId::from_u128(1)).is_none());
+    assert!(exchange.best_bid() == None);
+    assert!(exchange.best_ask() == Some(101.into()));
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 101,
+                remaining: 10,
+                status: Open,
+            },
+            Order {
+                limit_price: 102,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+fn rests_if_nothing_to_take() {
+    let mut exchange = orderbook();
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .id(OrderId::from_u128(1))
+        .limit(99, 40)
+        .build()
+        .with_max_participation(50);
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Open);
+    assert!(report.rejected_reason() == None);
+    assert!(exchange.best_bid() < exchange.best_ask());
+}
+
+#[test]
+fn across_levels() {
+    let mut exchange = orderbook();
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(102, 40)
+        .build()
+        .with_max_participation(50);
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    let_assert!([first, second] = report.trades().as_slice());
+    assert!((first.price(), first.quantity()) == (100.into(), 10.into()));
+    assert!((second.price(), second.quantity()) == (101.into(), 5.into()));
+}
+
+#[test]
+fn market_order_cancels_the_rest() {
+    let mut exchange = orderbook();
+
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market(30)
+        .build()
+        .with_max_participation(50);
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let_assert!([first, second] = report.trades().as_slice());
+    assert!((first.price(), first.quantity()) == (100.into(), 10.into()));
+    assert!((second.price(), second.quantity()) == (101.into(), 5.into()));
+    assert!(exchange.len() == (2, 0));
+}
+
+#[test]
+fn fill_or_kill_not_capped() {
+    let mut exchange = orderbook();
+
+    let fill_or_kill = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(101, 20)
+        .ioc()
+        .all_or_none()
+        .build()
+        .with_max_participation(50);
+
+    let_assert!(Ok(report) = exchange.matching(fill_or_kill));
+    assert!(report.trades().len() == 2);
+    assert!(report.status() == OrderStatus::Completed);
+}
+
+#[test]
+fn pro_rata() {
+    let mut exchange = Orderbook::new().with_algo::<ProRataAlgo>();
+    for quantity in [10, 30] {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .limit(100, quantity)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+
+    // Half of the level is split across it, just as if it were all the
+    // order wanted.
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(100, 40)
+        .build()
+        .with_max_participation(50);
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    let_assert!([first, second] = report.trades().as_slice());
+    assert!(first.quantity() == 5.into());
+    assert!(second.quantity() == 15.into());
+    assert!(report.status() == OrderStatus::Closed);
+    assert!(exchange.best_bid() < exchange.best_ask());
+}
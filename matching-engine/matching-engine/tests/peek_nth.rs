@@ -0,0 +1,74 @@
+use assert2::assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price, quantity) in [
+            (OrderSide::Ask, 101, 10),
+            (OrderSide::Ask, 100, 20),
+            (OrderSide::Ask, 101, 30),
+            (OrderSide::Ask, 102, 40),
+            (OrderSide::Bid, 98, 10),
+            (OrderSide::Bid, 99, 20),
+            (OrderSide::Bid, 99, 30),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn price_time_priority() {
+    let exchange = orderbook();
+
+    let remaining = |side, n| {
+        exchange
+            .peek_nth(&side, n)
+            .map(|order| order.displayed().to_string())
+    };
+
+    assert!(remaining(OrderSide::Ask, 0).as_deref() == Some("20"));
+    assert!(remaining(OrderSide::Ask, 1).as_deref() == Some("10"));
+    assert!(remaining(OrderSide::Ask, 2).as_deref() == Some("30"));
+    assert!(remaining(OrderSide::Ask, 3).as_deref() == Some("40"));
+    assert!(remaining(OrderSide::Bid, 0).as_deref() == Some("20"));
+    assert!(remaining(OrderSide::Bid, 2).as_deref() == Some("10"));
+}
+
+#[test]
+fn same_as_iter() {
+    let exchange = orderbook();
+
+    for side in [OrderSide::Ask, OrderSide::Bid] {
+        for n in 0..8 {
+            assert!(
+                exchange.peek_nth(&side, n).map(|order| order.id())
+                    == exchange.iter(&side).nth(n).map(|order| order.id())
+            );
+        }
+
+        assert!(
+            exchange.peek_nth(&side, 0).map(|order| order.id())
+                == exchange.peek(&side).map(|order| order.id())
+        );
+    }
+}
+
+#[test]
+fn beyond_the_book() {
+    let exchange = orderbook();
+
+    assert!(exchange.peek_nth(&OrderSide::Ask, 4).is_none());
+    assert!(exchange.peek_nth(&OrderSide::Bid, 3).is_none());
+    assert!(Orderbook::new().peek_nth(&OrderSide::Bid, 0).is_none());
+}
@@ -0,0 +1,489 @@
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_core::Trade;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use matching_engine_algo::Checkpoint;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::PersistError;
+use matching_engine_algo::Persistence;
+use matching_engine_algo::Recovery;
+
+/// Retries for a while, since sled releases the lock on its files from a
+/// background thread once a previous handle is dropped.
+fn retry<T, E>(mut open: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+    for _ in 0..50 {
+        if let Ok(opened) = open() {
+            return Ok(opened);
+        }
+
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    open()
+}
+
+fn open(path: &Path) -> Result<Orderbook, PersistError> {
+    retry(|| Orderbook::open_persistent(path))
+}
+
+fn place(
+    exchange: &mut Orderbook,
+    side: OrderSide,
+    limit_price: u32,
+    quantity: u32,
+) {
+    let limit_order = Order::builder()
+        .side(side)
+        .limit(limit_price, quantity)
+        .build();
+
+    assert!(exchange.matching(limit_order).is_ok());
+}
+
+#[test]
+fn restore_resting_orders() {
+    let dir = tempfile::tempdir().unwrap();
+
+    {
+        let_assert!(Ok(mut exchange) = open(dir.path()));
+        place(&mut exchange, OrderSide::Ask, 100, 10);
+        place(&mut exchange, OrderSide::Ask, 100, 20);
+        place(&mut exchange, OrderSide::Ask, 101, 30);
+        place(&mut exchange, OrderSide::Bid, 99, 40);
+
+        // Fills the first ask and part of the second.
+        place(&mut exchange, OrderSide::Bid, 100, 15);
+        assert!(let Ok(()) = exchange.flush());
+    }
+
+    let_assert!(Ok(exchange) = open(dir.path()));
+    assert!(
+        exchange.recovery()
+            == Some(Recovery {
+                restored: 3,
+                discarded: 0
+            })
+    );
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 100,
+                remaining: 15,
+                status: Partial,
+            },
+            Order {
+                limit_price: 101,
+                remaining: 30,
+                status: Open,
+            },
+        ],
+        Bid: [
+            Order {
+                limit_price: 99,
+                remaining: 40,
+                status: Open,
+            },
+        ],
+    }
+    "###);
+}
+
+#[test]
+fn keep_time_priority() {
+    let dir = tempfile::tempdir().unwrap();
+
+    {
+        let_assert!(Ok(mut exchange) = open(dir.path()));
+        place(&mut exchange, OrderSide::Bid, 100, 10);
+        place(&mut exchange, OrderSide::Bid, 100, 20);
+        place(&mut exchange, OrderSide::Bid, 100, 30);
+        assert!(let Ok(()) = exchange.flush());
+    }
+
+    let_assert!(Ok(mut exchange) = open(dir.path()));
+    place(&mut exchange, OrderSide::Ask, 100, 15);
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [],
+        Bid: [
+            Order {
+                limit_price: 100,
+                remaining: 15,
+                status: Partial,
+            },
+            Order {
+                limit_price: 100,
+                remaining: 30,
+                status: Open,
+            },
+        ],
+    }
+    "###);
+}
+
+#[test]
+fn keep_time_priority_whatever_the_ids() {
+    let dir = tempfile::tempdir().unwrap();
+
+    // Queued in an order their ids would never sort in.
+    let order_ids = [3, 1, 2].map(OrderId::from_u128);
+    {
+        let_assert!(Ok(mut exchange) = open(dir.path()));
+        for order_id in order_ids {
+            let limit_order = Order::builder()
+                .side(OrderSide::Bid)
+                .id(order_id)
+                .limit(100, 10)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+        assert!(let Ok(()) = exchange.flush());
+    }
+
+    let_assert!(Ok(mut exchange) = open(dir.path()));
+    let queue = exchange
+        .iter(&OrderSide::Bid)
+        .map(|order| order.id())
+        .collect::<Vec<_>>();
+    assert!(queue == order_ids);
+
+    let market_order = Order::builder().side(OrderSide::Ask).market(30).build();
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let makers = report
+        .trades()
+        .iter()
+        .map(|trade| trade.maker())
+        .collect::<Vec<_>>();
+    assert!(makers == order_ids);
+}
+
+#[test]
+fn repair_corrupt_entries() {
+    let dir = tempfile::tempdir().unwrap();
+
+    {
+        let_assert!(Ok(mut exchange) = open(dir.path()));
+        place(&mut exchange, OrderSide::Ask, 100, 10);
+        place(&mut exchange, OrderSide::Ask, 101, 20);
+        assert!(let Ok(()) = exchange.flush());
+    }
+
+    {
+        // Simulates writes that did not complete: a truncated value and a
+        // value whose checksum no longer matches.
+        let db = retry(|| sled::open(dir.path())).unwrap();
+        let first = db.first().unwrap().unwrap();
+        db.insert(first.0, &first.1[..first.1.len() / 2]).unwrap();
+        let (key, value) = db.iter().nth(1).unwrap().unwrap();
+        let mut value = value.to_vec();
+        *value.last_mut().unwrap() ^= 0xff;
+        db.insert(key, value).unwrap();
+        db.insert(b"garbage", b"garbage").unwrap();
+        db.flush().unwrap();
+    }
+
+    let_assert!(Ok(exchange) = open(dir.path()));
+    assert!(
+        exchange.recovery()
+            == Some(Recovery {
+                restored: 0,
+                discarded: 3
+            })
+    );
+    drop(exchange);
+
+    // Repaired entries are gone for good.
+    let_assert!(Ok(exchange) = open(dir.path()));
+    assert!(exchange.recovery() == Some(Recovery::default()));
+}
+
+#[test]
+fn keep_sequences() {
+    let dir = tempfile::tempdir().unwrap();
+    let sequences = |exchange: &Orderbook| {
+        exchange
+            .iter(&OrderSide::Ask)
+            .map(|order| order.sequence())
+            .collect::<Vec<_>>()
+    };
+
+    {
+        let_assert!(Ok(mut exchange) = open(dir.path()));
+        place(&mut exchange, OrderSide::Ask, 100, 10);
+        place(&mut exchange, OrderSide::Ask, 100, 20);
+        place(&mut exchange, OrderSide::Ask, 100, 30);
+        assert!(sequences(&exchange) == [0, 1, 2]);
+
+        let_assert!(Some(order) = exchange.pop(&OrderSide::Ask));
+        assert!(order.sequence() == 0);
+        assert!(sequences(&exchange) == [1, 2]);
+
+        // The last sequence handed out is no longer in use by any order.
+        let_assert!(Some(order) = exchange.iter(&OrderSide::Ask).nth(1));
+        let order_id = order.id();
+        assert!(exchange.remove(&order_id).is_some());
+        assert!(let Ok(()) = exchange.flush());
+    }
+
+    let_assert!(Ok(mut exchange) = open(dir.path()));
+    assert!(sequences(&exchange) == [1]);
+
+    // Sequences are never handed out twice.
+    place(&mut exchange, OrderSide::Ask, 100, 40);
+    assert!(sequences(&exchange) == [1, 3]);
+}
+
+#[test]
+fn keep_trade_ids() {
+    let dir = tempfile::tempdir().unwrap();
+    let trade_id = |exchange: &mut Orderbook| {
+        let market_order =
+            Order::builder().side(OrderSide::Bid).market(5).build();
+        let_assert!(Ok(report) = exchange.matching(market_order));
+        let_assert!([trade] = report.trades().as_slice());
+
+        trade.trade_id()
+    };
+
+    {
+        let_assert!(Ok(mut exchange) = open(dir.path()));
+        place(&mut exchange, OrderSide::Ask, 100, 10);
+        assert!(trade_id(&mut exchange) == 1);
+        assert!(let Ok(()) = exchange.flush());
+    }
+
+    // No order was queued after the trade, yet its id is never handed out
+    // again, be it to a trade or to an order.
+    let_assert!(Ok(mut exchange) = open(dir.path()));
+    assert!(trade_id(&mut exchange) == 2);
+    place(&mut exchange, OrderSide::Ask, 101, 10);
+    let_assert!(Some(order) = exchange.iter(&OrderSide::Ask).last());
+    assert!(order.sequence() == 3);
+}
+
+#[test]
+fn closed_while_peeked() {
+    let dir = tempfile::tempdir().unwrap();
+
+    {
+        let_assert!(Ok(mut exchange) = open(dir.path()));
+        place(&mut exchange, OrderSide::Ask, 100, 10);
+        place(&mut exchange, OrderSide::Ask, 101, 20);
+
+        // The first ask is written through as partially filled...
+        place(&mut exchange, OrderSide::Bid, 100, 4);
+        assert!(let Ok(()) = exchange.flush());
+
+        // ...and then filled in full, just as matching does, though the
+        // process crashes before the closed order is removed from the book.
+        let mut market_order =
+            Order::builder().side(OrderSide::Bid).market(6).build();
+        {
+            let_assert!(
+                Some(mut top_order) = exchange.peek_mut(&OrderSide::Ask)
+            );
+            assert!(let Ok(_) = top_order.trade(&mut market_order));
+            assert!(top_order.is_closed());
+        }
+        assert!(let Ok(()) = exchange.flush());
+    }
+
+    // Nothing of the closed order is left behind to be repaired.
+    let_assert!(Ok(exchange) = open(dir.path()));
+    assert!(
+        exchange.recovery()
+            == Some(Recovery {
+                restored: 1,
+                discarded: 0
+            })
+    );
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 101,
+                remaining: 20,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+fn keep_links() {
+    let dir = tempfile::tempdir().unwrap();
+    let (ask, bid) = (OrderId::from_u128(1), OrderId::from_u128(2));
+
+    {
+        let_assert!(Ok(mut exchange) = open(dir.path()));
+        for (order_id, side, limit_price) in
+            [(ask, OrderSide::Ask, 101), (bid, OrderSide::Bid, 99)]
+        {
+            let limit_order = Order::builder()
+                .side(side)
+                .id(order_id)
+                .limit(limit_price, 10)
+                .build();
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+        assert!(let Ok(()) = exchange.link(&ask, &bid, None));
+        assert!(let Ok(()) = exchange.flush());
+    }
+
+    {
+        let_assert!(Ok(mut exchange) = open(dir.path()));
+        assert!(
+            exchange.recovery()
+                == Some(Recovery {
+                    restored: 2,
+                    discarded: 0
+                })
+        );
+        assert!(exchange.linked(&ask) == Some(bid));
+
+        // Filling either order still cancels the other one.
+        place(&mut exchange, OrderSide::Bid, 101, 1);
+        assert!(exchange.get(&bid).is_none());
+        assert!(let Ok(()) = exchange.flush());
+    }
+
+    // The link is gone along with the cancelled order.
+    let_assert!(Ok(exchange) = open(dir.path()));
+    assert!(
+        exchange.recovery()
+            == Some(Recovery {
+                restored: 1,
+                discarded: 0
+            })
+    );
+    assert!(exchange.linked(&ask).is_none());
+}
+
+fn replenish(persistence: Persistence) -> (Option<u64>, String) {
+    let dir = tempfile::tempdir().unwrap();
+
+    let_assert!(Ok(exchange) = open(dir.path()));
+    let mut exchange = exchange.with_persistence(persistence);
+    let iceberg_order = Order::builder()
+        .side(OrderSide::Ask)
+        .limit(100, 50)
+        .iceberg(5)
+        .build();
+    assert!(exchange.matching(iceberg_order).is_ok());
+
+    // Takes six slices, the last one only in part.
+    place(&mut exchange, OrderSide::Bid, 100, 28);
+    assert!(let Ok(()) = exchange.flush());
+
+    let writes = exchange.storage_writes();
+    drop(exchange);
+
+    let_assert!(Ok(exchange) = open(dir.path()));
+    (writes, format!("{exchange:?}"))
+}
+
+#[test]
+fn persist_resting_only() {
+    let (always, restored) = replenish(Persistence::Always);
+    let (resting, restored_resting) = replenish(Persistence::Resting);
+
+    // Placing the iceberg order takes two writes either way, the order and
+    // the next sequence. Each slice shown after a fill moves the order to the
+    // back of the level, three more writes, the partial fill is one more, and
+    // so is the next sequence after each of the six trades took its id;
+    // unless only where the order rests once matching is done is written.
+    assert!(always == Some(2 + 5 * 3 + 1 + 6));
+    assert!(resting == Some(2 + 3));
+    assert!(restored == restored_resting);
+}
+
+#[test]
+fn fully_filled_orders_are_never_written() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let_assert!(Ok(exchange) = open(dir.path()));
+    let mut exchange = exchange.with_persistence(Persistence::Resting);
+    place(&mut exchange, OrderSide::Ask, 100, 10);
+    let placed = exchange.storage_writes();
+
+    for _ in 0..10 {
+        place(&mut exchange, OrderSide::Bid, 100, 1);
+    }
+
+    // The bids fill at once, and the ask is written once per call, along
+    // with the next sequence its trade took.
+    assert!(exchange.storage_writes() == placed.map(|writes| writes + 20));
+}
+
+#[test]
+fn hold_back_until_checkpoint() {
+    let dir = tempfile::tempdir().unwrap();
+    let (ask, bid) = (OrderId::from_u128(1), OrderId::from_u128(2));
+    let link = |exchange: &mut Orderbook| {
+        for (order_id, side, limit_price) in
+            [(ask, OrderSide::Ask, 101), (bid, OrderSide::Bid, 99)]
+        {
+            let limit_order = Order::builder()
+                .side(side)
+                .id(order_id)
+                .limit(limit_price, 10)
+                .build();
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+        assert!(let Ok(()) = exchange.link(&ask, &bid, None));
+    };
+    let checkpoint = |exchange: &Orderbook| Checkpoint {
+        offset: 2,
+        state_hash: exchange.checksum(usize::MAX),
+    };
+
+    {
+        let_assert!(Ok(exchange) = open(dir.path()));
+        let mut exchange = exchange.with_persistence(Persistence::Checkpoint);
+        link(&mut exchange);
+        assert!(let Ok(()) = exchange.flush());
+        assert!(exchange.storage_writes() == Some(0));
+    }
+
+    // Flushing writes nothing held back, be it orders or links.
+    let_assert!(Ok(exchange) = open(dir.path()));
+    assert!(exchange.recovery() == Some(Recovery::default()));
+    assert!(exchange.last_checkpoint() == None);
+
+    {
+        let mut exchange = exchange.with_persistence(Persistence::Checkpoint);
+        link(&mut exchange);
+        assert!(let Ok(()) = exchange.checkpoint(checkpoint(&exchange)));
+
+        // Filling the ask cancels the bid, though only in memory.
+        place(&mut exchange, OrderSide::Bid, 101, 10);
+        assert!(exchange.is_empty());
+    }
+
+    let_assert!(Ok(exchange) = open(dir.path()));
+    assert!(
+        exchange.recovery()
+            == Some(Recovery {
+                restored: 2,
+                discarded: 0
+            })
+    );
+    assert!(exchange.last_checkpoint() == Some(checkpoint(&exchange)));
+    assert!(exchange.linked(&ask) == Some(bid));
+}
@@ -0,0 +1,134 @@
+//! A Post-Only order is an order that may only make liquidity: it rests on
+//! the book as long as it would not match any resting order, and is
+//! cancelled as a whole, before trading at all, otherwise.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price) in [(OrderSide::Ask, 101), (OrderSide::Bid, 99)]
+        {
+            let limit_order =
+                Order::builder().side(side).limit(limit_price, 10).build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn rests_inside_the_spread() {
+    let mut exchange = orderbook();
+
+    let post_only = Order::builder()
+        .side(OrderSide::Bid)
+        .id(OrderId::from_u128(1))
+        .limit(100, 10)
+        .post_only()
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(post_only));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Open);
+    assert!(exchange.get(&OrderId::from_u128(1)).is_some());
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 101,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+        Bid: [
+            Order {
+                limit_price: 100,
+                remaining: 10,
+                status: Open,
+            },
+            Order {
+                limit_price: 99,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+    }
+    "###);
+}
+
+#[test]
+fn rests_on_empty_book() {
+    let mut exchange = Orderbook::new();
+
+    let post_only = Order::builder()
+        .side(OrderSide::Ask)
+        .limit(100, 10)
+        .post_only()
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(post_only));
+    assert!(report.status() == OrderStatus::Open);
+    assert!(exchange.best_ask() == Some(100.into()));
+}
+
+#[test]
+fn cancelled_at_the_touch() {
+    let mut exchange = orderbook();
+
+    let post_only = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(101, 10)
+        .post_only()
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(post_only));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Cancelled);
+
+    // Neither side is touched.
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 101,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+        Bid: [
+            Order {
+                limit_price: 99,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+    }
+    "###);
+}
+
+#[test]
+fn cancelled_through_the_touch() {
+    let mut exchange = orderbook();
+
+    let post_only = Order::builder()
+        .side(OrderSide::Ask)
+        .limit(90, 10)
+        .post_only()
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(post_only));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Cancelled);
+    assert!(exchange.best_bid() == Some(99.into()));
+    assert!(exchange.best_ask() == Some(101.into()));
+}
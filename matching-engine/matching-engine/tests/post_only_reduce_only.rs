@@ -0,0 +1,153 @@
+//! An order that is both Post-Only and Reduce-Only is a passive quote that
+//! may only reduce the position of its account: unlike a plain reduce-only
+//! order, it rests on the book, though never more of it than the position
+//! left to reduce by the other such orders of the account.
+//!
+//! Post-only comes first: an order that would cross is rejected as a whole,
+//! whatever its size. Only an order bound to rest is then capped, or
+//! rejected as reduce-only if there is nothing left for it to reduce.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_types::AccountId;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::Quantity;
+use matching_engine_algo::AmendError;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::RejectReason;
+use tap::Tap;
+
+const ALICE: AccountId = AccountId::from_u128(1);
+
+/// A book of someone else's orders, with Alice holding 15 long.
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price) in [(OrderSide::Ask, 101), (OrderSide::Bid, 99)]
+        {
+            let limit_order =
+                Order::builder().side(side).limit(limit_price, 10).build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+
+        exchange.set_position(ALICE, OrderSide::Bid, Quantity::from(15));
+    })
+}
+
+fn quote(order_id: u128, limit_price: u32, quantity: u32) -> Order {
+    Order::builder()
+        .side(OrderSide::Ask)
+        .account_id(ALICE)
+        .id(OrderId::from_u128(order_id))
+        .limit(limit_price, quantity)
+        .post_only()
+        .reduce_only()
+        .build()
+}
+
+#[test]
+fn rests_capped_to_position() {
+    let mut exchange = orderbook();
+
+    let_assert!(Ok(report) = exchange.matching(quote(1, 102, 20)));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Open);
+    assert!(report.rejected_reason() == None);
+
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(1)));
+    assert!(order.remaining() == Quantity::from(15));
+}
+
+#[test]
+fn crossing_rejected_whatever_its_size() {
+    let mut exchange = orderbook();
+
+    // It both crosses the bid at 99 and exceeds the position, so it is
+    // rejected as post-only, before it could be capped.
+    let_assert!(Ok(report) = exchange.matching(quote(1, 99, 20)));
+    assert!(report.trades().is_empty());
+    assert!(report.status() == OrderStatus::Cancelled);
+    assert!(report.rejected_reason() == Some(RejectReason::PostOnly));
+    assert!(exchange.get(&OrderId::from_u128(1)).is_none());
+
+    // Priced not to cross, the very same order rests, capped.
+    let_assert!(Ok(report) = exchange.matching(quote(2, 100, 20)));
+    assert!(report.status() == OrderStatus::Open);
+
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(2)));
+    assert!(order.remaining() == Quantity::from(15));
+}
+
+#[test]
+fn capped_by_resting_quotes() {
+    let mut exchange = orderbook();
+
+    assert!(exchange.matching(quote(1, 102, 10)).is_ok());
+    assert!(exchange.matching(quote(2, 103, 10)).is_ok());
+
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(2)));
+    assert!(order.remaining() == Quantity::from(5));
+
+    // Once both rest, there is nothing left to reduce.
+    let_assert!(Ok(report) = exchange.matching(quote(3, 104, 10)));
+    assert!(report.status() == OrderStatus::Cancelled);
+    assert!(report.rejected_reason() == Some(RejectReason::ReduceOnly));
+}
+
+#[test]
+fn nothing_to_reduce() {
+    let mut exchange = orderbook();
+    exchange.set_position(ALICE, OrderSide::Ask, Quantity::from(15));
+
+    // Selling would only increase a short position.
+    let_assert!(Ok(report) = exchange.matching(quote(1, 102, 10)));
+    assert!(report.status() == OrderStatus::Cancelled);
+    assert!(report.rejected_reason() == Some(RejectReason::ReduceOnly));
+    assert!(exchange.get(&OrderId::from_u128(1)).is_none());
+}
+
+#[test]
+fn amend_past_position() {
+    let mut exchange = orderbook();
+
+    assert!(exchange.matching(quote(1, 102, 10)).is_ok());
+
+    let_assert!(
+        Err(AmendError::Rejected(RejectReason::ReduceOnly)) =
+            exchange.amend(&OrderId::from_u128(1), 102.into(), 20.into())
+    );
+    assert!(exchange
+        .amend(&OrderId::from_u128(1), 102.into(), 15.into())
+        .is_ok());
+}
+
+#[test]
+fn position_kept_up_to_date() {
+    let mut exchange = orderbook();
+
+    // Selling 10 to the bid at 99 leaves Alice 5 long.
+    let sell = Order::builder()
+        .side(OrderSide::Ask)
+        .account_id(ALICE)
+        .limit(99, 10)
+        .build();
+    let_assert!(Ok(report) = exchange.matching(sell));
+    assert!(report.trades().len() == 1);
+    assert!(exchange.position(&ALICE) == Some((OrderSide::Bid, 5.into())));
+
+    assert!(exchange.matching(quote(1, 102, 20)).is_ok());
+    let_assert!(Some(order) = exchange.get(&OrderId::from_u128(1)));
+    assert!(order.remaining() == Quantity::from(5));
+
+    // Once the quote is filled, there is nothing left to reduce.
+    let buy = Order::builder().side(OrderSide::Bid).limit(102, 15).build();
+    assert!(exchange.matching(buy).is_ok());
+    assert!(exchange.position(&ALICE) == None);
+
+    let_assert!(Ok(report) = exchange.matching(quote(2, 103, 10)));
+    assert!(report.rejected_reason() == Some(RejectReason::ReduceOnly));
+}
@@ -0,0 +1,220 @@
+//! A price collar cancels incoming limit orders priced too far off the mid
+//! price, as a circuit breaker against orders that would otherwise sweep the
+//! book, or rest way off the market, by mistake.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+#[cfg(not(feature = "integer"))]
+use exchange_core::ExchangeExt;
+use exchange_types::AssetPair;
+use exchange_types::Order;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+#[cfg(not(feature = "integer"))]
+use exchange_types::Price;
+use exchange_types::PriceCollar;
+use matching_engine_algo::MatchingConfig;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::RejectReason;
+use matching_engine_rt::Engine;
+use matching_engine_rt::EngineError;
+use matching_engine_rt::MultiEngine;
+use matching_engine_rt::SymbolConfig;
+use matching_engine_rt::SymbolConfigs;
+#[cfg(not(feature = "integer"))]
+use rust_decimal::Decimal;
+#[cfg(not(feature = "integer"))]
+use rust_decimal_macros::dec;
+use tap::Tap;
+use uuid::Uuid;
+
+/// A book with a mid price of 100, collared at 5% off it, i.e. from 95 to
+/// 105.
+fn orderbook() -> Orderbook {
+    Orderbook::new()
+        .with_price_collar(PriceCollar::new(5))
+        .tap_mut(|exchange| {
+            for (side, limit_price) in [
+                (OrderSide::Ask, 101),
+                (OrderSide::Ask, 110),
+                (OrderSide::Bid, 99),
+            ] {
+                let limit_order =
+                    Order::builder().side(side).limit(limit_price, 10).build();
+
+                assert!(exchange.matching(limit_order).is_ok());
+            }
+        })
+}
+
+#[test]
+fn band() {
+    let exchange = orderbook();
+
+    assert!(exchange.price_collar() == Some(95.into()..=105.into()));
+}
+
+#[test]
+fn just_inside() {
+    let mut exchange = orderbook();
+
+    for (side, limit_price) in [(OrderSide::Bid, 105), (OrderSide::Ask, 95)] {
+        let limit_order =
+            Order::builder().side(side).limit(limit_price, 5).build();
+
+        let_assert!(Ok(report) = exchange.matching(limit_order));
+        assert!(report.trades().len() == 1);
+        assert!(report.status() == OrderStatus::Completed);
+    }
+}
+
+#[test]
+#[cfg(not(feature = "integer"))]
+fn just_outside() {
+    let mut exchange = orderbook();
+
+    for (side, limit_price) in [
+        (OrderSide::Bid, dec!(105.01)),
+        (OrderSide::Ask, dec!(94.99)),
+    ] {
+        let limit_order =
+            Order::builder().side(side).limit(limit_price, 5).build();
+
+        let_assert!(Ok(report) = exchange.matching(limit_order));
+        assert!(report.trades().is_empty());
+        assert!(report.status() == OrderStatus::Cancelled);
+    }
+
+    // Neither of them traded nor rests on the book.
+    assert!(exchange.len() == (2, 1));
+    assert!(exchange.volume() == (20.into(), 10.into()));
+}
+
+#[test]
+fn market_orders() {
+    let mut exchange = orderbook();
+
+    // Market orders have no price to collar, so they may still sweep past
+    // it; capping their slippage is what keeps them in check.
+    let market_order = Order::builder().side(OrderSide::Bid).market(20).build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let_assert!(Some(trade) = report.trades().last());
+    assert!(trade.price() == 110.into());
+}
+
+#[test]
+fn no_reference_price() {
+    let mut exchange = Orderbook::new().with_price_collar(PriceCollar::new(5));
+
+    // Until both sides rest some order, there is no mid price to collar
+    // around, so any price goes.
+    for (side, limit_price) in [(OrderSide::Ask, 100), (OrderSide::Ask, 1000)] {
+        assert!(exchange.price_collar().is_none());
+
+        let limit_order =
+            Order::builder().side(side).limit(limit_price, 10).build();
+
+        let_assert!(Ok(report) = exchange.matching(limit_order));
+        assert!(report.status() == OrderStatus::Open);
+    }
+
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(1, 10).build();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    assert!(exchange.price_collar().is_some());
+}
+
+#[test]
+fn reference_price() {
+    let mut exchange = orderbook();
+    exchange.set_reference_price(200.into());
+
+    // Centred on the reference price rather than on the mid price.
+    assert!(exchange.price_collar() == Some(190.into()..=210.into()));
+}
+
+#[test]
+fn engine_reference_price() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let create = |order_id, limit_price: u32| OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: 10.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: symbol.clone(),
+        limit_price: limit_price.into(),
+        side: OrderSide::Ask,
+    };
+
+    let mut engine =
+        Engine::new(symbol.clone()).with_price_collar(PriceCollar::new(5));
+    engine.set_reference_price(200.into());
+
+    // Collared from 190 to 210 even before any order rests.
+    let_assert!(
+        Err(EngineError::Rejected {
+            reason: RejectReason::PriceCollar,
+            ..
+        }) = engine.process(create(1, 100))
+    );
+    assert!(let Ok(_) = engine.process(create(2, 200)));
+
+    // So is every engine of a symbol, even one yet to see any order.
+    let mut configs = SymbolConfigs::new();
+    let config = SymbolConfig {
+        price_collar: Some(PriceCollar::new(5)),
+        ..SymbolConfig::default()
+    };
+    assert!(configs.insert(symbol.clone(), config).is_ok());
+    let mut engine = MultiEngine::new().with_configs(configs);
+    engine.set_reference_price(&symbol, 200.into());
+
+    let_assert!(
+        Err(EngineError::Rejected {
+            reason: RejectReason::PriceCollar,
+            ..
+        }) = engine.process(create(1, 100))
+    );
+    assert!(let Ok(_) = engine.process(create(2, 200)));
+}
+
+#[test]
+#[cfg(not(feature = "integer"))]
+fn float_reference_price() {
+    // A reference price of 100.1 fed as a float is a hair below it, and so
+    // is the upper bound it sets, 105.105.
+    let reference_price = Price::from(Decimal::from_f64_retain(100.1).unwrap());
+    let limit_order = || {
+        Order::builder()
+            .side(OrderSide::Bid)
+            .limit(dec!(105.105), 5)
+            .build()
+    };
+
+    let mut exchange = orderbook();
+    exchange.set_reference_price(reference_price);
+
+    let_assert!(Ok(report) = exchange.matching(limit_order()));
+    assert!(report.status() == OrderStatus::Cancelled);
+
+    // Unless the collar tolerates that much.
+    let mut exchange = Orderbook::new()
+        .with_price_collar(PriceCollar::new(5).with_tolerance(dec!(0.000001)));
+    exchange.set_reference_price(reference_price);
+
+    let_assert!(Ok(report) = exchange.matching(limit_order()));
+    assert!(report.status() == OrderStatus::Open);
+    assert!(report.rejected_reason() == None);
+
+    // Which still rejects anything past the bound itself.
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .limit(dec!(105.11), 5)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.status() == OrderStatus::Cancelled);
+}
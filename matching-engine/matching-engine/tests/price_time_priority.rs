@@ -0,0 +1,350 @@
+//! Makers resting at the very same price must be consumed in strict arrival
+//! order: no maker may be skipped nor double-counted, regardless of whether
+//! the incoming order's price exactly equals theirs, how many levels it
+//! sweeps, or whether the top maker was partially filled by a previous pass.
+
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook(side: OrderSide, orders: &[(u32, u32)]) -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for &(limit_price, quantity) in orders {
+            let limit_order = Order::builder()
+                .side(side)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+mod single_level {
+    use super::*;
+
+    #[test]
+    fn fifo_within_level() {
+        let mut exchange =
+            orderbook(OrderSide::Ask, &[(100, 10), (100, 20), (100, 30)]);
+
+        let limit_order =
+            Order::builder().side(OrderSide::Bid).limit(100, 25).build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [
+                Order {
+                    limit_price: 100,
+                    remaining: 5,
+                    status: Partial,
+                },
+                Order {
+                    limit_price: 100,
+                    remaining: 30,
+                    status: Open,
+                },
+            ],
+            Bid: [],
+        }
+        "###);
+    }
+
+    #[test]
+    fn exact_level_quantity() {
+        let mut exchange =
+            orderbook(OrderSide::Bid, &[(100, 10), (100, 20), (100, 30)]);
+
+        let limit_order =
+            Order::builder().side(OrderSide::Ask).limit(100, 60).build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [],
+            Bid: [],
+        }
+        "###);
+        assert_eq!(exchange.len(), (0, 0));
+    }
+
+    #[test]
+    fn exact_maker_quantity() {
+        let mut exchange =
+            orderbook(OrderSide::Bid, &[(100, 10), (100, 20), (100, 30)]);
+
+        let limit_order =
+            Order::builder().side(OrderSide::Ask).limit(100, 10).build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [],
+            Bid: [
+                Order {
+                    limit_price: 100,
+                    remaining: 20,
+                    status: Open,
+                },
+                Order {
+                    limit_price: 100,
+                    remaining: 30,
+                    status: Open,
+                },
+            ],
+        }
+        "###);
+    }
+
+    #[test]
+    fn remainder_rests_behind() {
+        let mut exchange = orderbook(OrderSide::Ask, &[(100, 10), (100, 20)]);
+
+        let limit_order =
+            Order::builder().side(OrderSide::Bid).limit(100, 50).build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [],
+            Bid: [
+                Order {
+                    limit_price: 100,
+                    remaining: 20,
+                    status: Partial,
+                },
+            ],
+        }
+        "###);
+    }
+}
+
+mod multiple_levels {
+    use super::*;
+
+    #[test]
+    fn ask_levels() {
+        let mut exchange = orderbook(
+            OrderSide::Ask,
+            &[(101, 40), (100, 10), (101, 30), (100, 20)],
+        );
+
+        let limit_order =
+            Order::builder().side(OrderSide::Bid).limit(101, 65).build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [
+                Order {
+                    limit_price: 101,
+                    remaining: 5,
+                    status: Partial,
+                },
+                Order {
+                    limit_price: 101,
+                    remaining: 30,
+                    status: Open,
+                },
+            ],
+            Bid: [],
+        }
+        "###);
+    }
+
+    #[test]
+    fn bid_levels() {
+        let mut exchange = orderbook(
+            OrderSide::Bid,
+            &[(99, 40), (100, 10), (99, 30), (100, 20)],
+        );
+
+        let limit_order =
+            Order::builder().side(OrderSide::Ask).limit(99, 65).build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [],
+            Bid: [
+                Order {
+                    limit_price: 99,
+                    remaining: 5,
+                    status: Partial,
+                },
+                Order {
+                    limit_price: 99,
+                    remaining: 30,
+                    status: Open,
+                },
+            ],
+        }
+        "###);
+    }
+
+    #[test]
+    fn market_sweep() {
+        let mut exchange = orderbook(
+            OrderSide::Ask,
+            &[(100, 10), (101, 10), (100, 10), (101, 10)],
+        );
+
+        let market_order =
+            Order::builder().side(OrderSide::Bid).market(25).build();
+
+        assert!(exchange.matching(market_order).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [
+                Order {
+                    limit_price: 101,
+                    remaining: 5,
+                    status: Partial,
+                },
+                Order {
+                    limit_price: 101,
+                    remaining: 10,
+                    status: Open,
+                },
+            ],
+            Bid: [],
+        }
+        "###);
+    }
+}
+
+mod partial_top_maker {
+    use super::*;
+
+    #[test]
+    fn carried_across_passes() {
+        let mut exchange =
+            orderbook(OrderSide::Ask, &[(100, 10), (100, 20), (100, 30)]);
+
+        let limit_order =
+            Order::builder().side(OrderSide::Bid).limit(100, 6).build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [
+                Order {
+                    limit_price: 100,
+                    remaining: 4,
+                    status: Partial,
+                },
+                Order {
+                    limit_price: 100,
+                    remaining: 20,
+                    status: Open,
+                },
+                Order {
+                    limit_price: 100,
+                    remaining: 30,
+                    status: Open,
+                },
+            ],
+            Bid: [],
+        }
+        "###);
+
+        // The partially filled top maker keeps its priority and must be
+        // exhausted before the next maker at the same price is touched.
+        let limit_order =
+            Order::builder().side(OrderSide::Bid).limit(100, 10).build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [
+                Order {
+                    limit_price: 100,
+                    remaining: 14,
+                    status: Partial,
+                },
+                Order {
+                    limit_price: 100,
+                    remaining: 30,
+                    status: Open,
+                },
+            ],
+            Bid: [],
+        }
+        "###);
+    }
+
+    #[test]
+    fn mixed_with_better_level() {
+        let mut exchange = orderbook(
+            OrderSide::Bid,
+            &[(100, 10), (101, 10), (100, 20), (101, 20)],
+        );
+
+        let limit_order =
+            Order::builder().side(OrderSide::Ask).limit(100, 15).build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [],
+            Bid: [
+                Order {
+                    limit_price: 101,
+                    remaining: 15,
+                    status: Partial,
+                },
+                Order {
+                    limit_price: 100,
+                    remaining: 10,
+                    status: Open,
+                },
+                Order {
+                    limit_price: 100,
+                    remaining: 20,
+                    status: Open,
+                },
+            ],
+        }
+        "###);
+
+        // Sweeping past the partial maker at 101 must land on the oldest
+        // maker at 100, not the largest nor the most recent one.
+        let limit_order =
+            Order::builder().side(OrderSide::Ask).limit(100, 20).build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [],
+            Bid: [
+                Order {
+                    limit_price: 100,
+                    remaining: 5,
+                    status: Partial,
+                },
+                Order {
+                    limit_price: 100,
+                    remaining: 20,
+                    status: Open,
+                },
+            ],
+        }
+        "###);
+        assert_eq!(exchange.len(), (0, 2));
+    }
+}
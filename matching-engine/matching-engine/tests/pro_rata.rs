@@ -0,0 +1,259 @@
+//! A pro-rata orderbook splits an incoming order across every resting order
+//! of a level in proportion to what each of them shows, instead of filling
+//! them one by one in time priority.
+
+use assert2::assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::Quantity;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+#[cfg(not(feature = "integer"))]
+use rust_decimal_macros::dec;
+
+fn orderbook(
+    asks: impl IntoIterator<Item = (u128, i32, Quantity)>,
+) -> Orderbook<ProRataAlgo> {
+    let mut exchange = Orderbook::new().with_algo::<ProRataAlgo>();
+
+    for (id, limit_price, quantity) in asks {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .id(OrderId::from_u128(id))
+            .limit(limit_price, quantity)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+
+    exchange
+}
+
+fn fills(
+    exchange: &mut Orderbook<ProRataAlgo>,
+    incoming_order: Order,
+) -> Vec<(OrderId, Quantity)> {
+    exchange
+        .matching(incoming_order)
+        .unwrap()
+        .trades()
+        .iter()
+        .map(|trade| (trade.maker(), trade.quantity()))
+        .collect()
+}
+
+fn remaining(exchange: &Orderbook<ProRataAlgo>) -> Vec<(OrderId, Quantity)> {
+    exchange
+        .iter(&OrderSide::Ask)
+        .map(|order| (order.id(), order.remaining()))
+        .collect()
+}
+
+#[test]
+fn split_in_proportion() {
+    let mut exchange = orderbook([
+        (1, 100, 10.into()),
+        (2, 100, 20.into()),
+        (3, 100, 5.into()),
+    ]);
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(10).build();
+
+    // 10 * 10 / 35, 10 * 20 / 35 and 10 * 5 / 35 are rounded down to 2, 5
+    // and 1, and the 2 left over go to the largest order.
+    assert!(
+        fills(&mut exchange, market_order)
+            == [
+                (OrderId::from_u128(1), 2.into()),
+                (OrderId::from_u128(2), 7.into()),
+                (OrderId::from_u128(3), 1.into()),
+            ]
+    );
+
+    // Resting orders keep their time priority.
+    assert!(
+        remaining(&exchange)
+            == [
+                (OrderId::from_u128(1), 8.into()),
+                (OrderId::from_u128(2), 13.into()),
+                (OrderId::from_u128(3), 4.into()),
+            ]
+    );
+}
+
+#[test]
+fn filled_in_place() {
+    let mut exchange = orderbook([
+        (1, 100, 10.into()),
+        (2, 100, 20.into()),
+        (3, 100, 5.into()),
+    ]);
+    let sequences = |exchange: &Orderbook<ProRataAlgo>| {
+        exchange
+            .iter(&OrderSide::Ask)
+            .map(|order| (order.id(), order.sequence()))
+            .collect::<Vec<_>>()
+    };
+    let before = sequences(&exchange);
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(10).build();
+    assert!(fills(&mut exchange, market_order).len() == 3);
+
+    // Partially filled orders are neither requeued nor given new sequences.
+    assert!(sequences(&exchange) == before);
+}
+
+#[test]
+#[cfg(not(feature = "integer"))]
+fn leftover_to_oldest() {
+    for quantity in [dec!(0.5), dec!(0.50)] {
+        let mut exchange = orderbook([
+            (1, 100, 1.into()),
+            (2, 100, 1.into()),
+            (3, 100, 1.into()),
+        ]);
+
+        let market_order = Order::builder()
+            .side(OrderSide::Bid)
+            .market(quantity)
+            .build();
+
+        // Shares are rounded down to the last decimal place any quantity
+        // needs, however it is written, and the oldest order breaks the tie
+        // among equally large ones.
+        assert!(
+            fills(&mut exchange, market_order)
+                == [
+                    (OrderId::from_u128(1), dec!(0.3).into()),
+                    (OrderId::from_u128(2), dec!(0.1).into()),
+                    (OrderId::from_u128(3), dec!(0.1).into()),
+                ]
+        );
+
+        let (ask, _) = exchange.volume();
+        assert!(ask == dec!(2.5).into());
+    }
+}
+
+#[test]
+fn rounded_to_lots() {
+    let mut exchange = Orderbook::new()
+        .with_lot_size(5.into())
+        .with_algo::<ProRataAlgo>();
+    for (id, quantity) in [(1, 20), (2, 20), (3, 10)] {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .id(OrderId::from_u128(id))
+            .limit(100, quantity)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(30).build();
+
+    // 30 * 20 / 50, 30 * 20 / 50 and 30 * 10 / 50 are rounded down to whole
+    // lots of 5, i.e. 10, 10 and 5, and the lot left over goes to the oldest
+    // of the largest orders.
+    assert!(
+        fills(&mut exchange, market_order)
+            == [
+                (OrderId::from_u128(1), 15.into()),
+                (OrderId::from_u128(2), 10.into()),
+                (OrderId::from_u128(3), 5.into()),
+            ]
+    );
+}
+
+#[test]
+fn sweep_levels() {
+    let mut exchange = orderbook([
+        (1, 100, 10.into()),
+        (2, 100, 20.into()),
+        (3, 101, 10.into()),
+        (4, 101, 30.into()),
+    ]);
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(38).build();
+
+    // The first level is taken whole, so its orders fill in time priority,
+    // and only the second one is split.
+    assert!(
+        fills(&mut exchange, market_order)
+            == [
+                (OrderId::from_u128(1), 10.into()),
+                (OrderId::from_u128(2), 20.into()),
+                (OrderId::from_u128(3), 2.into()),
+                (OrderId::from_u128(4), 6.into()),
+            ]
+    );
+
+    assert!(
+        remaining(&exchange)
+            == [
+                (OrderId::from_u128(3), 8.into()),
+                (OrderId::from_u128(4), 24.into()),
+            ]
+    );
+}
+
+#[test]
+fn rest_the_remainder() {
+    let mut exchange = orderbook([(1, 100, 10.into()), (2, 100, 20.into())]);
+
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(100, 50).build();
+
+    assert!(fills(&mut exchange, limit_order).len() == 2);
+    assert!(exchange.len() == (0, 1));
+    assert!(exchange.best_bid() == Some(100.into()));
+}
+
+#[test]
+fn hybrid() {
+    let asks = [
+        (1, 100, 10.into()),
+        (2, 100, 20.into()),
+        (3, 100, 30.into()),
+    ];
+    let mut exchange = orderbook(asks).with_pro_rata_ratio(60);
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(30).build();
+
+    // 40% of it, 12, fills the oldest orders first: all of the first one and
+    // 2 of the second. The other 18 are split across the 18 and 30 they still
+    // show, rounded down to 6 and 11, and the 1 left over goes to the
+    // largest.
+    assert!(
+        fills(&mut exchange, market_order)
+            == [
+                (OrderId::from_u128(1), 10.into()),
+                (OrderId::from_u128(2), 8.into()),
+                (OrderId::from_u128(3), 12.into()),
+            ]
+    );
+}
+
+#[test]
+fn hybrid_all_fifo() {
+    let asks = [
+        (1, 100, 10.into()),
+        (2, 100, 20.into()),
+        (3, 100, 30.into()),
+    ];
+    let mut exchange = orderbook(asks).with_pro_rata_ratio(0);
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(15).build();
+
+    assert!(
+        fills(&mut exchange, market_order)
+            == [
+                (OrderId::from_u128(1), 10.into()),
+                (OrderId::from_u128(2), 5.into()),
+            ]
+    );
+}
@@ -0,0 +1,102 @@
+use std::num::NonZeroUsize;
+
+use exchange_core::ExchangeExt;
+use exchange_types::AssetPair;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use matching_engine_rt::Engine;
+use matching_engine_rt::EngineError;
+use matching_engine_rt::Recorder;
+use uuid::Uuid;
+
+fn symbol() -> AssetPair {
+    AssetPair::new("BTC", "USDC")
+}
+
+/// A deterministic flow that both rests and trades on each side.
+fn requests() -> impl Iterator<Item = OrderRequest> {
+    (0..64u32).map(|i| match i % 16 {
+        15 => OrderRequest::Delete {
+            order_id: Uuid::from_u128(u128::from(i - 7)),
+        },
+        _ => OrderRequest::Create {
+            account_id: Uuid::from_u128(u128::from(i % 4)),
+            amount: (10 + i % 7).into(),
+            order_id: Uuid::from_u128(u128::from(i)),
+            symbol: symbol(),
+            limit_price: (95 + i % 11).into(),
+            side: if i % 2 == 0 {
+                OrderSide::Ask
+            } else {
+                OrderSide::Bid
+            },
+        },
+    })
+}
+
+/// Deletes of orders that were filled in the meantime are rejected, which is
+/// fine for the flow.
+fn process(engine: &mut Engine, request: OrderRequest) {
+    match engine.process(request) {
+        Ok(_) | Err(EngineError::OrderNotFound { .. }) => (),
+        Err(error) => panic!("{error}"),
+    }
+}
+
+fn replay(recorded: &[u8]) -> Engine {
+    let mut engine = Engine::new(symbol());
+
+    for line in recorded.split(|&b| b == b'\n').filter(|l| !l.is_empty()) {
+        let request = serde_json::from_slice(line).unwrap();
+        process(&mut engine, request);
+    }
+
+    engine
+}
+
+#[test]
+fn replay_full_flow() {
+    let mut engine = Engine::new(symbol());
+    let mut recorder = Recorder::new(Vec::new(), NonZeroUsize::MIN);
+
+    for request in requests() {
+        assert!(recorder.record(&request).unwrap());
+        process(&mut engine, request);
+    }
+
+    let recorded = recorder.into_inner().unwrap();
+    let replayed = replay(&recorded);
+
+    assert!(!engine.orderbook().is_empty());
+
+    assert_eq!(
+        replayed.orderbook().ladder(usize::MAX),
+        engine.orderbook().ladder(usize::MAX),
+    );
+}
+
+#[test]
+fn replay_sampled_flow() {
+    let every = NonZeroUsize::new(3).unwrap();
+    let mut recorder = Recorder::new(Vec::new(), every);
+
+    let sampled = requests()
+        .filter(|request| recorder.record(request).unwrap())
+        .count();
+
+    let recorded = recorder.into_inner().unwrap();
+    assert_eq!(sampled, 22);
+    assert_eq!(recorded.iter().filter(|&&b| b == b'\n').count(), sampled);
+
+    let mut expected = Engine::new(symbol());
+    for request in requests().step_by(every.get()) {
+        process(&mut expected, request);
+    }
+
+    let replayed = replay(&recorded);
+
+    assert_eq!(
+        replayed.orderbook().ladder(usize::MAX),
+        expected.orderbook().ladder(usize::MAX),
+    );
+}
@@ -0,0 +1,189 @@
+//! A Reduce-Only order is an order that may only reduce an existing
+//! position, so it must never add liquidity to the book: it trades against
+//! whatever resting orders it can, and any unfilled remainder is cancelled
+//! instead of resting.
+
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for limit_price in [100, 101] {
+            let limit_order = Order::builder()
+                .side(OrderSide::Ask)
+                .limit(limit_price, 10)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+mod valid {
+    use super::*;
+
+    mod no_price {
+        use super::*;
+
+        #[test]
+        fn full_match() {
+            let mut exchange = orderbook();
+
+            let reduce_only = Order::builder()
+                .side(OrderSide::Bid)
+                .market(15)
+                .reduce_only()
+                .build();
+
+            assert!(exchange.matching(reduce_only).is_ok());
+
+            insta::assert_debug_snapshot!(&exchange, @r###"
+            {
+                Ask: [
+                    Order {
+                        limit_price: 101,
+                        remaining: 5,
+                        status: Partial,
+                    },
+                ],
+                Bid: [],
+            }
+            "###);
+        }
+
+        #[test]
+        fn partial_match() {
+            let mut exchange = orderbook();
+
+            let reduce_only = Order::builder()
+                .side(OrderSide::Bid)
+                .market(30)
+                .reduce_only()
+                .build();
+
+            assert!(exchange.matching(reduce_only).is_ok());
+
+            insta::assert_debug_snapshot!(&exchange, @r###"
+            {
+                Ask: [],
+                Bid: [],
+            }
+            "###);
+        }
+    }
+
+    mod with_price {
+        use super::*;
+
+        #[test]
+        fn full_match() {
+            let mut exchange = orderbook();
+
+            let reduce_only = Order::builder()
+                .side(OrderSide::Bid)
+                .limit(101, 15)
+                .reduce_only()
+                .build();
+
+            assert!(exchange.matching(reduce_only).is_ok());
+
+            insta::assert_debug_snapshot!(&exchange, @r###"
+            {
+                Ask: [
+                    Order {
+                        limit_price: 101,
+                        remaining: 5,
+                        status: Partial,
+                    },
+                ],
+                Bid: [],
+            }
+            "###);
+        }
+
+        #[test]
+        fn partial_match() {
+            let mut exchange = orderbook();
+
+            let reduce_only = Order::builder()
+                .side(OrderSide::Bid)
+                .limit(100, 15)
+                .reduce_only()
+                .build();
+
+            assert!(exchange.matching(reduce_only).is_ok());
+
+            // The unfilled remainder is cancelled rather than resting at 100.
+            insta::assert_debug_snapshot!(&exchange, @r###"
+            {
+                Ask: [
+                    Order {
+                        limit_price: 101,
+                        remaining: 10,
+                        status: Open,
+                    },
+                ],
+                Bid: [],
+            }
+            "###);
+        }
+    }
+}
+
+mod invalid {
+    use super::*;
+
+    #[test]
+    fn no_match() {
+        let mut exchange = orderbook();
+
+        let reduce_only = Order::builder()
+            .side(OrderSide::Bid)
+            .limit(99, 10)
+            .reduce_only()
+            .build();
+
+        assert!(exchange.matching(reduce_only).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [
+                Order {
+                    limit_price: 100,
+                    remaining: 10,
+                    status: Open,
+                },
+                Order {
+                    limit_price: 101,
+                    remaining: 10,
+                    status: Open,
+                },
+            ],
+            Bid: [],
+        }
+        "###);
+    }
+
+    #[test]
+    fn empty_book() {
+        let mut exchange = Orderbook::new();
+
+        let reduce_only = Order::builder()
+            .side(OrderSide::Ask)
+            .limit(100, 10)
+            .reduce_only()
+            .build();
+
+        assert!(exchange.matching(reduce_only).is_ok());
+
+        insta::assert_debug_snapshot!(&exchange, @r###"
+        {
+            Ask: [],
+            Bid: [],
+        }
+        "###);
+    }
+}
@@ -0,0 +1,93 @@
+//! Rejected requests are logged one JSON record per line, along with why
+//! they were rejected and the very payload they came in with, whether it
+//! could be parsed or not.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_types::AssetPair;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use exchange_types::PriceCollar;
+use matching_engine_algo::RejectReason;
+use matching_engine_rt::Engine;
+use matching_engine_rt::Payload;
+use matching_engine_rt::Rejection;
+use matching_engine_rt::RejectionLog;
+use uuid::Uuid;
+
+fn create(
+    order_id: u128,
+    symbol: &AssetPair,
+    limit_price: u32,
+) -> OrderRequest {
+    OrderRequest::Create {
+        account_id: Uuid::from_u128(order_id),
+        amount: 10.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: symbol.clone(),
+        limit_price: limit_price.into(),
+        side: if order_id.is_multiple_of(2) {
+            OrderSide::Bid
+        } else {
+            OrderSide::Ask
+        },
+    }
+}
+
+fn rejections(log: Vec<u8>) -> Vec<Rejection> {
+    log.split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn rejected_and_malformed() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine =
+        Engine::new(symbol.clone()).with_price_collar(PriceCollar::new(5));
+    let mut log = RejectionLog::new(Vec::new());
+
+    // Collared from 95 to 105 from now on.
+    assert!(let Ok(_) = engine.process(create(1, &symbol, 101)));
+    assert!(let Ok(_) = engine.process(create(2, &symbol, 99)));
+
+    for request in [
+        create(4, &symbol, 150),
+        create(6, &AssetPair::new("ETH", "USDC"), 100),
+    ] {
+        let_assert!(Err(error) = engine.process(request.clone()));
+        assert!(log.rejected(request, &error).is_ok());
+    }
+
+    let raw = b"{\"type_op\": \"CREATE\", \"amount\": \n";
+    let_assert!(Err(error) = serde_json::from_slice::<OrderRequest>(raw));
+    assert!(log.malformed(raw, &error).is_ok());
+
+    let raw = [0xff, 0x00, 0x01];
+    let_assert!(Err(error) = OrderRequest::from_postcard(&raw));
+    assert!(log.malformed(&raw, &error).is_ok());
+
+    let_assert!(Ok(log) = log.into_inner());
+    let log = rejections(log);
+    let_assert!([collared, mismatch, json, postcard] = log.as_slice());
+
+    // Policy rejections tell the reason the book gave.
+    assert!(collared.reason == Some(RejectReason::PriceCollar));
+    assert!(collared.payload == Payload::Request(create(4, &symbol, 150)));
+
+    // Others are turned down before reaching the book.
+    assert!(mismatch.reason == None);
+    assert!(mismatch.error.contains("ETH/USDC"));
+    let_assert!(
+        Payload::Request(OrderRequest::Create { .. }) = &mismatch.payload
+    );
+
+    // Payloads that could not be parsed are kept as they came in.
+    assert!(json.reason == None);
+    assert!(
+        json.payload
+            == Payload::Raw("{\"type_op\": \"CREATE\", \"amount\":".to_owned())
+    );
+    assert!(postcard.payload == Payload::Bytes(raw.to_vec()));
+}
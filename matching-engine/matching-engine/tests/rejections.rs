@@ -0,0 +1,164 @@
+//! Whenever the book itself cancels an incoming order, rather than letting it
+//! trade or rest as it asked, the report tells why it did.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_types::AccountId;
+use exchange_types::AssetPair;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::PriceCollar;
+use matching_engine_algo::MatchingAlgo;
+use matching_engine_algo::MatchingStrategy;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use matching_engine_algo::RejectReason;
+use matching_engine_algo::SelfTradePrevention;
+use matching_engine_rt::Engine;
+use matching_engine_rt::EngineError;
+use tap::Tap;
+use uuid::Uuid;
+
+const ALICE: AccountId = AccountId::from_u128(1);
+const BOB: AccountId = AccountId::from_u128(2);
+
+/// A book with a mid price of 100, where the best ask belongs to Alice.
+fn rest<A: MatchingStrategy>(exchange: &mut Orderbook<A>) {
+    for (side, account_id, limit_price, quantity) in [
+        (OrderSide::Ask, ALICE, 101, 10),
+        (OrderSide::Ask, BOB, 102, 10),
+        (OrderSide::Ask, BOB, 150, 100),
+        (OrderSide::Bid, BOB, 99, 10),
+    ] {
+        let limit_order = Order::builder()
+            .side(side)
+            .account_id(account_id)
+            .limit(limit_price, quantity)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+}
+
+fn check<A: MatchingStrategy>() {
+    let builder = Order::builder().side(OrderSide::Bid);
+    for (incoming_order, trades, rejected_reason) in [
+        // Resting as asked is no rejection at all.
+        (builder.limit(95, 10).build(), 0, None),
+        (
+            builder.limit(101, 10).post_only().build(),
+            0,
+            Some(RejectReason::PostOnly),
+        ),
+        (
+            builder.market(1000).all_or_none().build(),
+            0,
+            Some(RejectReason::FillOrKill),
+        ),
+        (
+            builder.account_id(ALICE).limit(101, 5).build(),
+            0,
+            Some(RejectReason::SelfTrade),
+        ),
+        // Some of them trade before whatever is left is cancelled.
+        (
+            builder.market(50).max_slippage(5).build(),
+            2,
+            Some(RejectReason::MaxSlippage),
+        ),
+        (
+            builder.limit(102, 30).reduce_only().build(),
+            2,
+            Some(RejectReason::ReduceOnly),
+        ),
+        // Immediate-or-cancel orders expire, just as they asked.
+        (builder.market(1000).build(), 3, None),
+    ] {
+        let mut exchange = Orderbook::new().with_algo::<A>().tap_mut(rest);
+
+        let_assert!(Ok(report) = exchange.matching(incoming_order));
+        assert!(report.trades().len() == trades);
+        assert!(report.rejected_reason() == rejected_reason);
+
+        if rejected_reason.is_some() {
+            assert!(matches!(
+                report.status(),
+                OrderStatus::Cancelled | OrderStatus::Closed
+            ));
+        }
+    }
+}
+
+#[test]
+fn price_time_priority() {
+    check::<MatchingAlgo>();
+}
+
+#[test]
+fn pro_rata() {
+    check::<ProRataAlgo>();
+}
+
+#[test]
+fn price_collar() {
+    let mut exchange = Orderbook::new()
+        .with_price_collar(PriceCollar::new(5))
+        .tap_mut(rest);
+
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(110, 10).build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.trades().is_empty());
+    assert!(report.rejected_reason() == Some(RejectReason::PriceCollar));
+}
+
+#[test]
+fn cancel_oldest_is_no_rejection() {
+    // The resting order is the one cancelled, so the incoming one trades
+    // with the next ask instead.
+    let mut exchange = Orderbook::new()
+        .with_self_trade_prevention(SelfTradePrevention::CancelOldest)
+        .tap_mut(rest);
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .account_id(ALICE)
+        .limit(102, 5)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.trades().len() == 1);
+    assert!(report.rejected_reason().is_none());
+}
+
+#[test]
+fn engine() {
+    let symbol = AssetPair::new("BTC", "USDC");
+    let mut engine = Engine::new(symbol.clone());
+
+    let create = |order_id: u128, side: OrderSide| OrderRequest::Create {
+        account_id: Uuid::from_u128(1),
+        amount: 10.into(),
+        order_id: Uuid::from_u128(order_id),
+        symbol: symbol.clone(),
+        limit_price: 100.into(),
+        side,
+    };
+
+    assert!(let Ok(_) = engine.process(create(1, OrderSide::Ask)));
+
+    let_assert!(
+        Err(EngineError::Rejected { order_id, reason }) =
+            engine.process(create(2, OrderSide::Bid))
+    );
+    assert!(order_id == OrderId::from_u128(2));
+    assert!(reason == RejectReason::SelfTrade);
+
+    // Rejected orders never reach the book.
+    assert!(engine.orderbook().get(&order_id).is_none());
+}
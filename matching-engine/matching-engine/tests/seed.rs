@@ -0,0 +1,68 @@
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use exchange_types::Price;
+use exchange_types::Quantity;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::SeedError;
+
+fn level(price: u32, quantity: u32) -> (Price, Quantity) {
+    (price.into(), quantity.into())
+}
+
+#[test]
+fn depth_shape() {
+    let exchange = Orderbook::seed_from_depth(
+        [level(99, 10), level(98, 20), level(97, 30)],
+        [level(101, 5), level(102, 15)],
+    )
+    .unwrap();
+
+    assert!(exchange.len() == (2, 3));
+    assert!(exchange.spread() == Some((101.into(), 99.into())));
+
+    insta::assert_snapshot!(exchange.ladder(10), @r###"
+    Qty Bid | Ask Qty
+     10  99 | 101 5
+     20  98 | 102 15
+     30  97 |
+    "###);
+}
+
+#[test]
+fn match_against_seed() {
+    let mut exchange = Orderbook::seed_from_depth(
+        [level(99, 10), level(98, 20)],
+        [level(101, 5), level(102, 15)],
+    )
+    .unwrap();
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(10).build();
+
+    assert!(exchange.matching(market_order).is_ok());
+
+    insta::assert_snapshot!(exchange.ladder(10), @r###"
+    Qty Bid | Ask Qty
+     10  99 | 102 10
+     20  98 |
+    "###);
+}
+
+#[test]
+fn reject_crossed() {
+    let_assert!(
+        Err(SeedError::Crossed { .. }) =
+            Orderbook::seed_from_depth([level(100, 10)], [level(100, 10)],)
+    );
+}
+
+#[test]
+fn reject_empty_level() {
+    let_assert!(
+        Err(SeedError::EmptyLevel { .. }) =
+            Orderbook::seed_from_depth([level(100, 0)], [])
+    );
+}
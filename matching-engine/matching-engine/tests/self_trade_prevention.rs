@@ -0,0 +1,283 @@
+//! Orders of the same account must never trade with each other. Whenever an
+//! incoming order would do so, either the incoming order, the resting order or
+//! both of them are cancelled, depending on the orderbook configuration.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::AccountId;
+use exchange_types::AssetPair;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderRequest;
+use exchange_types::OrderSide;
+use matching_engine_algo::MatchingStrategy;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use matching_engine_algo::SelfTradePrevention;
+use matching_engine_rt::Engine;
+use tap::Tap;
+use uuid::Uuid;
+
+const ALICE: AccountId = AccountId::from_u128(1);
+const BOB: AccountId = AccountId::from_u128(2);
+
+/// The id of Alice's resting order.
+const RESTING: OrderId = OrderId::from_u128(2);
+
+fn orderbook(self_trade_prevention: SelfTradePrevention) -> Orderbook {
+    Orderbook::new()
+        .with_self_trade_prevention(self_trade_prevention)
+        .tap_mut(|exchange| {
+            for (id, account_id, limit_price, quantity) in
+                [(1, BOB, 100, 10), (2, ALICE, 100, 10), (3, BOB, 101, 10)]
+            {
+                let limit_order = Order::builder()
+                    .side(OrderSide::Ask)
+                    .id(OrderId::from_u128(id))
+                    .account_id(account_id)
+                    .limit(limit_price, quantity)
+                    .build();
+
+                assert!(exchange.matching(limit_order).is_ok());
+            }
+        })
+}
+
+#[test]
+fn cancel_newest() {
+    let mut exchange = orderbook(SelfTradePrevention::CancelNewest);
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .account_id(ALICE)
+        .limit(101, 25)
+        .build();
+
+    // The incoming order trades until it reaches its own resting order, and
+    // the remaining is cancelled instead of resting on the book.
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.cancelled().is_empty());
+    let trades = report.into_trades();
+    assert!(trades.len() == 1);
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 100,
+                remaining: 10,
+                status: Open,
+            },
+            Order {
+                limit_price: 101,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+fn cancel_oldest() {
+    let mut exchange = orderbook(SelfTradePrevention::CancelOldest);
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .account_id(ALICE)
+        .limit(101, 25)
+        .build();
+
+    // The resting order is cancelled and the incoming order moves on to the
+    // next maker.
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.cancelled() == [RESTING]);
+    let trades = report.into_trades();
+    assert!(trades.len() == 2);
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [],
+        Bid: [
+            Order {
+                limit_price: 101,
+                remaining: 5,
+                status: Partial,
+            },
+        ],
+    }
+    "###);
+}
+
+#[test]
+fn cancel_both() {
+    let mut exchange = orderbook(SelfTradePrevention::CancelBoth);
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .account_id(ALICE)
+        .limit(101, 25)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.cancelled() == [RESTING]);
+    let trades = report.into_trades();
+    assert!(trades.len() == 1);
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 101,
+                remaining: 10,
+                status: Open,
+            },
+        ],
+        Bid: [],
+    }
+    "###);
+}
+
+#[test]
+fn pro_rata_cancel_oldest() {
+    let mut exchange =
+        orderbook(SelfTradePrevention::CancelOldest).with_algo::<ProRataAlgo>();
+
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .account_id(ALICE)
+        .limit(100, 20)
+        .build();
+
+    // Alice's resting order is cancelled rather than given its share.
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.cancelled() == [RESTING]);
+    assert!(exchange.get(&RESTING).is_none());
+}
+
+#[test]
+fn anonymous_orders_trade() {
+    let mut exchange = orderbook(SelfTradePrevention::CancelNewest);
+
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(100, 20).build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    let trades = report.into_trades();
+    assert!(trades.len() == 2);
+    assert!(exchange.len() == (1, 0));
+}
+
+#[test]
+fn non_crossing_orders_rest() {
+    let mut exchange = Orderbook::new().tap_mut(|exchange| {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .account_id(ALICE)
+            .limit(110, 10)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    });
+
+    // Alice's bid would never trade with her own ask, so both rest.
+    let limit_order = Order::builder()
+        .side(OrderSide::Bid)
+        .account_id(ALICE)
+        .limit(90, 10)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    assert!(report.trades().is_empty());
+    assert!(exchange.len() == (1, 1));
+}
+
+#[test]
+fn out_of_reach_orders_kept() {
+    // Once Bob's ask is taken, Alice's is the best one left, though it is
+    // priced beyond her own bid, so neither of hers is cancelled, whatever the
+    // mode or the way levels are matched.
+    fn check<A: MatchingStrategy>(mut exchange: Orderbook<A>) {
+        for (id, account_id, limit_price) in [(1, BOB, 100), (2, ALICE, 101)] {
+            let limit_order = Order::builder()
+                .side(OrderSide::Ask)
+                .id(OrderId::from_u128(id))
+                .account_id(account_id)
+                .limit(limit_price, 10)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+
+        let limit_order = Order::builder()
+            .side(OrderSide::Bid)
+            .account_id(ALICE)
+            .limit(100, 15)
+            .build();
+
+        let_assert!(Ok(report) = exchange.matching(limit_order));
+        assert!(report.trades().len() == 1);
+        assert!(report.cancelled().is_empty());
+        assert!(exchange.get(&OrderId::from_u128(2)).is_some());
+        assert!(exchange.len() == (1, 1));
+    }
+
+    for self_trade_prevention in [
+        SelfTradePrevention::CancelNewest,
+        SelfTradePrevention::CancelOldest,
+        SelfTradePrevention::CancelBoth,
+    ] {
+        let exchange = || {
+            Orderbook::new().with_self_trade_prevention(self_trade_prevention)
+        };
+
+        check(exchange());
+        check(exchange().with_algo::<ProRataAlgo>());
+    }
+}
+
+#[test]
+fn engine_reports_cancelled_resting() {
+    // Alice's and Bob's accounts, as requests carry them.
+    let (alice, bob) = (Uuid::from_u128(1), Uuid::from_u128(2));
+    let create =
+        |id, account_id, side, limit_price: u32| OrderRequest::Create {
+            account_id,
+            amount: 10.into(),
+            order_id: Uuid::from_u128(id),
+            symbol: AssetPair::new("BTC", "USDC"),
+            limit_price: limit_price.into(),
+            side,
+        };
+
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"))
+        .with_self_trade_prevention(SelfTradePrevention::CancelOldest);
+    for (id, account_id, limit_price) in [(1, alice, 100), (2, bob, 101)] {
+        let request = create(id, account_id, OrderSide::Ask, limit_price);
+        let_assert!(Ok(report) = engine.process(request));
+        assert!(report.cancelled.is_empty());
+    }
+
+    // Alice's resting order is cancelled rather than traded with, which the
+    // engine tells of along with the trade against Bob's.
+    let request = create(3, alice, OrderSide::Bid, 101);
+    let_assert!(Ok(report) = engine.process(request));
+    assert!(report.trades.len() == 1);
+    assert!(report.cancelled == [OrderId::from_u128(1)]);
+    assert!(engine.orderbook().is_empty());
+
+    // Cancelling both orders is no rejection, as the resting one is gone too.
+    let mut engine = Engine::new(AssetPair::new("BTC", "USDC"))
+        .with_self_trade_prevention(SelfTradePrevention::CancelBoth);
+    let request = create(1, alice, OrderSide::Ask, 100);
+    assert!(let Ok(_) = engine.process(request));
+
+    let request = create(2, alice, OrderSide::Bid, 100);
+    let_assert!(Ok(report) = engine.process(request));
+    assert!(report.trades.is_empty());
+    assert!(report.cancelled == [OrderId::from_u128(1)]);
+    assert!(engine.orderbook().is_empty());
+}
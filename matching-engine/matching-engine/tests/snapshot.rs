@@ -0,0 +1,327 @@
+//! A snapshot is a single self-contained document of every resting order in
+//! a book, which the very same book can be rebuilt from.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::AccountId;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use exchange_types::PriceCollar;
+use exchange_types::Quantity;
+use matching_engine_algo::LinkError;
+use matching_engine_algo::MatchingConfig;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::OrderbookSnapshot;
+use matching_engine_algo::SnapshotError;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for limit_order in [
+            Order::builder()
+                .side(OrderSide::Ask)
+                .id(OrderId::from_u128(1))
+                .limit(100, 10)
+                .build(),
+            Order::builder()
+                .side(OrderSide::Ask)
+                .id(OrderId::from_u128(2))
+                .account_id(AccountId::from_u128(1))
+                .limit(100, 20)
+                .iceberg(5)
+                .build(),
+            Order::builder()
+                .side(OrderSide::Ask)
+                .id(OrderId::from_u128(3))
+                .limit(100, 30)
+                .hidden()
+                .build(),
+            Order::builder()
+                .side(OrderSide::Ask)
+                .id(OrderId::from_u128(4))
+                .limit(101, 40)
+                .gtd(1_700_000_000)
+                .build(),
+            Order::builder()
+                .side(OrderSide::Bid)
+                .id(OrderId::from_u128(5))
+                .limit(99, 50)
+                .post_only()
+                .build(),
+            // Fills the first ask and the first slice of the iceberg one,
+            // whose next slice is queued behind the hidden one.
+            Order::builder()
+                .side(OrderSide::Bid)
+                .id(OrderId::from_u128(6))
+                .market(15)
+                .build(),
+        ] {
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+fn to_json(snapshot: &OrderbookSnapshot) -> String {
+    serde_json::to_string(snapshot).expect("snapshots are serializable")
+}
+
+#[test]
+fn roundtrip() {
+    let exchange = orderbook();
+
+    let snapshot = exchange.snapshot();
+    let json = to_json(&snapshot);
+
+    let_assert!(
+        Ok(snapshot) = serde_json::from_str::<OrderbookSnapshot>(&json)
+    );
+    let_assert!(Ok(restored) = Orderbook::from_snapshot(snapshot));
+
+    assert!(to_json(&restored.snapshot()) == json);
+    assert!(restored.view() == exchange.view());
+    assert!(restored.len() == exchange.len());
+    assert!(restored.volume() == exchange.volume());
+    assert!(restored.last_price() == Some(100.into()));
+}
+
+#[test]
+fn orders_keep_their_state() {
+    let snapshot = orderbook().snapshot();
+
+    let orders = snapshot
+        .orders()
+        .iter()
+        .map(|order| (order.id(), order.filled(), order.status()))
+        .collect::<Vec<(OrderId, Quantity, OrderStatus)>>();
+
+    // In the order they were queued, the iceberg one last since its slice
+    // was refreshed.
+    assert!(
+        orders
+            == [
+                (OrderId::from_u128(3), 0.into(), OrderStatus::Open),
+                (OrderId::from_u128(4), 0.into(), OrderStatus::Open),
+                (OrderId::from_u128(5), 0.into(), OrderStatus::Open),
+                (OrderId::from_u128(2), 5.into(), OrderStatus::Partial),
+            ]
+    );
+}
+
+#[test]
+fn priority_is_kept() {
+    let mut exchange = orderbook();
+    let_assert!(Ok(mut restored) = Orderbook::from_snapshot(exchange.snapshot()));
+
+    let market_order = || {
+        Order::builder()
+            .side(OrderSide::Bid)
+            .id(OrderId::from_u128(7))
+            .market(60)
+            .build()
+    };
+
+    let_assert!(Ok(report) = exchange.matching(market_order()));
+    let_assert!(Ok(restored_report) = restored.matching(market_order()));
+
+    assert!(format!("{report:?}") == format!("{restored_report:?}"));
+    assert!(to_json(&restored.snapshot()) == to_json(&exchange.snapshot()));
+
+    // New orders are queued after every restored one.
+    let limit_order = Order::builder()
+        .side(OrderSide::Ask)
+        .id(OrderId::from_u128(8))
+        .limit(101, 1)
+        .build();
+    assert!(restored.matching(limit_order).is_ok());
+    let_assert!(Some(order) = restored.get(&OrderId::from_u128(8)));
+    assert!(restored
+        .snapshot()
+        .orders()
+        .iter()
+        .all(|other| other.sequence() <= order.sequence()));
+}
+
+#[test]
+fn duplicate_order() {
+    let json = to_json(&orderbook().snapshot());
+
+    let mut value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    let orders = value["orders"].as_array_mut().unwrap();
+    orders.push(orders[0].clone());
+
+    let_assert!(
+        Ok(snapshot) = serde_json::from_value::<OrderbookSnapshot>(value)
+    );
+    let_assert!(
+        Err(SnapshotError::Duplicate { order_id }) =
+            Orderbook::from_snapshot(snapshot)
+    );
+    assert!(order_id == OrderId::from_u128(3));
+}
+
+#[test]
+fn duplicate_closing_order() {
+    let mut exchange = orderbook();
+    let at_the_close = Order::builder()
+        .side(OrderSide::Bid)
+        .id(OrderId::from_u128(6))
+        .limit(100, 5)
+        .atc()
+        .build();
+    assert!(exchange.matching(at_the_close).is_ok());
+
+    // Queued orders may take the id of neither a resting order nor another
+    // queued one.
+    let json = to_json(&exchange.snapshot());
+    let value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    for taken in [value["orders"][0].clone(), value["closing"][0].clone()] {
+        let mut value = value.clone();
+        let mut closing = value["closing"][0].clone();
+        closing["id"] = taken["id"].clone();
+        value["closing"].as_array_mut().unwrap().push(closing);
+
+        let_assert!(
+            Ok(snapshot) = serde_json::from_value::<OrderbookSnapshot>(value)
+        );
+        let_assert!(
+            Err(SnapshotError::Duplicate { order_id }) =
+                Orderbook::from_snapshot(snapshot)
+        );
+        assert!(order_id.to_string() == taken["id"].as_str().unwrap());
+    }
+}
+
+#[test]
+fn crossed_order() {
+    let json = to_json(&orderbook().snapshot());
+
+    let mut value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    value["orders"][2]["unit_price"] = serde_json::json!("100");
+
+    let_assert!(
+        Ok(snapshot) = serde_json::from_value::<OrderbookSnapshot>(value)
+    );
+    let_assert!(
+        Err(SnapshotError::Crossed { order_id }) =
+            Orderbook::from_snapshot(snapshot)
+    );
+    assert!(order_id == OrderId::from_u128(5));
+}
+
+#[test]
+fn links_are_kept() {
+    let mut exchange = orderbook();
+    let (iceberg, bid) = (OrderId::from_u128(2), OrderId::from_u128(5));
+    assert!(let Ok(()) = exchange.link(&iceberg, &bid, Some(2.into())));
+
+    let json = to_json(&exchange.snapshot());
+    let_assert!(
+        Ok(snapshot) = serde_json::from_str::<OrderbookSnapshot>(&json)
+    );
+    let_assert!(Ok(mut restored) = Orderbook::from_snapshot(snapshot));
+
+    assert!(to_json(&restored.snapshot()) == json);
+    assert!(restored.linked(&iceberg) == Some(bid));
+    assert!(restored.linked(&bid) == Some(iceberg));
+
+    // Filling what the link asks for still cancels the sibling.
+    let market_order = Order::builder().side(OrderSide::Bid).market(2).build();
+    let_assert!(Ok(report) = restored.matching(market_order));
+    assert!(report.cancelled() == [bid]);
+    assert!(restored.get(&bid).is_none());
+    assert!(restored.linked(&iceberg).is_none());
+}
+
+#[test]
+fn dangling_link() {
+    let mut exchange = orderbook();
+    let (iceberg, bid) = (OrderId::from_u128(2), OrderId::from_u128(5));
+    assert!(let Ok(()) = exchange.link(&iceberg, &bid, None));
+
+    let json = to_json(&exchange.snapshot());
+
+    // The bid is the third order, in the order they were queued.
+    let mut value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    value["orders"].as_array_mut().unwrap().remove(2);
+
+    let_assert!(
+        Ok(snapshot) = serde_json::from_value::<OrderbookSnapshot>(value)
+    );
+    let_assert!(
+        Err(SnapshotError::Link(LinkError::NotFound { order_id })) =
+            Orderbook::from_snapshot(snapshot)
+    );
+    assert!(order_id == bid);
+}
+
+#[test]
+fn at_the_close_orders_are_kept() {
+    let mut exchange = orderbook();
+    let at_the_close = Order::builder()
+        .side(OrderSide::Bid)
+        .id(OrderId::from_u128(6))
+        .limit(100, 5)
+        .atc()
+        .build();
+    assert!(exchange.matching(at_the_close).is_ok());
+
+    let json = to_json(&exchange.snapshot());
+    let_assert!(
+        Ok(snapshot) = serde_json::from_str::<OrderbookSnapshot>(&json)
+    );
+    assert!(snapshot.orders().len() == 4);
+    let_assert!(Ok(mut restored) = Orderbook::from_snapshot(snapshot));
+
+    assert!(to_json(&restored.snapshot()) == json);
+    let_assert!([order] = restored.closing());
+    assert!(order.id() == OrderId::from_u128(6));
+
+    // It still waits for the closing auction.
+    let (cross, expired) = restored.run_closing_auction();
+    let_assert!(Some(cross) = cross);
+    assert!(cross.quantity() == 5.into());
+    assert!(expired.is_empty());
+}
+
+#[test]
+fn reference_price_is_kept() {
+    let mut exchange = orderbook();
+    exchange.set_reference_price(200.into());
+
+    let json = to_json(&exchange.snapshot());
+    let_assert!(
+        Ok(snapshot) = serde_json::from_str::<OrderbookSnapshot>(&json)
+    );
+    let_assert!(Ok(restored) = Orderbook::from_snapshot(snapshot));
+    assert!(to_json(&restored.snapshot()) == json);
+
+    // The collar is centred on it once set again.
+    let restored = restored.with_price_collar(PriceCollar::new(5));
+    assert!(restored.price_collar() == Some(190.into()..=210.into()));
+}
+
+#[test]
+fn positions_are_kept() {
+    let mut exchange = orderbook();
+    let account_id = AccountId::from_u128(7);
+    let buy = Order::builder()
+        .side(OrderSide::Bid)
+        .account_id(account_id)
+        .limit(100, 4)
+        .build();
+    assert!(exchange.matching(buy).is_ok());
+
+    let json = to_json(&exchange.snapshot());
+    let_assert!(
+        Ok(snapshot) = serde_json::from_str::<OrderbookSnapshot>(&json)
+    );
+    let_assert!(Ok(restored) = Orderbook::from_snapshot(snapshot));
+
+    assert!(to_json(&restored.snapshot()) == json);
+    assert!(restored.position(&account_id) == Some((OrderSide::Bid, 4.into())));
+}
@@ -0,0 +1,99 @@
+//! Orders queued at the same price with the same sequence, as those restored
+//! from a snapshot taken before sequences were kept, are ranked by the
+//! configured tiebreak.
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::OrderbookSnapshot;
+use matching_engine_algo::Tiebreak;
+use tap::Tap;
+
+/// Restores a book of three asks at the same price, queued as `3`, `1` and
+/// `2`, with growing quantities, though all of them left with the very same
+/// sequence.
+fn orderbook() -> Orderbook {
+    let exchange = Orderbook::new().tap_mut(|exchange| {
+        for (id, quantity) in [(3, 10), (1, 20), (2, 30)] {
+            let limit_order = Order::builder()
+                .side(OrderSide::Ask)
+                .id(OrderId::from_u128(id))
+                .limit(100, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    });
+
+    let mut snapshot = serde_json::to_value(exchange.snapshot()).unwrap();
+    let_assert!(Some(orders) = snapshot["orders"].as_array_mut());
+    for order in orders {
+        let_assert!(Some(order) = order.as_object_mut());
+        assert!(order.remove("sequence").is_some());
+    }
+
+    let snapshot = serde_json::from_value::<OrderbookSnapshot>(snapshot);
+    let_assert!(Ok(snapshot) = snapshot);
+    assert!(snapshot.orders().iter().all(|order| order.sequence() == 0));
+
+    let_assert!(Ok(exchange) = Orderbook::from_snapshot(snapshot));
+    exchange
+}
+
+fn queue(exchange: &Orderbook) -> Vec<OrderId> {
+    exchange
+        .iter(&OrderSide::Ask)
+        .map(|order| order.id())
+        .collect()
+}
+
+#[test]
+fn configured_order() {
+    for (tiebreak, expected) in [
+        (Tiebreak::Insertion, [3, 1, 2]),
+        (Tiebreak::OrderId, [1, 2, 3]),
+        (Tiebreak::Size, [2, 1, 3]),
+    ] {
+        let mut exchange = orderbook().with_tiebreak(tiebreak);
+        assert!(exchange.tiebreak() == tiebreak);
+        assert!(queue(&exchange) == expected.map(OrderId::from_u128));
+
+        // Whatever comes first is also matched first.
+        let market_order =
+            Order::builder().side(OrderSide::Bid).market(5).build();
+
+        let_assert!(Ok(report) = exchange.matching(market_order));
+        assert!(report.trades().len() == 1);
+        assert!(report.trades()[0].maker() == OrderId::from_u128(expected[0]));
+    }
+}
+
+#[test]
+fn later_orders_queue_behind() {
+    let mut exchange = orderbook().with_tiebreak(Tiebreak::Size);
+
+    // Orders queued later get a sequence of their own, so they never jump
+    // ahead of the tied ones, however large they are.
+    let limit_order = Order::builder()
+        .side(OrderSide::Ask)
+        .id(OrderId::from_u128(4))
+        .limit(100, 100)
+        .build();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    assert!(queue(&exchange) == [2, 1, 3, 4].map(OrderId::from_u128));
+}
+
+#[test]
+fn restored_in_configured_order() {
+    // Restoring a snapshot of a book ranked by size keeps it ranked so.
+    let exchange = orderbook().with_tiebreak(Tiebreak::Size);
+
+    let_assert!(Ok(restored) = Orderbook::from_snapshot(exchange.snapshot()));
+    assert!(queue(&restored) == [2, 1, 3].map(OrderId::from_u128));
+}
@@ -0,0 +1,90 @@
+//! Every trade gets an id of its own, handed out by the book from the very
+//! sequence resting orders are queued by, so that a single execution may be
+//! referred to, e.g. to bust it.
+
+use std::collections::HashSet;
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use matching_engine_algo::MatchingStrategy;
+use matching_engine_algo::Orderbook;
+use matching_engine_algo::ProRataAlgo;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+
+/// Matches the same random orders against an empty book, returning the ids of
+/// every trade in the order they were executed.
+fn trade_ids<A: MatchingStrategy>(mut exchange: Orderbook<A>) -> Vec<u64> {
+    let mut rng = StdRng::seed_from_u64(0x7ade);
+    let mut trade_ids = Vec::new();
+
+    for id in 0..1_000 {
+        let side = if rng.gen_bool(0.5) {
+            OrderSide::Ask
+        } else {
+            OrderSide::Bid
+        };
+        let order = Order::builder().side(side).id(OrderId::from_u128(id));
+        let order = if rng.gen_bool(0.2) {
+            order.market(rng.gen_range(1..=30)).build()
+        } else {
+            order
+                .limit(rng.gen_range(95..=105), rng.gen_range(1..=20))
+                .build()
+        };
+
+        let_assert!(Ok(report) = exchange.matching(order));
+        trade_ids.extend(report.trades().iter().map(|trade| trade.trade_id()));
+    }
+
+    trade_ids
+}
+
+fn check<A: MatchingStrategy>(new: impl Fn() -> Orderbook<A>) {
+    let trade_ids = trade_ids(new());
+    assert!(trade_ids.len() > 100);
+
+    // Strictly increasing, hence unique.
+    assert!(trade_ids.windows(2).all(|ids| ids[0] < ids[1]));
+    assert!(trade_ids.iter().collect::<HashSet<_>>().len() == trade_ids.len());
+
+    // Replaying the very same orders assigns the very same ids.
+    assert!(self::trade_ids(new()) == trade_ids);
+}
+
+#[test]
+fn increasing_and_replayable() {
+    check(Orderbook::new);
+    check(|| Orderbook::new().with_algo::<ProRataAlgo>());
+}
+
+#[test]
+fn distinct_from_order_sequences() {
+    let mut exchange = Orderbook::new();
+    for limit_price in [100, 101] {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .limit(limit_price, 10)
+            .build();
+
+        assert!(exchange.matching(limit_order).is_ok());
+    }
+
+    // The bid is queued once it swept both asks, after their trades took
+    // their ids.
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(102, 25).build();
+    let order_id = limit_order.id();
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    let_assert!([first, second] = report.trades().as_slice());
+    assert!((first.trade_id(), second.trade_id()) == (2, 3));
+
+    let_assert!(Some(order) = exchange.get(&order_id));
+    assert!(order.sequence() == 4);
+}
@@ -0,0 +1,203 @@
+use std::sync::atomic::AtomicU64;
+
+use assert2::assert;
+use assert2::let_assert;
+use exchange_core::Asset;
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::OrderId;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+
+#[test]
+fn resting_order_trades_nothing() {
+    let mut exchange = Orderbook::new();
+
+    let limit_order =
+        Order::builder().side(OrderSide::Ask).limit(100, 10).build();
+
+    let_assert!(Ok(report) = exchange.matching(limit_order));
+    let trades = report.into_trades();
+    assert!(trades.is_empty());
+}
+
+#[test]
+fn trades_in_execution_order() {
+    let mut exchange = Orderbook::new();
+
+    let makers = [(101, 10), (100, 5), (100, 20)].map(|(price, quantity)| {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .limit(price, quantity)
+            .build();
+        let order_id = limit_order.id();
+
+        assert!(let Ok(_) = exchange.matching(limit_order));
+
+        order_id
+    });
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(30).build();
+    let taker = market_order.id();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let trades = report.into_trades();
+    assert!(!trades.spilled());
+
+    let trades = trades
+        .iter()
+        .map(|trade| {
+            assert!(trade.taker() == taker);
+            assert!(trade.notional() == trade.price() * trade.quantity());
+
+            (trade.maker(), trade.price(), trade.quantity())
+        })
+        .collect::<Vec<_>>();
+
+    assert!(
+        trades
+            == [
+                (makers[1], 100.into(), 5.into()),
+                (makers[2], 100.into(), 20.into()),
+                (makers[0], 101.into(), 5.into()),
+            ]
+    );
+}
+
+#[test]
+fn running_remaining() {
+    let mut exchange = Orderbook::new();
+
+    for (price, quantity) in [(100, 5), (100, 20), (101, 10)] {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .limit(price, quantity)
+            .build();
+
+        assert!(let Ok(_) = exchange.matching(limit_order));
+    }
+
+    let market_order = Order::builder().side(OrderSide::Bid).market(30).build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let trades = report
+        .trades()
+        .iter()
+        .map(|trade| (trade.taker_remaining(), trade.maker_remaining()))
+        .collect::<Vec<_>>();
+
+    assert!(
+        trades
+            == [
+                (Some(25.into()), 0.into()),
+                (Some(5.into()), 0.into()),
+                (Some(0.into()), 5.into()),
+            ]
+    );
+
+    // Orders priced by funds have no remaining quantity to speak of.
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .market_funds(202)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let_assert!([trade] = report.trades().as_slice());
+    assert!(trade.taker_remaining().is_none());
+    assert!(trade.maker_remaining() == 3.into());
+}
+
+#[test]
+fn remaining_reserve_is_not_shown() {
+    let mut exchange = Orderbook::new();
+
+    for limit_order in [
+        Order::builder()
+            .side(OrderSide::Ask)
+            .limit(99, 10)
+            .hidden()
+            .build(),
+        Order::builder()
+            .side(OrderSide::Ask)
+            .limit(100, 20)
+            .iceberg(5)
+            .build(),
+    ] {
+        assert!(let Ok(_) = exchange.matching(limit_order));
+    }
+
+    let mut remaining = Vec::new();
+    for quantity in [4, 9] {
+        let market_order = Order::builder()
+            .side(OrderSide::Bid)
+            .market(quantity)
+            .build();
+
+        let_assert!(Ok(report) = exchange.matching(market_order));
+        remaining.extend(report.trades().iter().map(|trade| {
+            (trade.maker_remaining(), trade.maker_total_remaining())
+        }));
+    }
+
+    // Hidden orders show nothing and iceberg ones only their current slice,
+    // whatever they have left.
+    assert!(
+        remaining
+            == [
+                (0.into(), 6.into()),
+                (0.into(), 0.into()),
+                (2.into(), 17.into()),
+            ]
+    );
+}
+
+#[test]
+fn pinned_ids() {
+    static IDS: AtomicU64 = AtomicU64::new(1);
+
+    let mut exchange = Orderbook::new();
+
+    for limit_price in [101, 100] {
+        let limit_order = Order::builder()
+            .side(OrderSide::Ask)
+            .id(OrderId::sequential(&IDS))
+            .limit(limit_price, 10)
+            .build();
+
+        assert!(let Ok(_) = exchange.matching(limit_order));
+    }
+
+    let market_order = Order::builder()
+        .side(OrderSide::Bid)
+        .id(OrderId::sequential(&IDS))
+        .market(15)
+        .build();
+
+    let_assert!(Ok(report) = exchange.matching(market_order));
+    let trades = report.into_trades();
+
+    insta::assert_ron_snapshot!(trades.as_slice(), @r###"
+    [
+      Trade(
+        trade_id: 2,
+        taker: OrderId("00000000-0000-0000-0000-000000000003"),
+        maker: OrderId("00000000-0000-0000-0000-000000000002"),
+        quantity: "10",
+        price: "100",
+        notional: "1000",
+        taker_remaining: Some("5"),
+        maker_remaining: "0",
+      ),
+      Trade(
+        trade_id: 3,
+        taker: OrderId("00000000-0000-0000-0000-000000000003"),
+        maker: OrderId("00000000-0000-0000-0000-000000000001"),
+        quantity: "5",
+        price: "101",
+        notional: "505",
+        taker_remaining: Some("0"),
+        maker_remaining: "5",
+      ),
+    ]
+    "###);
+}
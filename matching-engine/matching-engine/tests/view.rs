@@ -0,0 +1,138 @@
+use exchange_core::Exchange;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use exchange_types::OrderStatus;
+use matching_engine_algo::BookView;
+use matching_engine_algo::OrderView;
+use matching_engine_algo::Orderbook;
+use tap::Tap;
+
+fn clock() -> u64 {
+    1_700_000_000
+}
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price, quantity) in [
+            (OrderSide::Ask, 110, 100),
+            (OrderSide::Ask, 100, 100),
+            (OrderSide::Bid, 90, 100),
+            (OrderSide::Bid, 100, 40),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn matches_debug_view() {
+    let exchange = orderbook();
+
+    insta::assert_debug_snapshot!(&exchange, @r###"
+    {
+        Ask: [
+            Order {
+                limit_price: 100,
+                remaining: 60,
+                status: Partial,
+            },
+            Order {
+                limit_price: 110,
+                remaining: 100,
+                status: Open,
+            },
+        ],
+        Bid: [
+            Order {
+                limit_price: 90,
+                remaining: 100,
+                status: Open,
+            },
+        ],
+    }
+    "###);
+
+    let order = |limit_price: u32, remaining: u32, status| OrderView {
+        limit_price: limit_price.into(),
+        remaining: remaining.into(),
+        status,
+        created_at: 0,
+    };
+
+    assert_eq!(
+        exchange.view(),
+        BookView {
+            ask: vec![
+                order(100, 60, OrderStatus::Partial),
+                order(110, 100, OrderStatus::Open),
+            ],
+            bid: vec![order(90, 100, OrderStatus::Open)],
+        }
+    );
+}
+
+#[test]
+fn serialize() {
+    let exchange = orderbook();
+
+    let json = serde_json::to_string_pretty(&exchange.view()).unwrap();
+
+    insta::assert_snapshot!(json, @r###"
+    {
+      "Ask": [
+        {
+          "limit_price": "100",
+          "remaining": "60",
+          "status": "PARTIAL"
+        },
+        {
+          "limit_price": "110",
+          "remaining": "100",
+          "status": "OPEN"
+        }
+      ],
+      "Bid": [
+        {
+          "limit_price": "90",
+          "remaining": "100",
+          "status": "OPEN"
+        }
+      ]
+    }
+    "###);
+
+    let view: BookView = serde_json::from_str(&json).unwrap();
+    assert_eq!(view, exchange.view());
+}
+
+#[test]
+fn serialize_created_at() {
+    let mut exchange = Orderbook::new().with_clock(clock);
+    let limit_order =
+        Order::builder().side(OrderSide::Bid).limit(100, 10).build();
+    assert!(exchange.matching(limit_order).is_ok());
+
+    let json = serde_json::to_string_pretty(&exchange.view()).unwrap();
+
+    insta::assert_snapshot!(json, @r###"
+    {
+      "Ask": [],
+      "Bid": [
+        {
+          "limit_price": "100",
+          "remaining": "10",
+          "status": "OPEN",
+          "created_at": 1700000000
+        }
+      ]
+    }
+    "###);
+
+    let view: BookView = serde_json::from_str(&json).unwrap();
+    assert_eq!(view, exchange.view());
+}
@@ -0,0 +1,79 @@
+use assert2::assert;
+use exchange_core::Exchange;
+use exchange_core::ExchangeExt;
+use exchange_types::Order;
+use exchange_types::OrderSide;
+use matching_engine_algo::Orderbook;
+#[cfg(not(feature = "integer"))]
+use rust_decimal_macros::dec;
+use tap::Tap;
+
+fn orderbook() -> Orderbook {
+    Orderbook::new().tap_mut(|exchange| {
+        for (side, limit_price, quantity) in [
+            (OrderSide::Ask, 101, 10),
+            (OrderSide::Ask, 100, 10),
+            (OrderSide::Ask, 103, 20),
+            (OrderSide::Bid, 99, 10),
+            (OrderSide::Bid, 98, 30),
+        ] {
+            let limit_order = Order::builder()
+                .side(side)
+                .limit(limit_price, quantity)
+                .build();
+
+            assert!(exchange.matching(limit_order).is_ok());
+        }
+    })
+}
+
+#[test]
+fn within_best_level() {
+    let exchange = orderbook();
+
+    assert!(exchange.vwap(&OrderSide::Ask, 5.into()) == Some(100.into()));
+    assert!(exchange.vwap(&OrderSide::Bid, 10.into()) == Some(99.into()));
+}
+
+#[test]
+fn across_levels() {
+    let exchange = orderbook();
+
+    // (100 * 10 + 101 * 10 + 103 * 5) / 25
+    assert!(exchange.vwap(&OrderSide::Ask, 25.into()) == Some(101.into()));
+}
+
+#[test]
+#[cfg(not(feature = "integer"))]
+fn fractional_across_levels() {
+    let exchange = orderbook();
+
+    // (99 * 10 + 98 * 30) / 40
+    assert!(
+        exchange.vwap(&OrderSide::Bid, 40.into()) == Some(dec!(98.25).into())
+    );
+}
+
+#[test]
+fn too_thin() {
+    let exchange = orderbook();
+
+    assert!(exchange.vwap(&OrderSide::Ask, 41.into()).is_none());
+    assert!(Orderbook::new().vwap(&OrderSide::Bid, 1.into()).is_none());
+}
+
+#[test]
+fn zero_quantity() {
+    let exchange = orderbook();
+
+    assert!(exchange.vwap(&OrderSide::Ask, 0.into()).is_none());
+}
+
+#[test]
+fn leaves_book_untouched() {
+    let exchange = orderbook();
+
+    assert!(exchange.vwap(&OrderSide::Ask, 40.into()).is_some());
+    assert!(exchange.len() == (3, 2));
+    assert!(exchange.volume() == (40.into(), 40.into()));
+}